@@ -0,0 +1,302 @@
+// Copyright 2025 Martin Pool
+
+//! Filter mutants to code recently written, or written by certain authors, according
+//! to `git blame`.
+//!
+//! This lets a team adopting mutation testing on an existing codebase gate only the
+//! new code.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::process::Command;
+
+use anyhow::bail;
+use camino::{Utf8Path, Utf8PathBuf};
+use tracing::{debug, info, trace};
+
+use crate::mutant::Mutant;
+use crate::{Context, Result};
+
+/// Which lines to select from the blame.
+#[derive(Debug, Clone, Default)]
+pub struct BlameFilter {
+    /// Select lines last changed after this date, in any format understood by git,
+    /// like `2024-06-01` or `3 months ago`.
+    pub since: Option<String>,
+    /// Select lines whose author's name or email is one of these.
+    pub authors: Vec<String>,
+}
+
+/// The blame for one line of a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct BlameLine {
+    author: String,
+    /// The email address, without angle brackets.
+    author_mail: String,
+    /// True if the line was last changed before `--since`.
+    boundary: bool,
+    /// True if the line has changed in the working tree since the last commit.
+    uncommitted: bool,
+}
+
+impl BlameFilter {
+    /// True if this line should be mutated.
+    ///
+    /// Uncommitted lines are always selected, since they're about as new as code can be.
+    fn selects(&self, line: &BlameLine) -> bool {
+        line.uncommitted
+            || ((self.since.is_none() || !line.boundary)
+                && (self.authors.is_empty()
+                    || self
+                        .authors
+                        .iter()
+                        .any(|a| *a == line.author || *a == line.author_mail)))
+    }
+}
+
+/// Return only mutants that touch a line selected by the filter.
+pub fn blame_filter(
+    mutants: Vec<Mutant>,
+    tree_path: &Utf8Path,
+    filter: &BlameFilter,
+) -> Result<Vec<Mutant>> {
+    let mut blame_by_path: HashMap<Utf8PathBuf, Vec<BlameLine>> = HashMap::new();
+    for mutant in &mutants {
+        let path = mutant.source_file.path();
+        if !blame_by_path.contains_key(path) {
+            blame_by_path.insert(path.to_owned(), blame_file(tree_path, path, filter)?);
+        }
+    }
+    let n_mutants = mutants.len();
+    let matched: Vec<Mutant> = mutants
+        .into_iter()
+        .filter(|mutant| {
+            let lines = &blame_by_path[mutant.source_file.path()];
+            (mutant.span.start.line..=mutant.span.end.line).any(|line| {
+                lines
+                    .get(line - 1)
+                    .is_some_and(|blame| filter.selects(blame))
+            })
+        })
+        .collect();
+    info!(
+        "Blame filter selected {} of {n_mutants} mutants",
+        matched.len()
+    );
+    Ok(matched)
+}
+
+/// Run `git blame` on a file, and return the blame for each line.
+///
+/// Files that aren't committed yet have no blame, so all their lines are uncommitted.
+fn blame_file(
+    tree_path: &Utf8Path,
+    path: &Utf8Path,
+    filter: &BlameFilter,
+) -> Result<Vec<BlameLine>> {
+    // `--root` stops the first commit being shown as a boundary.
+    let mut argv = vec!["blame", "--line-porcelain", "--root"];
+    let since_arg = filter
+        .since
+        .as_ref()
+        .map(|since| format!("--since={since}"));
+    argv.extend(since_arg.as_deref());
+    argv.extend(["--", path.as_str()]);
+    debug!(?argv, "run git blame");
+    let output = Command::new("git")
+        .args(&argv)
+        .current_dir(tree_path)
+        .output()
+        .context("Failed to run git blame")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no such path") {
+            debug!(?path, "file isn't committed, so all its lines are new");
+            let code =
+                read_to_string(tree_path.join(path)).with_context(|| format!("read {path}"))?;
+            return Ok(vec![
+                BlameLine {
+                    uncommitted: true,
+                    ..BlameLine::default()
+                };
+                code.lines().count()
+            ]);
+        }
+        bail!("git blame failed on {path}: {}", stderr.trim());
+    }
+    let lines = parse_line_porcelain(&String::from_utf8_lossy(&output.stdout));
+    trace!(?path, n_lines = lines.len(), "blamed file");
+    Ok(lines)
+}
+
+/// Parse the output of `git blame --line-porcelain`, which has a full header for every
+/// line, followed by the text of the line itself indented by a tab.
+fn parse_line_porcelain(porcelain: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut current = BlameLine::default();
+    let mut in_header = false;
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            lines.push(std::mem::take(&mut current));
+            in_header = false;
+        } else if !in_header {
+            // The first line of each header is the commit hash and line numbers.
+            in_header = true;
+            current.uncommitted = line
+                .split(' ')
+                .next()
+                .is_some_and(|hash| !hash.is_empty() && hash.bytes().all(|b| b == b'0'));
+        } else if let Some(author) = line.strip_prefix("author ") {
+            author.clone_into(&mut current.author);
+        } else if let Some(mail) = line.strip_prefix("author-mail ") {
+            mail.trim_start_matches('<')
+                .trim_end_matches('>')
+                .clone_into(&mut current.author_mail);
+        } else if line == "boundary" {
+            current.boundary = true;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use indoc::indoc;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn git(dir: &Utf8Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    const PORCELAIN: &str = indoc! { "
+        ae83d914239aa7f0cdc62987febb6d2a1622854e 1 1 1
+        author Old
+        author-mail <old@example.com>
+        author-time 1577836800
+        summary 1
+        boundary
+        filename f
+        \ta
+        0fb935ff1e3b00734591f06c0c57886f46e976ec 2 2 2
+        author New
+        author-mail <new@example.com>
+        summary 2
+        previous ae83d914239aa7f0cdc62987febb6d2a1622854e f
+        filename f
+        \tauthor Someone
+        0fb935ff1e3b00734591f06c0c57886f46e976ec 3 3
+        author New
+        author-mail <new@example.com>
+        summary 2
+        filename f
+        \tboundary
+        0000000000000000000000000000000000000000 4 4 1
+        author Not Committed Yet
+        author-mail <not.committed.yet>
+        summary Version of f from f
+        filename f
+        \te
+    "};
+
+    #[test]
+    fn parse_porcelain() {
+        let lines = parse_line_porcelain(PORCELAIN);
+        assert_eq!(
+            lines,
+            [
+                BlameLine {
+                    author: "Old".to_owned(),
+                    author_mail: "old@example.com".to_owned(),
+                    boundary: true,
+                    uncommitted: false,
+                },
+                BlameLine {
+                    author: "New".to_owned(),
+                    author_mail: "new@example.com".to_owned(),
+                    boundary: false,
+                    uncommitted: false,
+                },
+                BlameLine {
+                    author: "New".to_owned(),
+                    author_mail: "new@example.com".to_owned(),
+                    boundary: false,
+                    uncommitted: false,
+                },
+                BlameLine {
+                    author: "Not Committed Yet".to_owned(),
+                    author_mail: "not.committed.yet".to_owned(),
+                    boundary: false,
+                    uncommitted: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_lines() {
+        let lines = parse_line_porcelain(PORCELAIN);
+        let selected = |filter: &BlameFilter| {
+            lines
+                .iter()
+                .map(|line| filter.selects(line))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(selected(&BlameFilter::default()), [true, true, true, true]);
+        assert_eq!(
+            selected(&BlameFilter {
+                since: Some("2021-01-01".to_owned()),
+                authors: Vec::new(),
+            }),
+            [false, true, true, true]
+        );
+        assert_eq!(
+            selected(&BlameFilter {
+                since: None,
+                authors: vec!["old@example.com".to_owned()],
+            }),
+            [true, false, false, true]
+        );
+        assert_eq!(
+            selected(&BlameFilter {
+                since: Some("2021-01-01".to_owned()),
+                authors: vec!["Old".to_owned()],
+            }),
+            [false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn untracked_file_is_all_uncommitted() {
+        let tmp = TempDir::new().unwrap();
+        let tree = Utf8Path::from_path(tmp.path()).unwrap();
+        git(tree, &["init", "-q"]);
+        write(tree.join("old.rs"), "fn old() {}\n").unwrap();
+        git(tree, &["add", "old.rs"]);
+        git(tree, &["commit", "-q", "-m", "old"]);
+        write(tree.join("new.rs"), "fn new() {}\n\nfn newer() {}\n").unwrap();
+        let filter = BlameFilter {
+            since: Some("2000-01-01".to_owned()),
+            authors: vec!["someone@example.com".to_owned()],
+        };
+        let lines = blame_file(tree, Utf8Path::new("new.rs"), &filter).unwrap();
+        assert_eq!(lines.len(), 3);
+        assert!(lines
+            .iter()
+            .all(|line| line.uncommitted && filter.selects(line)));
+        let lines = blame_file(tree, Utf8Path::new("old.rs"), &filter).unwrap();
+        assert_eq!(lines.len(), 1);
+        assert!(!lines[0].uncommitted);
+    }
+}
@@ -1,4 +1,4 @@
-// Copyright 2021-2024 Martin Pool
+// Copyright 2021-2025 Martin Pool
 
 //! A directory containing mutated source to run cargo builds and tests.
 
@@ -41,23 +41,32 @@ impl BuildDir {
     /// or in-place.
     pub fn for_baseline(
         workspace: &Workspace,
+        scratch_dir: Option<&Utf8Path>,
         options: &Options,
         console: &Console,
     ) -> Result<BuildDir> {
         if options.in_place {
             BuildDir::in_place(workspace.root())
         } else {
-            BuildDir::copy_from(workspace.root(), options, console)
+            BuildDir::copy_from(workspace.root(), scratch_dir, options, console)
         }
     }
 
     /// Make a new build dir, copying from a source directory, subject to exclusions.
-    pub fn copy_from(source: &Utf8Path, options: &Options, console: &Console) -> Result<BuildDir> {
+    ///
+    /// The copy is made in `scratch_dir` if it's given, or otherwise in the system
+    /// temporary directory.
+    pub fn copy_from(
+        source: &Utf8Path,
+        scratch_dir: Option<&Utf8Path>,
+        options: &Options,
+        console: &Console,
+    ) -> Result<BuildDir> {
         let name_base = format!("cargo-mutants-{}-", source.file_name().unwrap_or("unnamed"));
         let source_abs = source
             .canonicalize_utf8()
             .context("canonicalize source path")?;
-        let temp_dir = copy_tree(source, &name_base, options, console)?;
+        let temp_dir = copy_tree(source, &name_base, scratch_dir, options, console)?;
         let path: Utf8PathBuf = temp_dir
             .path()
             .to_owned()
@@ -115,7 +124,8 @@ mod test {
             leak_dirs: false,
             ..Default::default()
         };
-        let build_dir = BuildDir::copy_from(workspace.root(), &options, &Console::new()).unwrap();
+        let build_dir =
+            BuildDir::copy_from(workspace.root(), None, &options, &Console::new()).unwrap();
         let debug_form = format!("{build_dir:?}");
         println!("debug form is {debug_form:?}");
         assert!(debug_form.starts_with("BuildDir { path: "));
@@ -132,7 +142,7 @@ mod test {
             in_place: true,
             ..Default::default()
         };
-        let build_dir = BuildDir::for_baseline(&workspace, &options, &Console::new())?;
+        let build_dir = BuildDir::for_baseline(&workspace, None, &options, &Console::new())?;
         assert_eq!(
             build_dir.path().canonicalize_utf8()?,
             workspace.root().canonicalize_utf8()?
@@ -149,7 +159,7 @@ mod test {
             in_place: false,
             ..Default::default()
         };
-        let build_dir = BuildDir::for_baseline(&workspace, &options, &Console::new())?;
+        let build_dir = BuildDir::for_baseline(&workspace, None, &options, &Console::new())?;
         assert!(build_dir.path().is_dir());
         assert!(build_dir.path().join("Cargo.toml").is_file());
         assert!(build_dir.path().join("src").is_dir());
@@ -0,0 +1,228 @@
+// Copyright 2025 Martin Pool
+
+//! Outcomes of mutants from earlier runs, reused by `--incremental` for mutants whose
+//! source file and options haven't changed.
+//!
+//! Every run records the outcome of each mutant in `mutants.out/cache.json`, keyed by a
+//! hash of the content of the mutated file, the mutant's span, genre, and replacement,
+//! and the options that affect how it's built and tested. Like the timings, the cache is
+//! read before the output directory is rotated and written again at the end of the run,
+//! so it carries over from run to run even if some runs test only part of the tree.
+//!
+//! Only the mutated file is hashed, so a change to tests in other files won't cause a
+//! cached mutant to be tested again. Missed mutants aren't cached, because the usual
+//! next step is to add a test, often in another file, that catches them.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::fs::{read_to_string, File};
+use std::io::BufWriter;
+use std::time::Duration;
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::hash::{fmix64, fnv1a};
+use crate::outcome::{LabOutcome, PhaseResult, SummaryOutcome};
+use crate::process::Exit;
+use crate::{Context, Mutant, Options, Phase, Result};
+
+/// The name of the cache file within the output directory.
+pub const CACHE_JSON: &str = "cache.json";
+
+/// Outcomes of mutants from earlier runs, by key.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutcomeCache {
+    pub outcomes: BTreeMap<String, CachedOutcome>,
+}
+
+/// The outcome of one mutant in an earlier run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedOutcome {
+    /// The path of the mutated file relative to the tree, with forward slashes.
+    pub file: String,
+    /// The hash of the content of the file when the mutant was tested.
+    pub file_hash: String,
+    pub summary: SummaryOutcome,
+    pub phase_results: Vec<CachedPhaseResult>,
+}
+
+/// The result of one phase of testing a cached mutant, without the command that was run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedPhaseResult {
+    pub phase: Phase,
+    /// Duration in seconds.
+    pub duration: f64,
+    pub process_status: Exit,
+}
+
+impl From<&CachedPhaseResult> for PhaseResult {
+    fn from(cached: &CachedPhaseResult) -> PhaseResult {
+        PhaseResult {
+            phase: cached.phase,
+            duration: Duration::from_secs_f64(cached.duration),
+            process_status: cached.process_status,
+            argv: Vec::new(),
+        }
+    }
+}
+
+impl OutcomeCache {
+    /// Read the cache from an output directory, or return an empty cache if it doesn't
+    /// exist or can't be read.
+    pub fn load(output_dir: &Utf8Path) -> OutcomeCache {
+        let path = output_dir.join(CACHE_JSON);
+        let Ok(json) = read_to_string(&path) else {
+            debug!(?path, "No previous outcome cache");
+            return OutcomeCache::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|err| {
+            warn!("Failed to parse {path}: {err}");
+            OutcomeCache::default()
+        })
+    }
+
+    /// Return the outcome of an identical mutant from an earlier run, if there is one.
+    pub fn get(&self, mutant: &Mutant, options: &Options) -> Option<&CachedOutcome> {
+        self.outcomes.get(&cache_key(mutant, options))
+    }
+
+    /// Return a new cache with the outcomes of the mutants tested in this run, and those
+    /// from earlier runs except for files that have since changed.
+    ///
+    /// Timeouts aren't cached, because they're often caused by a busy machine, and nor are
+    /// missed mutants, because a test that catches them may be added in another file.
+    pub fn updated_by(&self, lab_outcome: &LabOutcome, options: &Options) -> OutcomeCache {
+        let mut new_outcomes = BTreeMap::new();
+        let mut file_hashes = HashMap::new();
+        for outcome in &lab_outcome.outcomes {
+            let Some(mutant) = outcome.scenario.mutant() else {
+                continue;
+            };
+            let file_hash = file_hash(mutant);
+            let file = mutant.source_file.tree_relative_slashes();
+            file_hashes.insert(file.clone(), file_hash.clone());
+            let summary = outcome.summary();
+            // Mutants that weren't run, because coverage showed they're never executed,
+            // depend on the coverage rather than the source.
+            if matches!(
+                summary,
+                SummaryOutcome::Timeout | SummaryOutcome::Failure | SummaryOutcome::MissedMutant
+            ) || outcome.phase_results().is_empty()
+            {
+                continue;
+            }
+            let phase_results = outcome
+                .phase_results()
+                .iter()
+                .map(|pr| CachedPhaseResult {
+                    phase: pr.phase,
+                    duration: pr.duration.as_secs_f64(),
+                    process_status: pr.process_status,
+                })
+                .collect();
+            new_outcomes.insert(
+                cache_key(mutant, options),
+                CachedOutcome {
+                    file,
+                    file_hash,
+                    summary,
+                    phase_results,
+                },
+            );
+        }
+        let mut outcomes: BTreeMap<String, CachedOutcome> = self
+            .outcomes
+            .iter()
+            .filter(|(_, cached)| {
+                file_hashes
+                    .get(&cached.file)
+                    .map_or(true, |hash| *hash == cached.file_hash)
+            })
+            .map(|(key, cached)| (key.clone(), cached.clone()))
+            .collect();
+        outcomes.extend(new_outcomes);
+        OutcomeCache { outcomes }
+    }
+
+    pub fn write(&self, output_dir: &Utf8Path) -> Result<()> {
+        serde_json::to_writer(
+            BufWriter::new(File::create(output_dir.join(CACHE_JSON))?),
+            self,
+        )
+        .context("write cache.json")
+    }
+}
+
+/// Hash the content of the file containing a mutant.
+fn file_hash(mutant: &Mutant) -> String {
+    stable_hash([mutant.source_file.code()])
+}
+
+/// The key for a mutant's outcome in the cache.
+///
+/// This isn't a cryptographic hash, but it is the same in every build, so the cache can be
+/// shared between machines and versions of Rust.
+fn cache_key(mutant: &Mutant, options: &Options) -> String {
+    let options = options.for_package(&mutant.source_file.package.name);
+    let span = mutant.span;
+    let mut parts = vec![
+        env!("CARGO_PKG_VERSION").to_owned(),
+        mutant.source_file.tree_relative_slashes(),
+        file_hash(mutant),
+        format!(
+            "{}:{}-{}:{}",
+            span.start.line, span.start.column, span.end.line, span.end.column
+        ),
+        mutant.genre.to_string(),
+        mutant.replacement.clone(),
+    ];
+    // Options that can change the outcome, hashed by their debug representation.
+    let relevant: [&dyn Debug; 15] = [
+        &options.additional_cargo_args,
+        &options.additional_cargo_test_args,
+        &options.env,
+        &options.features,
+        &options.profile,
+        &options.test_tool,
+        &options.test_package,
+        &options.cap_lints,
+        &options.check_only,
+        &options.detect_equivalent,
+        &options.test_timeout,
+        &options.test_timeout_multiplier,
+        &options.build_timeout,
+        &options.build_timeout_multiplier,
+        &options.minimum_test_timeout,
+    ];
+    parts.extend(relevant.iter().map(|option| format!("{option:?}")));
+    stable_hash(parts.iter().map(String::as_str))
+}
+
+/// Hash a sequence of strings with FNV-1a, as a hex string.
+///
+/// Each string is prefixed by its length, so that moving text from one to the next
+/// changes the hash.
+fn stable_hash<'a>(parts: impl IntoIterator<Item = &'a str>) -> String {
+    let mut bytes = Vec::new();
+    for part in parts {
+        bytes.extend_from_slice(&(part.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(part.as_bytes());
+    }
+    format!("{:016x}", fmix64(fnv1a(&bytes)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stable_hash_is_stable() {
+        assert_eq!(stable_hash(["a", "bc"]), "028d4aba4bf993b3");
+        assert_ne!(stable_hash(["a", "bc"]), stable_hash(["ab", "c"]));
+    }
+}
@@ -20,6 +20,7 @@ use crate::outcome::{Phase, PhaseResult};
 use crate::output::ScenarioOutput;
 use crate::package::PackageSelection;
 use crate::process::{Exit, Process};
+use crate::sccache::SCCACHE;
 use crate::Result;
 
 // Allowed nextest codes (those will be considered a mutation caught / ignored without a warning)
@@ -44,17 +45,14 @@ pub fn run_cargo(
     let _span = debug_span!("run", ?phase).entered();
     let start = Instant::now();
     let argv = cargo_argv(packages, phase, options);
-    let mut env = vec![
+    let mut env = cargo_env(options);
+    env.extend([
         // The tests might use Insta <https://insta.rs>, and we don't want it to write
         // updates to the source tree, and we *certainly* don't want it to write
         // updates and then let the test pass.
         ("INSTA_UPDATE".to_owned(), "no".to_owned()),
         ("INSTA_FORCE_PASS".to_owned(), "0".to_owned()),
-    ];
-    if let Some(encoded_rustflags) = encoded_rustflags(options) {
-        debug!(?encoded_rustflags);
-        env.push(("CARGO_ENCODED_RUSTFLAGS".to_owned(), encoded_rustflags));
-    }
+    ]);
     let process_status = Process::run(
         &argv,
         &env,
@@ -81,6 +79,30 @@ pub fn run_cargo(
     })
 }
 
+/// The environment variables to set for every cargo subprocess: those from the options,
+/// and those that pass through the options' lint cap, compiler wrapper, and shared build
+/// directory.
+pub fn cargo_env(options: &Options) -> Vec<(String, String)> {
+    let mut env = options.env.clone();
+    if let Some(encoded_rustflags) = encoded_rustflags(options) {
+        debug!(?encoded_rustflags);
+        env.push(("CARGO_ENCODED_RUSTFLAGS".to_owned(), encoded_rustflags));
+    }
+    if options.sccache {
+        env.push(("RUSTC_WRAPPER".to_owned(), SCCACHE.to_owned()));
+    }
+    if let Some(shared_target_dir) = &options.shared_target_dir {
+        // Only cargo's intermediate files go here; the final binaries are still put in
+        // each build directory's own `target`, so that tests using them aren't confused
+        // by binaries from other build directories.
+        env.push((
+            "CARGO_BUILD_BUILD_DIR".to_owned(),
+            shared_target_dir.to_string(),
+        ));
+    }
+    env
+}
+
 /// Return the name of the cargo binary.
 pub fn cargo_bin() -> String {
     // When run as a Cargo subcommand, which is the usual/intended case,
@@ -92,7 +114,7 @@ pub fn cargo_bin() -> String {
 /// Make up the argv for a cargo check/build/test invocation, including argv[0] as the
 /// cargo binary itself.
 // (This is split out so it's easier to test.)
-fn cargo_argv(packages: &PackageSelection, phase: Phase, options: &Options) -> Vec<String> {
+pub fn cargo_argv(packages: &PackageSelection, phase: Phase, options: &Options) -> Vec<String> {
     let mut cargo_args = vec![cargo_bin()];
     match phase {
         Phase::Test => match &options.test_tool {
@@ -172,7 +194,7 @@ fn cargo_argv(packages: &PackageSelection, phase: Phase, options: &Options) -> V
 ///
 /// See <https://doc.rust-lang.org/cargo/reference/environment-variables.html>
 /// <https://doc.rust-lang.org/rustc/lints/levels.html#capping-lints>
-fn encoded_rustflags(options: &Options) -> Option<String> {
+pub fn encoded_rustflags(options: &Options) -> Option<String> {
     let cap_lints_arg = "--cap-lints=warn";
     let separator = "\x1f";
     if !options.cap_lints {
@@ -228,6 +250,15 @@ mod test {
         );
     }
 
+    #[test]
+    fn shared_target_dir_sets_cargo_build_dir() {
+        let options = Options::from_arg_strs(["mutants", "--shared-target-dir", "/tmp/shared"]);
+        let env = cargo_env(&options);
+        assert!(env.contains(&("CARGO_BUILD_BUILD_DIR".to_owned(), "/tmp/shared".to_owned())));
+        let env = cargo_env(&Options::default());
+        assert!(!env.iter().any(|(name, _)| name == "CARGO_BUILD_BUILD_DIR"));
+    }
+
     #[test]
     fn generate_cargo_args_with_additional_cargo_test_args_and_package() {
         let mut options = Options::default();
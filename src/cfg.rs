@@ -0,0 +1,351 @@
+// Copyright 2025 Martin Pool
+
+//! Evaluate `#[cfg(...)]` attributes, so that no mutants are generated in code that
+//! won't be compiled with the features and target used for the tests.
+//!
+//! Mutants in inactive code would always be unviable or missed. However, skipping
+//! code that actually is compiled would silently reduce coverage, so every predicate
+//! that can't be evaluated with confidence is treated as unknown, and the code under
+//! it is mutated as before. Only code that is certainly inactive is skipped.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::env;
+use std::process::Command;
+use std::sync::Arc;
+
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, ExprLit, Lit, Meta, Token};
+use tracing::{debug, warn};
+
+use crate::package::Package;
+use crate::Options;
+
+/// cfg names that, if the target is known, are false unless rustc says they're set.
+const KNOWN_NAMES: &[&str] = &["unix", "windows", "miri"];
+
+/// The cfgs set by rustc for the target, from `rustc --print cfg`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TargetCfgs {
+    /// Names like `unix`.
+    names: HashSet<String>,
+    /// Key-value pairs like `target_os = "linux"`.
+    values: HashSet<(String, String)>,
+}
+
+impl TargetCfgs {
+    /// Find the cfgs for the host target, or None if they're unknown.
+    ///
+    /// If a different target is selected by extra arguments to cargo, the cfgs for it
+    /// aren't known.
+    pub fn for_options(options: &Options) -> Option<Arc<TargetCfgs>> {
+        if all_cargo_args(options).any(|arg| arg.starts_with("--target")) {
+            debug!("Target is set by cargo args, so target cfgs are unknown");
+            return None;
+        }
+        let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_owned());
+        match Command::new(&rustc).args(["--print", "cfg"]).output() {
+            Ok(output) if output.status.success() => Some(Arc::new(TargetCfgs::parse(
+                &String::from_utf8_lossy(&output.stdout),
+            ))),
+            Ok(output) => {
+                warn!(
+                    "{rustc} --print cfg failed, so code for other targets will be mutated: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                None
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to run {rustc} --print cfg, so code for other targets will be mutated: {err}"
+                );
+                None
+            }
+        }
+    }
+
+    /// Parse the output of `rustc --print cfg`.
+    fn parse(text: &str) -> TargetCfgs {
+        let mut cfgs = TargetCfgs::default();
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            if let Some((key, value)) = line.split_once('=') {
+                cfgs.values
+                    .insert((key.to_owned(), value.trim_matches('"').to_owned()));
+            } else {
+                cfgs.names.insert(line.to_owned());
+            }
+        }
+        cfgs
+    }
+}
+
+/// The cfgs that are known to be set or not while building one package.
+#[derive(Debug, Default, Clone)]
+pub struct CfgContext {
+    /// The target cfgs, if known.
+    target: Option<Arc<TargetCfgs>>,
+    /// The enabled features of the package, if known.
+    features: Option<BTreeSet<String>>,
+}
+
+impl CfgContext {
+    pub fn new(target: Option<Arc<TargetCfgs>>, package: &Package, options: &Options) -> Self {
+        CfgContext {
+            target,
+            features: enabled_features(package, options),
+        }
+    }
+
+    /// True if any of these attributes is a `#[cfg(...)]` that's certainly false.
+    pub fn attrs_inactive(&self, attrs: &[Attribute]) -> bool {
+        attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+            .any(|attr| {
+                attr.parse_args::<Meta>()
+                    .is_ok_and(|meta| self.eval(&meta) == Some(false))
+            })
+    }
+
+    /// Evaluate a cfg predicate, returning None if its value is unknown.
+    fn eval(&self, meta: &Meta) -> Option<bool> {
+        match meta {
+            Meta::Path(path) => {
+                let name = path.get_ident()?.to_string();
+                if name == "test" || name == "debug_assertions" {
+                    // These depend on the profile and on how the code is built, not the target.
+                    return None;
+                }
+                let target = self.target.as_ref()?;
+                if target.names.contains(&name) {
+                    Some(true)
+                } else if KNOWN_NAMES.contains(&name.as_str()) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            Meta::NameValue(name_value) => {
+                let key = name_value.path.get_ident()?.to_string();
+                let Expr::Lit(ExprLit {
+                    lit: Lit::Str(value),
+                    ..
+                }) = &name_value.value
+                else {
+                    return None;
+                };
+                let value = value.value();
+                if key == "feature" {
+                    Some(self.features.as_ref()?.contains(&value))
+                } else if (key.starts_with("target_") && key != "target_feature") || key == "panic"
+                {
+                    // Target features can be turned on by RUSTFLAGS, so they're unknown.
+                    Some(self.target.as_ref()?.values.contains(&(key, value)))
+                } else {
+                    None
+                }
+            }
+            Meta::List(list) => {
+                let operator = list.path.get_ident()?.to_string();
+                let args = list
+                    .parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+                    .ok()?;
+                let values: Vec<Option<bool>> = args.iter().map(|arg| self.eval(arg)).collect();
+                // A false operand makes `all` false even if others are unknown, and
+                // likewise a true operand makes `any` true.
+                match (operator.as_str(), values.as_slice()) {
+                    ("not", [value]) => value.map(|v| !v),
+                    ("all", _) if values.contains(&Some(false)) => Some(false),
+                    ("any", _) if values.contains(&Some(true)) => Some(true),
+                    ("all" | "any", _) if values.contains(&None) => None,
+                    ("all", _) => Some(true),
+                    ("any", _) => Some(false),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Iterate all the extra arguments passed to cargo.
+fn all_cargo_args(options: &Options) -> impl Iterator<Item = &String> {
+    options
+        .additional_cargo_args
+        .iter()
+        .chain(&options.additional_cargo_test_args)
+}
+
+/// Find the features that will be enabled for a package, or None if they're unknown.
+///
+/// This includes the features turned on by other packages in the workspace that
+/// depend on it, because they might be unified into the same build.
+fn enabled_features(package: &Package, options: &Options) -> Option<BTreeSet<String>> {
+    if all_cargo_args(options).any(|arg| {
+        arg.starts_with("--features") || arg.starts_with("-F") || arg == "--all-features"
+    }) {
+        debug!("Features are set by cargo args, so feature cfgs are unknown");
+        return None;
+    }
+    if options.features.all_features {
+        return Some(package.features.keys().cloned().collect());
+    }
+    let mut requested: Vec<String> = package.requested_features.iter().cloned().collect();
+    if !options.features.no_default_features {
+        requested.push("default".to_owned());
+    }
+    for feature in options
+        .features
+        .features
+        .iter()
+        .flat_map(|f| f.split([',', ' ']))
+        .filter(|f| !f.is_empty())
+    {
+        match feature.split_once('/') {
+            Some((package_name, feature)) if package_name == package.name => {
+                requested.push(feature.to_owned());
+            }
+            Some(_) => (),
+            None => requested.push(feature.to_owned()),
+        }
+    }
+    Some(feature_closure(&package.features, requested))
+}
+
+/// Find all the features turned on, directly or indirectly, by some requested features.
+fn feature_closure(
+    table: &BTreeMap<String, Vec<String>>,
+    requested: Vec<String>,
+) -> BTreeSet<String> {
+    let mut enabled = BTreeSet::new();
+    let mut queue = requested;
+    while let Some(feature) = queue.pop() {
+        let Some(implied) = table.get(&feature) else {
+            // Not a feature of this package: perhaps "default" when there are no
+            // default features.
+            continue;
+        };
+        if !enabled.insert(feature) {
+            continue;
+        }
+        for item in implied {
+            // `dep:x` enables an optional dependency, but not a feature. `x/y` and `x?/y`
+            // enable features in dependencies, and the first also enables dependency `x`,
+            // which might have an implicit feature of the same name.
+            if item.starts_with("dep:") {
+                continue;
+            }
+            match item.split_once('/') {
+                Some((dep, _)) if !dep.ends_with('?') => queue.push(dep.to_owned()),
+                Some(_) => (),
+                None => queue.push(item.clone()),
+            }
+        }
+    }
+    enabled
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn context(features: &[&str]) -> CfgContext {
+        CfgContext {
+            target: Some(Arc::new(TargetCfgs::parse(indoc! { r#"
+                debug_assertions
+                panic="unwind"
+                target_arch="x86_64"
+                target_family="unix"
+                target_feature="sse2"
+                target_os="linux"
+                unix
+            "# }))),
+            features: Some(features.iter().map(ToString::to_string).collect()),
+        }
+    }
+
+    #[test]
+    fn evaluate_predicates() {
+        let c = context(&["std"]);
+        assert_eq!(c.eval(&parse_quote!(unix)), Some(true));
+        assert_eq!(c.eval(&parse_quote!(windows)), Some(false));
+        assert_eq!(c.eval(&parse_quote!(target_os = "linux")), Some(true));
+        assert_eq!(c.eval(&parse_quote!(target_os = "macos")), Some(false));
+        assert_eq!(c.eval(&parse_quote!(feature = "std")), Some(true));
+        assert_eq!(c.eval(&parse_quote!(feature = "serde")), Some(false));
+        assert_eq!(c.eval(&parse_quote!(not(feature = "std"))), Some(false));
+        assert_eq!(
+            c.eval(&parse_quote!(all(unix, feature = "std"))),
+            Some(true)
+        );
+        assert_eq!(
+            c.eval(&parse_quote!(any(windows, feature = "serde"))),
+            Some(false)
+        );
+        assert_eq!(c.eval(&parse_quote!(any(windows, unix))), Some(true));
+    }
+
+    #[test]
+    fn unknown_predicates() {
+        let c = context(&[]);
+        assert_eq!(c.eval(&parse_quote!(debug_assertions)), None);
+        assert_eq!(c.eval(&parse_quote!(test)), None);
+        assert_eq!(c.eval(&parse_quote!(tokio_unstable)), None);
+        assert_eq!(c.eval(&parse_quote!(target_feature = "avx2")), None);
+        assert_eq!(c.eval(&parse_quote!(not(tokio_unstable))), None);
+        assert_eq!(c.eval(&parse_quote!(all(unix, tokio_unstable))), None);
+        assert_eq!(
+            c.eval(&parse_quote!(all(windows, tokio_unstable))),
+            Some(false),
+            "any false operand makes all() false"
+        );
+        assert_eq!(c.eval(&parse_quote!(any(unix, tokio_unstable))), Some(true));
+        assert_eq!(c.eval(&parse_quote!(any(windows, tokio_unstable))), None);
+
+        let unknown = CfgContext::default();
+        assert_eq!(unknown.eval(&parse_quote!(windows)), None);
+        assert_eq!(unknown.eval(&parse_quote!(feature = "std")), None);
+    }
+
+    #[test]
+    fn inactive_attrs() {
+        let c = context(&[]);
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[inline]), parse_quote!(#[cfg(windows)])];
+        assert!(c.attrs_inactive(&attrs));
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[cfg(any(unix, windows))])];
+        assert!(!c.attrs_inactive(&attrs));
+        assert!(!CfgContext::default().attrs_inactive(&[parse_quote!(#[cfg(windows)])]));
+    }
+
+    #[test]
+    fn closure_of_features() {
+        let table: BTreeMap<String, Vec<String>> = [
+            ("default", &["std"][..]),
+            ("std", &["alloc", "serde?/std"]),
+            ("alloc", &[]),
+            ("json", &["dep:serde_json", "serde/derive"]),
+            ("serde", &["dep:serde"]),
+            ("unused", &[]),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_owned(), v.iter().map(ToString::to_string).collect()))
+        .collect();
+        assert_eq!(
+            feature_closure(&table, vec!["default".to_owned()]),
+            ["alloc", "default", "std"]
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect()
+        );
+        assert_eq!(
+            feature_closure(&table, vec!["json".to_owned(), "nonexistent".to_owned()]),
+            ["json", "serde"]
+                .into_iter()
+                .map(ToOwned::to_owned)
+                .collect()
+        );
+    }
+}
@@ -8,91 +8,702 @@
 //!
 //! The config file is then merged in to the [Options].
 
+use std::collections::BTreeMap;
 use std::default::Default;
+use std::fmt;
 use std::fs::read_to_string;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 
-use anyhow::Context;
+use anyhow::{bail, ensure, Context};
 use camino::{Utf8Path, Utf8PathBuf};
-use serde::Deserialize;
+use serde::de::value::{MapAccessDeserializer, SeqAccessDeserializer};
+use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
 
-use crate::options::TestTool;
-use crate::Result;
+use crate::hooks::Hooks;
+use crate::options::{ScoreAs, TestTool, Visibility};
+use crate::package::Package;
+use crate::{Genre, Result};
 
 /// Configuration read from a config file.
 ///
 /// This is similar to [Options], and eventually merged into it, but separate because it
 /// can be deserialized.
-#[derive(Debug, Default, Clone, Deserialize)]
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     /// Pass `--cap-lints` to rustc.
     pub cap_lints: bool,
+    /// Check all mutants before building and testing the ones that pass.
+    pub check_first: bool,
     /// Copy `.git` and other VCS directories to the build directory.
     pub copy_vcs: Option<bool>,
+    /// Don't test mutants that build to the same test binaries as the unmutated tree.
+    pub detect_equivalent: bool,
+    /// Expand macros with rustc, and mutate the code generated by derives.
+    pub expand_macros: bool,
     /// Generate these error values from functions returning Result.
     pub error_values: Vec<String>,
+    /// Generate these error values, by error type, instead of `error_values`.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub error_values_by_type: BTreeMap<String, String>,
+    /// Generate mutants of these genres, which are off by default.
+    pub enable_genres: Vec<Genre>,
+    /// Don't generate mutants of these genres.
+    pub exclude_genres: Vec<Genre>,
+    /// Generate only mutants of these genres, if this is a list; or, if it's a table,
+    /// settings for each genre.
+    pub genres: GenresConfig,
+    /// Test a random fraction of the mutants of each genre, between 0 and 1.
+    pub genre_sample_ratio: Option<f64>,
+    /// Test at most this many mutants of each genre.
+    pub max_mutants_per_genre: Option<usize>,
+    /// Mutate only functions with at least this cyclomatic complexity.
+    pub min_complexity: Option<usize>,
+    /// Mutate only functions with at most this cyclomatic complexity.
+    pub max_complexity: Option<usize>,
+    /// Mutate only functions with at least this many lines.
+    pub min_function_lines: Option<usize>,
+    /// Mutate only functions with at most this many lines.
+    pub max_function_lines: Option<usize>,
     /// Generate mutants from source files matching these globs.
     pub examine_globs: Vec<String>,
     /// Exclude mutants from source files matching these globs.
     pub exclude_globs: Vec<String>,
+    /// Exclude mutants from packages whose names match these globs.
+    ///
+    /// This is combined with values from the --exclude-package argument.
+    pub exclude_packages: Vec<String>,
     /// Exclude mutants from source files matches these regexps.
     pub exclude_re: Vec<String>,
     /// Examine only mutants matching these regexps.
     pub examine_re: Vec<String>,
+    /// Examine only mutants in functions whose return type matches these regexps.
+    pub return_type: Vec<String>,
     /// Pass extra args to every cargo invocation.
     pub additional_cargo_args: Vec<String>,
     /// Pass extra args to cargo test.
     pub additional_cargo_test_args: Vec<String>,
+    /// Set these environment variables in cargo build and test subprocesses.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Append a summary of each run to this file, for `cargo mutants history`.
+    pub history_file: Option<Utf8PathBuf>,
+    /// Shell commands to run before and after each scenario, and the whole run.
+    #[serde(skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    /// Reuse outcomes of unchanged mutants from earlier runs.
+    pub incremental: bool,
+    /// Fail with a distinct exit code if the mutation score is below this percentage.
+    pub minimum_score: Option<f64>,
     /// Minimum test timeout, in seconds, as a floor on the autoset value.
     pub minimum_test_timeout: Option<f64>,
+    /// Show a desktop notification when the run finishes.
+    pub notify: bool,
+    /// Post a JSON summary to this URL when the run finishes.
+    pub notify_webhook: Option<String>,
     /// Output directory.
     pub output: Option<Utf8PathBuf>,
+    /// Run these plugin programs to generate additional mutants.
+    pub plugins: Vec<String>,
+    /// Check batches of mutants before testing them, to discard unviable mutants.
+    pub prescreen: bool,
     /// Cargo profile.
+    ///
+    /// This can also be set as `cargo_profile`, which is needed if the file also
+    /// has named profiles in `[profile.NAME]` tables.
+    #[serde(alias = "cargo_profile", rename(serialize = "cargo_profile"))]
     pub profile: Option<String>,
+    /// Command that runs cargo-mutants on SSH worker hosts.
+    pub remote_command: Option<String>,
+    /// Directory on SSH worker hosts to copy the tree into.
+    pub remote_dir: Option<String>,
+    /// Build with the sccache compiler cache.
+    pub sccache: bool,
+    /// Build the mutants of each file together, selected at runtime.
+    pub schemata: bool,
+    /// Make the build directories in this directory, if it has room.
+    pub scratch_dir: Option<Utf8PathBuf>,
+    /// Share cargo's intermediate build files between build directories, in this
+    /// directory.
+    pub shared_target_dir: Option<Utf8PathBuf>,
+    /// Seed for the random number generator used for shuffling and sampling.
+    pub seed: Option<u64>,
+    /// Hosts to test mutants on over SSH, as well as locally.
+    ///
+    /// This is combined with values from the --ssh-worker argument.
+    pub ssh_workers: Vec<String>,
+    /// Skip functions and other items carrying these attributes.
+    ///
+    /// This is combined with values from the --skip-attr argument.
+    pub skip_attrs: Vec<String>,
     /// Skip calls to functions or methods with these names.
     ///
     /// This is combined with values from the --skip-calls argument.
     pub skip_calls: Vec<String>,
     /// Use built-in defaults for `skip_calls` in addition to any explicit values.
     pub skip_calls_defaults: Option<bool>,
+    /// Run only the tests that cover each mutant, found with cargo-llvm-cov.
+    pub test_impact: bool,
     /// Run tests from these packages for all mutants.
     pub test_package: Vec<String>,
     /// Choice of test tool: cargo or nextest.
     pub test_tool: Option<TestTool>,
+    /// Timeout for each `cargo test`, in seconds, instead of a multiple of the baseline.
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub test_timeout: Option<f64>,
     /// Timeout multiplier, relative to the baseline 'cargo test'.
+    #[serde(alias = "test_timeout_multiplier")]
     pub timeout_multiplier: Option<f64>,
+    /// How to count mutants that timed out in the score.
+    pub score_timeouts: Option<ScoreAs>,
+    /// How to count unviable mutants in the score.
+    pub score_unviable: Option<ScoreAs>,
+    /// Generate mutants only in items with this visibility.
+    pub visibility: Option<Visibility>,
+    /// Timeout for each build, in seconds, instead of a multiple of the baseline.
+    #[serde(deserialize_with = "deserialize_secs")]
+    pub build_timeout: Option<f64>,
+    /// Timeouts for mutants in source files matching globs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub timeout_overrides: Vec<TimeoutOverrideConfig>,
     /// Build timeout multiplier, relative to the baseline 'cargo build'.
     pub build_timeout_multiplier: Option<f64>,
     /// Run tests from all packages in the workspace, not just the mutated package.
     ///
     /// Overrides `test_package`.
     pub test_workspace: Option<bool>,
+    /// The name of the `[profile.NAME]` table that was applied to this config, if any.
+    #[serde(skip)]
+    pub named_profile: Option<String>,
+}
+
+/// Timeouts for mutants in some source files, from a `[[timeout_overrides]]` table.
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TimeoutOverrideConfig {
+    /// A glob matching source files, interpreted like `examine_globs`.
+    pub path: String,
+    /// Timeout for `cargo test`, in seconds.
+    #[serde(default, deserialize_with = "deserialize_secs")]
+    pub test_timeout: Option<f64>,
+    /// Timeout for the build, in seconds.
+    #[serde(default, deserialize_with = "deserialize_secs")]
+    pub build_timeout: Option<f64>,
+}
+
+/// Deserialize a duration in seconds, given either as a number or as a string like
+/// `"10m"` or `"90s"`.
+fn deserialize_secs<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<f64>, D::Error> {
+    struct SecsVisitor;
+
+    impl Visitor<'_> for SecsVisitor {
+        type Value = Option<f64>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a number of seconds or a duration like \"10m\"")
+        }
+
+        fn visit_f64<E: de::Error>(self, secs: f64) -> Result<Self::Value, E> {
+            Ok(Some(secs))
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn visit_i64<E: de::Error>(self, secs: i64) -> Result<Self::Value, E> {
+            Ok(Some(secs as f64))
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn visit_u64<E: de::Error>(self, secs: u64) -> Result<Self::Value, E> {
+            Ok(Some(secs as f64))
+        }
+
+        fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+            humantime::parse_duration(s)
+                .map(|duration| Some(duration.as_secs_f64()))
+                .map_err(|err| E::custom(format!("invalid duration {s:?}: {err}")))
+        }
+    }
+
+    deserializer.deserialize_any(SecsVisitor)
+}
+
+/// The `genres` config key.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum GenresConfig {
+    /// Generate only mutants of these genres, if non-empty.
+    List(Vec<Genre>),
+    /// Settings for each genre, from a `[genres]` table.
+    Table(BTreeMap<Genre, GenreConfig>),
+}
+
+impl Default for GenresConfig {
+    fn default() -> Self {
+        GenresConfig::List(Vec::new())
+    }
+}
+
+impl GenresConfig {
+    /// The genres to generate exclusively, if this is a list.
+    pub fn list(&self) -> &[Genre] {
+        match self {
+            GenresConfig::List(genres) => genres,
+            GenresConfig::Table(_) => &[],
+        }
+    }
+
+    /// The settings for one genre, if this is a table that has them.
+    pub fn settings(&self, genre: Genre) -> Option<&GenreConfig> {
+        match self {
+            GenresConfig::List(_) => None,
+            GenresConfig::Table(table) => table.get(&genre),
+        }
+    }
+
+    /// The genres turned on, or off, in the table.
+    pub fn switched(&self, enabled: bool) -> Vec<Genre> {
+        match self {
+            GenresConfig::List(_) => Vec::new(),
+            GenresConfig::Table(table) => table
+                .iter()
+                .filter(|(_, config)| config.enabled == Some(enabled))
+                .map(|(genre, _)| *genre)
+                .collect(),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for GenresConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct GenresVisitor;
+
+        impl<'de> Visitor<'de> for GenresVisitor {
+            type Value = GenresConfig;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a list of genres or a table of genre settings")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+                Deserialize::deserialize(SeqAccessDeserializer::new(seq)).map(GenresConfig::List)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let table: BTreeMap<Genre, BoolOrGenreConfig> =
+                    Deserialize::deserialize(MapAccessDeserializer::new(map))?;
+                Ok(GenresConfig::Table(
+                    table
+                        .into_iter()
+                        .map(|(genre, BoolOrGenreConfig(config))| (genre, config))
+                        .collect(),
+                ))
+            }
+        }
+
+        deserializer.deserialize_any(GenresVisitor)
+    }
+}
+
+/// Settings for one genre in the `[genres]` table.
+///
+/// This can be written as just a bool, like `NumericLiteral = true`, to turn the genre
+/// on or off.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GenreConfig {
+    /// Generate mutants of this genre, even if it's off by default; or, if false, don't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// For `NumericLiteral`: add and subtract each of these amounts, instead of 1.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deltas: Vec<u64>,
+}
+
+/// A [`GenreConfig`] that can also be written as just a bool.
+struct BoolOrGenreConfig(GenreConfig);
+
+impl<'de> Deserialize<'de> for BoolOrGenreConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct GenreConfigVisitor;
+
+        impl<'de> Visitor<'de> for GenreConfigVisitor {
+            type Value = BoolOrGenreConfig;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("true, false, or a table of genre settings")
+            }
+
+            fn visit_bool<E: de::Error>(self, enabled: bool) -> Result<Self::Value, E> {
+                Ok(BoolOrGenreConfig(GenreConfig {
+                    enabled: Some(enabled),
+                    ..GenreConfig::default()
+                }))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                Deserialize::deserialize(MapAccessDeserializer::new(map)).map(BoolOrGenreConfig)
+            }
+        }
+
+        deserializer.deserialize_any(GenreConfigVisitor)
+    }
 }
 
 impl Config {
-    pub fn read_file(path: &Path) -> Result<Config> {
-        let toml = read_to_string(path).with_context(|| format!("read config {path:?}"))?;
-        Config::from_str(&toml).with_context(|| format!("parse toml from {path:?}"))
+    /// Read a config file, applying the named profile if the file defines it.
+    ///
+    /// If the file `extends` another config file, it's merged over that file.
+    #[allow(clippy::unnecessary_debug_formatting)] // quote paths, which may have spaces
+    pub fn read_file(path: &Path, profile: Option<&str>) -> Result<Config> {
+        let table = read_table(path)?;
+        Config::from_table(table, profile).with_context(|| format!("parse toml from {path:?}"))
     }
 
     /// Read the config from a tree's `.cargo/mutants.toml`, and return a default (empty)
     /// Config is the file does not exist.
-    pub fn read_tree_config(workspace_dir: &Utf8Path) -> Result<Config> {
+    pub fn read_tree_config(workspace_dir: &Utf8Path, profile: Option<&str>) -> Result<Config> {
         let path = workspace_dir.join(".cargo").join("mutants.toml");
         if path.exists() {
-            Config::read_file(path.as_ref())
+            Config::read_file(path.as_ref(), profile)
         } else {
             Ok(Config::default())
         }
     }
+
+    /// Build a config from a toml table, first applying the named profile if the table
+    /// defines it.
+    fn from_table(mut table: toml::Table, profile: Option<&str>) -> Result<Config> {
+        let applied = apply_named_profile(&mut table, profile)?;
+        let mut config: Config = toml::Value::Table(table).try_into()?;
+        if applied {
+            config.named_profile = profile.map(ToOwned::to_owned);
+        }
+        Ok(config)
+    }
+
+    /// Read the `.cargo/mutants.toml` of each workspace member that has one, other than
+    /// a package in the workspace root, and merge it over the workspace's own config.
+    ///
+    /// Lists in the package config are appended to those from the workspace config,
+    /// and other values replace the workspace's values.
+    ///
+    /// Returns the merged config for each package that has its own config file.
+    pub fn read_package_configs(
+        workspace_dir: &Utf8Path,
+        packages: &[Arc<Package>],
+        profile: Option<&str>,
+    ) -> Result<Vec<(String, Config)>> {
+        let mut workspace_table = read_tree_table(workspace_dir)?.unwrap_or_default();
+        let workspace_applied = apply_named_profile(&mut workspace_table, profile)?;
+        let mut configs = Vec::new();
+        for package in packages {
+            if package.relative_dir.as_str().is_empty() {
+                continue;
+            }
+            let package_dir = workspace_dir.join(&package.relative_dir);
+            let Some(mut package_table) = read_tree_table(&package_dir)? else {
+                continue;
+            };
+            let package_applied = apply_named_profile(&mut package_table, profile)?;
+            let mut table = workspace_table.clone();
+            merge_tables(&mut table, package_table);
+            let mut config: Config = toml::Value::Table(table)
+                .try_into()
+                .with_context(|| format!("parse config for package {}", package.name))?;
+            if workspace_applied || package_applied {
+                config.named_profile = profile.map(ToOwned::to_owned);
+            }
+            debug!(package = package.name, ?config, "read package config");
+            configs.push((package.name.clone(), config));
+        }
+        Ok(configs)
+    }
+}
+
+/// Read `.cargo/mutants.toml` in a directory as a toml table, if it exists.
+fn read_tree_table(dir: &Utf8Path) -> Result<Option<toml::Table>> {
+    let path = dir.join(".cargo").join("mutants.toml");
+    if !path.is_file() {
+        return Ok(None);
+    }
+    read_table(path.as_std_path()).map(Some)
+}
+
+/// Read a config file as a toml table, merged over the files it `extends`, if any.
+fn read_table(path: &Path) -> Result<toml::Table> {
+    read_table_extending(path, &mut Vec::new())
+}
+
+/// Read a config file and the files it extends, where `chain` is the files that
+/// (directly or indirectly) extend this one.
+#[allow(clippy::unnecessary_debug_formatting)] // quote paths, which may have spaces
+fn read_table_extending(path: &Path, chain: &mut Vec<PathBuf>) -> Result<toml::Table> {
+    let toml = read_to_string(path).with_context(|| format!("read config {path:?}"))?;
+    let mut table: toml::Table = toml
+        .parse()
+        .with_context(|| format!("parse toml from {path:?}"))?;
+    let Some(base_path) = extends_path(path, &table)? else {
+        return Ok(table);
+    };
+    table.remove("extends");
+    let canonical = path
+        .canonicalize()
+        .with_context(|| format!("find config {}", path.display()))?;
+    ensure!(
+        !chain.contains(&canonical),
+        "Config files extend each other in a cycle through {}",
+        path.display()
+    );
+    chain.push(canonical);
+    let mut base = read_table_extending(&base_path, chain)
+        .with_context(|| format!("read config extended by {}", path.display()))?;
+    merge_tables(&mut base, table);
+    Ok(base)
+}
+
+/// Return the path of the config file named by `extends` in the config file at `path`,
+/// if there is one.
+///
+/// Relative paths are relative to the directory containing the config file.
+pub fn extends_path(path: &Path, table: &toml::Table) -> Result<Option<PathBuf>> {
+    match table.get("extends") {
+        None => Ok(None),
+        Some(toml::Value::String(extends)) => {
+            Ok(Some(path.parent().unwrap_or(Path::new(".")).join(extends)))
+        }
+        Some(_) => bail!(
+            "`extends` in {} should be the path of a config file",
+            path.display()
+        ),
+    }
+}
+
+/// Apply a named profile from the `[profile.NAME]` tables in a config, replacing the
+/// values at the top level, and then remove the named profiles.
+///
+/// Returns true if the profile was found.
+fn apply_named_profile(table: &mut toml::Table, profile: Option<&str>) -> Result<bool> {
+    let profiles = match table.remove("profile") {
+        Some(toml::Value::Table(profiles)) => profiles,
+        Some(cargo_profile) => {
+            // Just the name of a cargo profile.
+            table.insert("profile".to_owned(), cargo_profile);
+            return Ok(false);
+        }
+        None => return Ok(false),
+    };
+    let Some(name) = profile else {
+        return Ok(false);
+    };
+    match profiles.get(name) {
+        Some(toml::Value::Table(overrides)) => {
+            for (key, value) in overrides {
+                table.insert(key.clone(), value.clone());
+            }
+            Ok(true)
+        }
+        Some(_) => bail!("profile.{name} should be a table"),
+        None => Ok(false),
+    }
+}
+
+/// Merge `overlay` into `base`, appending arrays, merging tables, and replacing other
+/// values.
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Array(base_array)), toml::Value::Array(array)) => {
+                base_array.extend(array);
+            }
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(table)) => {
+                merge_tables(base_table, table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
 }
 
 impl FromStr for Config {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        toml::de::from_str(s).with_context(|| "parse toml")
+        let table = s.parse().with_context(|| "parse toml")?;
+        Config::from_table(table, None).with_context(|| "parse toml")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn merge_appends_arrays_and_replaces_other_values() {
+        let mut base: toml::Table = toml::from_str(
+            "exclude_re = [\"a\"]\ntimeout_multiplier = 2.0\nadditional_cargo_test_args = [\"--all-targets\"]\n",
+        )
+        .unwrap();
+        let overlay: toml::Table =
+            toml::from_str("exclude_re = [\"b\"]\ntimeout_multiplier = 3.0\ncheck_first = true\n")
+                .unwrap();
+        merge_tables(&mut base, overlay);
+        let config: Config = toml::Value::Table(base).try_into().unwrap();
+        assert_eq!(config.exclude_re, ["a", "b"]);
+        assert_eq!(config.timeout_multiplier, Some(3.0));
+        assert_eq!(config.additional_cargo_test_args, ["--all-targets"]);
+        assert!(config.check_first);
+    }
+
+    #[test]
+    fn named_profile_replaces_values() {
+        let toml = indoc! { r#"
+            timeout_multiplier = 2.0
+            exclude_re = ["a"]
+            cargo_profile = "mutants"
+
+            [profile.quick]
+            exclude_re = ["b"]
+            genres = ["FnValue"]
+
+            [profile.nightly]
+            timeout_multiplier = 10.0
+        "#};
+        let table: toml::Table = toml.parse().unwrap();
+
+        let config = Config::from_table(table.clone(), Some("quick")).unwrap();
+        assert_eq!(config.named_profile.as_deref(), Some("quick"));
+        assert_eq!(config.exclude_re, ["b"]);
+        assert_eq!(config.genres.list(), [Genre::FnValue]);
+        assert_eq!(config.timeout_multiplier, Some(2.0));
+        assert_eq!(config.profile.as_deref(), Some("mutants"));
+
+        let config = Config::from_table(table.clone(), Some("release")).unwrap();
+        assert_eq!(config.named_profile, None);
+        assert_eq!(config.exclude_re, ["a"]);
+
+        let config = Config::from_table(table, None).unwrap();
+        assert_eq!(config.named_profile, None);
+        assert_eq!(config.timeout_multiplier, Some(2.0));
+    }
+
+    #[test]
+    fn profile_key_can_still_name_a_cargo_profile() {
+        let table: toml::Table = r#"profile = "mutants""#.parse().unwrap();
+        let config = Config::from_table(table, Some("quick")).unwrap();
+        assert_eq!(config.named_profile, None);
+        assert_eq!(config.profile.as_deref(), Some("mutants"));
+    }
+
+    #[test]
+    fn genres_table_with_bools_and_settings() {
+        let config = Config::from_str(indoc! { r"
+            [genres]
+            FnValue = false
+            StringLiteral = true
+
+            [genres.NumericLiteral]
+            enabled = true
+            deltas = [1, 10]
+        "})
+        .unwrap();
+        assert_eq!(config.genres.list(), []);
+        assert_eq!(
+            config.genres.switched(true),
+            [Genre::NumericLiteral, Genre::StringLiteral]
+        );
+        assert_eq!(config.genres.switched(false), [Genre::FnValue]);
+        assert_eq!(
+            config.genres.settings(Genre::NumericLiteral),
+            Some(&GenreConfig {
+                enabled: Some(true),
+                deltas: vec![1, 10],
+            })
+        );
+        assert_eq!(config.genres.settings(Genre::BinaryOperator), None);
+    }
+
+    #[test]
+    fn genres_list_is_still_accepted() {
+        let config = Config::from_str(r#"genres = ["FnValue", "BinaryOperator"]"#).unwrap();
+        assert_eq!(
+            config.genres.list(),
+            [Genre::FnValue, Genre::BinaryOperator]
+        );
+        assert_eq!(config.genres.settings(Genre::FnValue), None);
+    }
+
+    #[test]
+    fn unknown_genre_setting_is_rejected() {
+        let err = Config::from_str("[genres.NumericLiteral]\ndelta = [2]\n").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("unknown field `delta`"),
+            "{err:#}"
+        );
+        let err = Config::from_str("genres = 3").unwrap_err();
+        assert!(
+            format!("{err:#}").contains("a list of genres or a table of genre settings"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn extends_merges_over_base_config() {
+        let tmp = tempfile::tempdir().unwrap();
+        let shared = tmp.path().join("shared");
+        let tree = tmp.path().join("tree");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::create_dir(&tree).unwrap();
+        std::fs::write(
+            shared.join("base.toml"),
+            "exclude_re = [\"a\"]\ntimeout_multiplier = 2.0\ncheck_first = true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            shared.join("policy.toml"),
+            "extends = \"base.toml\"\nexclude_re = [\"b\"]\n",
+        )
+        .unwrap();
+        let path = tree.join("mutants.toml");
+        std::fs::write(
+            &path,
+            "extends = \"../shared/policy.toml\"\nexclude_re = [\"c\"]\ntimeout_multiplier = 3.0\n",
+        )
+        .unwrap();
+        let config = Config::read_file(&path, None).unwrap();
+        assert_eq!(config.exclude_re, ["a", "b", "c"]);
+        assert_eq!(config.timeout_multiplier, Some(3.0));
+        assert!(config.check_first);
+    }
+
+    #[test]
+    fn extends_cycle_is_an_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::write(tmp.path().join("a.toml"), "extends = \"b.toml\"\n").unwrap();
+        std::fs::write(tmp.path().join("b.toml"), "extends = \"a.toml\"\n").unwrap();
+        let err = Config::read_file(&tmp.path().join("a.toml"), None).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("Config files extend each other in a cycle"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn extends_must_be_a_string() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("mutants.toml");
+        std::fs::write(&path, "extends = [\"a.toml\"]\n").unwrap();
+        let err = Config::read_file(&path, None).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("should be the path of a config file"),
+            "{err:#}"
+        );
     }
 }
@@ -0,0 +1,250 @@
+// Copyright 2025 Martin Pool
+
+//! `cargo mutants config check`: find mistakes in config files, and show the effective
+//! config.
+//!
+//! Reading the config for a normal run stops at the first unknown key. This instead
+//! reports every unknown or deprecated key in every config file in the workspace, then
+//! checks that the values can be used, and prints the config after merging and
+//! applying any `--profile`.
+
+#![warn(clippy::pedantic)]
+
+use std::fs::read_to_string;
+
+use anyhow::ensure;
+use camino::Utf8PathBuf;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+use tracing::{error, info, warn};
+
+use crate::config::{extends_path, Config};
+use crate::options::Options;
+use crate::workspace::Workspace;
+use crate::{Args, Context, Result};
+
+/// Check all the config files in a workspace, and print the effective config.
+///
+/// Returns an error if any config file has unknown keys or invalid values.
+pub fn check_config(args: &Args, workspace: &Workspace) -> Result<()> {
+    let profile = args.profile.as_deref();
+    let mut n_unknown = 0;
+    let mut paths = config_paths(workspace);
+    let mut i = 0;
+    while let Some(path) = paths.get(i).cloned() {
+        i += 1;
+        let toml = read_to_string(&path).with_context(|| format!("read config {path}"))?;
+        let table: toml::Table = toml
+            .parse()
+            .with_context(|| format!("parse toml from {path}"))?;
+        let problems = check_table(&table);
+        for key in &problems.unknown_keys {
+            error!("{path}: unknown key {key:?}");
+        }
+        for message in &problems.deprecated {
+            warn!("{path}: {message}");
+        }
+        if problems == ConfigProblems::default() {
+            info!("{path}: ok");
+        }
+        n_unknown += problems.unknown_keys.len();
+        if let Some(base_path) = extends_path(path.as_std_path(), &table)? {
+            let base_path = Utf8PathBuf::try_from(base_path)?
+                .canonicalize_utf8()
+                .with_context(|| format!("find config extended by {path}"))?;
+            if !paths.contains(&base_path) {
+                paths.push(base_path);
+            }
+        }
+    }
+    ensure!(
+        n_unknown == 0,
+        "Found {n_unknown} unknown keys in config files"
+    );
+
+    let config = Config::read_tree_config(workspace.root(), profile)?;
+    check_values(args, &config).context("Invalid values in workspace config")?;
+    print!("{}", to_toml(&config)?);
+    for (package_name, package_config) in
+        Config::read_package_configs(workspace.root(), workspace.packages(), profile)?
+    {
+        check_values(args, &package_config)
+            .with_context(|| format!("Invalid values in config for package {package_name}"))?;
+        print!(
+            "\n# Config for package {package_name}\n{}",
+            to_toml(&package_config)?
+        );
+    }
+    Ok(())
+}
+
+/// Paths of the config files in the workspace root and in each package that has one.
+///
+/// Files that they extend are not included.
+fn config_paths(workspace: &Workspace) -> Vec<Utf8PathBuf> {
+    let root = workspace.root();
+    let mut dirs = vec![root.to_owned()];
+    dirs.extend(
+        workspace
+            .packages()
+            .iter()
+            .filter(|package| !package.relative_dir.as_str().is_empty())
+            .map(|package| root.join(&package.relative_dir)),
+    );
+    dirs.into_iter()
+        .map(|dir| dir.join(".cargo").join("mutants.toml"))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Check that values like regexes and error values in the config can be used.
+fn check_values(args: &Args, config: &Config) -> Result<()> {
+    Options::new(args, config)?.parsed_error_values()?;
+    Ok(())
+}
+
+fn to_toml(config: &Config) -> Result<String> {
+    toml::to_string(config).context("format config as toml")
+}
+
+/// Problems found in the keys of one config file.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ConfigProblems {
+    /// Keys that aren't understood, like `profile.quick.timeout`.
+    unknown_keys: Vec<String>,
+    /// Descriptions of deprecated settings.
+    deprecated: Vec<String>,
+}
+
+/// Check the keys of a config file, including those in named profiles.
+fn check_table(table: &toml::Table) -> ConfigProblems {
+    let mut problems = ConfigProblems::default();
+    let mut table = table.clone();
+    // `extends` is resolved before the config is deserialized, and can only be at the top level.
+    table.remove("extends");
+    check_keys(&table, "", &mut problems);
+    if let Some(toml::Value::Table(profiles)) = table.get("profile") {
+        for (name, profile) in profiles {
+            if let toml::Value::Table(profile) = profile {
+                check_keys(profile, &format!("profile.{name}."), &mut problems);
+            }
+        }
+    }
+    problems
+}
+
+fn check_keys(table: &toml::Table, prefix: &str, problems: &mut ConfigProblems) {
+    let known = config_keys();
+    for (key, value) in table {
+        if !known.contains(&key.as_str()) {
+            problems.unknown_keys.push(format!("{prefix}{key}"));
+        } else if key == "profile" && value.is_str() {
+            problems.deprecated.push(format!(
+                "`{prefix}profile` naming a cargo profile is deprecated: use `{prefix}cargo_profile` instead"
+            ));
+        }
+    }
+}
+
+/// Return the keys that can be set in a config file, taken from the definition of
+/// [Config].
+fn config_keys() -> &'static [&'static str] {
+    let mut fields: &'static [&'static str] = &[];
+    // This always fails after collecting the field names.
+    let _ = Config::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// A deserializer that just records the field names of the struct being deserialized.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("expected a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("only collecting field names"))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+        identifier ignored_any
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn config_keys_include_all_fields() {
+        let keys = config_keys();
+        assert!(keys.contains(&"exclude_re"));
+        assert!(keys.contains(&"test_workspace"));
+        assert!(keys.contains(&"profile"));
+        assert!(keys.contains(&"cargo_profile"));
+        assert!(!keys.contains(&"named_profile"));
+    }
+
+    #[test]
+    fn report_all_unknown_keys() {
+        let table: toml::Table = indoc! { r#"
+            exclude_re = ["a"]
+            exclude_regex = ["b"]
+            timeout = 20
+
+            [env]
+            ANY_NAME = "is fine"
+
+            [profile.quick]
+            genres = ["FnValue"]
+            genre = ["FnValue"]
+        "# }
+        .parse()
+        .unwrap();
+        assert_eq!(
+            check_table(&table),
+            ConfigProblems {
+                unknown_keys: vec![
+                    "exclude_regex".to_owned(),
+                    "timeout".to_owned(),
+                    "profile.quick.genre".to_owned(),
+                ],
+                deprecated: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn string_profile_is_deprecated() {
+        let table: toml::Table = r#"profile = "mutants""#.parse().unwrap();
+        let problems = check_table(&table);
+        assert!(problems.unknown_keys.is_empty());
+        assert_eq!(
+            problems.deprecated,
+            ["`profile` naming a cargo profile is deprecated: use `cargo_profile` instead"]
+        );
+        let table: toml::Table = indoc! { r#"
+            extends = "base.toml"
+            cargo_profile = "mutants"
+
+            [profile.quick]
+            cap_lints = true
+        "# }
+        .parse()
+        .unwrap();
+        assert_eq!(check_table(&table), ConfigProblems::default());
+    }
+}
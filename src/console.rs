@@ -3,6 +3,7 @@
 //! Print messages and progress bars on the terminal.
 
 use std::borrow::Cow;
+use std::fmt::Write as _;
 use std::fs::File;
 use std::io;
 use std::sync::{Arc, Mutex};
@@ -12,16 +13,25 @@ use camino::{Utf8Path, Utf8PathBuf};
 use console::{style, StyledObject};
 use humantime::format_duration;
 use nutmeg::Destination;
-use tracing::Level;
+use serde_json::json;
+use tracing::{warn, Level};
 use tracing_subscriber::fmt::MakeWriter;
 use tracing_subscriber::prelude::*;
 
-use crate::options::Colors;
+use crate::events::EventLog;
+use crate::github::{missed_mutant_annotation, step_summary, write_step_summary};
+use crate::options::{Colors, OutputFormat, ProgressStyle};
 use crate::outcome::{LabOutcome, ScenarioOutcome, SummaryOutcome};
 use crate::scenario::Scenario;
+use crate::scores::Scores;
 use crate::tail_file::TailFile;
+use crate::timings::Estimate;
 use crate::{Mutant, Options, Phase};
 
+mod tui;
+
+use tui::{Stream, Tui, TuiModel};
+
 /// An interface to the console for the rest of cargo-mutants.
 ///
 /// This wraps the Nutmeg view and model.
@@ -31,6 +41,26 @@ pub struct Console {
 
     /// The `mutants.out/debug.log` file, if it's open yet.
     debug_log: Arc<Mutex<Option<File>>>,
+
+    /// The `--events-file`, if one was requested.
+    event_log: Mutex<Option<EventLog>>,
+
+    /// The full-screen interface, while it's shown.
+    tui: Mutex<Option<Tui>>,
+
+    /// Progress reported as lines rather than drawn as a bar, for `--progress-style`.
+    progress_lines: Mutex<Option<ProgressLines>>,
+}
+
+/// How often to report progress as lines.
+const PROGRESS_LINE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Progress reported as occasional lines of text or JSON, rather than an animated bar.
+struct ProgressLines {
+    /// When the counts were last reported.
+    last_report: Option<Instant>,
+    /// Events written to stderr, for `--progress-style=json`, or None for plain text.
+    events: Option<EventLog>,
 }
 
 impl Console {
@@ -38,6 +68,9 @@ impl Console {
         Console {
             view: Arc::new(nutmeg::View::new(LabModel::default(), nutmeg_options())),
             debug_log: Arc::new(Mutex::new(None)),
+            event_log: Mutex::new(None),
+            tui: Mutex::new(None),
+            progress_lines: Mutex::new(None),
         }
     }
 
@@ -46,14 +79,31 @@ impl Console {
     }
 
     /// Update that a cargo task is starting.
-    pub fn scenario_started(&self, dir: &Utf8Path, scenario: &Scenario, log_file: File) {
+    pub fn scenario_started(
+        &self,
+        dir: &Utf8Path,
+        scenario: &Scenario,
+        log_file: File,
+        options: &Options,
+    ) {
+        self.event(
+            "scenario_started",
+            json!({ "name": scenario.to_string(), "scenario": scenario, "build_dir": dir }),
+        );
         let start = Instant::now();
-        let scenario_model = ScenarioModel::new(dir, scenario, start, log_file);
+        let scenario_model =
+            ScenarioModel::new(dir, scenario, start, log_file, options.show_output_lines);
         self.view.update(|model| {
             model.scenario_models.push(scenario_model);
         });
     }
 
+    /// Update that a scenario has stopped running in this directory, but is not yet
+    /// finished, and will be resumed later.
+    pub fn scenario_deferred(&self, dir: &Utf8Path) {
+        self.view.update(|model| model.remove_scenario(dir));
+    }
+
     /// Update that cargo finished.
     pub fn scenario_finished(
         &self,
@@ -62,19 +112,30 @@ impl Console {
         outcome: &ScenarioOutcome,
         options: &Options,
     ) {
+        self.event(
+            "scenario_finished",
+            json!({ "name": scenario.to_string(), "outcome": outcome, "build_dir": dir }),
+        );
         self.view.update(|model| {
-            if scenario.is_mutant() {
+            if let Scenario::Mutant(mutant) = scenario {
                 model.mutants_done += 1;
+                if let Some(estimate) = &mut model.estimate {
+                    estimate.mutant_finished(mutant);
+                }
             }
             match outcome.summary() {
                 SummaryOutcome::CaughtMutant => model.mutants_caught += 1,
                 SummaryOutcome::MissedMutant => model.mutants_missed += 1,
                 SummaryOutcome::Timeout => model.timeouts += 1,
                 SummaryOutcome::Unviable => model.unviable += 1,
+                SummaryOutcome::Equivalent => model.equivalent += 1,
                 SummaryOutcome::Success => model.successes += 1,
                 SummaryOutcome::Failure => model.failures += 1,
             }
             model.remove_scenario(dir);
+            if let Some(tui_model) = &mut model.tui {
+                tui_model.outcomes.push(outcome.clone());
+            }
         });
 
         if (outcome.mutant_caught() && !options.print_caught)
@@ -105,6 +166,14 @@ impl Console {
             s.push_str(" in ");
             s.push_str(&prs.join(" + "));
         }
+        if options.output_format == OutputFormat::Github
+            && outcome.summary() == SummaryOutcome::MissedMutant
+        {
+            if let Some(mutant) = scenario.mutant() {
+                s.push('\n');
+                s.push_str(&missed_mutant_annotation(mutant));
+            }
+        }
         if outcome.should_show_logs() || options.show_all_logs {
             s.push('\n');
             s.push_str(
@@ -116,6 +185,7 @@ impl Console {
         }
         s.push('\n');
         self.message(&s);
+        self.report_progress();
     }
 
     pub fn start_copy(&self, dir: &Utf8Path) {
@@ -153,16 +223,20 @@ impl Console {
             plural(mutants.len(), "mutant")
         ));
         let n_mutants = mutants.len();
+        self.event("testing_started", json!({ "total_mutants": n_mutants }));
         self.view.update(|model| {
             model.n_mutants = n_mutants;
             model.lab_start_time = Some(Instant::now());
         });
     }
 
-    /// Update that work is starting on testing a given number of mutants.
-    pub fn start_testing_mutants(&self, _n_mutants: usize) {
-        self.view
-            .update(|model| model.mutants_start_time = Some(Instant::now()));
+    /// Update that work is starting on testing the mutants, with an estimate of how long
+    /// they'll take if there are timings from previous runs.
+    pub fn start_testing_mutants(&self, estimate: Option<Estimate>) {
+        self.view.update(|model| {
+            model.mutants_start_time = Some(Instant::now());
+            model.estimate = estimate;
+        });
     }
 
     /// A new phase of this scenario started.
@@ -182,10 +256,29 @@ impl Console {
         self.view.update(|model| {
             model.scenario_models.clear();
         });
-        self.message(&format!(
-            "{}\n",
-            lab_outcome.summary_string(start_time, options)
-        ));
+        self.event(
+            "testing_finished",
+            json!({
+                "total_mutants": lab_outcome.total_mutants,
+                "missed": lab_outcome.missed,
+                "caught": lab_outcome.caught,
+                "timeout": lab_outcome.timeout,
+                "unviable": lab_outcome.unviable,
+                "success": lab_outcome.success,
+                "equivalent": lab_outcome.equivalent,
+                "uncovered": lab_outcome.uncovered,
+            }),
+        );
+        let summary_line = lab_outcome.summary_string(start_time, options);
+        self.message(&format!("{summary_line}\n"));
+        if options.progress_style != ProgressStyle::Quiet {
+            self.message(&Scores::new(lab_outcome).lowest_summary());
+        }
+        if options.output_format == OutputFormat::Github {
+            if let Err(err) = write_step_summary(&step_summary(lab_outcome, &summary_line)) {
+                warn!("Failed to write GitHub job summary: {err:#}");
+            }
+        }
     }
 
     pub fn clear(&self) {
@@ -193,6 +286,12 @@ impl Console {
     }
 
     pub fn message(&self, message: &str) {
+        if self
+            .view
+            .inspect_model(|model| model.hold_output(Stream::Stdout, message))
+        {
+            return;
+        }
         // A workaround for nutmeg not being able to coordinate writes to both stdout and
         // stderr...
         // <https://github.com/sourcefrog/nutmeg/issues/11>
@@ -200,8 +299,105 @@ impl Console {
         print!("{message}");
     }
 
+    /// Show the full-screen interface until the returned guard is dropped.
+    ///
+    /// While it's shown, messages are held back, and they're printed when it closes. If
+    /// the interface can't be shown, for example because stderr isn't a terminal, this
+    /// warns and progress is shown as usual.
+    pub fn start_tui(&self) -> TuiGuard<'_> {
+        if ::console::Term::stderr().is_term() {
+            self.view.suspend();
+            self.view
+                .update(|model| model.tui = Some(TuiModel::default()));
+            match Tui::start(&self.view) {
+                Ok(tui) => *self.tui.lock().unwrap() = Some(tui),
+                Err(err) => {
+                    self.stop_tui();
+                    warn!("Failed to start the TUI: {err}");
+                }
+            }
+        } else {
+            warn!("--tui needs a terminal on stderr; showing progress as usual");
+        }
+        TuiGuard { console: self }
+    }
+
+    /// Close the full-screen interface, if it's shown, and print the messages that were
+    /// held back while it was open.
+    fn stop_tui(&self) {
+        if let Some(tui) = self.tui.lock().unwrap().take() {
+            tui.stop();
+        }
+        let Some(tui_model) = self.view.update(|model| model.tui.take()) else {
+            return;
+        };
+        self.view.resume();
+        for (stream, message) in tui_model.held_output {
+            match stream {
+                Stream::Stdout => self.message(&message),
+                Stream::Stderr => self.view.message(message),
+            }
+        }
+    }
+
+    /// Stop drawing the progress bar for the rest of the run, for `--quiet-progress`.
+    ///
+    /// Messages are still printed.
+    pub fn hide_progress(&self) {
+        self.view.suspend();
+    }
+
+    /// Show progress in this style for the rest of the run.
+    pub fn set_progress_style(&self, progress_style: ProgressStyle) {
+        let events = match progress_style {
+            ProgressStyle::Fancy => return,
+            ProgressStyle::Quiet => {
+                self.hide_progress();
+                return;
+            }
+            ProgressStyle::Plain => None,
+            ProgressStyle::Json => Some(EventLog::stderr()),
+        };
+        self.view.suspend();
+        *self.progress_lines.lock().unwrap() = Some(ProgressLines {
+            last_report: None,
+            events,
+        });
+    }
+
     pub fn tick(&self) {
         self.view.update(|_| ());
+        self.report_progress();
+    }
+
+    /// If progress is reported as lines, and it's been a while since the last report,
+    /// report the counts so far.
+    fn report_progress(&self) {
+        let mut progress_lines = self.progress_lines.lock().unwrap();
+        let Some(progress_lines) = progress_lines.as_mut() else {
+            return;
+        };
+        if progress_lines
+            .last_report
+            .is_some_and(|last| last.elapsed() < PROGRESS_LINE_INTERVAL)
+        {
+            return;
+        }
+        if let Some(events) = &mut progress_lines.events {
+            let Some(fields) = self.view.inspect_model(|model| model.progress_fields()) else {
+                return;
+            };
+            if let Err(err) = events.emit("progress", fields) {
+                warn!("Failed to write progress event: {err:#}");
+            }
+        } else {
+            let Some(line) = self.view.inspect_model(|model| model.status_line()) else {
+                return;
+            };
+            self.view
+                .message(format!("{}\n", ::console::strip_ansi_codes(&line)));
+        }
+        progress_lines.last_report = Some(Instant::now());
     }
 
     /// Return a tracing `MakeWriter` that will send messages via nutmeg to the console.
@@ -222,6 +418,33 @@ impl Console {
         *self.debug_log.lock().unwrap() = Some(file);
     }
 
+    /// Start writing events to a file.
+    pub fn set_event_log(&self, event_log: EventLog) {
+        *self.event_log.lock().unwrap() = Some(event_log);
+    }
+
+    /// Write an event to the event log, if there is one.
+    ///
+    /// If the event can't be written, a warning is logged and no more events are written.
+    fn event(&self, event: &str, fields: serde_json::Value) {
+        if let Some(ProgressLines {
+            events: Some(events),
+            ..
+        }) = self.progress_lines.lock().unwrap().as_mut()
+        {
+            if let Err(err) = events.emit(event, fields.clone()) {
+                warn!("Failed to write progress event: {err:#}");
+            }
+        }
+        let mut event_log = self.event_log.lock().unwrap();
+        if let Some(log) = event_log.as_mut() {
+            if let Err(err) = log.emit(event, fields) {
+                warn!("Failed to write to events file: {err:#}");
+                *event_log = None;
+            }
+        }
+    }
+
     /// Configure tracing to send messages to the console and debug log.
     ///
     /// The debug log is opened later and provided by [`Console::set_debug_log`].
@@ -251,6 +474,18 @@ impl Console {
     }
 }
 
+/// Closes the full-screen interface when dropped, including when testing stops early
+/// because of an error or panic.
+pub struct TuiGuard<'a> {
+    console: &'a Console,
+}
+
+impl Drop for TuiGuard<'_> {
+    fn drop(&mut self) {
+        self.console.stop_tui();
+    }
+}
+
 /// An interface for tree-walking code to publish progress.
 pub(crate) struct WalkProgress {
     view: Arc<nutmeg::View<LabModel>>,
@@ -314,10 +549,16 @@ impl MakeWriter<'_> for TerminalWriter {
 
 impl std::io::Write for TerminalWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        // This calls `message` rather than `View::write` because the latter
-        // only requires a &View and it handles locking internally, without
-        // requiring exclusive use of the Arc<View>.
-        self.view.message(std::str::from_utf8(buf).unwrap());
+        let message = std::str::from_utf8(buf).unwrap();
+        if !self
+            .view
+            .inspect_model(|model| model.hold_output(Stream::Stderr, message))
+        {
+            // This calls `message` rather than `View::write` because the latter
+            // only requires a &View and it handles locking internally, without
+            // requiring exclusive use of the Arc<View>.
+            self.view.message(message);
+        }
         Ok(buf.len())
     }
 
@@ -373,13 +614,17 @@ struct LabModel {
     mutants_caught: usize,
     mutants_missed: usize,
     unviable: usize,
+    equivalent: usize,
     timeouts: usize,
     successes: usize,
     failures: usize,
+    /// State for the full-screen interface, while it's shown.
+    tui: Option<TuiModel>,
+    /// An estimate of the time to test the mutants, from previous runs' timings.
+    estimate: Option<Estimate>,
 }
 
 impl nutmeg::Model for LabModel {
-    #[allow(clippy::cast_precision_loss)]
     fn render(&mut self, width: usize) -> String {
         let mut s = String::with_capacity(1024);
         if let Some(walk_tree) = &mut self.walk_tree {
@@ -397,64 +642,118 @@ impl nutmeg::Model for LabModel {
             }
             s.push_str(&sm.render(width));
         }
-        if let Some(lab_start_time) = self.lab_start_time {
+        if let Some(status_line) = self.status_line() {
             if !s.is_empty() {
                 s.push('\n');
             }
-            let elapsed = lab_start_time.elapsed();
-            s += &format!(
-                "{}/{} mutants tested",
-                style(self.mutants_done).cyan(),
-                style(self.n_mutants).cyan(),
-            );
-            if self.mutants_missed > 0 {
-                s += &format!(
-                    ", {} {}",
-                    style(self.mutants_missed).cyan(),
-                    style("MISSED").red()
-                );
-            }
-            if self.timeouts > 0 {
-                s += &format!(
-                    ", {} {}",
-                    style(self.timeouts).cyan(),
-                    style("timeout").red()
-                );
-            }
-            if self.mutants_caught > 0 {
-                s.push_str(&format!(", {} caught", style(self.mutants_caught).cyan()));
-            }
-            if self.unviable > 0 {
-                s.push_str(&format!(", {} unviable", style(self.unviable).cyan()));
-            }
-            // Maybe don't report these, because they're uninteresting?
-            // if self.successes > 0 {
-            //     write!(s, ", {} successes", self.successes).unwrap();
-            // }
-            // if self.failures > 0 {
-            //     write!(s, ", {} failures", self.failures).unwrap();
-            // }
-            s.push_str(&format!(", {} elapsed", style_duration(elapsed)));
-            if self.mutants_done > 2 {
-                let done = self.mutants_done as f64;
-                let remain = self.n_mutants as f64 - done;
-                let mut remaining_secs =
-                    self.mutants_start_time.unwrap().elapsed().as_secs_f64() * remain / done;
-                if remaining_secs > 300.0 {
-                    // Round up to minutes
-                    remaining_secs = ((remaining_secs + 30.0) / 60.0).ceil() * 60.0;
-                }
-                s += &format!(
-                    ", about {} remaining",
-                    style_duration(Duration::from_secs_f64(remaining_secs.ceil()))
-                );
-            }
+            s.push_str(&status_line);
         }
         s
     }
 }
 
 impl LabModel {
+    /// Describe the counts so far, once testing has started.
+    fn status_line(&self) -> Option<String> {
+        let lab_start_time = self.lab_start_time?;
+        let mut s = String::new();
+        let elapsed = lab_start_time.elapsed();
+        write!(
+            s,
+            "{}/{} mutants tested",
+            style(self.mutants_done).cyan(),
+            style(self.n_mutants).cyan(),
+        )
+        .unwrap();
+        if self.mutants_missed > 0 {
+            write!(
+                s,
+                ", {} {}",
+                style(self.mutants_missed).cyan(),
+                style("MISSED").red()
+            )
+            .unwrap();
+        }
+        if self.timeouts > 0 {
+            write!(
+                s,
+                ", {} {}",
+                style(self.timeouts).cyan(),
+                style("timeout").red()
+            )
+            .unwrap();
+        }
+        if self.mutants_caught > 0 {
+            write!(s, ", {} caught", style(self.mutants_caught).cyan()).unwrap();
+        }
+        if self.unviable > 0 {
+            write!(s, ", {} unviable", style(self.unviable).cyan()).unwrap();
+        }
+        if self.equivalent > 0 {
+            write!(s, ", {} equivalent", style(self.equivalent).cyan()).unwrap();
+        }
+        // Maybe don't report these, because they're uninteresting?
+        // if self.successes > 0 {
+        //     write!(s, ", {} successes", self.successes).unwrap();
+        // }
+        // if self.failures > 0 {
+        //     write!(s, ", {} failures", self.failures).unwrap();
+        // }
+        write!(s, ", {} elapsed", style_duration(elapsed)).unwrap();
+        if let Some(remaining) = self.remaining() {
+            write!(s, ", about {} remaining", style_duration(remaining)).unwrap();
+        }
+        Some(s)
+    }
+
+    /// The counts so far as JSON fields, once testing has started.
+    fn progress_fields(&self) -> Option<serde_json::Value> {
+        let elapsed = self.lab_start_time?.elapsed();
+        Some(json!({
+            "mutants_done": self.mutants_done,
+            "total_mutants": self.n_mutants,
+            "missed": self.mutants_missed,
+            "caught": self.mutants_caught,
+            "timeout": self.timeouts,
+            "unviable": self.unviable,
+            "equivalent": self.equivalent,
+            "elapsed_secs": elapsed.as_secs_f64(),
+            "remaining_secs": self.remaining().map(|remaining| remaining.as_secs_f64()),
+        }))
+    }
+
+    /// If the full-screen interface is shown, keep a message to print after it closes,
+    /// and return true.
+    fn hold_output(&mut self, stream: Stream, message: &str) -> bool {
+        if let Some(tui_model) = &mut self.tui {
+            tui_model.held_output.push((stream, message.to_owned()));
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Estimate the time to test the remaining mutants, from the timings of previous runs
+    /// if there are any, and otherwise once a few have been tested.
+    #[allow(clippy::cast_precision_loss)]
+    fn remaining(&self) -> Option<Duration> {
+        let elapsed_secs = self.mutants_start_time?.elapsed().as_secs_f64();
+        let mut remaining_secs = if let Some(estimate) = &self.estimate {
+            estimate.remaining_secs(elapsed_secs)
+        } else if self.mutants_done > 2 {
+            let done = self.mutants_done as f64;
+            let remain = self.n_mutants as f64 - done;
+            elapsed_secs * remain / done
+        } else {
+            return None;
+        };
+        if remaining_secs > 300.0 {
+            // Round up to minutes
+            remaining_secs = ((remaining_secs + 30.0) / 60.0).ceil() * 60.0;
+        }
+        Some(Duration::from_secs_f64(remaining_secs.ceil()))
+    }
+
     fn find_scenario_mut(&mut self, dir: &Utf8Path) -> &mut ScenarioModel {
         self.scenario_models
             .iter_mut()
@@ -502,13 +801,19 @@ struct ScenarioModel {
 }
 
 impl ScenarioModel {
-    fn new(dir: &Utf8Path, scenario: &Scenario, start: Instant, log_file: File) -> ScenarioModel {
+    fn new(
+        dir: &Utf8Path,
+        scenario: &Scenario,
+        start: Instant,
+        log_file: File,
+        log_lines: usize,
+    ) -> ScenarioModel {
         ScenarioModel {
             dir: dir.to_owned(),
             name: style_scenario(scenario, true),
             phase: None,
             phase_start: start,
-            log_tail: TailFile::new(log_file),
+            log_tail: TailFile::new(log_file, log_lines),
             previous_phase_durations: Vec::new(),
         }
     }
@@ -532,9 +837,9 @@ impl nutmeg::Model for ScenarioModel {
         if let Some(phase) = self.phase {
             parts.push(style(format!("{phase:8}")).bold().cyan().to_string());
         }
-        parts.push(self.name.to_string());
+        parts.push(self.name.clone().into_owned());
         parts.push("...".to_string());
-        parts.push(style_secs(self.phase_start.elapsed()).to_string());
+        parts.push(style_secs(self.phase_start.elapsed()));
         // let mut prs = self
         //     .previous_phase_durations
         //     .iter()
@@ -545,12 +850,12 @@ impl nutmeg::Model for ScenarioModel {
         // }
         // parts.push(prs.join(" + "));
         let mut s = parts.join(" ");
-        if let Ok(last_line) = self.log_tail.last_line() {
-            s.push_str(&format!(
-                "\n{:8} {}",
-                style("└").cyan(),
-                style(last_line).dim()
-            ));
+        if let Ok(lines) = self.log_tail.last_lines() {
+            let lines = lines.collect::<Vec<_>>();
+            for (i, line) in lines.iter().enumerate() {
+                let tree = if i + 1 == lines.len() { "└" } else { "│" };
+                write!(s, "\n{:8} {}", style(tree).cyan(), style(line).dim()).unwrap();
+            }
         }
         s
     }
@@ -605,6 +910,7 @@ pub fn style_outcome(outcome: &ScenarioOutcome) -> StyledObject<&'static str> {
         SummaryOutcome::Failure => style("FAILED").red().bold(),
         SummaryOutcome::Success => style("ok").green(),
         SummaryOutcome::Unviable => style("unviable").blue(),
+        SummaryOutcome::Equivalent => style("equivalent").yellow(),
         SummaryOutcome::Timeout => style("TIMEOUT").red().bold(),
     }
 }
@@ -623,7 +929,7 @@ fn style_duration(duration: Duration) -> String {
         .to_string()
 }
 
-fn format_mb(bytes: u64) -> String {
+pub fn format_mb(bytes: u64) -> String {
     format!("{} MB", bytes / 1_000_000)
 }
 
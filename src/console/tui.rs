@@ -0,0 +1,557 @@
+// Copyright 2025 Martin Pool
+
+//! A full-screen terminal interface for `--tui`, showing each job in progress and the
+//! outcomes so far, in which the diff and log of any outcome can be viewed while the
+//! run continues.
+//!
+//! The interface is drawn from the same [`LabModel`] as the usual progress bars, by a
+//! thread that also reads keys. While it's shown, messages that would otherwise be
+//! printed are held in the model, and they're printed when it closes.
+
+use std::io::{self, stderr, Stderr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use ::console::strip_ansi_codes;
+use humantime::format_duration;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::crossterm::cursor::Show;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use super::{format_mb, style_outcome, style_scenario, LabModel};
+use crate::interrupt;
+use crate::outcome::{ScenarioOutcome, SummaryOutcome};
+
+/// How often to redraw, and to check for keys.
+const TICK: Duration = Duration::from_millis(100);
+
+/// The most lines of held messages to show at the bottom of the screen.
+const MAX_MESSAGE_LINES: usize = 4;
+
+/// Where a held message would have been printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// What the lab model keeps while the interface is shown.
+#[derive(Default)]
+pub(super) struct TuiModel {
+    /// Outcomes of the scenarios that finished since the interface opened, in order.
+    pub outcomes: Vec<ScenarioOutcome>,
+    /// Messages to print when the interface closes.
+    pub held_output: Vec<(Stream, String)>,
+}
+
+/// The thread drawing the interface.
+pub(super) struct Tui {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Tui {
+    /// Take over the terminal and start drawing the model.
+    pub(super) fn start(view: &Arc<nutmeg::View<LabModel>>) -> io::Result<Tui> {
+        enable_raw_mode()?;
+        let mut terminal = match execute!(stderr(), EnterAlternateScreen)
+            .and_then(|()| Terminal::new(CrosstermBackend::new(stderr())))
+        {
+            Ok(terminal) => terminal,
+            Err(err) => {
+                restore_terminal();
+                return Err(err);
+            }
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = thread::spawn({
+            let view = Arc::clone(view);
+            let stop = Arc::clone(&stop);
+            move || {
+                // If drawing fails, give the terminal back so that at least ctrl-c works.
+                let _ = run(&view, &stop, &mut terminal);
+                restore_terminal();
+            }
+        });
+        Ok(Tui { stop, thread })
+    }
+
+    /// Stop drawing, and give the terminal back.
+    pub(super) fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.thread.join();
+        restore_terminal();
+    }
+}
+
+fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(stderr(), LeaveAlternateScreen, Show);
+}
+
+fn run(
+    view: &nutmeg::View<LabModel>,
+    stop: &AtomicBool,
+    terminal: &mut Terminal<CrosstermBackend<Stderr>>,
+) -> io::Result<()> {
+    let mut ui = UiState::default();
+    while !stop.load(Ordering::Relaxed) {
+        view.inspect_model(|model| ui.draw_to(terminal, model))?;
+        if event::poll(TICK)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    view.inspect_model(|model| ui.key(key, model));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The state of the interface itself, apart from the model.
+#[derive(Default)]
+struct UiState {
+    /// The selected row of the visible outcomes, or `None` to follow the newest.
+    selected: Option<usize>,
+    /// Show only missed mutants and timeouts.
+    only_missed: bool,
+    /// The diff or log being viewed, if any.
+    viewer: Option<Viewer>,
+    /// True after the user asked to stop the run.
+    stopping: bool,
+    list_state: ListState,
+    /// The number of rows in the list or viewer when it was last drawn, for paging.
+    page_height: usize,
+}
+
+/// A diff or log being viewed.
+struct Viewer {
+    title: String,
+    lines: Vec<String>,
+    /// The first line shown.
+    scroll: usize,
+}
+
+impl UiState {
+    fn draw_to<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        model: &mut LabModel,
+    ) -> io::Result<()> {
+        terminal.draw(|frame| self.draw(frame, model)).map(|_| ())
+    }
+
+    fn visible<'a>(&self, model: &'a LabModel) -> Vec<&'a ScenarioOutcome> {
+        let Some(tui_model) = &model.tui else {
+            return Vec::new();
+        };
+        tui_model
+            .outcomes
+            .iter()
+            .filter(|outcome| {
+                !self.only_missed
+                    || matches!(
+                        outcome.summary(),
+                        SummaryOutcome::MissedMutant | SummaryOutcome::Timeout
+                    )
+            })
+            .collect()
+    }
+
+    /// The index of the selected outcome among the visible outcomes.
+    fn selected_index(&self, n_visible: usize) -> Option<usize> {
+        match self.selected {
+            Some(i) if i < n_visible => Some(i),
+            _ => n_visible.checked_sub(1),
+        }
+    }
+
+    fn key(&mut self, key: KeyEvent, model: &LabModel) {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            self.stop_run();
+        } else if let Some(viewer) = &mut self.viewer {
+            let last = viewer.lines.len().saturating_sub(self.page_height);
+            match key.code {
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('q') => self.viewer = None,
+                KeyCode::Up | KeyCode::Char('k') => viewer.scroll = viewer.scroll.saturating_sub(1),
+                KeyCode::Down | KeyCode::Char('j') => viewer.scroll = (viewer.scroll + 1).min(last),
+                KeyCode::PageUp => viewer.scroll = viewer.scroll.saturating_sub(self.page_height),
+                KeyCode::PageDown | KeyCode::Char(' ') => {
+                    viewer.scroll = (viewer.scroll + self.page_height).min(last);
+                }
+                KeyCode::Home | KeyCode::Char('g') => viewer.scroll = 0,
+                KeyCode::End | KeyCode::Char('G') => viewer.scroll = last,
+                _ => (),
+            }
+        } else {
+            let visible = self.visible(model);
+            let n = visible.len();
+            let current = self.selected_index(n).unwrap_or(0);
+            // Moving to the last row goes back to following the newest outcome.
+            let select = |i: usize| (i + 1 < n).then_some(i);
+            match key.code {
+                KeyCode::Char('q') => self.stop_run(),
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.selected = select(current.saturating_sub(1));
+                }
+                KeyCode::Down | KeyCode::Char('j') => self.selected = select(current + 1),
+                KeyCode::PageUp => self.selected = select(current.saturating_sub(self.page_height)),
+                KeyCode::PageDown => self.selected = select(current + self.page_height),
+                KeyCode::Home | KeyCode::Char('g') => self.selected = select(0),
+                KeyCode::End | KeyCode::Char('G') => self.selected = None,
+                KeyCode::Char('m') => {
+                    self.only_missed = !self.only_missed;
+                    self.selected = None;
+                }
+                KeyCode::Enter | KeyCode::Char('d' | 'l') if n > 0 => {
+                    let outcome = visible[current];
+                    let (what, content) =
+                        if key.code == KeyCode::Char('l') || !outcome.scenario.is_mutant() {
+                            ("log", outcome.get_log_content())
+                        } else {
+                            ("diff", outcome.get_diff_content())
+                        };
+                    let content = content.unwrap_or_else(|err| format!("{err:#}"));
+                    self.viewer = Some(Viewer {
+                        title: format!(
+                            "{what} of {}",
+                            strip_ansi_codes(&style_scenario(&outcome.scenario, true))
+                        ),
+                        lines: content.lines().map(ToOwned::to_owned).collect(),
+                        scroll: 0,
+                    });
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn stop_run(&mut self) {
+        interrupt::interrupt();
+        self.stopping = true;
+    }
+
+    fn draw(&mut self, frame: &mut Frame, model: &mut LabModel) {
+        let n_jobs = model.scenario_models.len() * 2 + model.copy_models.len();
+        let messages = model
+            .tui
+            .as_ref()
+            .map(|tui_model| message_lines(&tui_model.held_output))
+            .unwrap_or_default();
+        let messages_height = if messages.is_empty() {
+            0
+        } else {
+            messages.len() + 2
+        };
+        let [header_area, jobs_area, main_area, messages_area, footer_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(u16::try_from(n_jobs.max(1) + 2).unwrap_or(u16::MAX)),
+            Constraint::Min(3),
+            Constraint::Length(u16::try_from(messages_height).unwrap_or(u16::MAX)),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        self.page_height = usize::from(main_area.height.saturating_sub(2)).max(1);
+
+        frame.render_widget(Paragraph::new(progress_line(model)), header_area);
+        frame.render_widget(
+            Paragraph::new(job_lines(model)).block(Block::bordered().title(" Jobs ")),
+            jobs_area,
+        );
+        if let Some(viewer) = &self.viewer {
+            let lines: Vec<Line> = viewer
+                .lines
+                .iter()
+                .skip(viewer.scroll)
+                .map(|line| diff_line(line))
+                .collect();
+            frame.render_widget(
+                Paragraph::new(lines).block(Block::bordered().title(format!(" {} ", viewer.title))),
+                main_area,
+            );
+        } else {
+            let visible = self.visible(model);
+            let items: Vec<ListItem> = visible
+                .iter()
+                .map(|outcome| outcome_item(outcome))
+                .collect();
+            let title = if self.only_missed {
+                " Missed and timed out "
+            } else {
+                " Outcomes "
+            };
+            self.list_state.select(self.selected_index(visible.len()));
+            frame.render_stateful_widget(
+                List::new(items)
+                    .block(Block::bordered().title(title))
+                    .highlight_style(Style::new().add_modifier(Modifier::REVERSED)),
+                main_area,
+                &mut self.list_state,
+            );
+        }
+        if !messages.is_empty() {
+            frame.render_widget(
+                Paragraph::new(messages).block(Block::bordered().title(" Messages ")),
+                messages_area,
+            );
+        }
+        let help = if self.stopping {
+            "Stopping..."
+        } else if self.viewer.is_some() {
+            "↑↓ scroll  PgUp/PgDn page  Esc back  ^C stop"
+        } else {
+            "↑↓ select  Enter/d diff  l log  m missed only  q stop"
+        };
+        frame.render_widget(Paragraph::new(help.dim()), footer_area);
+    }
+}
+
+/// The overall progress, like the last line of the usual progress display.
+fn progress_line(model: &LabModel) -> Line<'static> {
+    let mut spans = vec![
+        Span::raw(model.mutants_done.to_string()).cyan(),
+        Span::raw("/"),
+        Span::raw(model.n_mutants.to_string()).cyan(),
+        Span::raw(" mutants tested"),
+    ];
+    for (count, label, style) in [
+        (model.mutants_missed, "MISSED", Style::new().red()),
+        (model.timeouts, "timeout", Style::new().red()),
+        (model.mutants_caught, "caught", Style::new()),
+        (model.unviable, "unviable", Style::new()),
+        (model.equivalent, "equivalent", Style::new()),
+    ] {
+        if count > 0 {
+            spans.push(Span::raw(", "));
+            spans.push(Span::raw(count.to_string()).cyan());
+            spans.push(Span::styled(format!(" {label}"), style));
+        }
+    }
+    if let Some(lab_start_time) = model.lab_start_time {
+        let elapsed = Duration::from_secs(lab_start_time.elapsed().as_secs());
+        spans.push(Span::raw(format!(", {} elapsed", format_duration(elapsed))));
+    }
+    if let Some(remaining) = model.remaining() {
+        spans.push(Span::raw(format!(
+            ", about {} remaining",
+            format_duration(remaining)
+        )));
+    }
+    Line::from(spans)
+}
+
+/// Two lines for each scenario in progress, showing its phase and the last line of its
+/// log, and one for each copy.
+fn job_lines(model: &mut LabModel) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    for copy_model in &model.copy_models {
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:8} ", "copy")).cyan(),
+            Span::raw(format!(
+                "{} in {:.1}s",
+                format_mb(copy_model.bytes_copied),
+                copy_model.start.elapsed().as_secs_f32()
+            )),
+        ]));
+    }
+    for sm in &mut model.scenario_models {
+        let phase = sm.phase.map(|phase| phase.to_string()).unwrap_or_default();
+        lines.push(Line::from(vec![
+            Span::raw(format!("{phase:8} ")).bold().cyan(),
+            Span::raw(strip_ansi_codes(&sm.name).into_owned()),
+            Span::raw(format!(" {:.1}s", sm.phase_start.elapsed().as_secs_f32())).cyan(),
+        ]));
+        let last_line = sm.log_tail.last_line().unwrap_or_default().to_owned();
+        lines.push(Line::from(vec![
+            Span::raw(format!("{:>8} ", "└")).cyan(),
+            Span::raw(last_line).dim(),
+        ]));
+    }
+    lines
+}
+
+fn outcome_item(outcome: &ScenarioOutcome) -> ListItem<'static> {
+    let color = match outcome.summary() {
+        SummaryOutcome::CaughtMutant | SummaryOutcome::Success => Color::Green,
+        SummaryOutcome::MissedMutant | SummaryOutcome::Timeout | SummaryOutcome::Failure => {
+            Color::Red
+        }
+        SummaryOutcome::Unviable => Color::Blue,
+        SummaryOutcome::Equivalent => Color::Yellow,
+    };
+    let duration: Duration = outcome.phase_results().iter().map(|pr| pr.duration).sum();
+    ListItem::new(Line::from(vec![
+        Span::styled(
+            format!("{:10} ", style_outcome(outcome).force_styling(false)),
+            Style::new().fg(color),
+        ),
+        Span::raw(strip_ansi_codes(&style_scenario(&outcome.scenario, true)).into_owned()),
+        Span::raw(format!(" {:.1}s", duration.as_secs_f32())).dim(),
+    ]))
+}
+
+/// Color a line of a diff; other text is shown as it is.
+fn diff_line(line: &str) -> Line<'static> {
+    let line = line.to_owned();
+    if line.starts_with("+++") || line.starts_with("---") {
+        Line::from(line).bold()
+    } else if line.starts_with('+') {
+        Line::from(line).green()
+    } else if line.starts_with('-') {
+        Line::from(line).red()
+    } else if line.starts_with("@@") {
+        Line::from(line).cyan()
+    } else {
+        Line::from(line)
+    }
+}
+
+/// The last few lines of warnings and other messages sent to stderr.
+fn message_lines(held_output: &[(Stream, String)]) -> Vec<Line<'static>> {
+    let mut lines: Vec<Line> = held_output
+        .iter()
+        .rev()
+        .filter(|(stream, _)| *stream == Stream::Stderr)
+        .flat_map(|(_, message)| message.lines().rev())
+        .take(MAX_MESSAGE_LINES)
+        .map(|line| Line::from(strip_ansi_codes(line).into_owned()))
+        .collect();
+    lines.reverse();
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Instant;
+
+    use camino::Utf8PathBuf;
+    use ratatui::backend::TestBackend;
+
+    use super::*;
+    use crate::console::ScenarioModel;
+    use crate::outcome::{Phase, PhaseResult};
+    use crate::output::OutputDir;
+    use crate::process::Exit;
+    use crate::visit::mutate_source_str;
+    use crate::{Options, Scenario};
+
+    /// Make a model with one missed and one caught mutant, and one job in progress.
+    fn model_with_outcomes(tmp: &Utf8PathBuf) -> LabModel {
+        let mutants = mutate_source_str(
+            "fn is_big(x: u32) -> bool {\n    x > 10\n}\n",
+            &Options::default(),
+        )
+        .unwrap();
+        let mut output_dir = OutputDir::new(tmp).unwrap();
+        let mut outcomes = Vec::new();
+        for (mutant, status) in mutants.iter().zip([Exit::Success, Exit::Failure(101)]) {
+            let scenario = Scenario::Mutant(mutant.clone());
+            let mut scenario_output = output_dir.start_scenario(&scenario).unwrap();
+            scenario_output
+                .write_diff("--- src/main.rs\n+++ replace\n-    x > 10\n+    true\n")
+                .unwrap();
+            let mut outcome = ScenarioOutcome::new(&scenario_output, scenario);
+            outcome.add_phase_result(PhaseResult {
+                phase: Phase::Test,
+                duration: Duration::from_millis(1500),
+                process_status: status,
+                argv: Vec::new(),
+            });
+            outcomes.push(outcome);
+        }
+        let scenario = Scenario::Mutant(mutants[2].clone());
+        let log_file = output_dir
+            .start_scenario(&scenario)
+            .unwrap()
+            .open_log_read()
+            .unwrap();
+        let mut job = ScenarioModel::new(tmp, &scenario, Instant::now(), log_file, 1);
+        job.phase_started(Phase::Build);
+        LabModel {
+            n_mutants: 5,
+            mutants_done: 2,
+            mutants_missed: 1,
+            mutants_caught: 1,
+            scenario_models: vec![job],
+            tui: Some(TuiModel {
+                outcomes,
+                held_output: vec![(Stream::Stderr, "WARN something odd\n".to_owned())],
+            }),
+            ..LabModel::default()
+        }
+    }
+
+    fn render(ui: &mut UiState, model: &mut LabModel) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        ui.draw_to(&mut terminal, model).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_owned()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draw_progress_jobs_and_outcomes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tmp = Utf8PathBuf::try_from(tmp.path().to_owned()).unwrap();
+        let mut model = model_with_outcomes(&tmp);
+        let screen = render(&mut UiState::default(), &mut model);
+        assert_eq!(screen[0], "2/5 mutants tested, 1 MISSED, 1 caught");
+        assert!(screen[2].starts_with("│build    src/main.rs:2:7: replace > with == in is_big "));
+        assert!(screen.iter().any(|line| line
+            == "│MISSED     src/main.rs:2:5: replace is_big -> bool with true 1.5s             │"));
+        assert!(screen
+            .iter()
+            .any(|line| line.contains("│WARN something odd")));
+        assert!(screen[19].starts_with("↑↓ select"));
+    }
+
+    #[test]
+    fn keys_select_filter_and_view_diff() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tmp = Utf8PathBuf::try_from(tmp.path().to_owned()).unwrap();
+        let mut model = model_with_outcomes(&tmp);
+        let mut ui = UiState::default();
+        let press = |ui: &mut UiState, model: &LabModel, code: KeyCode| {
+            ui.key(KeyEvent::new(code, KeyModifiers::NONE), model);
+        };
+        // The newest outcome is selected until the selection moves.
+        assert_eq!(ui.selected_index(2), Some(1));
+        press(&mut ui, &model, KeyCode::Up);
+        assert_eq!(ui.selected_index(2), Some(0));
+        press(&mut ui, &model, KeyCode::Down);
+        assert_eq!(ui.selected, None);
+
+        press(&mut ui, &model, KeyCode::Char('m'));
+        assert_eq!(ui.visible(&model).len(), 1);
+        press(&mut ui, &model, KeyCode::Char('d'));
+        let viewer = ui.viewer.as_ref().unwrap();
+        assert_eq!(
+            viewer.title,
+            "diff of src/main.rs:2:5: replace is_big -> bool with true"
+        );
+        assert_eq!(viewer.lines[2], "-    x > 10");
+        let screen = render(&mut ui, &mut model);
+        assert!(screen.iter().any(|line| line.starts_with("│+    true")));
+
+        press(&mut ui, &model, KeyCode::Esc);
+        assert!(ui.viewer.is_none());
+    }
+}
@@ -4,7 +4,7 @@
 
 use anyhow::Context;
 use camino::{Utf8Path, Utf8PathBuf};
-use ignore::WalkBuilder;
+use ignore::{Walk, WalkBuilder};
 use path_slash::PathExt;
 use tempfile::TempDir;
 use tracing::{debug, warn};
@@ -24,25 +24,8 @@ use windows::copy_symlink;
 
 static VCS_DIRS: &[&str] = &[".git", ".hg", ".bzr", ".svn", "_darcs", ".jj", ".pijul"];
 
-/// Copy a source tree, with some exclusions, to a new temporary directory.
-pub fn copy_tree(
-    from_path: &Utf8Path,
-    name_base: &str,
-    options: &Options,
-    console: &Console,
-) -> Result<TempDir> {
-    let mut total_bytes = 0;
-    let mut total_files = 0;
-    let temp_dir = tempfile::Builder::new()
-        .prefix(name_base)
-        .suffix(".tmp")
-        .tempdir()
-        .context("create temp dir")?;
-    let dest = temp_dir
-        .path()
-        .try_into()
-        .context("Convert path to UTF-8")?;
-    console.start_copy(dest);
+/// Walk the files in a source tree that should be copied, skipping the exclusions.
+pub(crate) fn walk_tree(from_path: &Utf8Path, options: &Options) -> Walk {
     let mut walk_builder = WalkBuilder::new(from_path);
     let copy_vcs = options.copy_vcs; // for lifetime
     walk_builder
@@ -59,7 +42,35 @@ pub fn copy_tree(
                 && (copy_vcs || !VCS_DIRS.contains(&name.as_ref()))
         });
     debug!(?walk_builder);
-    for entry in walk_builder.build() {
+    walk_builder.build()
+}
+
+/// Copy a source tree, with some exclusions, to a new temporary directory.
+///
+/// The directory is made in `temp_parent` if it's given, or otherwise in the system
+/// temporary directory.
+pub fn copy_tree(
+    from_path: &Utf8Path,
+    name_base: &str,
+    temp_parent: Option<&Utf8Path>,
+    options: &Options,
+    console: &Console,
+) -> Result<TempDir> {
+    let mut total_bytes = 0;
+    let mut total_files = 0;
+    let mut builder = tempfile::Builder::new();
+    builder.prefix(name_base).suffix(".tmp");
+    let temp_dir = match temp_parent {
+        Some(parent) => builder.tempdir_in(parent),
+        None => builder.tempdir(),
+    }
+    .context("create temp dir")?;
+    let dest = temp_dir
+        .path()
+        .try_into()
+        .context("Convert path to UTF-8")?;
+    console.start_copy(dest);
+    for entry in walk_tree(from_path, options) {
         check_interrupted()?;
         let entry = entry?;
         let relative_path = entry
@@ -71,6 +82,7 @@ pub fn copy_tree(
             .join(relative_path)
             .try_into()
             .context("Convert path to UTF-8")?;
+        #[allow(clippy::unnecessary_debug_formatting)]
         let ft = entry
             .file_type()
             .with_context(|| format!("Expected file to have a file type: {:?}", entry.path()))?;
@@ -135,7 +147,7 @@ mod test {
         write(src.join("main.rs"), "fn main() {}")?;
 
         let options = Options::from_arg_strs(["--gitignore=true"]);
-        let dest_tmpdir = copy_tree(&a, "a", &options, &Console::new())?;
+        let dest_tmpdir = copy_tree(&a, "a", None, &options, &Console::new())?;
         let dest = dest_tmpdir.path();
         assert!(dest.join("Cargo.toml").is_file());
         assert!(dest.join("src").is_dir());
@@ -158,7 +170,7 @@ mod test {
         write(tmp.join("foo"), "bar")?;
 
         let options = Options::from_arg_strs(["--gitignore=true"]);
-        let dest_tmpdir = copy_tree(&tmp, "a", &options, &Console::new())?;
+        let dest_tmpdir = copy_tree(&tmp, "a", None, &options, &Console::new())?;
         let dest = dest_tmpdir.path();
         assert!(
             dest.join("foo").is_file(),
@@ -183,7 +195,7 @@ mod test {
         write(tmp.join("foo"), "bar")?;
 
         let options = Options::from_arg_strs(["mutants", "--gitignore=true"]);
-        let dest_tmpdir = copy_tree(&tmp, "a", &options, &Console::new())?;
+        let dest_tmpdir = copy_tree(&tmp, "a", None, &options, &Console::new())?;
         let dest = dest_tmpdir.path();
         assert!(
             !dest.join("foo").is_file(),
@@ -208,7 +220,7 @@ mod test {
         write(tmp.join("foo"), "bar")?;
 
         let options = Options::from_arg_strs(["mutants", "--gitignore=false"]);
-        let dest_tmpdir = copy_tree(&tmp, "a", &options, &Console::new())?;
+        let dest_tmpdir = copy_tree(&tmp, "a", None, &options, &Console::new())?;
         let dest = dest_tmpdir.path();
         // gitignore didn't exclude `foo`
         assert!(dest.join("foo").is_file());
@@ -231,7 +243,7 @@ mod test {
         write(src.join("main.rs"), "fn main() {}")?;
 
         let options = Options::from_arg_strs(["mutants"]);
-        let dest_tmpdir = copy_tree(&tmp, "a", &options, &Console::new())?;
+        let dest_tmpdir = copy_tree(&tmp, "a", None, &options, &Console::new())?;
         let dest = dest_tmpdir.path();
         assert!(!dest.join(".git").is_dir(), ".git should not be copied");
         assert!(
@@ -265,7 +277,7 @@ mod test {
         write(src.join("main.rs"), "fn main() {}")?;
 
         let options = Options::from_arg_strs(["mutants", "--copy-vcs=true"]);
-        let dest_tmpdir = copy_tree(&tmp, "a", &options, &Console::new())?;
+        let dest_tmpdir = copy_tree(&tmp, "a", None, &options, &Console::new())?;
         let dest = dest_tmpdir.path();
         assert!(dest.join(".git").is_dir(), ".git should be copied");
         assert!(dest.join(".git/foo").is_file(), ".git/foo should be copied");
@@ -0,0 +1,275 @@
+// Copyright 2025 Martin Pool
+
+//! Read line coverage from an LCOV file or an llvm-cov JSON export, for `--coverage`.
+//!
+//! Mutants whose lines are all instrumented but never executed by the tests can't be
+//! caught, so they're reported as missed without being built or tested. Mutants in files
+//! or lines that have no coverage data are tested as usual.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::read_to_string;
+
+use anyhow::{bail, Context};
+use camino::Utf8Path;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{Mutant, Result};
+
+/// Execution counts for the instrumented lines of each file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    /// Line counts keyed by the path relative to the tree, with forward slashes.
+    files: HashMap<String, BTreeMap<usize, u64>>,
+}
+
+impl Coverage {
+    /// Load coverage from a file, in LCOV format or as JSON exported by `llvm-cov export`
+    /// or `cargo llvm-cov --json`.
+    ///
+    /// Absolute paths within `tree_root` are made relative to it, to match the mutants.
+    pub fn load(path: &Utf8Path, tree_root: &Utf8Path) -> Result<Coverage> {
+        let content = read_to_string(path).with_context(|| format!("read {path}"))?;
+        let coverage = if content.trim_start().starts_with('{') {
+            Coverage::from_llvm_cov_json(&content, tree_root)
+        } else {
+            Coverage::from_lcov(&content, tree_root)
+        }
+        .with_context(|| format!("parse coverage from {path}"))?;
+        debug!(n_files = coverage.files.len(), "loaded coverage");
+        Ok(coverage)
+    }
+
+    fn from_lcov(lcov: &str, tree_root: &Utf8Path) -> Result<Coverage> {
+        let mut coverage = Coverage::default();
+        let mut lines = None;
+        for (i, line) in lcov.lines().enumerate() {
+            let line = line.trim();
+            if let Some(file) = line.strip_prefix("SF:") {
+                lines = Some(coverage.file_mut(file, tree_root));
+            } else if let Some(da) = line.strip_prefix("DA:") {
+                let Some(lines) = lines.as_mut() else {
+                    bail!("DA record outside a file on line {}", i + 1);
+                };
+                let mut fields = da.split(',');
+                let (Some(Ok(line_number)), Some(Ok(count))) = (
+                    fields.next().map(str::parse::<usize>),
+                    fields.next().map(str::parse::<u64>),
+                ) else {
+                    bail!("invalid DA record on line {}: {line:?}", i + 1);
+                };
+                *lines.entry(line_number).or_default() += count;
+            } else if line == "end_of_record" {
+                lines = None;
+            }
+        }
+        Ok(coverage)
+    }
+
+    /// Read line counts from the segments of each file in an llvm-cov JSON export.
+    ///
+    /// Each segment is `[line, column, count, has_count, is_region_entry, is_gap_region]`,
+    /// and marks where the count changes. As in llvm-cov's own line report, a line is
+    /// instrumented if a region starts on it or a counted region continues through it,
+    /// and its count is the largest of those.
+    fn from_llvm_cov_json(json: &str, tree_root: &Utf8Path) -> Result<Coverage> {
+        let value: Value = serde_json::from_str(json)?;
+        let mut coverage = Coverage::default();
+        for file in value["data"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|data| data["files"].as_array().into_iter().flatten())
+        {
+            let Some(filename) = file["filename"].as_str() else {
+                continue;
+            };
+            let segments = file["segments"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Segment::from_json)
+                .collect::<Vec<_>>();
+            let lines = coverage.file_mut(filename, tree_root);
+            let mut wrapped: Option<&Segment> = None;
+            let mut i = 0;
+            while i < segments.len() {
+                let line = segments[i].line;
+                let start = i;
+                while i < segments.len() && segments[i].line == line {
+                    i += 1;
+                }
+                let starting = &segments[start..i];
+                let counts = starting
+                    .iter()
+                    .filter(|s| s.has_count && s.is_region_entry && !s.is_gap)
+                    .map(|s| s.count)
+                    .chain(wrapped.filter(|w| w.has_count).map(|w| w.count));
+                if let Some(count) = counts.max() {
+                    let entry = lines.entry(line).or_default();
+                    *entry = (*entry).max(count);
+                }
+                // Lines between this and the next segment are covered by the last one here.
+                let last = starting.last().expect("at least one segment");
+                let next_line = segments.get(i).map_or(line + 1, |s| s.line);
+                if last.has_count && !last.is_gap {
+                    for between in line + 1..next_line {
+                        let entry = lines.entry(between).or_default();
+                        *entry = (*entry).max(last.count);
+                    }
+                }
+                wrapped = Some(last);
+            }
+        }
+        Ok(coverage)
+    }
+
+    fn file_mut(&mut self, file: &str, tree_root: &Utf8Path) -> &mut BTreeMap<usize, u64> {
+        let path = Utf8Path::new(file);
+        let relative = path.strip_prefix(tree_root).unwrap_or(path);
+        self.files
+            .entry(relative.as_str().replace('\\', "/"))
+            .or_default()
+    }
+
+    /// True if the coverage shows that the lines of a mutant were never executed.
+    ///
+    /// This is only true if at least one of its lines is instrumented and none of them
+    /// have a nonzero count.
+    pub fn is_uncovered(&self, mutant: &Mutant) -> bool {
+        let Some(lines) = self.files.get(&mutant.source_file.tree_relative_slashes()) else {
+            return false;
+        };
+        let mut counts = lines
+            .range(mutant.span.start.line..=mutant.span.end.line)
+            .map(|(_, count)| *count)
+            .peekable();
+        counts.peek().is_some() && counts.all(|count| count == 0)
+    }
+}
+
+/// One segment from an llvm-cov JSON export.
+struct Segment {
+    line: usize,
+    count: u64,
+    has_count: bool,
+    is_region_entry: bool,
+    is_gap: bool,
+}
+
+impl Segment {
+    fn from_json(value: &Value) -> Option<Segment> {
+        let fields = value.as_array()?;
+        Some(Segment {
+            line: usize::try_from(fields.first()?.as_u64()?).ok()?,
+            count: fields.get(2)?.as_u64()?,
+            has_count: fields.get(3)?.as_bool()?,
+            is_region_entry: fields.get(4)?.as_bool()?,
+            is_gap: fields.get(5).and_then(Value::as_bool).unwrap_or(false),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::visit::mutate_source_str;
+    use crate::Options;
+
+    const SOURCE: &str = indoc! {"
+        fn double(x: u32) -> u32 {
+            x * 2
+        }
+
+        fn triple(x: u32) -> u32 {
+            x * 3
+        }
+    "};
+
+    fn lines(coverage: &Coverage, file: &str) -> Vec<(usize, u64)> {
+        coverage.files[file]
+            .iter()
+            .map(|(line, count)| (*line, *count))
+            .collect()
+    }
+
+    #[test]
+    fn parse_lcov() {
+        let lcov = indoc! {"
+            SF:/home/user/tree/src/lib.rs
+            FN:1,double
+            FNDA:1,double
+            DA:1,1
+            DA:2,1
+            DA:3,1
+            DA:5,0
+            DA:6,0
+            end_of_record
+            SF:/home/user/.cargo/registry/dep/src/lib.rs
+            DA:10,4
+            end_of_record
+        "};
+        let coverage = Coverage::from_lcov(lcov, Utf8Path::new("/home/user/tree")).unwrap();
+        assert_eq!(
+            lines(&coverage, "src/lib.rs"),
+            [(1, 1), (2, 1), (3, 1), (5, 0), (6, 0)]
+        );
+        assert_eq!(
+            lines(&coverage, "/home/user/.cargo/registry/dep/src/lib.rs"),
+            [(10, 4)]
+        );
+        assert!(Coverage::from_lcov("DA:1,1\n", Utf8Path::new("/")).is_err());
+        assert!(Coverage::from_lcov("SF:a.rs\nDA:x,1\n", Utf8Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn parse_llvm_cov_json_segments() {
+        let json = r#"{
+            "data": [{
+                "files": [{
+                    "filename": "/tree/src/lib.rs",
+                    "segments": [
+                        [1, 26, 3, true, true, false],
+                        [3, 2, 0, false, false, false],
+                        [5, 26, 0, true, true, false],
+                        [7, 2, 0, false, false, false]
+                    ]
+                }]
+            }],
+            "type": "llvm.coverage.json.export"
+        }"#;
+        let coverage = Coverage::from_llvm_cov_json(json, Utf8Path::new("/tree")).unwrap();
+        assert_eq!(
+            lines(&coverage, "src/lib.rs"),
+            [(1, 3), (2, 3), (3, 3), (5, 0), (6, 0), (7, 0)]
+        );
+    }
+
+    #[test]
+    fn mutants_in_unexecuted_lines_are_uncovered() {
+        let mutants = mutate_source_str(SOURCE, &Options::default()).unwrap();
+        let file = mutants[0].source_file.tree_relative_slashes();
+        let coverage = Coverage::from_lcov(
+            &format!("SF:{file}\nDA:1,1\nDA:2,1\nDA:3,1\nDA:5,0\nDA:6,0\nDA:7,0\nend_of_record\n"),
+            Utf8Path::new("/tree"),
+        )
+        .unwrap();
+        for mutant in &mutants {
+            assert_eq!(
+                coverage.is_uncovered(mutant),
+                mutant.span.start.line >= 5,
+                "{}",
+                mutant.name(true)
+            );
+        }
+        // Without coverage for the file, or for its lines, mutants are tested.
+        assert!(!Coverage::default().is_uncovered(&mutants[0]));
+        let coverage =
+            Coverage::from_lcov(&format!("SF:{file}\nDA:20,0\n"), Utf8Path::new("/")).unwrap();
+        assert!(mutants.iter().all(|mutant| !coverage.is_uncovered(mutant)));
+    }
+}
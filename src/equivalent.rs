@@ -0,0 +1,162 @@
+// Copyright 2025 Martin Pool
+
+//! Detect mutants that compile to exactly the same test binaries as the unmutated tree.
+//!
+//! The compiler sometimes optimizes away a mutation, for example in an unreachable
+//! branch or a function that's never called. Running the tests for these mutants would
+//! only waste time, and they'd always be reported as missed, so with
+//! `--detect-equivalent` they're reported as equivalent instead.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::hash::Hasher;
+use std::io::{BufReader, Read};
+use std::process::Command;
+
+use anyhow::bail;
+use camino::Utf8PathBuf;
+use serde::Deserialize;
+use tracing::{debug, trace};
+
+use crate::build_dir::BuildDir;
+use crate::cargo::{cargo_argv, cargo_env};
+use crate::options::TestTool;
+use crate::package::PackageSelection;
+use crate::{Context, Options, Phase, Result};
+
+/// Hashes of the contents of test binaries, keyed by their path.
+pub type ArtifactHashes = BTreeMap<Utf8PathBuf, u64>;
+
+/// Return the argv to list the test binaries built for some packages.
+///
+/// This is the same as the build phase, with cargo's JSON messages on stdout. The keys
+/// of the build for the unmutated tree should be the same as for mutants, so the argv
+/// is also used as a key for the baseline hashes.
+pub fn artifacts_argv(packages: &PackageSelection, options: &Options) -> Vec<String> {
+    let mut argv = cargo_argv(packages, Phase::Build, options);
+    argv.push(match options.test_tool {
+        TestTool::Cargo => "--message-format=json".to_owned(),
+        TestTool::Nextest => "--cargo-message-format=json".to_owned(),
+    });
+    argv
+}
+
+/// Build the tree if necessary, and hash all the test binaries.
+///
+/// This is normally run just after the build phase, so cargo has nothing more to build and
+/// just lists the binaries that are already there.
+pub fn artifact_hashes(
+    build_dir: &BuildDir,
+    argv: &[String],
+    options: &Options,
+) -> Result<ArtifactHashes> {
+    let mut hashes = ArtifactHashes::new();
+    for path in executables(&list_artifacts(build_dir, argv, options)?) {
+        let hash = hash_file(&path)?;
+        trace!(?path, hash, "hashed artifact");
+        hashes.insert(path, hash);
+    }
+    Ok(hashes)
+}
+
+/// Run the command from [`artifacts_argv`], and return cargo's JSON messages.
+pub fn list_artifacts(build_dir: &BuildDir, argv: &[String], options: &Options) -> Result<String> {
+    debug!(?argv, "list artifacts");
+    let mut command = Command::new(&argv[0]);
+    command
+        .args(&argv[1..])
+        .envs(cargo_env(options))
+        .current_dir(build_dir.path());
+    let output = command
+        .output()
+        .with_context(|| format!("Failed to run {argv:?}"))?;
+    if !output.status.success() {
+        bail!(
+            "Failed to list artifacts with {argv:?}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// A line of cargo's JSON output.
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    #[serde(default)]
+    executable: Option<Utf8PathBuf>,
+}
+
+/// Find the executables listed in cargo's JSON messages.
+fn executables(json_lines: &str) -> Vec<Utf8PathBuf> {
+    json_lines
+        .lines()
+        .filter_map(|line| serde_json::from_str::<CargoMessage>(line).ok())
+        .filter(|message| message.reason == "compiler-artifact")
+        .filter_map(|message| message.executable)
+        .collect()
+}
+
+fn hash_file(path: &Utf8PathBuf) -> Result<u64> {
+    let mut reader = BufReader::new(File::open(path).with_context(|| format!("open {path}"))?);
+    let mut hasher = DefaultHasher::new();
+    let mut buf = vec![0; 1 << 16];
+    loop {
+        let n = reader
+            .read(&mut buf)
+            .with_context(|| format!("read {path}"))?;
+        if n == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buf[..n]);
+    }
+}
+
+/// True if the binaries built for a mutant are the same as for the unmutated tree.
+///
+/// If no binaries were built then nothing is known about the mutant, so it's not
+/// considered equivalent.
+pub fn is_equivalent(baseline: &ArtifactHashes, mutant: &ArtifactHashes) -> bool {
+    !mutant.is_empty() && baseline == mutant
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn find_executables() {
+        assert_eq!(
+            executables(indoc! { r#"
+                {"reason":"compiler-artifact","target":{"kind":["lib"]},"filenames":["/t/libfoo.rlib"],"executable":null,"fresh":true}
+                {"reason":"compiler-artifact","target":{"kind":["lib"]},"filenames":["/t/deps/foo-1234"],"executable":"/t/deps/foo-1234","fresh":true}
+                {"reason":"compiler-message","message":{}}
+                {"reason":"build-finished","success":true}
+            "#}),
+            [Utf8PathBuf::from("/t/deps/foo-1234")]
+        );
+    }
+
+    #[test]
+    fn compare_hashes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = Utf8PathBuf::try_from(tmp.path().join("test-binary")).unwrap();
+        write(&path, b"hello").unwrap();
+        let baseline = ArtifactHashes::from([(path.clone(), hash_file(&path).unwrap())]);
+        assert!(is_equivalent(&baseline, &baseline.clone()));
+        write(&path, b"goodbye").unwrap();
+        let mutant = ArtifactHashes::from([(path.clone(), hash_file(&path).unwrap())]);
+        assert!(!is_equivalent(&baseline, &mutant));
+        assert!(
+            !is_equivalent(&ArtifactHashes::new(), &ArtifactHashes::new()),
+            "nothing is known if there are no binaries"
+        );
+    }
+}
@@ -0,0 +1,93 @@
+// Copyright 2025 Martin Pool
+
+//! A stream of events describing the progress of a run, written as newline-delimited
+//! JSON with `--events-file`, or to stderr with `--progress-style=json`.
+//!
+//! Each line is one JSON object with an `event` field naming the kind of event, and a
+//! `time` field with the RFC 3339 time at which it happened. Each event is written and
+//! flushed as it happens, so other programs can follow the file while cargo-mutants
+//! is running.
+
+use std::fs::File;
+use std::io::{self, Write};
+
+use camino::Utf8Path;
+use serde_json::{Map, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::{Context, Result};
+
+/// An open file or stream that events are appended to.
+pub struct EventLog {
+    file: Box<dyn Write + Send>,
+}
+
+impl EventLog {
+    /// Create or truncate the events file.
+    pub fn create(path: &Utf8Path) -> Result<EventLog> {
+        let file = File::create(path).with_context(|| format!("create events file {path}"))?;
+        Ok(EventLog {
+            file: Box::new(file),
+        })
+    }
+
+    /// Write events to stderr.
+    pub fn stderr() -> EventLog {
+        EventLog {
+            file: Box::new(io::stderr()),
+        }
+    }
+
+    /// Write one event, with the fields from `fields`, which should be a JSON object.
+    pub fn emit(&mut self, event: &str, fields: Value) -> Result<()> {
+        let mut line = Map::new();
+        line.insert("event".to_owned(), event.into());
+        line.insert(
+            "time".to_owned(),
+            OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .expect("format current time")
+                .into(),
+        );
+        if let Value::Object(fields) = fields {
+            line.extend(fields);
+        }
+        let mut json = serde_json::to_string(&line).context("serialize event")?;
+        json.push('\n');
+        self.file
+            .write_all(json.as_bytes())
+            .and_then(|()| self.file.flush())
+            .context("write event")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::read_to_string;
+
+    use camino::Utf8PathBuf;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn events_are_json_lines() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = Utf8PathBuf::try_from(tmp.path().join("events.ndjson")).unwrap();
+        let mut log = EventLog::create(&path).unwrap();
+        log.emit("testing_started", json!({"total_mutants": 2}))
+            .unwrap();
+        log.emit("testing_finished", json!({})).unwrap();
+        let content = read_to_string(&path).unwrap();
+        let events: Vec<Value> = content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "testing_started");
+        assert_eq!(events[0]["total_mutants"], 2);
+        assert!(events[0]["time"].as_str().unwrap().ends_with('Z'));
+        assert_eq!(events[1]["event"], "testing_finished");
+    }
+}
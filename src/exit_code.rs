@@ -26,5 +26,8 @@ pub const TIMEOUT: i32 = 3;
 /// The tests are already failing in an unmutated tree.
 pub const BASELINE_FAILED: i32 = 4;
 
+/// The mutation score was below `--minimum-score`.
+pub const SCORE_BELOW_MINIMUM: i32 = 5;
+
 /// An internal software error, from sysexit.
 pub const SOFTWARE: i32 = 70;
@@ -0,0 +1,195 @@
+// Copyright 2025 Martin Pool
+
+//! Find code generated by derive macros, by asking rustc to expand macros in a package.
+//!
+//! This is used by `--expand-macros` to mutate derived impls: each mutant replaces the
+//! `#[derive]` with a mutated copy of the impl that it would have generated.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::HashMap;
+use std::process::Command;
+
+use anyhow::bail;
+use camino::Utf8Path;
+use syn::spanned::Spanned;
+use syn::{Item, ItemImpl};
+use tracing::debug;
+
+use crate::cargo::cargo_bin;
+use crate::package::Package;
+use crate::span::Span;
+use crate::{Context, Result};
+
+/// The source text of impls generated by derive macros, as pretty-printed by rustc,
+/// keyed by the name of the type and the last component of the name of the trait.
+///
+/// The value is None if there are several types with the same name that derive the
+/// same trait, in which case we can't tell which impl belongs to which type.
+pub type DerivedImpls = HashMap<(String, String), Option<String>>;
+
+/// Run rustc to expand all macros in the library target of a package, and return the
+/// expanded source.
+///
+/// This uses unstable rustc options, so sets `RUSTC_BOOTSTRAP`, the same as
+/// `cargo expand`.
+pub fn expand_package(workspace_dir: &Utf8Path, package: &Package) -> Result<String> {
+    // Build in a new target dir so that the crate is never fresh, and so always
+    // prints its expansion.
+    let target_dir = tempfile::Builder::new()
+        .prefix("cargo-mutants-expand-")
+        .tempdir()
+        .context("Create temporary target directory")?;
+    let target_dir_arg = target_dir
+        .path()
+        .to_str()
+        .context("Temporary directory path is not UTF-8")?;
+    let args = [
+        "rustc",
+        "--package",
+        &package.name,
+        "--lib",
+        "--profile=check",
+        "--quiet",
+        "--target-dir",
+        target_dir_arg,
+        "--",
+        "-Zunpretty=expanded",
+    ];
+    debug!(?args, "expand macros");
+    let output = Command::new(cargo_bin())
+        .args(args)
+        .env("RUSTC_BOOTSTRAP", "1")
+        .current_dir(workspace_dir)
+        .output()
+        .with_context(|| format!("Failed to spawn cargo {args:?}"))?;
+    if !output.status.success() {
+        bail!(
+            "cargo rustc failed to expand macros: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("Expanded source is not UTF-8")
+}
+
+/// Find the impls generated by derive macros in expanded source code.
+///
+/// These are recognized by the `#[automatically_derived]` attribute, which is added by
+/// the built-in derive macros and by many others.
+pub fn derived_impls(expanded: &str) -> Result<DerivedImpls> {
+    let file = syn::parse_str::<syn::File>(expanded).context("Parse expanded source")?;
+    let mut impls = DerivedImpls::new();
+    collect_derived_impls(&file.items, expanded, &mut impls);
+    Ok(impls)
+}
+
+fn collect_derived_impls(items: &[Item], expanded: &str, impls: &mut DerivedImpls) {
+    for item in items {
+        match item {
+            Item::Impl(item_impl) => {
+                if let Some(key) = derived_impl_key(item_impl) {
+                    let code = Span::from(item_impl.span()).extract(expanded);
+                    impls
+                        .entry(key)
+                        .and_modify(|existing| *existing = None)
+                        .or_insert(Some(code));
+                }
+            }
+            Item::Mod(item_mod) => {
+                if let Some((_, items)) = &item_mod.content {
+                    collect_derived_impls(items, expanded, impls);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// If this is a derived impl, return the names of the type and trait.
+fn derived_impl_key(item_impl: &ItemImpl) -> Option<(String, String)> {
+    if !item_impl
+        .attrs
+        .iter()
+        .any(|attr| attr.path().is_ident("automatically_derived"))
+    {
+        return None;
+    }
+    let (_, trait_path, _) = item_impl.trait_.as_ref()?;
+    let syn::Type::Path(type_path) = &*item_impl.self_ty else {
+        return None;
+    };
+    Some((
+        type_path.path.segments.last()?.ident.to_string(),
+        trait_path.segments.last()?.ident.to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn find_derived_impls() {
+        let impls = derived_impls(indoc! { "
+            pub struct Point {
+                pub x: i32,
+            }
+            #[automatically_derived]
+            impl ::core::cmp::PartialEq for Point {
+                #[inline]
+                fn eq(&self, other: &Point) -> bool { self.x == other.x }
+            }
+            impl Point {
+                fn new() -> Point { Point { x: 0 } }
+            }
+            mod a {
+                pub struct Same;
+                #[automatically_derived]
+                impl ::core::clone::Clone for Same {
+                    fn clone(&self) -> Same { Same }
+                }
+                #[automatically_derived]
+                impl<T> ::core::fmt::Debug for Wrapper<T> {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        todo!()
+                    }
+                }
+            }
+            mod b {
+                pub struct Same;
+                #[automatically_derived]
+                impl ::core::clone::Clone for Same {
+                    fn clone(&self) -> Same { Same }
+                }
+            }
+        "})
+        .unwrap();
+        let mut keys = impls.keys().cloned().collect::<Vec<_>>();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                ("Point".to_owned(), "PartialEq".to_owned()),
+                ("Same".to_owned(), "Clone".to_owned()),
+                ("Wrapper".to_owned(), "Debug".to_owned()),
+            ]
+        );
+        assert_eq!(
+            impls[&("Point".to_owned(), "PartialEq".to_owned())].as_deref(),
+            Some(indoc! { "
+                #[automatically_derived]
+                impl ::core::cmp::PartialEq for Point {
+                    #[inline]
+                    fn eq(&self, other: &Point) -> bool { self.x == other.x }
+                }"
+            })
+        );
+        assert_eq!(
+            impls[&("Same".to_owned(), "Clone".to_owned())],
+            None,
+            "ambiguous impls are recorded as None"
+        );
+    }
+}
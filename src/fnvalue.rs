@@ -4,39 +4,158 @@
 
 #![warn(clippy::pedantic)]
 
+use std::collections::HashMap;
 use std::iter;
 
 use itertools::Itertools;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{
-    AngleBracketedGenericArguments, AssocType, Expr, GenericArgument, Ident, Path, PathArguments,
-    ReturnType, TraitBound, Type, TypeArray, TypeImplTrait, TypeParamBound, TypeSlice, TypeTuple,
+    AngleBracketedGenericArguments, AssocType, Expr, GenericArgument, GenericParam, Generics,
+    Ident, Path, PathArguments, ReturnType, TraitBound, Type, TypeArray, TypeImplTrait,
+    TypeParamBound, TypeSlice, TypeTuple, WherePredicate,
 };
 use tracing::trace;
 
 use crate::pretty::ToPrettyString;
 
+/// Error values to return as `Err(..)` from functions returning `Result`.
+#[derive(Debug, Default)]
+pub(crate) struct ErrorValues {
+    /// Values for error types that don't have their own values in `by_type`.
+    pub default: Vec<Expr>,
+
+    /// Values for particular error types.
+    pub by_type: Vec<(Path, Expr)>,
+}
+
+impl ErrorValues {
+    /// Return the error values for a function with this return type.
+    ///
+    /// If it returns `Result<T, E>` where `E` matches any of the error types in
+    /// `by_type`, then only the values for those types are used.
+    pub fn for_return_type(&self, return_type: &ReturnType) -> Vec<Expr> {
+        if let ReturnType::Type(_, type_) = return_type {
+            if let Some(error_path) = result_error_path(type_) {
+                let matched = self
+                    .by_type
+                    .iter()
+                    .filter(|(configured, _)| paths_match(error_path, configured))
+                    .map(|(_, value)| value.clone())
+                    .collect_vec();
+                if !matched.is_empty() {
+                    return matched;
+                }
+            }
+        }
+        self.default.clone()
+    }
+}
+
+/// If this is `Result<T, E>`, return the path of `E`.
+pub(crate) fn result_error_path(type_: &Type) -> Option<&Path> {
+    let Type::Path(syn::TypePath { qself: None, path }) = type_ else {
+        return None;
+    };
+    let last = path.segments.last()?;
+    if last.ident != "Result" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) =
+        &last.arguments
+    else {
+        return None;
+    };
+    match args.iter().nth(1) {
+        Some(GenericArgument::Type(Type::Path(syn::TypePath {
+            qself: None,
+            path: error_path,
+        }))) => Some(error_path),
+        _ => None,
+    }
+}
+
+/// True if the names in one path are a suffix of the other, so that `Error` matches
+/// `anyhow::Error`, ignoring any type arguments.
+fn paths_match(a: &Path, b: &Path) -> bool {
+    let a = a.segments.iter().map(|s| &s.ident).collect_vec();
+    let b = b.segments.iter().map(|s| &s.ident).collect_vec();
+    let n = a.len().min(b.len());
+    a[a.len() - n..] == b[b.len() - n..]
+}
+
+/// The trait bounds on the generic type parameters in scope, by the name of the parameter.
+pub(crate) type TypeParams = HashMap<Ident, Vec<Path>>;
+
+/// Add the type parameters declared by some generics, and their trait bounds from both
+/// the parameter list and the where clause.
+///
+/// Parameters replace any of the same name from an outer scope.
+pub(crate) fn add_type_params(type_params: &mut TypeParams, generics: &Generics) {
+    for param in &generics.params {
+        if let GenericParam::Type(type_param) = param {
+            type_params.insert(
+                type_param.ident.clone(),
+                trait_bound_paths(&type_param.bounds),
+            );
+        }
+    }
+    for predicate in generics.where_clause.iter().flat_map(|w| &w.predicates) {
+        if let WherePredicate::Type(predicate) = predicate {
+            if let Type::Path(syn::TypePath { qself: None, path }) = &predicate.bounded_ty {
+                if let Some(bounds) = path.get_ident().and_then(|i| type_params.get_mut(i)) {
+                    bounds.extend(trait_bound_paths(&predicate.bounds));
+                }
+            }
+        }
+    }
+}
+
+fn trait_bound_paths<'a>(bounds: impl IntoIterator<Item = &'a TypeParamBound>) -> Vec<Path> {
+    bounds
+        .into_iter()
+        .filter_map(|bound| match bound {
+            TypeParamBound::Trait(TraitBound { path, .. }) => Some(path.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Generate replacement text for a function based on its return type.
 pub(crate) fn return_type_replacements(
     return_type: &ReturnType,
     error_exprs: &[Expr],
+    type_params: &TypeParams,
 ) -> Vec<TokenStream> {
     match return_type {
         ReturnType::Default => vec![quote! { () }],
-        ReturnType::Type(_rarrow, type_) => type_replacements(type_, error_exprs).collect_vec(),
+        ReturnType::Type(_rarrow, type_) => {
+            type_replacements(type_, error_exprs, type_params).collect_vec()
+        }
     }
 }
 
 /// Generate some values that we hope are reasonable replacements for a type.
 #[allow(clippy::too_many_lines)]
-fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item = TokenStream> {
+fn type_replacements(
+    type_: &Type,
+    error_exprs: &[Expr],
+    type_params: &TypeParams,
+) -> impl Iterator<Item = TokenStream> {
     // This could probably change to run from some configuration rather than
     // hardcoding various types, which would make it easier to support tree-specific
     // mutation values, and perhaps reduce duplication. However, it seems better
     // to support all the core cases with direct code first to learn what generalizations
     // are needed.
     match type_ {
+        Type::Path(syn::TypePath { qself: None, path })
+            if path
+                .get_ident()
+                .is_some_and(|i| type_params.contains_key(i)) =>
+        {
+            let ident = path.get_ident().expect("type parameter is an ident");
+            type_param_replacements(ident, &type_params[ident], error_exprs, type_params)
+        }
         Type::Path(syn::TypePath { path, .. }) => {
             // dbg!(&path);
             if path.is_ident("bool") {
@@ -57,7 +176,7 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
                 vec![quote! { 0.0 }, quote! { 1.0 }, quote! { -1.0 }]
             } else if path_ends_with(path, "Result") {
                 if let Some(ok_type) = match_first_type_arg(path, "Result") {
-                    type_replacements(ok_type, error_exprs)
+                    type_replacements(ok_type, error_exprs, type_params)
                         .map(|rep| {
                             quote! { Ok(#rep) }
                         })
@@ -76,23 +195,27 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
                 vec![quote! { HttpResponse::Ok().finish() }]
             } else if let Some(some_type) = match_first_type_arg(path, "Option") {
                 iter::once(quote! { None })
-                    .chain(type_replacements(some_type, error_exprs).map(|rep| {
-                        quote! { Some(#rep) }
-                    }))
+                    .chain(
+                        type_replacements(some_type, error_exprs, type_params).map(|rep| {
+                            quote! { Some(#rep) }
+                        }),
+                    )
                     .collect_vec()
             } else if let Some(element_type) = match_first_type_arg(path, "Vec") {
                 // Generate an empty Vec, and then a one-element vec for every recursive
                 // value.
                 iter::once(quote! { vec![] })
-                    .chain(type_replacements(element_type, error_exprs).map(|rep| {
-                        quote! { vec![#rep] }
-                    }))
+                    .chain(
+                        type_replacements(element_type, error_exprs, type_params).map(|rep| {
+                            quote! { vec![#rep] }
+                        }),
+                    )
                     .collect_vec()
             } else if let Some(borrowed_type) = match_first_type_arg(path, "Cow") {
                 // TODO: We could specialize Cows for cases like Vec and Box where
                 // we would have to leak to make the reference; perhaps it would only
                 // look better...
-                type_replacements(borrowed_type, error_exprs)
+                type_replacements(borrowed_type, error_exprs, type_params)
                     .flat_map(|rep| {
                         [
                             quote! { Cow::Borrowed(#rep) },
@@ -106,20 +229,23 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
                 // imported, but we must strip or rewrite the arguments, so that
                 // `std::sync::Arc<String>` becomes either `std::sync::Arc::<String>::new`
                 // or at least `std::sync::Arc::new`. Similarly for other types.
-                type_replacements(inner_type, error_exprs)
+                type_replacements(inner_type, error_exprs, type_params)
                     .map(|rep| {
                         quote! { #container_type::new(#rep) }
                     })
                     .collect_vec()
             } else if let Some((collection_type, inner_type)) = known_collection(path) {
                 iter::once(quote! { #collection_type::new() })
-                    .chain(type_replacements(inner_type, error_exprs).map(|rep| {
-                        quote! { #collection_type::from_iter([#rep]) }
-                    }))
+                    .chain(
+                        type_replacements(inner_type, error_exprs, type_params).map(|rep| {
+                            quote! { #collection_type::from_iter([#rep]) }
+                        }),
+                    )
                     .collect_vec()
             } else if let Some((collection_type, key_type, value_type)) = known_map(path) {
-                let key_reps = type_replacements(key_type, error_exprs).collect_vec();
-                let val_reps = type_replacements(value_type, error_exprs).collect_vec();
+                let key_reps = type_replacements(key_type, error_exprs, type_params).collect_vec();
+                let val_reps =
+                    type_replacements(value_type, error_exprs, type_params).collect_vec();
                 iter::once(quote! { #collection_type::new() })
                     .chain(
                         key_reps
@@ -134,13 +260,15 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
                 // to call it, but we strongly suspect that you could construct it from
                 // an `A`.
                 iter::once(quote! { #collection_type::new() })
-                    .chain(type_replacements(inner_type, error_exprs).flat_map(|rep| {
-                        [
-                            quote! { #collection_type::from_iter([#rep]) },
-                            quote! { #collection_type::new(#rep) },
-                            quote! { #collection_type::from(#rep) },
-                        ]
-                    }))
+                    .chain(
+                        type_replacements(inner_type, error_exprs, type_params).flat_map(|rep| {
+                            [
+                                quote! { #collection_type::from_iter([#rep]) },
+                                quote! { #collection_type::new(#rep) },
+                                quote! { #collection_type::from(#rep) },
+                            ]
+                        }),
+                    )
                     .collect_vec()
             } else {
                 trace!(
@@ -156,12 +284,15 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
         // large, and values like "all zeros" and "all ones" seem likely to catch
         // lots of things.
         {
-            type_replacements(elem, error_exprs)
+            type_replacements(elem, error_exprs, type_params)
                 .map(|r| quote! { [ #r; #len ] })
                 .collect_vec()
         }
         Type::Slice(TypeSlice { elem, .. }) => iter::once(quote! { Vec::leak(Vec::new()) })
-            .chain(type_replacements(elem, error_exprs).map(|r| quote! { Vec::leak(vec![ #r ]) }))
+            .chain(
+                type_replacements(elem, error_exprs, type_params)
+                    .map(|r| quote! { Vec::leak(vec![ #r ]) }),
+            )
             .collect_vec(),
         Type::Reference(syn::TypeReference {
             mutability: None,
@@ -174,10 +305,11 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
             }
             Type::Slice(TypeSlice { elem, .. }) => iter::once(quote! { Vec::leak(Vec::new()) })
                 .chain(
-                    type_replacements(elem, error_exprs).map(|r| quote! { Vec::leak(vec![ #r ]) }),
+                    type_replacements(elem, error_exprs, type_params)
+                        .map(|r| quote! { Vec::leak(vec![ #r ]) }),
                 )
                 .collect_vec(),
-            _ => type_replacements(elem, error_exprs)
+            _ => type_replacements(elem, error_exprs, type_params)
                 .map(|rep| {
                     quote! { &#rep }
                 })
@@ -190,12 +322,13 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
         }) => match &**elem {
             Type::Slice(TypeSlice { elem, .. }) => iter::once(quote! { Vec::leak(Vec::new()) })
                 .chain(
-                    type_replacements(elem, error_exprs).map(|r| quote! { Vec::leak(vec![ #r ]) }),
+                    type_replacements(elem, error_exprs, type_params)
+                        .map(|r| quote! { Vec::leak(vec![ #r ]) }),
                 )
                 .collect_vec(),
             _ => {
                 // Make &mut with static lifetime by leaking them on the heap.
-                type_replacements(elem, error_exprs)
+                type_replacements(elem, error_exprs, type_params)
                     .map(|rep| {
                         quote! { Box::leak(Box::new(#rep)) }
                     })
@@ -206,7 +339,7 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
             // Generate the cartesian product of replacements of every type within the tuple.
             elems
                 .iter()
-                .map(|elem| type_replacements(elem, error_exprs).collect_vec())
+                .map(|elem| type_replacements(elem, error_exprs, type_params).collect_vec())
                 .multi_cartesian_product()
                 .map(|reps| {
                     quote! { ( #( #reps ),* ) }
@@ -218,7 +351,7 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
             if let Some(item_type) = match_impl_iterator(impl_trait) {
                 iter::once(quote! { ::std::iter::empty() })
                     .chain(
-                        type_replacements(item_type, error_exprs)
+                        type_replacements(item_type, error_exprs, type_params)
                             .map(|r| quote! { ::std::iter::once(#r) }),
                     )
                     .collect_vec()
@@ -238,6 +371,41 @@ fn type_replacements(type_: &Type, error_exprs: &[Expr]) -> impl Iterator<Item =
     .into_iter()
 }
 
+/// Generate values of a generic type parameter that can be constructed from its trait
+/// bounds.
+///
+/// If the bounds don't give any way to construct a value, there are no replacements,
+/// since anything else would be unviable.
+fn type_param_replacements(
+    ident: &Ident,
+    bounds: &[Path],
+    error_exprs: &[Expr],
+    type_params: &TypeParams,
+) -> Vec<TokenStream> {
+    let mut reps = Vec::new();
+    for bound in bounds {
+        if path_ends_with(bound, "Default") {
+            reps.push(quote! { Default::default() });
+        } else if let Some(from_type) = match_first_type_arg(bound, "From") {
+            // Converting from another type parameter could recurse forever, and is
+            // unlikely to be more interesting than the direct bounds of that parameter.
+            if matches!(from_type, Type::Path(syn::TypePath { path, .. })
+                if path.get_ident().is_some_and(|i| type_params.contains_key(i)))
+            {
+                continue;
+            }
+            reps.extend(
+                type_replacements(from_type, error_exprs, type_params)
+                    .map(|rep| quote! { <#ident as #bound>::from(#rep) }),
+            );
+        }
+    }
+    if reps.is_empty() {
+        trace!(%ident, "No replacements for type parameter with these bounds");
+    }
+    reps
+}
+
 fn path_ends_with(path: &Path, ident: &str) -> bool {
     path.segments.last().is_some_and(|s| s.ident == ident)
 }
@@ -443,7 +611,7 @@ mod test {
     use crate::fnvalue::match_impl_iterator;
     use crate::pretty::ToPrettyString;
 
-    use super::{known_map, return_type_replacements};
+    use super::{add_type_params, known_map, return_type_replacements, ErrorValues, TypeParams};
 
     #[test]
     fn recurse_into_result_bool() {
@@ -783,9 +951,68 @@ mod test {
         );
     }
 
+    #[test]
+    fn error_values_for_return_type() {
+        let error_values = ErrorValues {
+            default: vec![parse_quote! { "default".into() }],
+            by_type: vec![
+                (
+                    parse_quote! { anyhow::Error },
+                    parse_quote! { anyhow!("a") },
+                ),
+                (parse_quote! { MyError }, parse_quote! { MyError::Bad }),
+            ],
+        };
+        let values = |return_type: ReturnType| {
+            error_values
+                .for_return_type(&return_type)
+                .iter()
+                .map(ToPrettyString::to_pretty_string)
+                .collect_vec()
+        };
+        assert_eq!(
+            values(parse_quote! { -> Result<(), anyhow::Error> }),
+            ["anyhow!(\"a\")"]
+        );
+        // A shorter path matches the configured type if it's a suffix.
+        assert_eq!(
+            values(parse_quote! { -> Result<(), Error> }),
+            ["anyhow!(\"a\")"]
+        );
+        assert_eq!(
+            values(parse_quote! { -> Result<u8, crate::MyError> }),
+            ["MyError::Bad"]
+        );
+        assert_eq!(
+            values(parse_quote! { -> Result<(), std::io::Error> }),
+            ["\"default\".into()"]
+        );
+        assert_eq!(
+            values(parse_quote! { -> Result<()> }),
+            ["\"default\".into()"]
+        );
+        assert_eq!(values(parse_quote! { -> u32 }), ["\"default\".into()"]);
+    }
+
     fn check_replacements(return_type: &ReturnType, error_exprs: &[Expr], expected: &[&str]) {
         assert_eq!(
-            return_type_replacements(return_type, error_exprs)
+            return_type_replacements(return_type, error_exprs, &TypeParams::new())
+                .into_iter()
+                .map(|t| t.to_pretty_string())
+                .collect_vec(),
+            expected
+        );
+    }
+
+    fn check_generic_replacements(
+        generics: &syn::Generics,
+        return_type: &ReturnType,
+        expected: &[&str],
+    ) {
+        let mut type_params = TypeParams::new();
+        add_type_params(&mut type_params, generics);
+        assert_eq!(
+            return_type_replacements(return_type, &[], &type_params)
                 .into_iter()
                 .map(|t| t.to_pretty_string())
                 .collect_vec(),
@@ -793,6 +1020,66 @@ mod test {
         );
     }
 
+    #[test]
+    fn type_param_with_default_bound() {
+        check_generic_replacements(
+            &parse_quote! { <T: Clone + Default> },
+            &parse_quote! { -> T },
+            &["Default::default()"],
+        );
+        let mut generics: syn::Generics = parse_quote! { <T> };
+        generics.where_clause = Some(parse_quote! { where T: std::default::Default });
+        check_generic_replacements(
+            &generics,
+            &parse_quote! { -> Option<T> },
+            &["None", "Some(Default::default())"],
+        );
+    }
+
+    #[test]
+    fn type_param_with_from_bound() {
+        check_generic_replacements(
+            &parse_quote! { <T: From<bool>> },
+            &parse_quote! { -> Vec<T> },
+            &[
+                "vec![]",
+                "vec![<T as From<bool>>::from(true)]",
+                "vec![<T as From<bool>>::from(false)]",
+            ],
+        );
+    }
+
+    #[test]
+    fn type_param_without_useful_bounds_has_no_replacements() {
+        check_generic_replacements(&parse_quote! { <T: Clone> }, &parse_quote! { -> T }, &[]);
+        check_generic_replacements(
+            &parse_quote! { <T: From<U>, U: From<T>> },
+            &parse_quote! { -> T },
+            &[],
+        );
+        check_generic_replacements(
+            &parse_quote! { <T: Clone> },
+            &parse_quote! { -> Result<T, String> },
+            &[],
+        );
+        check_generic_replacements(
+            &parse_quote! { <T: Clone> },
+            &parse_quote! { -> Option<T> },
+            &["None"],
+        );
+    }
+
+    #[test]
+    fn fn_type_param_shadows_outer_param() {
+        let mut type_params = TypeParams::new();
+        add_type_params(&mut type_params, &parse_quote! { <T: Default> });
+        add_type_params(&mut type_params, &parse_quote! { <T: Clone> });
+        assert_eq!(
+            return_type_replacements(&parse_quote! { -> T }, &[], &type_params).len(),
+            0
+        );
+    }
+
     #[test]
     fn match_map() {
         assert!(known_map(&parse_quote! { BTreeMap<String, usize> }).is_some());
@@ -0,0 +1,142 @@
+// Copyright 2025 Martin Pool
+
+//! Output for GitHub Actions, with `--output-format=github`.
+//!
+//! Each missed mutant is printed as a [workflow command] that GitHub shows as a warning
+//! annotation on the mutated line, and at the end of the run a Markdown summary is
+//! appended to the [job summary].
+//!
+//! [workflow command]: https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions#setting-a-warning-message
+//! [job summary]: https://docs.github.com/en/actions/reference/workflow-commands-for-github-actions#adding-a-job-summary
+
+#![warn(clippy::pedantic)]
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::Write as _;
+
+use crate::outcome::{LabOutcome, SummaryOutcome};
+use crate::{Context, Mutant, Result};
+
+/// The environment variable naming the file that the job summary is appended to.
+const STEP_SUMMARY_VAR: &str = "GITHUB_STEP_SUMMARY";
+
+/// Format a warning annotation for a missed mutant.
+pub fn missed_mutant_annotation(mutant: &Mutant) -> String {
+    let span = &mutant.span;
+    format!(
+        "::warning file={},line={},col={},endLine={},endColumn={},title={}::{}",
+        escape_property(&mutant.source_file.tree_relative_slashes()),
+        span.start.line,
+        span.start.column,
+        span.end.line,
+        span.end.column,
+        escape_property("Missed mutant"),
+        escape_data(&format!("mutant survived: {}", mutant.describe_change())),
+    )
+}
+
+/// Format a Markdown summary of the run, with a table of outcomes and a list of
+/// missed mutants.
+pub fn step_summary(lab_outcome: &LabOutcome, summary_line: &str) -> String {
+    let mut s = format!("## cargo-mutants\n\n{summary_line}\n\n");
+    s.push_str("| Outcome | Mutants |\n| --- | ---: |\n");
+    for (name, count) in [
+        ("Missed", lab_outcome.missed),
+        ("Caught", lab_outcome.caught),
+        ("Timeout", lab_outcome.timeout),
+        ("Unviable", lab_outcome.unviable),
+        ("Equivalent", lab_outcome.equivalent),
+    ] {
+        if count > 0 {
+            writeln!(s, "| {name} | {count} |").unwrap();
+        }
+    }
+    let mut missed: Vec<&Mutant> = lab_outcome
+        .outcomes
+        .iter()
+        .filter(|outcome| outcome.summary() == SummaryOutcome::MissedMutant)
+        .filter_map(|outcome| outcome.scenario.mutant())
+        .collect();
+    missed.sort_by_key(|mutant| {
+        (
+            mutant.source_file.tree_relative_slashes(),
+            mutant.span.start.line,
+            mutant.span.start.column,
+        )
+    });
+    if !missed.is_empty() {
+        s.push_str("\n### Missed mutants\n\n");
+        for mutant in missed {
+            writeln!(
+                s,
+                "- `{}:{}:{}`: {}",
+                mutant.source_file.tree_relative_slashes(),
+                mutant.span.start.line,
+                mutant.span.start.column,
+                code_span(&mutant.describe_change())
+            )
+            .unwrap();
+        }
+    }
+    s
+}
+
+/// Append the summary to the job summary file if running in GitHub Actions, or otherwise
+/// print it.
+pub fn write_step_summary(summary: &str) -> Result<()> {
+    match env::var_os(STEP_SUMMARY_VAR) {
+        Some(path) if !path.is_empty() => {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("open {STEP_SUMMARY_VAR} file {path:?}"))?;
+            file.write_all(summary.as_bytes())
+                .with_context(|| format!("write {STEP_SUMMARY_VAR} file {path:?}"))
+        }
+        _ => {
+            print!("\n{summary}");
+            Ok(())
+        }
+    }
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property value in a workflow command.
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Format text as a Markdown code span, so that it's shown literally.
+fn code_span(s: &str) -> String {
+    if s.contains('`') {
+        format!("`` {s} ``")
+    } else {
+        format!("`{s}`")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn escape_workflow_command_values() {
+        assert_eq!(escape_data("50% of a:b,\nc"), "50%25 of a:b,%0Ac");
+        assert_eq!(escape_property("50% of a:b,\nc"), "50%25 of a%3Ab%2C%0Ac");
+    }
+
+    #[test]
+    fn code_spans() {
+        assert_eq!(code_span("replace a * b with 0"), "`replace a * b with 0`");
+        assert_eq!(code_span("replace `x` with 0"), "`` replace `x` with 0 ``");
+    }
+}
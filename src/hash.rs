@@ -0,0 +1,45 @@
+// Copyright 2025 Martin Pool
+
+//! Stable, non-cryptographic hashes.
+//!
+//! Unlike the standard library's hashers, these give the same result in every build and
+//! on every machine, so they can be used for values that are stored or compared across
+//! runs, such as shard assignments, cache keys, and report fingerprints.
+
+#![warn(clippy::pedantic)]
+
+/// The 64-bit FNV-1a hash.
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// The finalizer from `MurmurHash3`, which mixes the high bits of an FNV hash into the low
+/// bits: otherwise the remainder modulo a small number depends on very few bits of the
+/// input.
+pub fn fmix64(mut hash: u64) -> u64 {
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fnv1a_is_stable() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn fmix64_is_stable() {
+        assert_eq!(fmix64(0), 0);
+        assert_eq!(fmix64(1), 0xb456_bcfc_34c2_cb2c);
+    }
+}
@@ -0,0 +1,227 @@
+// Copyright 2025 Martin Pool
+
+//! Color unified diffs of Rust code for the terminal, for `--list --diff`.
+//!
+//! Removed and added lines are red and green, with keywords in bold and comments dimmed,
+//! and unchanged lines are syntax-highlighted. Each line is highlighted on its own with
+//! a simple lexer, because a hunk of a diff usually isn't valid Rust by itself; strings
+//! and block comments that continue onto the next line are just not highlighted there.
+
+#![warn(clippy::pedantic)]
+
+use console::Style;
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "yield",
+];
+
+/// The kinds of token that are highlighted differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Keyword,
+    /// An identifier starting with a capital letter, which is usually a type or variant.
+    TypeName,
+    Literal,
+    Comment,
+    Other,
+}
+
+/// Return a diff with ANSI colors for the terminal.
+///
+/// Styling is always applied: the caller should only call this if colors are enabled.
+pub fn highlight_diff(diff: &str) -> String {
+    let mut out = String::with_capacity(diff.len() * 2);
+    for line in diff.split_inclusive('\n') {
+        let (text, newline) = match line.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (line, ""),
+        };
+        if text.starts_with("--- ") || text.starts_with("+++ ") {
+            out.push_str(&style().bold().apply_to(text).to_string());
+        } else if text.starts_with("@@") {
+            out.push_str(&style().cyan().apply_to(text).to_string());
+        } else if let Some(code) = text.strip_prefix('-') {
+            highlight_changed_line(&mut out, '-', code, &style().red());
+        } else if let Some(code) = text.strip_prefix('+') {
+            highlight_changed_line(&mut out, '+', code, &style().green());
+        } else {
+            for (token, text) in tokens(text) {
+                let token_style = match token {
+                    Token::Keyword => style().blue().bold(),
+                    Token::TypeName => style().cyan(),
+                    Token::Literal => style().yellow(),
+                    Token::Comment => style().dim(),
+                    Token::Other => style(),
+                };
+                out.push_str(&token_style.apply_to(text).to_string());
+            }
+        }
+        out.push_str(newline);
+    }
+    out
+}
+
+fn style() -> Style {
+    Style::new().force_styling(true)
+}
+
+/// Highlight a removed or added line in a single color, emphasizing keywords and dimming
+/// comments.
+fn highlight_changed_line(out: &mut String, prefix: char, code: &str, line_style: &Style) {
+    // Only keywords and comments stand out, so plain runs are joined to keep the
+    // escapes short.
+    let mut plain = prefix.to_string();
+    for (token, text) in tokens(code) {
+        let token_style = match token {
+            Token::Keyword => line_style.clone().bold(),
+            Token::Comment => line_style.clone().dim(),
+            _ => {
+                plain.push_str(text);
+                continue;
+            }
+        };
+        if !plain.is_empty() {
+            out.push_str(&line_style.apply_to(&plain).to_string());
+            plain.clear();
+        }
+        out.push_str(&token_style.apply_to(text).to_string());
+    }
+    if !plain.is_empty() {
+        out.push_str(&line_style.apply_to(&plain).to_string());
+    }
+}
+
+/// Split one line of Rust code into tokens.
+fn tokens(line: &str) -> Vec<(Token, &str)> {
+    let mut tokens: Vec<(Token, &str)> = Vec::new();
+    let mut rest = line;
+    while let Some(c) = rest.chars().next() {
+        let len = if rest.starts_with("//") {
+            rest.len()
+        } else if rest.starts_with("/*") {
+            rest.find("*/").map_or(rest.len(), |i| i + 2)
+        } else if c == '"' {
+            string_len(rest)
+        } else if c == '\'' {
+            char_len(rest)
+        } else if c.is_alphanumeric() || c == '_' {
+            rest.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len())
+        } else {
+            c.len_utf8()
+        };
+        let (text, tail) = rest.split_at(len);
+        let token = if text.starts_with("//") || text.starts_with("/*") {
+            Token::Comment
+        } else if c == '"' || c.is_ascii_digit() || (c == '\'' && text.len() > 1) {
+            Token::Literal
+        } else if KEYWORDS.contains(&text) {
+            Token::Keyword
+        } else if c.is_uppercase() {
+            Token::TypeName
+        } else {
+            Token::Other
+        };
+        // Merge adjacent plain text, so that it's not styled one character at a time.
+        match tokens.last_mut() {
+            Some((last_token, last_text)) if *last_token == token && token == Token::Other => {
+                let start = line.len() - rest.len() - last_text.len();
+                *last_text = &line[start..line.len() - tail.len()];
+            }
+            _ => tokens.push((token, text)),
+        }
+        rest = tail;
+    }
+    tokens
+}
+
+/// The length of a string literal at the start of `s`, or the rest of the line if it's
+/// not closed.
+fn string_len(s: &str) -> usize {
+    let mut escaped = false;
+    for (i, c) in s.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => return i + 1,
+            _ => escaped = false,
+        }
+    }
+    s.len()
+}
+
+/// The length of a char literal at the start of `s`, or 1 if it's a lifetime or label
+/// rather than a char.
+fn char_len(s: &str) -> usize {
+    let mut chars = s.char_indices().skip(1);
+    match chars.next() {
+        Some((_, '\\')) => s[2..].find('\'').map_or(1, |i| i + 3),
+        Some((_, c)) => match chars.next() {
+            Some((i, '\'')) if c != '\'' => i + 1,
+            _ => 1,
+        },
+        None => 1,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use console::strip_ansi_codes;
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn tokenize_line() {
+        assert_eq!(
+            tokens(r#"    let s: &'a str = "a \"b\""; // hi"#),
+            [
+                (Token::Other, "    "),
+                (Token::Keyword, "let"),
+                (Token::Other, " s: &'a str = "),
+                (Token::Literal, r#""a \"b\"""#),
+                (Token::Other, "; "),
+                (Token::Comment, "// hi"),
+            ]
+        );
+        assert_eq!(
+            tokens("x * /* ~ changed by cargo-mutants ~ */ Some('\\n')"),
+            [
+                (Token::Other, "x * "),
+                (Token::Comment, "/* ~ changed by cargo-mutants ~ */"),
+                (Token::Other, " "),
+                (Token::TypeName, "Some"),
+                (Token::Other, "("),
+                (Token::Literal, "'\\n'"),
+                (Token::Other, ")"),
+            ]
+        );
+        assert_eq!(tokens("0x1f"), [(Token::Literal, "0x1f")]);
+    }
+
+    #[test]
+    fn highlighted_diff_has_the_same_text() {
+        let diff = indoc! { r"
+            --- src/lib.rs
+            +++ replace < with <= in is_small
+            @@ -1,3 +1,3 @@
+             pub fn is_small(x: u32) -> bool {
+            -    x < 4
+            +    x <= /* ~ changed by cargo-mutants ~ */ 4
+             }
+        " };
+        let highlighted = highlight_diff(diff);
+        assert_eq!(strip_ansi_codes(&highlighted), diff);
+        assert!(highlighted.contains(&style().blue().bold().apply_to("fn").to_string()));
+        assert!(highlighted.contains(&style().red().apply_to("-    x < 4").to_string()));
+        assert!(highlighted.contains(
+            &style()
+                .green()
+                .dim()
+                .apply_to("/* ~ changed by cargo-mutants ~ */")
+                .to_string()
+        ));
+    }
+}
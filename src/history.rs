@@ -0,0 +1,268 @@
+// Copyright 2025 Martin Pool
+
+//! A history of runs, appended to a JSON-lines file after each run with
+//! `--history-file`, and shown by `cargo mutants history`.
+//!
+//! Each line records the time, git commit, counts, score, and the names of the missed
+//! mutants, so that trends and newly missed mutants can be seen over weeks of runs.
+//! Mutants are named without their line and column, as in the missed baseline, so
+//! that unrelated edits don't make them look new.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::fs::{read_to_string, OpenOptions};
+use std::io::Write as _;
+use std::process::Command;
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use tracing::debug;
+
+use crate::outcome::{LabOutcome, SummaryOutcome};
+use crate::scores::{format_score, Tally};
+use crate::{Context, Result, Scenario};
+
+/// Which history to show.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+pub struct HistoryArgs {
+    /// Show at most this many of the most recent runs.
+    #[arg(long, default_value_t = 20)]
+    runs: usize,
+}
+
+/// One run, as recorded in the history file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryRecord {
+    /// When the run finished, in RFC 3339 format.
+    pub time: String,
+    /// The version of cargo-mutants.
+    pub cargo_mutants_version: String,
+    /// The git commit of the tree that was tested, if it's in a git repository.
+    pub commit: Option<String>,
+    pub total_mutants: usize,
+    pub caught: usize,
+    pub missed: usize,
+    pub timeout: usize,
+    pub unviable: usize,
+    /// The percentage of caught, missed, and timed out mutants that were caught.
+    pub score: Option<f64>,
+    /// Names of the missed mutants, without line numbers.
+    pub missed_mutants: BTreeSet<String>,
+}
+
+impl HistoryRecord {
+    pub fn new(lab_outcome: &LabOutcome, commit: Option<String>) -> HistoryRecord {
+        let missed_mutants = lab_outcome
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.summary() == SummaryOutcome::MissedMutant)
+            .filter_map(|outcome| match &outcome.scenario {
+                Scenario::Mutant(mutant) => Some(mutant.name(false)),
+                Scenario::Baseline => None,
+            })
+            .collect();
+        let score = Tally::from(lab_outcome).score();
+        HistoryRecord {
+            time: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .expect("format current time"),
+            cargo_mutants_version: crate::VERSION.to_owned(),
+            commit,
+            total_mutants: lab_outcome.total_mutants,
+            caught: lab_outcome.caught,
+            missed: lab_outcome.missed,
+            timeout: lab_outcome.timeout,
+            unviable: lab_outcome.unviable,
+            score,
+            missed_mutants,
+        }
+    }
+}
+
+/// Append a record of this run to the history file, creating it if necessary.
+pub fn append_history(
+    history_file: &Utf8Path,
+    workspace_root: &Utf8Path,
+    lab_outcome: &LabOutcome,
+) -> Result<()> {
+    let record = HistoryRecord::new(lab_outcome, git_commit(workspace_root));
+    let mut json = serde_json::to_string(&record).context("serialize history record")?;
+    json.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_file)
+        .and_then(|mut file| file.write_all(json.as_bytes()))
+        .with_context(|| format!("append to history file {history_file}"))
+}
+
+/// Read all the records from a history file, oldest first.
+pub fn read_history(history_file: &Utf8Path) -> Result<Vec<HistoryRecord>> {
+    let content = read_to_string(history_file)
+        .with_context(|| format!("read history file {history_file}"))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("parse line {} of history file {history_file}", i + 1))
+        })
+        .collect()
+}
+
+/// Describe the trend of the most recent runs, and how the missed mutants in the
+/// latest run differ from the run before.
+pub fn format_history(records: &[HistoryRecord], args: &HistoryArgs) -> String {
+    let mut s = String::new();
+    if records.is_empty() {
+        s.push_str("No runs recorded yet.\n");
+        return s;
+    }
+    writeln!(
+        s,
+        "{:<17}  {:<12}  {:>7}  {:>6}  {:>6}  {:>8}  {:>6}  {:>6}",
+        "Time", "Commit", "Mutants", "Caught", "Missed", "Timeouts", "Score", "Change"
+    )
+    .unwrap();
+    let first = records.len().saturating_sub(args.runs);
+    for (i, record) in records.iter().enumerate().skip(first) {
+        let change = match (
+            i.checked_sub(1).and_then(|j| records[j].score),
+            record.score,
+        ) {
+            (Some(previous), Some(score)) => format!("{:+.1}", score - previous),
+            _ => String::new(),
+        };
+        let row = format!(
+            "{:<17}  {:<12}  {:>7}  {:>6}  {:>6}  {:>8}  {:>6}  {:>6}",
+            format_time(&record.time),
+            record.commit.as_deref().unwrap_or("-"),
+            record.total_mutants,
+            record.caught,
+            record.missed,
+            record.timeout,
+            format_score(record.score),
+            change,
+        );
+        writeln!(s, "{}", row.trim_end()).unwrap();
+    }
+    if let [.., previous, latest] = records {
+        let newly_missed: Vec<&String> = latest
+            .missed_mutants
+            .difference(&previous.missed_mutants)
+            .collect();
+        let no_longer_missed: Vec<&String> = previous
+            .missed_mutants
+            .difference(&latest.missed_mutants)
+            .collect();
+        for (title, names) in [
+            ("Newly missed since the previous run", newly_missed),
+            ("No longer missed since the previous run", no_longer_missed),
+        ] {
+            if !names.is_empty() {
+                writeln!(s, "\n{title}:").unwrap();
+                for name in names {
+                    writeln!(s, "  {name}").unwrap();
+                }
+            }
+        }
+    }
+    s
+}
+
+/// Shorten an RFC 3339 time to the minute, like `2025-01-31 12:34`.
+fn format_time(time: &str) -> String {
+    time.get(..16).unwrap_or(time).replacen('T', " ", 1)
+}
+
+/// Return the abbreviated hash of the current git commit, if the tree is in git.
+fn git_commit(workspace_root: &Utf8Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .current_dir(workspace_root)
+        .output()
+        .inspect_err(|err| debug!(?err, "failed to run git rev-parse"))
+        .ok()?;
+    if output.status.success() {
+        Some(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use camino::Utf8PathBuf;
+    use indoc::indoc;
+
+    use super::*;
+
+    fn record(time: &str, caught: usize, missed: &[&str]) -> HistoryRecord {
+        let score = Tally {
+            caught,
+            missed: missed.len(),
+            ..Tally::default()
+        }
+        .score();
+        HistoryRecord {
+            time: time.to_owned(),
+            cargo_mutants_version: "25.0.1".to_owned(),
+            commit: Some("0123456789ab".to_owned()),
+            total_mutants: caught + missed.len(),
+            caught,
+            missed: missed.len(),
+            score,
+            missed_mutants: missed.iter().map(ToString::to_string).collect(),
+            ..HistoryRecord::default()
+        }
+    }
+
+    #[test]
+    fn append_and_read_history() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Utf8PathBuf::try_from(tmp.path().to_owned()).unwrap();
+        let path = dir.join("history.jsonl");
+        let lab_outcome = LabOutcome::new();
+        append_history(&path, &dir, &lab_outcome).unwrap();
+        append_history(&path, &dir, &lab_outcome).unwrap();
+        let records = read_history(&path).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].total_mutants, 0);
+        assert_eq!(records[0].score, None);
+        assert_eq!(records[0].cargo_mutants_version, crate::VERSION);
+    }
+
+    #[test]
+    fn format_trend_and_changes() {
+        let records = [
+            record("2025-01-01T09:00:00Z", 3, &["a.rs: x", "a.rs: y"]),
+            record("2025-01-08T09:00:00.5Z", 4, &["a.rs: x"]),
+            record("2025-01-15T09:00:00Z", 3, &["a.rs: x", "b.rs: z"]),
+        ];
+        assert_eq!(
+            format_history(&records, &HistoryArgs { runs: 2 }),
+            indoc! { "
+                Time               Commit        Mutants  Caught  Missed  Timeouts   Score  Change
+                2025-01-08 09:00   0123456789ab        5       4       1         0   80.0%   +20.0
+                2025-01-15 09:00   0123456789ab        5       3       2         0   60.0%   -20.0
+
+                Newly missed since the previous run:
+                  b.rs: z
+            " }
+        );
+    }
+
+    #[test]
+    fn format_empty_history() {
+        assert_eq!(
+            format_history(&[], &HistoryArgs { runs: 20 }),
+            "No runs recorded yet.\n"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright 2025 Martin Pool
+
+//! Shell commands run before and after each scenario, and before and after the whole run,
+//! from the `[hooks]` config table or the `--before-scenario` and similar options.
+//!
+//! Hooks can prepare or clean up state outside the build directory that the tests depend
+//! on, such as resetting a database. They're described to the hook through environment
+//! variables whose names start with `MUTANTS_`.
+//!
+//! A hook that fails stops the run, since later results would not be trustworthy.
+
+#![warn(clippy::pedantic)]
+
+use anyhow::bail;
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::console::Console;
+use crate::output::{OutputDir, ScenarioOutput};
+use crate::process::Process;
+use crate::{Result, Scenario, ScenarioOutcome};
+
+/// Shell commands to run around scenarios and the whole run.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Hooks {
+    /// Run in the source tree before testing the baseline or any mutants.
+    pub before_run: Option<String>,
+    /// Run in the source tree after all the mutants have been tested.
+    pub after_run: Option<String>,
+    /// Run in the build directory before building and testing each scenario.
+    pub before_scenario: Option<String>,
+    /// Run in the build directory after each scenario has been tested.
+    pub after_scenario: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        *self == Hooks::default()
+    }
+
+    /// Run the `before_run` hook, if there is one, with its output in `log/before_run.log`.
+    pub fn before_run(
+        &self,
+        workspace_root: &Utf8Path,
+        output_dir: &mut OutputDir,
+        env: &[(String, String)],
+        console: &Console,
+    ) -> Result<()> {
+        let Some(command) = &self.before_run else {
+            return Ok(());
+        };
+        run_hook_for_run(
+            "before_run",
+            command,
+            workspace_root,
+            output_dir,
+            env,
+            console,
+        )
+    }
+
+    /// Run the `after_run` hook, if there is one, with its output in `log/after_run.log`.
+    pub fn after_run(
+        &self,
+        workspace_root: &Utf8Path,
+        output_dir: &mut OutputDir,
+        env: &[(String, String)],
+        console: &Console,
+    ) -> Result<()> {
+        let Some(command) = &self.after_run else {
+            return Ok(());
+        };
+        run_hook_for_run(
+            "after_run",
+            command,
+            workspace_root,
+            output_dir,
+            env,
+            console,
+        )
+    }
+
+    /// Run the `before_scenario` hook, if there is one, with its output in the scenario's log.
+    pub fn before_scenario(
+        &self,
+        scenario: &Scenario,
+        build_dir: &Utf8Path,
+        env: &[(String, String)],
+        scenario_output: &mut ScenarioOutput,
+        console: &Console,
+    ) -> Result<()> {
+        let Some(command) = &self.before_scenario else {
+            return Ok(());
+        };
+        let env = scenario_env(env, scenario, build_dir, None);
+        run_hook(
+            "before_scenario",
+            command,
+            env,
+            build_dir,
+            scenario_output,
+            console,
+        )
+    }
+
+    /// Run the `after_scenario` hook, if there is one, with its output in the scenario's log.
+    pub fn after_scenario(
+        &self,
+        scenario: &Scenario,
+        outcome: &ScenarioOutcome,
+        build_dir: &Utf8Path,
+        env: &[(String, String)],
+        scenario_output: &mut ScenarioOutput,
+        console: &Console,
+    ) -> Result<()> {
+        let Some(command) = &self.after_scenario else {
+            return Ok(());
+        };
+        let env = scenario_env(env, scenario, build_dir, Some(outcome));
+        run_hook(
+            "after_scenario",
+            command,
+            env,
+            build_dir,
+            scenario_output,
+            console,
+        )
+    }
+}
+
+/// Run a hook for the whole run, in the source tree, with its output in its own log.
+fn run_hook_for_run(
+    name: &str,
+    command: &str,
+    workspace_root: &Utf8Path,
+    output_dir: &mut OutputDir,
+    env: &[(String, String)],
+    console: &Console,
+) -> Result<()> {
+    let mut hook_output = output_dir.start_named_log(name)?;
+    let mut env = env.to_vec();
+    env.push((
+        "MUTANTS_OUTPUT_DIR".to_owned(),
+        output_dir.path().to_string(),
+    ));
+    run_hook(
+        name,
+        command,
+        env,
+        workspace_root,
+        &mut hook_output,
+        console,
+    )
+}
+
+/// The environment variables describing a scenario to its hooks, in addition to `env`.
+fn scenario_env(
+    env: &[(String, String)],
+    scenario: &Scenario,
+    build_dir: &Utf8Path,
+    outcome: Option<&ScenarioOutcome>,
+) -> Vec<(String, String)> {
+    let mut env = env.to_vec();
+    let mut set = |name: &str, value: String| env.push((format!("MUTANTS_{name}"), value));
+    set("BUILD_DIR", build_dir.to_string());
+    match scenario {
+        Scenario::Baseline => set("SCENARIO", "baseline".to_owned()),
+        Scenario::Mutant(mutant) => {
+            set("SCENARIO", "mutant".to_owned());
+            set("NAME", mutant.name(true));
+            set("PACKAGE", mutant.source_file.package.name.clone());
+            set("FILE", mutant.source_file.tree_relative_slashes());
+            set("LINE", mutant.span.start.line.to_string());
+            set("GENRE", mutant.genre.to_string());
+            if let Some(function) = &mutant.function {
+                set("FUNCTION", function.function_name.clone());
+            }
+        }
+    }
+    if let Some(outcome) = outcome {
+        set("OUTCOME", format!("{:?}", outcome.summary()));
+    }
+    env
+}
+
+/// Run one hook through the shell, and fail if it doesn't succeed.
+fn run_hook(
+    name: &str,
+    command: &str,
+    mut env: Vec<(String, String)>,
+    cwd: &Utf8Path,
+    scenario_output: &mut ScenarioOutput,
+    console: &Console,
+) -> Result<()> {
+    debug!(?name, ?command, "run hook");
+    env.push(("MUTANTS_HOOK".to_owned(), name.to_owned()));
+    let argv: Vec<String> = if cfg!(windows) {
+        vec!["cmd".to_owned(), "/C".to_owned(), command.to_owned()]
+    } else {
+        vec!["sh".to_owned(), "-c".to_owned(), command.to_owned()]
+    };
+    scenario_output.message(&format!("{name} hook"))?;
+    let exit = Process::run(&argv, &env, cwd, None, None, scenario_output, console)?;
+    if !exit.is_success() {
+        bail!(
+            "{name} hook {command:?} failed with {exit:?}; see {}",
+            scenario_output.output_dir.join(scenario_output.log_path())
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn baseline_scenario_env() {
+        let env = scenario_env(
+            &[("A".to_owned(), "1".to_owned())],
+            &Scenario::Baseline,
+            Utf8Path::new("/tmp/build"),
+            None,
+        );
+        assert_eq!(
+            env,
+            [
+                ("A".to_owned(), "1".to_owned()),
+                ("MUTANTS_BUILD_DIR".to_owned(), "/tmp/build".to_owned()),
+                ("MUTANTS_SCENARIO".to_owned(), "baseline".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn hooks_from_toml() {
+        let hooks: Hooks = toml::from_str(r#"before_scenario = "make reset-db""#).unwrap();
+        assert_eq!(hooks.before_scenario.as_deref(), Some("make reset-db"));
+        assert!(hooks.after_scenario.is_none());
+        assert!(!hooks.is_empty());
+        assert!(Hooks::default().is_empty());
+        assert!(toml::from_str::<Hooks>("before_mutant = \"x\"").is_err());
+    }
+}
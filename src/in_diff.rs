@@ -4,18 +4,54 @@
 //! for example from uncommitted or unmerged changes.
 
 use std::collections::HashMap;
+use std::fs::read_to_string;
 use std::iter::once;
+use std::process::Command;
 
 use anyhow::{anyhow, bail};
 use camino::Utf8Path;
 use indoc::formatdoc;
 use itertools::Itertools;
 use patch::{Line, Patch};
-use tracing::{info, trace, warn};
+use tracing::{debug, info, trace, warn};
 
 use crate::mutant::Mutant;
 use crate::source::SourceFile;
-use crate::Result;
+use crate::{Context, Result};
+
+/// Read the diff given to `--in-diff`.
+///
+/// If there's a file of this name it's read as a diff, and otherwise it's taken as a
+/// git revision range, like `origin/main..HEAD` or `origin/main`, and git is run in the
+/// source tree to produce the diff.
+pub fn read_diff(in_diff: &str, tree_path: &Utf8Path) -> Result<String> {
+    if Utf8Path::new(in_diff).is_file() {
+        return read_to_string(in_diff).with_context(|| format!("Failed to read {in_diff}"));
+    }
+    let argv = [
+        "diff",
+        "--no-color",
+        "--no-ext-diff",
+        "--find-renames",
+        // Paths relative to the source tree, which might be a subdirectory of the repo.
+        "--relative",
+        in_diff,
+        "--",
+    ];
+    debug!(?argv, "run git diff");
+    let output = Command::new("git")
+        .args(argv)
+        .current_dir(tree_path)
+        .output()
+        .context("Failed to run git diff")?;
+    if !output.status.success() {
+        bail!(
+            "{in_diff:?} is not a diff file, and git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    String::from_utf8(output.stdout).context("git diff output is not UTF-8")
+}
 
 /// Return only mutants to functions whose source was touched by this diff.
 pub fn diff_filter(mutants: Vec<Mutant>, diff_text: &str) -> Result<Vec<Mutant>> {
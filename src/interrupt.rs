@@ -13,8 +13,14 @@ use crate::Result;
 static INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
 pub fn install_handler() {
-    ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst))
-        .expect("install ctrl-c handler");
+    ctrlc::set_handler(interrupt).expect("install ctrl-c handler");
+}
+
+/// Ask the program to stop, as if ctrl-c had been pressed.
+///
+/// This is used by the TUI, which gets ctrl-c as a key rather than a signal.
+pub fn interrupt() {
+    INTERRUPTED.store(true, Ordering::SeqCst);
 }
 
 /// Return an error if the program was interrupted and should exit.
@@ -6,17 +6,36 @@
 #![warn(clippy::pedantic)]
 
 use std::cmp::{max, min};
+use std::collections::{HashMap, VecDeque};
+use std::io::Write;
 use std::panic::resume_unwind;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
-use std::{thread, vec};
+use std::{iter, thread, vec};
 
+use camino::Utf8Path;
 use itertools::Itertools;
-use tracing::{debug, debug_span, error, trace, warn};
+use tracing::{debug, debug_span, error, info, trace, warn};
 
 use crate::{
-    cargo::run_cargo, options::TestPackages, outcome::LabOutcome, output::OutputDir,
-    package::Package, package::PackageSelection, timeouts::Timeouts, workspace::Workspace,
+    cargo::run_cargo,
+    coverage::Coverage,
+    equivalent::{artifact_hashes, artifacts_argv, is_equivalent, list_artifacts, ArtifactHashes},
+    interrupt::check_interrupted,
+    mutant::Genre,
+    options::{TestPackages, TestTool},
+    outcome::{LabOutcome, SummaryOutcome},
+    output::{clean_filename, OutputDir, ScenarioOutput},
+    package::Package,
+    package::PackageSelection,
+    prescreen::prescreen,
+    remote::{sync_trees, RemoteWorker},
+    schemata::{plan_schemata, Schema, ACTIVE_MUTANT_ENV},
+    scratch::choose_scratch_dir,
+    test_impact::{options_for_tests, TestMap, TEST_MAP_JSON},
+    timeouts::{PackageTestDurations, Timeouts},
+    timings::Estimate,
+    workspace::Workspace,
     BaselineStrategy, BuildDir, Console, Context, Mutant, Options, Phase, Result, Scenario,
     ScenarioOutcome,
 };
@@ -28,94 +47,206 @@ use crate::{
 ///
 /// Before testing the mutants, the lab checks that the source tree passes its tests with no
 /// mutations applied.
+#[allow(clippy::too_many_lines)] // long but pretty straightforward
 pub fn test_mutants(
     mut mutants: Vec<Mutant>,
     workspace: &Workspace,
-    output_dir: OutputDir,
+    mut output_dir: OutputDir,
     options: &Options,
     console: &Console,
 ) -> Result<LabOutcome> {
     let start_time = Instant::now();
     console.set_debug_log(output_dir.open_debug_log()?);
+    let seed = options.seed.unwrap_or_else(|| fastrand::u64(..));
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let n_discovered = mutants.len();
+    mutants = sample_by_genre(mutants, options, &mut rng);
+    if mutants.len() < n_discovered {
+        info!(
+            "Sampled {} of {} mutants; use --seed={seed} to repeat this selection",
+            mutants.len(),
+            n_discovered,
+        );
+    }
     if options.shuffle {
-        fastrand::shuffle(&mut mutants);
+        rng.shuffle(&mut mutants);
     }
     output_dir.write_mutants_list(&mutants)?;
+    if options.incremental {
+        let (cached, untested): (Vec<_>, Vec<_>) = mutants
+            .into_iter()
+            .map(|mutant| (output_dir.cached_outcome(&mutant, options).cloned(), mutant))
+            .partition(|(cached, _)| cached.is_some());
+        if !cached.is_empty() {
+            info!(
+                "Reused outcomes of {} unchanged mutants from earlier runs",
+                cached.len()
+            );
+        }
+        output_dir.add_cached_outcomes(
+            cached
+                .into_iter()
+                .map(|(cached, mutant)| (mutant, cached.expect("cached outcome")))
+                .collect(),
+        )?;
+        mutants = untested.into_iter().map(|(_, mutant)| mutant).collect();
+    }
+    if let Some(coverage_path) = &options.coverage {
+        let coverage = Coverage::load(coverage_path, workspace.root())?;
+        let (uncovered, covered): (Vec<_>, Vec<_>) = mutants
+            .into_iter()
+            .partition(|mutant| coverage.is_uncovered(mutant));
+        if !uncovered.is_empty() {
+            info!(
+                "{} mutants aren't executed by any tests according to {coverage_path}, so they're missed without testing",
+                uncovered.len()
+            );
+        }
+        output_dir.add_uncovered_outcomes(uncovered)?;
+        mutants = covered;
+    }
     console.discovered_mutants(&mutants);
     if mutants.is_empty() {
-        warn!("No mutants found under the active filters");
-        return Ok(LabOutcome::default());
+        if output_dir.lab_outcome.total_mutants > 0 {
+            info!("All the mutants already have outcomes, from an earlier run or from coverage");
+            console.lab_finished(&output_dir.lab_outcome, start_time, options);
+        } else {
+            warn!("No mutants found under the active filters");
+        }
+        output_dir.write_cache(options)?;
+        return Ok(output_dir.take_lab_outcome());
     }
+    let _tui = options.tui.then(|| console.start_tui());
+    options
+        .hooks
+        .before_run(workspace.root(), &mut output_dir, &options.env, console)?;
     let output_mutex = Mutex::new(output_dir);
-    let baseline_build_dir = BuildDir::for_baseline(workspace, options, console)?;
-    let jobserver = options
-        .jobserver
-        .then(|| {
-            let n_tasks = options.jobserver_tasks.unwrap_or_else(num_cpus::get);
-            debug!(n_tasks, "starting jobserver");
-            jobserver::Client::new(n_tasks)
-        })
-        .transpose()
-        .context("Start jobserver")?;
-    let tests_for_mutant = TestsForMutant::new(options, workspace);
-    let lab = Lab {
+    let scratch_dir = choose_scratch_dir(workspace.root(), options);
+    let baseline_build_dir =
+        BuildDir::for_baseline(workspace, scratch_dir.as_deref(), options, console)?;
+    let jobserver = start_jobserver(options)?;
+    let tests_for_mutant = TestsForMutant::new(options, workspace)?;
+    let mut lab = Lab {
         output_mutex,
         jobserver,
         tests_for_mutant,
         options,
         console,
+        package_timeouts: HashMap::new(),
+        test_map: None,
     };
-    let timeouts = match options.baseline {
+    // A resumed run reuses the baseline that passed before it was interrupted.
+    let resumed_baseline = lab
+        .output_mutex
+        .lock()
+        .expect("lock output_dir")
+        .lab_outcome
+        .outcomes
+        .iter()
+        .find(|outcome| !outcome.scenario.is_mutant())
+        .cloned();
+    let baseline_outcome = match options.baseline {
+        BaselineStrategy::Run if resumed_baseline.is_some() => resumed_baseline,
         BaselineStrategy::Run => {
             let outcome = lab.run_baseline(&baseline_build_dir, &mutants)?;
-            if outcome.success() {
-                Timeouts::from_baseline(&outcome, options)
-            } else {
+            if !outcome.success() {
                 error!(
                     "cargo {phase} failed in an unmutated tree, so no mutants were tested",
                     phase = outcome.last_phase(),
                 );
-                return Ok(lab
-                    .output_mutex
-                    .into_inner()
-                    .expect("lock output_dir")
-                    .take_lab_outcome());
+                let mut output_dir = lab.output_mutex.into_inner().expect("lock output_dir");
+                options.hooks.after_run(
+                    workspace.root(),
+                    &mut output_dir,
+                    &options.env,
+                    console,
+                )?;
+                output_dir.write_cache(options)?;
+                return Ok(output_dir.take_lab_outcome());
             }
+            Some(outcome)
         }
-        BaselineStrategy::Skip => Timeouts::without_baseline(options),
+        BaselineStrategy::Skip => None,
     };
-    debug!(?timeouts);
+    if !options.check_only {
+        lab.test_map = lab.load_or_generate_test_map(&baseline_build_dir, &mutants)?;
+    }
+    let timeouts_for = |options: &Options| match &baseline_outcome {
+        Some(outcome) => Timeouts::from_baseline(outcome, options),
+        None => Timeouts::without_baseline(options),
+    };
+    let timeouts = timeouts_for(options);
+    lab.package_timeouts = options
+        .per_package
+        .iter()
+        .map(|(name, package_options)| (name.clone(), timeouts_for(package_options)))
+        .collect();
+    if let Some(test_durations) = baseline_outcome
+        .as_ref()
+        .and_then(|outcome| lab.package_test_durations(&baseline_build_dir, outcome, &mutants))
+    {
+        lab.set_timeouts_from_package_tests(&test_durations, &mutants, timeouts);
+    }
+    debug!(?timeouts, package_timeouts = ?lab.package_timeouts);
+
+    let estimate = Estimate::new(
+        &mutants,
+        lab.output_mutex
+            .lock()
+            .expect("lock output_dir")
+            .previous_timings(),
+        min(options.jobs.unwrap_or(1), mutants.len()),
+    );
+    console.start_testing_mutants(estimate);
+    if options.prescreen && !options.check_only {
+        mutants = prescreen(
+            mutants,
+            &baseline_build_dir,
+            &lab.output_mutex,
+            options,
+            console,
+        )?;
+    }
 
     let build_dir_0 = Mutex::new(Some(baseline_build_dir));
+    // First thread to start can use the baseline's build dir; others need to copy a new one.
+    let take_build_dir = || -> Result<BuildDir> {
+        let build_dir_0 = build_dir_0.lock().expect("lock build dir 0").take(); // separate for lock
+        if let Some(d) = build_dir_0 {
+            Ok(d)
+        } else {
+            BuildDir::copy_from(workspace.root(), scratch_dir.as_deref(), options, console)
+        }
+    };
     // Create n threads, each dedicated to one build directory. Each of them tries to take a
     // scenario to test off the queue, and then exits when there are no more left.
-    console.start_testing_mutants(mutants.len());
     let n_threads = max(1, min(options.jobs.unwrap_or(1), mutants.len()));
-    let work_queue = &Mutex::new(mutants.into_iter());
-    thread::scope(|scope| -> crate::Result<()> {
-        let mut threads = Vec::new();
-        for _i_thread in 0..n_threads {
-            threads.push(scope.spawn(|| -> crate::Result<()> {
-                trace!(thread_id = ?thread::current().id(), "start thread");
-                // First thread to start can use the baseline's build dir;
-                // others need to copy a new one
-                let build_dir_0 = build_dir_0.lock().expect("lock build dir 0").take(); // separate for lock
-                let build_dir = &if let Some(d) = build_dir_0 {
-                    d
-                } else {
-                    BuildDir::copy_from(workspace.root(), options, console)?
-                };
-                lab.run_queue(build_dir, timeouts, work_queue)
-            }));
-        }
-        join_threads(threads)
-    })?;
-
-    let output_dir = lab
+    if options.check_first && !options.check_only {
+        lab.check_then_test(mutants, timeouts, n_threads, &take_build_dir)?;
+    } else if options.schemata && !options.check_only {
+        lab.test_schemata(mutants, timeouts, n_threads, &take_build_dir)?;
+    } else {
+        if !options.ssh_workers.is_empty() {
+            sync_trees(workspace.root(), options)?;
+        }
+        lab.test_all(
+            mutants,
+            timeouts,
+            n_threads,
+            &take_build_dir,
+            workspace.root(),
+        )?;
+    }
+
+    let mut output_dir = lab
         .output_mutex
         .into_inner()
         .expect("final unlock mutants queue");
+    options
+        .hooks
+        .after_run(workspace.root(), &mut output_dir, &options.env, console)?;
     console.lab_finished(&output_dir.lab_outcome, start_time, options);
+    output_dir.write_cache(options)?;
     let lab_outcome = output_dir.take_lab_outcome();
     if lab_outcome.total_mutants == 0 {
         // This should be unreachable as we also bail out before copying
@@ -127,19 +258,120 @@ pub fn test_mutants(
     Ok(lab_outcome)
 }
 
+/// Test mutants one at a time in a single build directory, as they're returned by `next`,
+/// and pass each outcome to `finished`, for a remote worker.
+pub fn test_each_mutant(
+    workspace: &Workspace,
+    output_dir: OutputDir,
+    options: &Options,
+    console: &Console,
+    mut next: impl FnMut() -> Result<Option<(Mutant, Timeouts)>>,
+    mut finished: impl FnMut(&ScenarioOutcome) -> Result<()>,
+) -> Result<()> {
+    let scratch_dir = choose_scratch_dir(workspace.root(), options);
+    let build_dir = BuildDir::for_baseline(workspace, scratch_dir.as_deref(), options, console)?;
+    let lab = Lab {
+        output_mutex: Mutex::new(output_dir),
+        jobserver: None,
+        tests_for_mutant: TestsForMutant::new(options, workspace)?,
+        options,
+        console,
+        package_timeouts: HashMap::new(),
+        test_map: None,
+    };
+    let mut worker = lab.make_worker(&build_dir);
+    while let Some((mutant, timeouts)) = next()? {
+        let _span = debug_span!("mutant", name = mutant.name(false)).entered();
+        let test_packages = worker.test_packages(&mutant);
+        let outcome =
+            worker.run_one_scenario(&Scenario::Mutant(mutant), &test_packages, timeouts)?;
+        finished(&outcome)?;
+    }
+    Ok(())
+}
+
+/// Start a jobserver to limit concurrency between child processes, if it's enabled.
+fn start_jobserver(options: &Options) -> Result<Option<jobserver::Client>> {
+    options
+        .jobserver
+        .then(|| {
+            let n_tasks = options.jobserver_tasks.unwrap_or_else(num_cpus::get);
+            debug!(n_tasks, "starting jobserver");
+            jobserver::Client::new(n_tasks)
+        })
+        .transpose()
+        .context("Start jobserver")
+}
+
+/// Select a random subset of the mutants of each genre, limited by
+/// [`Options::genre_sample_ratio`] and [`Options::max_mutants_per_genre`].
+///
+/// The selection depends only on the list of mutants and the state of the rng, so it's
+/// repeatable given the same seed. Selected mutants stay in their original order.
+fn sample_by_genre(
+    mutants: Vec<Mutant>,
+    options: &Options,
+    rng: &mut fastrand::Rng,
+) -> Vec<Mutant> {
+    if options.genre_sample_ratio.is_none() && options.max_mutants_per_genre.is_none() {
+        return mutants;
+    }
+    // Group indexes by genre, in order of first appearance so that the rng is consumed
+    // in a stable order.
+    let mut groups: Vec<(Genre, Vec<usize>)> = Vec::new();
+    for (i, mutant) in mutants.iter().enumerate() {
+        match groups.iter_mut().find(|(genre, _)| *genre == mutant.genre) {
+            Some((_, indexes)) => indexes.push(i),
+            None => groups.push((mutant.genre, vec![i])),
+        }
+    }
+    let mut keep = vec![false; mutants.len()];
+    for (genre, mut indexes) in groups {
+        let available = indexes.len();
+        let mut n = options.genre_sample_ratio.map_or(available, |ratio| {
+            #[allow(
+                clippy::cast_precision_loss,
+                clippy::cast_possible_truncation,
+                clippy::cast_sign_loss
+            )]
+            let n = (available as f64 * ratio).ceil() as usize;
+            n
+        });
+        if let Some(max) = options.max_mutants_per_genre {
+            n = min(n, max);
+        }
+        if n < available {
+            debug!(%genre, available, n, "sample mutants of genre");
+            rng.shuffle(&mut indexes);
+        }
+        for &i in indexes.iter().take(n) {
+            keep[i] = true;
+        }
+    }
+    mutants
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(mutant, keep)| keep.then_some(mutant))
+        .collect()
+}
+
 #[mutants::skip] // it's a little hard to observe that the threads were collected?
-fn join_threads(threads: Vec<thread::ScopedJoinHandle<'_, Result<()>>>) -> Result<()> {
+fn join_threads<T>(threads: Vec<thread::ScopedJoinHandle<'_, Result<T>>>) -> Result<Vec<T>> {
     // The errors potentially returned from `join` are a special `std::thread::Result`
     // that does not implement error, indicating that the thread panicked.
     // Probably the most useful thing is to `resume_unwind` it.
     // Inside that, there's an actual Mutants error indicating a non-panic error.
     // Most likely, this would be "interrupted" but it might be some IO error
     // etc. In that case, print them all and return the first.
+    let mut values = Vec::new();
     let errors = threads
         .into_iter()
         .filter_map(|thread| match thread.join() {
             Err(panic) => resume_unwind(panic),
-            Ok(Ok(())) => None,
+            Ok(Ok(value)) => {
+                values.push(value);
+                None
+            }
             Ok(Err(err)) => {
                 // To avoid console spam don't print "interrupted" errors for each thread,
                 // since that should have been printed by check_interrupted but do return them.
@@ -153,10 +385,20 @@ fn join_threads(threads: Vec<thread::ScopedJoinHandle<'_, Result<()>>>) -> Resul
     if let Some(first_err) = errors.into_iter().next() {
         Err(first_err)
     } else {
-        Ok(())
+        Ok(values)
     }
 }
 
+/// The packages containing any of the mutants, in order of their names.
+fn mutated_packages(mutants: &[Mutant]) -> Vec<Arc<Package>> {
+    mutants
+        .iter()
+        .map(|m| Arc::clone(&m.source_file.package))
+        .sorted_by_key(|p| p.name.clone())
+        .unique()
+        .collect_vec()
+}
+
 /// Common context across all scenarios, threads, and build dirs.
 struct Lab<'a> {
     output_mutex: Mutex<OutputDir>,
@@ -164,6 +406,10 @@ struct Lab<'a> {
     tests_for_mutant: TestsForMutant,
     options: &'a Options,
     console: &'a Console,
+    /// Timeouts for mutants in packages that have their own options, by package name.
+    package_timeouts: HashMap<String, Timeouts>,
+    /// The tests that cover each function, for `--test-impact` and `--test-map`.
+    test_map: Option<TestMap>,
 }
 
 impl Lab<'_> {
@@ -174,19 +420,322 @@ impl Lab<'_> {
     ///
     /// If it succeeds, return the timeouts to be used for the other scenarios.
     fn run_baseline(&self, build_dir: &BuildDir, mutants: &[Mutant]) -> Result<ScenarioOutcome> {
-        let all_mutated_packages: Vec<Arc<Package>> = mutants
-            .iter()
-            .map(|m| Arc::clone(&m.source_file.package))
-            .sorted_by_key(|p| p.name.clone())
-            .unique()
-            .collect_vec();
         self.make_worker(build_dir).run_one_scenario(
             &Scenario::Baseline,
-            &PackageSelection::Explicit(all_mutated_packages),
+            &PackageSelection::Explicit(mutated_packages(mutants)),
             Timeouts::for_baseline(self.options),
         )
     }
 
+    /// Find how long the tests of each mutated package took in the baseline, if mutants
+    /// run only the tests of their own package and there's more than one package.
+    fn package_test_durations(
+        &self,
+        build_dir: &BuildDir,
+        baseline: &ScenarioOutcome,
+        mutants: &[Mutant],
+    ) -> Option<PackageTestDurations> {
+        let options = self.options;
+        if options.check_only
+            || options.test_timeout.is_some()
+            || options.test_tool != TestTool::Cargo
+            || !matches!(self.tests_for_mutant, TestsForMutant::Mutated)
+        {
+            return None;
+        }
+        let packages = mutated_packages(mutants);
+        if packages.len() < 2 {
+            return None;
+        }
+        let argv = artifacts_argv(&PackageSelection::Explicit(packages.clone()), options);
+        let artifacts_json = list_artifacts(build_dir, &argv, options)
+            .inspect_err(|err| warn!("Failed to list test binaries to set timeouts: {err:#}"))
+            .ok()?;
+        let durations = PackageTestDurations::from_baseline(
+            baseline,
+            &artifacts_json,
+            build_dir.path(),
+            &packages.iter().map(AsRef::as_ref).collect_vec(),
+        );
+        debug!(?durations, "baseline test durations by package");
+        durations
+    }
+
+    /// Set the test timeout for the mutants in each package from how long that package's
+    /// tests took in the baseline.
+    fn set_timeouts_from_package_tests(
+        &mut self,
+        test_durations: &PackageTestDurations,
+        mutants: &[Mutant],
+        timeouts: Timeouts,
+    ) {
+        for package in mutated_packages(mutants) {
+            let Some(test_duration) = test_durations.for_package(&package.name) else {
+                continue;
+            };
+            let package_timeouts = self
+                .package_timeouts
+                .get(&package.name)
+                .copied()
+                .unwrap_or(timeouts)
+                .for_test_duration(test_duration, self.options.for_package(&package.name));
+            self.package_timeouts
+                .insert(package.name.clone(), package_timeouts);
+        }
+        let Some((shortest, longest)) = self
+            .package_timeouts
+            .values()
+            .filter_map(|timeouts| timeouts.test)
+            .minmax()
+            .into_option()
+        else {
+            return;
+        };
+        if self.options.show_times && shortest != longest {
+            info!(
+                "Auto-set test timeouts for each package from {} to {}",
+                humantime::format_duration(shortest),
+                humantime::format_duration(longest)
+            );
+        }
+    }
+
+    /// Load the test map given by `--test-map`, or with `--test-impact` find which tests
+    /// cover each function in the unmutated build dir and write the map to the output
+    /// directory.
+    fn load_or_generate_test_map(
+        &self,
+        build_dir: &BuildDir,
+        mutants: &[Mutant],
+    ) -> Result<Option<TestMap>> {
+        let test_map = if let Some(path) = &self.options.test_map {
+            TestMap::load(path)?
+        } else if self.options.test_impact {
+            let mut output_dir = self.output_mutex.lock().expect("lock output_dir");
+            let mut log = output_dir.start_named_log("test_impact")?;
+            let packages = PackageSelection::Explicit(
+                mutants
+                    .iter()
+                    .map(|m| Arc::clone(&m.source_file.package))
+                    .sorted_by_key(|p| p.name.clone())
+                    .unique()
+                    .collect_vec(),
+            );
+            let test_map = TestMap::generate(build_dir, &packages, self.options, &mut log)
+                .context("Failed to find the tests covering each function: check that cargo-llvm-cov is installed")?;
+            test_map.write(&output_dir.path().join(TEST_MAP_JSON))?;
+            test_map
+        } else {
+            return Ok(None);
+        };
+        info!(
+            "Running only the tests that cover each mutant, from a map of {} tests",
+            test_map.tests.len()
+        );
+        Ok(Some(test_map))
+    }
+
+    /// Build and test all the mutants, using `n_threads` build directories, and any SSH
+    /// workers, which already have a copy of the tree.
+    fn test_all(
+        &self,
+        mutants: Vec<Mutant>,
+        timeouts: Timeouts,
+        n_threads: usize,
+        take_build_dir: &(dyn Fn() -> Result<BuildDir> + Sync),
+        tree_root: &Utf8Path,
+    ) -> Result<()> {
+        let work_queue = &Mutex::new(VecDeque::from(mutants));
+        thread::scope(|scope| -> crate::Result<()> {
+            let mut threads = Vec::new();
+            for _i_thread in 0..n_threads {
+                threads.push(scope.spawn(|| -> crate::Result<()> {
+                    trace!(thread_id = ?thread::current().id(), "start thread");
+                    let build_dir = &take_build_dir()?;
+                    self.run_queue(build_dir, timeouts, work_queue)
+                }));
+            }
+            for (i, host) in self.options.ssh_workers.iter().enumerate() {
+                threads.push(scope.spawn(move || -> crate::Result<()> {
+                    trace!(thread_id = ?thread::current().id(), host, "start remote thread");
+                    self.run_remote_queue(i + 1, host, tree_root, timeouts, work_queue)
+                }));
+            }
+            join_threads(threads)?;
+            // A remote worker that failed after the local threads finished may have
+            // returned a mutant to the queue.
+            if !work_queue.lock().expect("lock work queue").is_empty() {
+                debug!("testing mutants returned by failed remote workers");
+                self.run_queue(&take_build_dir()?, timeouts, work_queue)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Test mutants from the queue on a worker on a remote host, until the queue is empty.
+    ///
+    /// If the worker can't be started or fails, for example because the connection is
+    /// lost, a warning is shown, the mutant it was testing is returned to the queue to be
+    /// tested by another worker, and this worker stops.
+    fn run_remote_queue(
+        &self,
+        worker_number: usize,
+        host: &str,
+        tree_root: &Utf8Path,
+        timeouts: Timeouts,
+        work_queue: &Mutex<VecDeque<Mutant>>,
+    ) -> Result<()> {
+        let name = format!("ssh_{worker_number}_{host}");
+        let _span = debug_span!("remote worker", name).entered();
+        let worker_log = self
+            .output_mutex
+            .lock()
+            .expect("lock output_dir to start remote worker")
+            .start_named_log(&clean_filename(&name))?;
+        let mut remote = match RemoteWorker::start(host, tree_root, self.options, &worker_log) {
+            Ok(remote) => remote,
+            Err(err) => {
+                warn!("Failed to start worker on {host}: {err:#}; continuing without it");
+                return Ok(());
+            }
+        };
+        // The console shows progress for each worker as if it were a build dir.
+        let dir = Utf8Path::new(&name);
+        loop {
+            check_interrupted()?;
+            let Some(mutant) = work_queue
+                .lock()
+                .expect("Lock pending work queue")
+                .pop_front()
+            else {
+                return remote.finish();
+            };
+            let _span = debug_span!("mutant", name = mutant.name(false)).entered();
+            let timeouts = self
+                .package_timeouts
+                .get(&mutant.source_file.package.name)
+                .copied()
+                .unwrap_or(timeouts);
+            let diff = mutant.diff(&mutant.mutated_code());
+            let scenario = Scenario::Mutant(mutant);
+            let mut scenario_output = self
+                .output_mutex
+                .lock()
+                .expect("lock output_dir to start scenario")
+                .start_scenario(&scenario)?;
+            self.console.scenario_started(
+                dir,
+                &scenario,
+                scenario_output.open_log_read()?,
+                self.options,
+            );
+            scenario_output.write_diff(&diff)?;
+            scenario_output.message(&format!("tested on {host}"))?;
+            let mutant = scenario.mutant().expect("scenario is a mutant");
+            let remote_outcome = match remote.test(mutant, timeouts) {
+                Ok(remote_outcome) => remote_outcome,
+                Err(err) => {
+                    warn!("Worker on {host} failed: {err:#}; continuing without it");
+                    scenario_output.message(&format!(
+                        "worker on {host} failed; the mutant will be tested again: {err:#}"
+                    ))?;
+                    self.console.scenario_deferred(dir);
+                    work_queue
+                        .lock()
+                        .expect("Lock pending work queue")
+                        .push_front(mutant.clone());
+                    return Ok(());
+                }
+            };
+            scenario_output
+                .open_log_append()?
+                .write_all(remote_outcome.log.as_bytes())
+                .context("write remote log")?;
+            let mut outcome = ScenarioOutcome::new(&scenario_output, scenario.clone());
+            for phase_result in remote_outcome.phase_results {
+                outcome.add_phase_result(phase_result);
+            }
+            if remote_outcome.summary == SummaryOutcome::Equivalent {
+                outcome.set_equivalent();
+            }
+            self.output_mutex
+                .lock()
+                .expect("lock output dir to add outcome")
+                .add_scenario_outcome(&outcome)?;
+            self.console
+                .scenario_finished(dir, &scenario, &outcome, self.options);
+        }
+    }
+
+    /// Build the mutants of each file together in schemata, and test them one at a time
+    /// without rebuilding, using up to `n_threads` build directories.
+    fn test_schemata(
+        &self,
+        mutants: Vec<Mutant>,
+        timeouts: Timeouts,
+        n_threads: usize,
+        take_build_dir: &(dyn Fn() -> Result<BuildDir> + Sync),
+    ) -> Result<()> {
+        let schemata = plan_schemata(mutants);
+        debug!(n_schemata = schemata.len(), "planned schemata");
+        let n_threads = min(n_threads, schemata.len());
+        let work_queue = &Mutex::new(schemata.into_iter());
+        thread::scope(|scope| -> crate::Result<()> {
+            let mut threads = Vec::new();
+            for _i_thread in 0..n_threads {
+                threads.push(scope.spawn(|| -> crate::Result<()> {
+                    trace!(thread_id = ?thread::current().id(), "start schema thread");
+                    let build_dir = &take_build_dir()?;
+                    self.make_worker(build_dir)
+                        .run_schema_queue(work_queue, timeouts)
+                }));
+            }
+            join_threads(threads).map(|_| ())
+        })
+    }
+
+    /// Check every mutant first, and then build and test only the ones that passed,
+    /// reusing the same build directories.
+    fn check_then_test(
+        &self,
+        mutants: Vec<Mutant>,
+        timeouts: Timeouts,
+        n_threads: usize,
+        take_build_dir: &(dyn Fn() -> Result<BuildDir> + Sync),
+    ) -> Result<()> {
+        let check_queue = Mutex::new(mutants.into_iter().enumerate());
+        let checked = Mutex::new(Vec::new());
+        let build_dirs = thread::scope(|scope| -> crate::Result<Vec<BuildDir>> {
+            let mut threads = Vec::new();
+            for _i_thread in 0..n_threads {
+                threads.push(scope.spawn(|| -> crate::Result<BuildDir> {
+                    trace!(thread_id = ?thread::current().id(), "start check thread");
+                    let build_dir = take_build_dir()?;
+                    self.make_worker(&build_dir)
+                        .check_queue(&check_queue, timeouts, &checked)?;
+                    Ok(build_dir)
+                }));
+            }
+            join_threads(threads)
+        })?;
+        let mut checked = checked.into_inner().expect("lock checked mutants");
+        checked.sort_by_key(|checked_mutant| checked_mutant.index);
+        debug!(n_viable = checked.len(), "finished checking mutants");
+        let n_threads = min(build_dirs.len(), checked.len());
+        let work_queue = Mutex::new(checked.into_iter());
+        thread::scope(|scope| -> crate::Result<()> {
+            let mut threads = Vec::new();
+            for build_dir in &build_dirs[..n_threads] {
+                threads.push(scope.spawn(|| -> crate::Result<()> {
+                    trace!(thread_id = ?thread::current().id(), "start test thread");
+                    self.make_worker(build_dir)
+                        .run_checked_queue(&work_queue, timeouts)
+                }));
+            }
+            join_threads(threads).map(|_| ())
+        })
+    }
+
     /// Run until the input queue is empty.
     ///
     /// The queue, inside a mutex, can be consumed by multiple threads.
@@ -194,7 +743,7 @@ impl Lab<'_> {
         &self,
         build_dir: &BuildDir,
         timeouts: Timeouts,
-        work_queue: &Mutex<vec::IntoIter<Mutant>>,
+        work_queue: &Mutex<VecDeque<Mutant>>,
     ) -> Result<()> {
         self.make_worker(build_dir).run_queue(work_queue, timeouts)
     }
@@ -207,6 +756,9 @@ impl Lab<'_> {
             tests_for_mutant: &self.tests_for_mutant,
             options: self.options,
             console: self.console,
+            package_timeouts: &self.package_timeouts,
+            test_map: self.test_map.as_ref(),
+            baseline_artifacts: HashMap::new(),
         }
     }
 }
@@ -222,31 +774,253 @@ struct Worker<'a> {
     tests_for_mutant: &'a TestsForMutant,
     options: &'a Options,
     console: &'a Console,
+    package_timeouts: &'a HashMap<String, Timeouts>,
+    test_map: Option<&'a TestMap>,
+    /// Hashes of the test binaries built from the unmutated tree in this build dir, keyed
+    /// by the command that lists them, for `--detect-equivalent`.
+    baseline_artifacts: HashMap<Vec<String>, ArtifactHashes>,
 }
 
-impl Worker<'_> {
+/// A mutant that passed `cargo check` in the first stage of `--check-first`, and is
+/// waiting to be built and tested.
+struct CheckedMutant {
+    /// The position of the mutant in the original list, so that the original order can
+    /// be restored.
+    index: usize,
+    scenario: Scenario,
+    scenario_output: ScenarioOutput,
+    outcome: ScenarioOutcome,
+}
+
+impl<'a> Worker<'a> {
     /// Run until the input queue is empty.
-    fn run_queue(
-        mut self,
-        work_queue: &Mutex<vec::IntoIter<Mutant>>,
-        timeouts: Timeouts,
-    ) -> Result<()> {
+    fn run_queue(mut self, work_queue: &Mutex<VecDeque<Mutant>>, timeouts: Timeouts) -> Result<()> {
         let _span = debug_span!("worker thread", build_dir = ?self.build_dir.path()).entered();
         loop {
             // Not a `for` statement so that we don't hold the lock
             // for the whole iteration.
-            let Some(mutant) = work_queue.lock().expect("Lock pending work queue").next() else {
+            let Some(mutant) = work_queue
+                .lock()
+                .expect("Lock pending work queue")
+                .pop_front()
+            else {
                 return Ok(());
             };
             let _span = debug_span!("mutant", name = mutant.name(false)).entered();
-            let test_packages = match self.tests_for_mutant {
-                TestsForMutant::Workspace => PackageSelection::All,
-                TestsForMutant::Mutated => {
-                    PackageSelection::Explicit(vec![mutant.source_file.package.clone()])
-                }
-                TestsForMutant::Explicit(packages) => PackageSelection::Explicit(packages.clone()),
+            let test_packages = self.test_packages(&mutant);
+            self.run_one_scenario(&Scenario::Mutant(mutant), &test_packages, timeouts)?;
+        }
+    }
+
+    /// Check mutants from the queue until it is empty.
+    ///
+    /// Mutants that fail the check are finished as unviable; the others are added to
+    /// `checked` to be built and tested later.
+    fn check_queue(
+        mut self,
+        work_queue: &Mutex<iter::Enumerate<vec::IntoIter<Mutant>>>,
+        timeouts: Timeouts,
+        checked: &Mutex<Vec<CheckedMutant>>,
+    ) -> Result<()> {
+        let _span = debug_span!("check thread", build_dir = ?self.build_dir.path()).entered();
+        loop {
+            let Some((index, mutant)) = work_queue.lock().expect("Lock check queue").next() else {
+                return Ok(());
+            };
+            let _span = debug_span!("check mutant", name = mutant.name(false)).entered();
+            let test_packages = self.test_packages(&mutant);
+            let scenario = Scenario::Mutant(mutant);
+            let (mut scenario_output, mut outcome) = self.start_scenario(&scenario)?;
+            if self.run_phases(
+                &scenario,
+                &[Phase::Check],
+                &test_packages,
+                timeouts,
+                &mut scenario_output,
+                &mut outcome,
+            )? {
+                self.console.scenario_deferred(self.build_dir.path());
+                checked
+                    .lock()
+                    .expect("lock checked mutants")
+                    .push(CheckedMutant {
+                        index,
+                        scenario,
+                        scenario_output,
+                        outcome,
+                    });
+            } else {
+                self.finish_scenario(&scenario, &outcome)?;
+            }
+        }
+    }
+
+    /// Build and test mutants that already passed `cargo check`, until the queue is empty.
+    fn run_checked_queue(
+        mut self,
+        work_queue: &Mutex<vec::IntoIter<CheckedMutant>>,
+        timeouts: Timeouts,
+    ) -> Result<()> {
+        let _span = debug_span!("test thread", build_dir = ?self.build_dir.path()).entered();
+        loop {
+            let Some(CheckedMutant {
+                scenario,
+                mut scenario_output,
+                mut outcome,
+                ..
+            }) = work_queue.lock().expect("Lock checked queue").next()
+            else {
+                return Ok(());
             };
+            let mutant = scenario.mutant().expect("checked scenario is a mutant");
+            let _span = debug_span!("mutant", name = mutant.name(false)).entered();
+            let test_packages = self.test_packages(mutant);
+            self.console.scenario_started(
+                self.build_dir.path(),
+                &scenario,
+                scenario_output.open_log_read()?,
+                self.options,
+            );
+            self.before_scenario(&scenario, &mut scenario_output)?;
+            self.run_phases(
+                &scenario,
+                self.options.phases(),
+                &test_packages,
+                timeouts,
+                &mut scenario_output,
+                &mut outcome,
+            )?;
+            self.after_scenario(&scenario, &outcome, &mut scenario_output)?;
+            self.finish_scenario(&scenario, &outcome)?;
+        }
+    }
+
+    /// Build and test schemata from the queue until it is empty.
+    fn run_schema_queue(
+        mut self,
+        work_queue: &Mutex<vec::IntoIter<Schema>>,
+        timeouts: Timeouts,
+    ) -> Result<()> {
+        let _span = debug_span!("schema thread", build_dir = ?self.build_dir.path()).entered();
+        loop {
+            let Some(schema) = work_queue.lock().expect("Lock schema queue").next() else {
+                return Ok(());
+            };
+            self.test_schema(schema, timeouts)?;
+        }
+    }
+
+    /// Build all the mutants in a schema at once, and then run the tests for each of them
+    /// with the environment variable that activates it.
+    ///
+    /// If the schema doesn't build, split it in half and try each half, until the
+    /// mutants that don't build are tested on their own.
+    fn test_schema(&mut self, schema: Schema, timeouts: Timeouts) -> Result<()> {
+        if schema.mutants.len() == 1 {
+            let mutant = schema.mutants.into_iter().next().expect("one mutant");
+            let _span = debug_span!("mutant", name = mutant.name(false)).entered();
+            let test_packages = self.test_packages(&mutant);
             self.run_one_scenario(&Scenario::Mutant(mutant), &test_packages, timeouts)?;
+            return Ok(());
+        }
+        let dir = self.build_dir.path();
+        let first = &schema.mutants[0];
+        let _span = debug_span!("schema", name = schema.log_name()).entered();
+        let test_packages = self.test_packages(first);
+        let first_scenario = Scenario::Mutant(first.clone());
+        let (options, mutant_timeouts) = self.options_and_timeouts(&first_scenario, timeouts);
+        let mut schema_output = self
+            .output_mutex
+            .lock()
+            .expect("lock output_dir to start schema")
+            .start_named_log(&schema.log_name())?;
+        schema_output.message(&format!(
+            "build {} mutants in {} together",
+            schema.mutants.len(),
+            first.source_file.tree_relative_slashes()
+        ))?;
+        // Progress is shown for the first mutant while the schema builds.
+        self.console.scenario_started(
+            dir,
+            &first_scenario,
+            schema_output.open_log_read()?,
+            self.options,
+        );
+        first.apply(self.build_dir, &schema.code())?;
+        self.console.scenario_phase_started(dir, Phase::Build);
+        let build_result = match run_cargo(
+            self.build_dir,
+            self.jobserver,
+            &test_packages,
+            Phase::Build,
+            mutant_timeouts.build,
+            &mut schema_output,
+            options,
+            self.console,
+        ) {
+            Ok(build_result) => build_result,
+            Err(err) => {
+                first.revert(self.build_dir)?;
+                return Err(err);
+            }
+        };
+        self.console.scenario_phase_finished(dir, Phase::Build);
+        self.console.scenario_deferred(dir);
+        if !build_result.is_success() {
+            first.revert(self.build_dir)?;
+            debug!("schema failed to build; splitting it");
+            let (a, b) = schema.split();
+            self.test_schema(a, timeouts)?;
+            return self.test_schema(b, timeouts);
+        }
+        let schema_log = schema_output.log_path().to_owned();
+        for (i, mutant) in schema.mutants.iter().enumerate() {
+            let id = (i + 1).to_string();
+            let _span = debug_span!("mutant", name = mutant.name(false)).entered();
+            let scenario = Scenario::Mutant(mutant.clone());
+            let (mut scenario_output, mut outcome) = self.start_scenario(&scenario)?;
+            self.before_scenario(&scenario, &mut scenario_output)?;
+            scenario_output.message(&format!(
+                "built together with the other mutants in {schema_log}, \
+                and tested with {ACTIVE_MUTANT_ENV}={id}"
+            ))?;
+            outcome.add_phase_result(build_result.clone());
+            let mut test_options = self
+                .impact_options(&scenario, options, &mut scenario_output)?
+                .unwrap_or_else(|| options.clone());
+            test_options.env.push((ACTIVE_MUTANT_ENV.to_owned(), id));
+            self.console.scenario_phase_started(dir, Phase::Test);
+            match run_cargo(
+                self.build_dir,
+                self.jobserver,
+                &test_packages,
+                Phase::Test,
+                mutant_timeouts.test,
+                &mut scenario_output,
+                &test_options,
+                self.console,
+            ) {
+                Ok(test_result) => outcome.add_phase_result(test_result),
+                Err(err) => {
+                    first.revert(self.build_dir)?;
+                    return Err(err);
+                }
+            }
+            self.console.scenario_phase_finished(dir, Phase::Test);
+            self.after_scenario(&scenario, &outcome, &mut scenario_output)?;
+            self.finish_scenario(&scenario, &outcome)?;
+        }
+        first.revert(self.build_dir)
+    }
+
+    /// Choose which packages to test for a mutant.
+    fn test_packages(&self, mutant: &Mutant) -> PackageSelection {
+        match self.tests_for_mutant {
+            TestsForMutant::Workspace => PackageSelection::All,
+            TestsForMutant::Mutated => {
+                PackageSelection::Explicit(vec![mutant.source_file.package.clone()])
+            }
+            TestsForMutant::Explicit(packages) => PackageSelection::Explicit(packages.clone()),
         }
     }
 
@@ -256,29 +1030,165 @@ impl Worker<'_> {
         test_packages: &PackageSelection,
         timeouts: Timeouts,
     ) -> Result<ScenarioOutcome> {
+        let (mut scenario_output, mut outcome) = self.start_scenario(scenario)?;
+        self.before_scenario(scenario, &mut scenario_output)?;
+        self.run_phases(
+            scenario,
+            self.options.phases(),
+            test_packages,
+            timeouts,
+            &mut scenario_output,
+            &mut outcome,
+        )?;
+        self.after_scenario(scenario, &outcome, &mut scenario_output)?;
+        self.finish_scenario(scenario, &outcome)?;
+        Ok(outcome)
+    }
+
+    /// Allocate output files for a scenario, and show that it started.
+    fn start_scenario(&self, scenario: &Scenario) -> Result<(ScenarioOutput, ScenarioOutcome)> {
         let mut scenario_output = self
             .output_mutex
             .lock()
             .expect("lock output_dir to start scenario")
             .start_scenario(scenario)?;
-        let dir = self.build_dir.path();
-        self.console
-            .scenario_started(dir, scenario, scenario_output.open_log_read()?);
-        debug!(?test_packages);
-
+        self.console.scenario_started(
+            self.build_dir.path(),
+            scenario,
+            scenario_output.open_log_read()?,
+            self.options,
+        );
         if let Some(mutant) = scenario.mutant() {
-            let mutated_code = mutant.mutated_code();
-            let diff = scenario.mutant().unwrap().diff(&mutated_code);
+            let diff = mutant.diff(&mutant.mutated_code());
             scenario_output.write_diff(&diff)?;
-            mutant.apply(self.build_dir, &mutated_code)?;
         }
+        let outcome = ScenarioOutcome::new(&scenario_output, scenario.clone());
+        Ok((scenario_output, outcome))
+    }
+
+    /// Run the `before_scenario` hook, if any, before the mutant is applied.
+    fn before_scenario(
+        &self,
+        scenario: &Scenario,
+        scenario_output: &mut ScenarioOutput,
+    ) -> Result<()> {
+        self.options.hooks.before_scenario(
+            scenario,
+            self.build_dir.path(),
+            &self.options.env,
+            scenario_output,
+            self.console,
+        )
+    }
+
+    /// Run the `after_scenario` hook, if any, after the mutant is reverted.
+    fn after_scenario(
+        &self,
+        scenario: &Scenario,
+        outcome: &ScenarioOutcome,
+        scenario_output: &mut ScenarioOutput,
+    ) -> Result<()> {
+        self.options.hooks.after_scenario(
+            scenario,
+            outcome,
+            self.build_dir.path(),
+            &self.options.env,
+            scenario_output,
+            self.console,
+        )
+    }
+
+    /// The options and timeouts for a scenario.
+    ///
+    /// Mutants in packages with their own config file use that package's options.
+    fn options_and_timeouts(
+        &self,
+        scenario: &Scenario,
+        timeouts: Timeouts,
+    ) -> (&'a Options, Timeouts) {
+        match scenario.mutant() {
+            Some(mutant) => {
+                let package_name = &mutant.source_file.package.name;
+                let options = self.options.for_package(package_name);
+                let mut timeouts = self
+                    .package_timeouts
+                    .get(package_name)
+                    .copied()
+                    .unwrap_or(timeouts);
+                // Mutants in some source files may have their own timeouts.
+                if let Some(timeout_override) =
+                    options.timeout_override(&mutant.source_file.tree_relative_path)
+                {
+                    timeouts = timeouts.with_override(timeout_override);
+                    debug!(?timeouts, "timeouts overridden for mutant");
+                }
+                (options, timeouts)
+            }
+            None => (self.options, timeouts),
+        }
+    }
 
-        let mut outcome = ScenarioOutcome::new(&scenario_output, scenario.clone());
-        for &phase in self.options.phases() {
+    /// Options to run only the tests that cover a mutant, if there's a test map.
+    fn impact_options(
+        &self,
+        scenario: &Scenario,
+        options: &Options,
+        scenario_output: &mut ScenarioOutput,
+    ) -> Result<Option<Options>> {
+        let (Some(test_map), Some(mutant)) = (self.test_map, scenario.mutant()) else {
+            return Ok(None);
+        };
+        let tests = test_map.tests_for(mutant);
+        if tests.is_empty() {
+            scenario_output.message("no tests cover this mutant")?;
+        } else {
+            scenario_output
+                .message(&format!("run {} tests that cover this mutant", tests.len()))?;
+        }
+        Ok(Some(options_for_tests(options, &tests)))
+    }
+
+    /// Apply the mutant, if any, run some phases until one fails, and then revert it.
+    ///
+    /// Returns true if all the phases succeeded.
+    fn run_phases(
+        &mut self,
+        scenario: &Scenario,
+        phases: &[Phase],
+        test_packages: &PackageSelection,
+        timeouts: Timeouts,
+        scenario_output: &mut ScenarioOutput,
+        outcome: &mut ScenarioOutcome,
+    ) -> Result<bool> {
+        let dir = self.build_dir.path();
+        debug!(?test_packages, ?phases);
+        let (options, timeouts) = self.options_and_timeouts(scenario, timeouts);
+        let artifacts_argv =
+            (options.detect_equivalent && scenario.is_mutant() && phases.contains(&Phase::Build))
+                .then(|| artifacts_argv(test_packages, options));
+        if let Some(argv) = &artifacts_argv {
+            if !self.baseline_artifacts.contains_key(argv) {
+                // Build the unmutated tree in this directory, if it's not already built.
+                let hashes = artifact_hashes(self.build_dir, argv, options)
+                    .inspect_err(|err| warn!("Failed to hash unmutated test binaries: {err:#}"))
+                    .unwrap_or_default();
+                self.baseline_artifacts.insert(argv.clone(), hashes);
+            }
+        }
+        let impact_options = if phases.contains(&Phase::Test) {
+            self.impact_options(scenario, options, scenario_output)?
+        } else {
+            None
+        };
+        if let Some(mutant) = scenario.mutant() {
+            mutant.apply(self.build_dir, &mutant.mutated_code())?;
+        }
+        let mut success = true;
+        for &phase in phases {
             self.console.scenario_phase_started(dir, phase);
-            let timeout = match phase {
-                Phase::Test => timeouts.test,
-                Phase::Build | Phase::Check => timeouts.build,
+            let (timeout, phase_options) = match phase {
+                Phase::Test => (timeouts.test, impact_options.as_ref().unwrap_or(options)),
+                Phase::Build | Phase::Check => (timeouts.build, options),
             };
             match run_cargo(
                 self.build_dir,
@@ -286,17 +1196,28 @@ impl Worker<'_> {
                 test_packages,
                 phase,
                 timeout,
-                &mut scenario_output,
-                self.options,
+                scenario_output,
+                phase_options,
                 self.console,
             ) {
                 Ok(phase_result) => {
-                    let success = phase_result.is_success(); // so we can move it away
+                    success = phase_result.is_success(); // so we can move it away
                     outcome.add_phase_result(phase_result);
                     self.console.scenario_phase_finished(dir, phase);
                     if !success {
                         break;
                     }
+                    if phase == Phase::Build {
+                        if let Some(argv) = &artifacts_argv {
+                            if self.is_equivalent(argv, options)? {
+                                scenario_output.message(
+                                    "test binaries are the same as for the unmutated tree",
+                                )?;
+                                outcome.set_equivalent();
+                                break;
+                            }
+                        }
+                    }
                 }
                 Err(err) => {
                     error!(?err, ?phase, "scenario execution internal error");
@@ -311,15 +1232,33 @@ impl Worker<'_> {
         if let Some(mutant) = scenario.mutant() {
             mutant.revert(self.build_dir)?;
         }
+        Ok(success)
+    }
+
+    /// True if the test binaries built for the currently applied mutant are the same as
+    /// those from the unmutated tree.
+    fn is_equivalent(&self, argv: &[String], options: &Options) -> Result<bool> {
+        let baseline = &self.baseline_artifacts[argv];
+        match artifact_hashes(self.build_dir, argv, options) {
+            Ok(hashes) => Ok(is_equivalent(baseline, &hashes)),
+            Err(err) => {
+                check_interrupted()?;
+                warn!("Failed to hash mutated test binaries: {err:#}");
+                Ok(false)
+            }
+        }
+    }
+
+    /// Record the outcome of a finished scenario.
+    fn finish_scenario(&self, scenario: &Scenario, outcome: &ScenarioOutcome) -> Result<()> {
         self.output_mutex
             .lock()
             .expect("lock output dir to add outcome")
-            .add_scenario_outcome(&outcome)?;
+            .add_scenario_outcome(outcome)?;
         debug!(outcome = ?outcome.summary());
         self.console
-            .scenario_finished(dir, scenario, &outcome, self.options);
-
-        Ok(outcome)
+            .scenario_finished(self.build_dir.path(), scenario, outcome, self.options);
+        Ok(())
     }
 }
 
@@ -335,13 +1274,100 @@ pub enum TestsForMutant {
 }
 
 impl TestsForMutant {
-    fn new(options: &Options, workspace: &Workspace) -> Self {
-        match options.test_package {
+    fn new(options: &Options, workspace: &Workspace) -> Result<Self> {
+        Ok(match options.test_package {
             TestPackages::Workspace => TestsForMutant::Workspace,
             TestPackages::Mutated => TestsForMutant::Mutated,
             TestPackages::Named(ref package_names) => {
-                TestsForMutant::Explicit(workspace.packages_by_name(package_names))
+                TestsForMutant::Explicit(workspace.packages_by_name(package_names)?)
             }
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::visit::mutate_source_str;
+
+    fn example_mutants() -> Vec<Mutant> {
+        let options = Options::default();
+        mutate_source_str(
+            indoc! {"
+                fn a(x: i32, y: i32) -> i32 {
+                    x + y - x * y / 2 + (x % y) - (y << 1)
+                }
+
+                fn b(x: i32) -> bool {
+                    x > 1 && x < 10 || x == 100
+                }
+            "},
+            &options,
+        )
+        .unwrap()
+    }
+
+    fn count_genre(mutants: &[Mutant], genre: Genre) -> usize {
+        mutants.iter().filter(|m| m.genre == genre).count()
+    }
+
+    #[test]
+    fn no_sampling_by_default() {
+        let mutants = example_mutants();
+        let n = mutants.len();
+        let sampled = sample_by_genre(mutants, &Options::default(), &mut fastrand::Rng::new());
+        assert_eq!(sampled.len(), n);
+    }
+
+    #[test]
+    fn max_mutants_per_genre_caps_each_genre() {
+        let mutants = example_mutants();
+        assert!(count_genre(&mutants, Genre::BinaryOperator) > 3);
+        assert!(count_genre(&mutants, Genre::FnValue) > 2);
+        let options = Options {
+            max_mutants_per_genre: Some(2),
+            ..Options::default()
+        };
+        let sampled = sample_by_genre(mutants, &options, &mut fastrand::Rng::with_seed(1));
+        assert_eq!(count_genre(&sampled, Genre::BinaryOperator), 2);
+        assert_eq!(count_genre(&sampled, Genre::FnValue), 2);
+    }
+
+    #[test]
+    fn genre_sample_ratio_rounds_up() {
+        let mutants = example_mutants();
+        let n_binary = count_genre(&mutants, Genre::BinaryOperator);
+        let options = Options {
+            genre_sample_ratio: Some(0.1),
+            ..Options::default()
+        };
+        let sampled = sample_by_genre(mutants, &options, &mut fastrand::Rng::with_seed(1));
+        assert_eq!(
+            count_genre(&sampled, Genre::BinaryOperator),
+            n_binary.div_ceil(10)
+        );
+        assert_eq!(count_genre(&sampled, Genre::FnValue), 1);
+    }
+
+    #[test]
+    fn sampling_is_repeatable_with_the_same_seed() {
+        let options = Options {
+            max_mutants_per_genre: Some(3),
+            ..Options::default()
+        };
+        let names = |seed| {
+            sample_by_genre(
+                example_mutants(),
+                &options,
+                &mut fastrand::Rng::with_seed(seed),
+            )
+            .iter()
+            .map(|m| m.name(true))
+            .collect::<Vec<_>>()
+        };
+        assert_eq!(names(42), names(42));
+        assert_ne!(names(42), names(43));
     }
 }
@@ -0,0 +1,72 @@
+// Copyright 2025 Martin Pool
+
+//! Ranges of lines selected by `--lines`.
+
+use std::str::FromStr;
+
+use anyhow::{ensure, Context, Error};
+
+use crate::span::Span;
+
+/// An inclusive range of 1-based line numbers.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct LineRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl LineRange {
+    /// True if any part of the span is on a line in this range.
+    pub fn intersects(&self, span: &Span) -> bool {
+        span.start.line <= self.end && span.end.line >= self.start
+    }
+}
+
+impl FromStr for LineRange {
+    type Err = Error;
+
+    /// Parse either a single line number like `12`, or a range like `100-250`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s.split_once('-').unwrap_or((s, s));
+        let start = start.trim().parse().context("line range start")?;
+        let end = end.trim().parse().context("line range end")?;
+        ensure!(start >= 1, "line numbers start at 1");
+        ensure!(start <= end, "line range must not end before it starts");
+        Ok(LineRange { start, end })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_line_ranges() {
+        assert_eq!(
+            LineRange::from_str("100-250").unwrap(),
+            LineRange {
+                start: 100,
+                end: 250
+            }
+        );
+        assert_eq!(
+            LineRange::from_str("12").unwrap(),
+            LineRange { start: 12, end: 12 }
+        );
+        assert!(LineRange::from_str("").is_err());
+        assert!(LineRange::from_str("0-3").is_err());
+        assert!(LineRange::from_str("9-3").is_err());
+        assert!(LineRange::from_str("3-").is_err());
+        assert!(LineRange::from_str("a-b").is_err());
+    }
+
+    #[test]
+    fn intersects_spans() {
+        let range = LineRange::from_str("10-20").unwrap();
+        assert!(range.intersects(&Span::quad(10, 1, 10, 5)));
+        assert!(range.intersects(&Span::quad(20, 1, 21, 5)));
+        assert!(range.intersects(&Span::quad(5, 1, 30, 1)));
+        assert!(!range.intersects(&Span::quad(5, 1, 9, 80)));
+        assert!(!range.intersects(&Span::quad(21, 1, 22, 1)));
+    }
+}
@@ -8,6 +8,7 @@
 use itertools::Itertools;
 use serde_json::{json, Value};
 
+use crate::highlight::highlight_diff;
 use crate::mutant::Mutant;
 use crate::path::Utf8PathSlashes;
 use crate::source::SourceFile;
@@ -15,7 +16,8 @@ use crate::Options;
 
 /// Return a string representation of a list of mutants.
 ///
-/// The format is controlled by the `emit_json`, `emit_diffs`, `show_line_col`, and `colors` options.
+/// The format is controlled by the `emit_json`, `emit_diffs`, `diff_context`, `show_line_col`,
+/// and `colors` options.
 pub fn list_mutants(mutants: &[Mutant], options: &Options) -> String {
     if options.emit_json {
         // Panic: only if we created illegal json, which would be a bug.
@@ -25,7 +27,7 @@ pub fn list_mutants(mutants: &[Mutant], options: &Options) -> String {
             if options.emit_diffs {
                 obj.as_object_mut().unwrap().insert(
                     "diff".to_owned(),
-                    json!(mutant.diff(&mutant.mutated_code())),
+                    json!(mutant.diff_with_context(&mutant.mutated_code(), options.diff_context)),
                 );
             }
             list.push(obj);
@@ -44,7 +46,12 @@ pub fn list_mutants(mutants: &[Mutant], options: &Options) -> String {
             }
             out.push('\n');
             if options.emit_diffs {
-                out.push_str(&mutant.diff(&mutant.mutated_code()));
+                let diff = mutant.diff_with_context(&mutant.mutated_code(), options.diff_context);
+                if colors {
+                    out.push_str(&highlight_diff(&diff));
+                } else {
+                    out.push_str(&diff);
+                }
                 out.push('\n');
             }
         }
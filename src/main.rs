@@ -7,66 +7,111 @@
 #![warn(clippy::pedantic)]
 #![allow(clippy::module_name_repetitions, clippy::needless_raw_string_hashes)]
 
+mod blame;
 mod build_dir;
+mod cache;
 mod cargo;
+mod cfg;
 mod config;
+mod config_check;
 mod console;
 mod copy_tree;
+mod coverage;
+mod equivalent;
+mod events;
 mod exit_code;
+mod expand;
 mod fnvalue;
+mod github;
 mod glob;
+mod hash;
+mod highlight;
+mod history;
+mod hooks;
 mod in_diff;
 mod interrupt;
 mod lab;
+mod line_range;
 mod list;
 mod manifest;
+mod missed_baseline;
 mod mutant;
+mod notify;
+mod operators;
 mod options;
 mod outcome;
 mod output;
 mod package;
 mod path;
+mod plugin;
+mod prescreen;
 mod pretty;
 mod process;
+mod remote;
+mod replay;
+mod report;
+mod resume;
+mod sccache;
 mod scenario;
+mod schema;
+mod schemata;
+mod scores;
+mod scratch;
 mod shard;
 mod source;
 mod span;
 mod tail_file;
+mod test_impact;
 #[cfg(test)]
 #[path = "../tests/util/mod.rs"]
 mod test_util;
 mod timeouts;
+mod timings;
 mod visit;
+mod watch;
 mod workspace;
 
 use std::env;
-use std::fs::read_to_string;
 use std::io;
+use std::iter;
 use std::process::exit;
 
-use anyhow::{anyhow, ensure, Context, Result};
+use anyhow::{anyhow, bail, ensure, Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::builder::styling::{self};
 use clap::builder::Styles;
-use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand, ValueEnum};
 use clap_complete::{generate, Shell};
 use color_print::cstr;
 use console::enable_console_colors;
-use output::{load_previously_caught, OutputDir};
+use output::{load_previously_caught, OutputDir, OUTDIR_NAME};
 use tracing::{debug, info};
 
+use crate::blame::{blame_filter, BlameFilter};
 use crate::build_dir::BuildDir;
 use crate::console::Console;
-use crate::in_diff::diff_filter;
+use crate::events::EventLog;
+use crate::history::{append_history, format_history, read_history};
+use crate::in_diff::{diff_filter, read_diff};
 use crate::interrupt::check_interrupted;
 use crate::lab::test_mutants;
+use crate::line_range::LineRange;
 use crate::list::{list_files, list_mutants};
-use crate::mutant::{Genre, Mutant};
-use crate::options::{Colors, Options, TestTool};
+use crate::missed_baseline::{apply_missed_baseline, MISSED_BASELINE_TOML};
+use crate::mutant::{Genre, Mutant, DEFAULT_DIFF_CONTEXT};
+use crate::notify::notify_finished;
+use crate::operators::list_operators;
+use crate::options::{
+    parse_env_var, Colors, Options, OutputFormat, ProgressStyle, ScoreAs, TestTool,
+};
 use crate::outcome::{Phase, ScenarioOutcome};
+use crate::replay::replay;
+use crate::resume::load_resumed;
+use crate::sccache::Sccache;
 use crate::scenario::Scenario;
+use crate::scores::apply_minimum_score;
 use crate::shard::Shard;
+use crate::watch::watch;
 use crate::workspace::{PackageFilter, Workspace};
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -114,6 +159,14 @@ pub enum BaselineStrategy {
     after_help = SPONSOR_MESSAGE,
 )]
 pub struct Args {
+    /// Run this shell command in the source tree after all mutants are tested.
+    #[arg(long, value_name = "COMMAND", help_heading = "Execution")]
+    after_run: Option<String>,
+
+    /// Run this shell command in the build directory after each scenario is tested.
+    #[arg(long, value_name = "COMMAND", help_heading = "Execution")]
+    after_scenario: Option<String>,
+
     /// Show cargo output for all invocations (very verbose).
     #[arg(long, help_heading = "Output")]
     all_logs: bool,
@@ -122,6 +175,29 @@ pub struct Args {
     #[arg(long, value_enum, default_value_t = BaselineStrategy::Run, help_heading = "Execution")]
     baseline: BaselineStrategy,
 
+    /// Run this shell command in the source tree before testing the baseline or any mutants.
+    #[arg(long, value_name = "COMMAND", help_heading = "Execution")]
+    before_run: Option<String>,
+
+    /// Run this shell command in the build directory before building and testing each
+    /// scenario.
+    ///
+    /// Details of the scenario are passed in environment variables like `MUTANTS_NAME`.
+    #[arg(long, value_name = "COMMAND", help_heading = "Execution")]
+    before_scenario: Option<String>,
+
+    /// Include only mutants in lines written by these authors, according to `git blame`.
+    ///
+    /// Authors can be given by name or email address, and the option may be repeated.
+    #[arg(long, help_heading = "Filters")]
+    blame_author: Vec<String>,
+
+    /// Include only mutants in lines changed since this date, according to `git blame`.
+    ///
+    /// The date can be in any format understood by git, like `2025-06-01` or `3 months ago`.
+    #[arg(long, help_heading = "Filters")]
+    blame_since: Option<String>,
+
     /// Turn off all rustc lints, so that denied warnings won't make mutants unviable.
     #[arg(long, action = ArgAction::Set, help_heading = "Build")]
     cap_lints: Option<bool>,
@@ -134,6 +210,10 @@ pub struct Args {
     #[arg(long, help_heading = "Execution")]
     check: bool,
 
+    /// Cargo check all mutants first, then build and test only those that pass the check.
+    #[arg(long, help_heading = "Execution")]
+    check_first: bool,
+
     /// Draw colors in output.
     #[arg(
         long,
@@ -153,15 +233,34 @@ pub struct Args {
     #[arg(long, help_heading = "Copying", visible_alias = "copy_git")]
     copy_vcs: Option<bool>,
 
+    /// Report mutants in lines that this LCOV or llvm-cov JSON coverage file shows are
+    /// never executed as missed, without building or testing them.
+    #[arg(long, help_heading = "Tests", value_name = "FILE")]
+    coverage: Option<Utf8PathBuf>,
+
+    /// Don't run tests for mutants whose test binaries are the same as the unmutated tree.
+    #[arg(long, help_heading = "Execution")]
+    detect_equivalent: bool,
+
     /// Show the mutation diffs.
     #[arg(long, help_heading = "Filters")]
     diff: bool,
 
+    /// Lines of context around each change in the diffs shown by `--list --diff`.
+    #[arg(
+        long,
+        value_name = "LINES",
+        default_value_t = DEFAULT_DIFF_CONTEXT,
+        help_heading = "Filters"
+    )]
+    diff_context: usize,
+
     /// Rust crate directory to examine.
     #[arg(
         long,
         short = 'd',
         conflicts_with = "manifest_path",
+        global = true,
         help_heading = "Input"
     )]
     dir: Option<Utf8PathBuf>,
@@ -170,6 +269,31 @@ pub struct Args {
     #[arg(long)]
     completions: Option<Shell>,
 
+    /// Set this environment variable, like `DATABASE_URL=postgres://localhost/test`, in cargo build and test subprocesses.
+    ///
+    /// May be repeated. These override variables of the same name from the config `[env]` table.
+    #[arg(long, value_parser = parse_env_var, help_heading = "Execution")]
+    env: Vec<(String, String)>,
+
+    /// Generate mutants of this genre, which is off by default: for example, `NumericLiteral`.
+    ///
+    /// May be repeated. This is combined with the genres from the config `enable_genres` key.
+    #[arg(long, help_heading = "Generate")]
+    enable_genre: Vec<Genre>,
+
+    /// Generate only mutants of this genre: for example, `BinaryOperator`.
+    ///
+    /// May be repeated or comma-separated. This overrides the config `genres` key.
+    #[arg(long, value_delimiter = ',', help_heading = "Generate")]
+    genre: Vec<Genre>,
+
+    /// Don't generate mutants of this genre: for example, `DeleteStatement`.
+    ///
+    /// May be repeated or comma-separated. This is combined with the genres from the
+    /// config `exclude_genres` key.
+    #[arg(long, value_delimiter = ',', help_heading = "Generate")]
+    exclude_genre: Vec<Genre>,
+
     /// Return this error values from functions returning Result:
     /// for example, `::anyhow::anyhow!("mutated")`.
     #[arg(long, help_heading = "Generate")]
@@ -191,6 +315,12 @@ pub struct Args {
     #[arg(long, short = 'e', help_heading = "Filters")]
     exclude: Vec<String>,
 
+    /// Don't test mutants from packages matching this name or glob, like `svc-legacy` or `*-sys`.
+    ///
+    /// May be repeated, and is combined with the config `exclude_packages` key.
+    #[arg(long, help_heading = "Filters")]
+    exclude_package: Vec<String>,
+
     /// Regex for mutations to exclude, matched against the names shown by `--list`.
     #[arg(long, short = 'E', alias = "exclude-regex", help_heading = "Filters")]
     exclude_re: Vec<String>,
@@ -213,10 +343,23 @@ pub struct Args {
     )]
     in_place: bool,
 
+    /// Reuse the outcomes of mutants from earlier runs if the file containing them and
+    /// the options that affect testing haven't changed.
+    #[arg(long, help_heading = "Execution")]
+    incremental: bool,
+
     /// Skip mutants that were caught in previous runs.
     #[arg(long, help_heading = "Filters")]
     iterate: bool,
 
+    /// After testing, watch the source tree and test the mutants in files that change.
+    #[arg(
+        long,
+        help_heading = "Execution",
+        conflicts_with_all = ["list", "list_files", "in_diff", "shard"]
+    )]
+    watch: bool,
+
     /// Run this many cargo build/test jobs in parallel.
     #[arg(
         long,
@@ -263,9 +406,15 @@ pub struct Args {
     list_files: bool,
 
     /// Path to Cargo.toml for the package to mutate.
-    #[arg(long, help_heading = "Input")]
+    #[arg(long, global = true, help_heading = "Input")]
     manifest_path: Option<Utf8PathBuf>,
 
+    /// File listing accepted missed mutants, which don't fail the run.
+    ///
+    /// By default, `mutants-baseline.toml` in the workspace root is used if it exists.
+    #[arg(long, help_heading = "Input")]
+    missed_baseline: Option<Utf8PathBuf>,
+
     /// Don't read .cargo/mutants.toml.
     #[arg(long, help_heading = "Input")]
     no_config: bool,
@@ -282,18 +431,126 @@ pub struct Args {
     #[arg(long, action = ArgAction::Set, default_value = "true", help_heading = "Output")]
     line_col: bool,
 
+    /// Show a full-screen view of the jobs in progress and the outcomes so far, in which
+    /// the diff and log of each outcome can be viewed during the run.
+    #[arg(long, help_heading = "Output")]
+    tui: bool,
+
+    /// Show the last lines of each running scenario's log under the progress bar: 10 by
+    /// default, or the given number.
+    #[arg(
+        long,
+        value_name = "LINES",
+        num_args = 0..=1,
+        default_missing_value = "10",
+        help_heading = "Output"
+    )]
+    show_output: Option<usize>,
+
+    /// Don't draw the progress bar, and print only the outcome of each scenario and a
+    /// one-line summary, for CI logs: the same as `--progress-style=quiet`.
+    ///
+    /// An explicit `--progress-style` takes precedence.
+    #[arg(
+        long,
+        env = "CARGO_MUTANTS_QUIET_PROGRESS",
+        value_parser = clap::builder::BoolishValueParser::new(),
+        help_heading = "Output"
+    )]
+    quiet_progress: bool,
+
+    /// After testing, write the HTML report and open it in the default web browser.
+    #[arg(
+        long,
+        help_heading = "Output",
+        conflicts_with_all = ["list", "list_files"]
+    )]
+    open: bool,
+
+    /// Show a desktop notification when testing finishes.
+    #[arg(long, help_heading = "Output")]
+    notify: bool,
+
+    /// Post a JSON summary to this URL when testing finishes, such as a chat webhook.
+    #[arg(long, value_name = "URL", help_heading = "Output")]
+    notify_webhook: Option<String>,
+
+    /// Generate mutants only in public items: `pub` functions, and functions in trait
+    /// impls and `pub` traits.
+    ///
+    /// Items inside a module that isn't `pub` aren't public, even if they're re-exported.
+    ///
+    /// This is the same as setting `visibility = "pub"` in the config file.
+    #[arg(long, help_heading = "Filters")]
+    only_pub: bool,
+
     /// Create mutants.out within this directory.
     #[arg(
         long,
         short = 'o',
         env = "CARGO_MUTANTS_OUTPUT",
+        global = true,
         help_heading = "Output"
     )]
     output: Option<Utf8PathBuf>,
 
-    /// Include only mutants in code touched by this diff.
+    /// Append a summary of each run to this JSON-lines file, for `cargo mutants history`.
+    #[arg(long, value_name = "PATH", global = true, help_heading = "Output")]
+    history_file: Option<Utf8PathBuf>,
+
+    /// Write a JSON event to this file as each mutant starts and finishes.
+    #[arg(long, value_name = "PATH", help_heading = "Output")]
+    events_file: Option<Utf8PathBuf>,
+
+    /// Fail with exit code 5 if the mutation score is below this percentage, rather than
+    /// failing because of any missed mutant.
+    #[arg(long, value_name = "PERCENT", help_heading = "Output")]
+    minimum_score: Option<f64>,
+
+    /// How to count mutants that timed out in the score: missed, caught, or excluded.
+    #[arg(long, value_name = "AS", help_heading = "Output")]
+    score_timeouts: Option<ScoreAs>,
+
+    /// How to count unviable mutants in the score: excluded, caught, or missed.
+    #[arg(long, value_name = "AS", help_heading = "Output")]
+    score_unviable: Option<ScoreAs>,
+
+    /// Print results as plain text, or with annotations and a job summary for GitHub Actions.
+    #[arg(long, value_enum, default_value_t, help_heading = "Output")]
+    output_format: OutputFormat,
+
+    /// Show progress as an animated bar, as plain status lines every few seconds, as
+    /// JSON events on stderr, or not at all. The default is `fancy`.
+    #[arg(long, value_enum, help_heading = "Output", conflicts_with = "tui")]
+    progress_style: Option<ProgressStyle>,
+
+    /// Include only mutants whose code is on these lines, like `100-250` or `12`.
+    ///
+    /// The ranges apply to every file that's examined, so this is typically combined
+    /// with `--file`. The list may be comma-separated and may be repeated.
+    #[arg(long, value_delimiter = ',', help_heading = "Filters")]
+    lines: Vec<LineRange>,
+
+    /// Include only mutants in code touched by this diff: either a diff file, or a git
+    /// revision range like `origin/main..HEAD`.
     #[arg(long, short = 'D', help_heading = "Filters")]
-    in_diff: Option<Utf8PathBuf>,
+    in_diff: Option<String>,
+
+    /// Include only mutants in functions with at least this cyclomatic complexity.
+    #[arg(long, help_heading = "Filters")]
+    min_complexity: Option<usize>,
+
+    /// Include only mutants in functions with at most this cyclomatic complexity.
+    #[arg(long, help_heading = "Filters")]
+    max_complexity: Option<usize>,
+
+    /// Include only mutants in functions at least this many lines long.
+    #[arg(long, help_heading = "Filters")]
+    min_function_lines: Option<usize>,
+
+    /// Include only mutants in functions at most this many lines long.
+    #[arg(long, help_heading = "Filters")]
+    max_function_lines: Option<usize>,
 
     /// Minimum timeout for tests, in seconds, as a lower bound on the auto-set time.
     #[arg(
@@ -304,6 +561,8 @@ pub struct Args {
     minimum_test_timeout: Option<f64>,
 
     /// Only test mutants from these packages.
+    ///
+    /// Package names may be globs, like `svc-*`.
     #[arg(id = "package", long, short = 'p', help_heading = "Filters")]
     mutate_packages: Vec<String>,
 
@@ -315,14 +574,120 @@ pub struct Args {
     #[arg(long, help_heading = "Execution")]
     no_shuffle: bool,
 
-    /// Build with this cargo profile.
-    #[arg(long, help_heading = "Build")]
+    /// Seed for the random shuffle and per-genre sampling, to make them repeatable.
+    #[arg(long, help_heading = "Execution")]
+    seed: Option<u64>,
+
+    /// Test at most this many randomly-chosen mutants of each genre.
+    #[arg(long, help_heading = "Execution")]
+    max_mutants_per_genre: Option<usize>,
+
+    /// Test this fraction (between 0 and 1) of randomly-chosen mutants of each genre.
+    #[arg(long, help_heading = "Execution")]
+    genre_sample_ratio: Option<f64>,
+
+    /// Expand macros using rustc, and mutate the impls generated by derive macros.
+    #[arg(long, help_heading = "Generate")]
+    expand_macros: bool,
+
+    /// Run this program to generate additional mutants: see the manual for the protocol.
+    ///
+    /// May be repeated. This is combined with the plugins from the config `plugins` key.
+    #[arg(long, help_heading = "Generate")]
+    plugin: Vec<String>,
+
+    /// Check mutants in batches before testing them, to quickly discard those that don't build.
+    #[arg(long, help_heading = "Execution")]
+    prescreen: bool,
+
+    /// Use this profile from the config file, or if it's not defined there, build with this cargo profile.
+    #[arg(long, global = true, help_heading = "Build")]
     profile: Option<String>,
 
+    /// Command that runs cargo-mutants on SSH worker hosts, in a shell in the copy of the
+    /// tree.
+    ///
+    /// The default is `cargo mutants`.
+    #[arg(long, help_heading = "Execution")]
+    remote_command: Option<String>,
+
+    /// Directory on SSH worker hosts to copy the tree into, relative to the remote home
+    /// directory.
+    ///
+    /// The default is `.cache/cargo-mutants/` followed by the name of the tree's directory.
+    #[arg(long, help_heading = "Execution")]
+    remote_dir: Option<String>,
+
+    /// Continue an interrupted run in the existing `mutants.out`, testing only the
+    /// mutants that didn't finish.
+    ///
+    /// The source and the options that select mutants should be the same as in the
+    /// interrupted run.
+    #[arg(
+        long,
+        help_heading = "Execution",
+        conflicts_with_all = ["list", "list_files", "iterate"]
+    )]
+    resume: bool,
+
+    /// Regex for the return types of functions to mutate, like `Result` or `^bool$`.
+    ///
+    /// Mutants outside of functions are skipped, and functions with no return type
+    /// are matched as `()`.
+    #[arg(long, help_heading = "Filters")]
+    return_type: Vec<String>,
+
+    /// Build all the mutants of each file at once, and select each one at runtime, so
+    /// that the tree isn't rebuilt for every mutant.
+    #[arg(long, help_heading = "Execution", conflicts_with_all = ["check", "check_first"])]
+    schemata: bool,
+
+    /// Build with the sccache compiler cache, by setting `RUSTC_WRAPPER`, and report how
+    /// many compilations were cached.
+    #[arg(long, help_heading = "Build")]
+    sccache: bool,
+
+    /// Make the build directories in this directory, such as a tmpfs like `/dev/shm`,
+    /// rather than the system temporary directory.
+    ///
+    /// If it can't be written or doesn't have room for all the build directories, the
+    /// system temporary directory is used instead.
+    #[arg(long, value_name = "DIR", help_heading = "Build")]
+    scratch_dir: Option<Utf8PathBuf>,
+
     /// Run only one shard of all generated mutants: specify as e.g. 1/4.
     #[arg(long, help_heading = "Execution")]
     shard: Option<Shard>,
 
+    /// Share cargo's intermediate build files, including compiled dependencies, between
+    /// all the build directories, in this directory.
+    ///
+    /// Final binaries are still kept separately in each build directory. This needs
+    /// cargo 1.91 or later.
+    #[arg(long, value_name = "DIR", help_heading = "Build")]
+    shared_target_dir: Option<Utf8PathBuf>,
+
+    /// Test mutants on this host over SSH, as well as locally, after copying the tree there
+    /// with rsync.
+    ///
+    /// May be repeated, and a host may be given more than once to run several workers
+    /// there. Each host needs cargo-mutants of the same version, and the toolchain to
+    /// build the tree.
+    #[arg(
+        long,
+        value_name = "HOST",
+        help_heading = "Execution",
+        conflicts_with_all = ["check_first", "schemata", "test_impact", "test_map"]
+    )]
+    ssh_worker: Vec<String>,
+
+    /// Skip functions and other items carrying this attribute, like `generated` or `inline(always)`.
+    ///
+    /// An attribute name without arguments matches the attribute with any arguments. The
+    /// option may be repeated, and is combined with the config `skip_attrs` key.
+    #[arg(long, help_heading = "Filters")]
+    skip_attr: Vec<String>,
+
     /// Skip calls to functions and methods named in this list.
     ///
     /// The list may contain comma-separated names and may be repeated.
@@ -340,6 +705,16 @@ pub struct Args {
     #[arg(long)]
     skip_calls_defaults: Option<bool>,
 
+    /// Run only the tests that cover each mutant, found by running each test under
+    /// cargo-llvm-cov after the baseline.
+    #[arg(long, help_heading = "Tests")]
+    test_impact: bool,
+
+    /// Run only the tests that cover each mutant, according to a `test_map.json` written
+    /// by an earlier run with `--test-impact`.
+    #[arg(long, help_heading = "Tests", conflicts_with = "test_impact")]
+    test_map: Option<Utf8PathBuf>,
+
     /// Run tests from these packages for all mutants.
     #[arg(long, help_heading = "Tests")]
     test_package: Vec<String>,
@@ -356,15 +731,25 @@ pub struct Args {
     #[arg(long, help_heading = "Tests")]
     test_workspace: Option<bool>,
 
-    /// Maximum run time for all cargo commands, in seconds.
-    #[arg(long, short = 't', help_heading = "Execution")]
+    /// Maximum run time for each cargo test, in seconds.
+    #[arg(
+        long,
+        short = 't',
+        visible_alias = "test-timeout",
+        help_heading = "Execution"
+    )]
     timeout: Option<f64>,
 
     /// Test timeout multiplier (relative to base test time).
-    #[arg(long, help_heading = "Execution", conflicts_with = "timeout")]
+    #[arg(
+        long,
+        visible_alias = "test-timeout-multiplier",
+        help_heading = "Execution",
+        conflicts_with = "timeout"
+    )]
     timeout_multiplier: Option<f64>,
 
-    /// Maximum run time for cargo build command, in seconds.
+    /// Maximum run time for each cargo build, in seconds.
     #[arg(long, help_heading = "Execution")]
     build_timeout: Option<f64>,
 
@@ -376,6 +761,10 @@ pub struct Args {
     #[arg(long, short = 'V', help_heading = "Output")]
     unviable: bool,
 
+    /// Rewrite the missed baseline file to accept the mutants missed in this run.
+    #[arg(long, help_heading = "Output")]
+    update_missed_baseline: bool,
+
     /// Show version and quit.
     #[arg(long, action = clap::ArgAction::SetTrue)]
     version: bool,
@@ -395,10 +784,65 @@ pub struct Args {
 
     /// Pass remaining arguments to cargo test after all options and after `--`.
     #[arg(last = true, help_heading = "Execution")]
+    #[allow(clippy::struct_field_names)]
     cargo_test_args: Vec<String>,
 
     #[command(flatten)]
     features: Features,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Commands other than testing mutants.
+#[derive(Subcommand, PartialEq, Eq, Debug)]
+enum Command {
+    /// Inspect `.cargo/mutants.toml` config files.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Compare the outcomes of two runs, failing if any mutants are newly missed.
+    Compare(report::CompareArgs),
+
+    /// Show the trend of mutation scores, and newly missed mutants, from the history file.
+    History(history::HistoryArgs),
+
+    /// Merge the outcomes of several runs, such as shards, into one `mutants.out`.
+    Merge(report::MergeArgs),
+
+    /// List the genres of mutants, with an example of each, and whether they're enabled.
+    Operators(operators::OperatorsArgs),
+
+    /// Test one mutant again in the source tree, and print its log.
+    Replay(replay::ReplayArgs),
+
+    /// Write a report of the results of the last run, from `mutants.out`.
+    Report(report::ReportFormats),
+
+    /// Show the outcome, diff, and log path of one mutant from the last run.
+    Show(report::ShowArgs),
+
+    /// Step through the missed mutants from the last run, and accept each into the missed
+    /// baseline, or mark it as todo or equivalent.
+    Triage,
+
+    /// Test mutants requested by a coordinator running with `--ssh-worker`, on standard
+    /// input and output.
+    #[command(hide = true)]
+    Worker,
+
+    /// Print the JSON Schema describing a JSON output file.
+    Schema {
+        #[arg(value_enum)]
+        name: schema::SchemaName,
+    },
+}
+
+#[derive(Subcommand, PartialEq, Eq, Debug)]
+enum ConfigCommand {
+    /// Report unknown or deprecated keys in the config files, and print the effective
+    /// config.
+    Check,
 }
 
 #[derive(clap::Args, PartialEq, Eq, Debug, Default, Clone)]
@@ -420,8 +864,9 @@ pub struct Features {
     pub all_features: bool,
 }
 
+#[allow(clippy::too_many_lines)] // long but pretty straightforward
 fn main() -> Result<()> {
-    let args = match Cargo::try_parse() {
+    let mut args = match Cargo::try_parse() {
         Ok(Cargo::Mutants(args)) => args,
         Err(e) => {
             e.print().expect("Failed to show clap error message");
@@ -434,6 +879,25 @@ fn main() -> Result<()> {
             exit(code);
         }
     };
+    if args.command == Some(Command::Worker) {
+        // A worker is configured by the coordinator's arguments, but runs in its own copy
+        // of the tree, with its output going only to the coordinator.
+        let Cargo::Mutants(coordinator_args) = Cargo::try_parse_from(
+            iter::once("cargo".to_owned()).chain(remote::read_worker_args()?),
+        )
+        .context("parse the coordinator's arguments")?;
+        args = Args {
+            command: Some(Command::Worker),
+            dir: args.dir.take(),
+            manifest_path: None,
+            output: None,
+            shared_target_dir: None,
+            colors: Colors::Never,
+            progress_style: Some(ProgressStyle::Quiet),
+            tui: false,
+            ..coordinator_args
+        };
+    }
 
     if args.version {
         println!("{NAME} {VERSION}");
@@ -441,6 +905,17 @@ fn main() -> Result<()> {
     } else if let Some(shell) = args.completions {
         generate(shell, &mut Cargo::command(), "cargo", &mut io::stdout());
         return Ok(());
+    } else if let Some(Command::Schema { name }) = args.command {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&schema::schema(name)).context("serialize schema")?
+        );
+        return Ok(());
+    } else if let Some(Command::Compare(compare_args)) = &args.command {
+        exit(report::compare(compare_args)?);
+    } else if let Some(Command::Merge(merge_args)) = &args.command {
+        let output_parent = args.output.as_deref().unwrap_or(Utf8Path::new("."));
+        exit(report::merge(merge_args, output_parent)?);
     }
 
     let console = Console::new();
@@ -459,15 +934,79 @@ fn main() -> Result<()> {
         Utf8Path::new(".")
     };
     let workspace = Workspace::open(start_dir)?;
+    if let Some(Command::Config(ConfigCommand::Check)) = &args.command {
+        return config_check::check_config(&args, &workspace);
+    }
     let config = if args.no_config {
         config::Config::default()
     } else {
-        config::Config::read_tree_config(workspace.root())?
+        config::Config::read_tree_config(workspace.root(), args.profile.as_deref())?
     };
+    if let Some(profile) = &config.named_profile {
+        info!("Using profile {profile:?} from the config file");
+    }
     debug!(?config);
+    if let Some(Command::Report(formats)) = &args.command {
+        let output_parent = args
+            .output
+            .as_deref()
+            .or(config.output.as_deref())
+            .unwrap_or(workspace.root());
+        for report_path in
+            report::write_reports(&output_parent.join(OUTDIR_NAME), workspace.root(), formats)?
+        {
+            println!("{report_path}");
+        }
+        return Ok(());
+    }
+    if let Some(Command::Show(show_args)) = &args.command {
+        let output_parent = args
+            .output
+            .as_deref()
+            .or(config.output.as_deref())
+            .unwrap_or(workspace.root());
+        return report::show(output_parent, show_args);
+    }
+    if let Some(Command::Triage) = &args.command {
+        let output_parent = args
+            .output
+            .as_deref()
+            .or(config.output.as_deref())
+            .unwrap_or(workspace.root());
+        let baseline_path = args
+            .missed_baseline
+            .clone()
+            .unwrap_or_else(|| workspace.root().join(MISSED_BASELINE_TOML));
+        return report::triage(output_parent, workspace.root(), &baseline_path);
+    }
+    if let Some(Command::History(history_args)) = &args.command {
+        let Some(history_file) = args.history_file.as_ref().or(config.history_file.as_ref()) else {
+            bail!("No history file: set --history-file or history_file in the config");
+        };
+        print!(
+            "{}",
+            format_history(&read_history(history_file)?, history_args)
+        );
+        return Ok(());
+    }
     debug!(?args.features);
-    let options = Options::new(&args, &config)?;
+    let mut options = Options::new(&args, &config)?;
+    if !args.no_config {
+        for (package_name, package_config) in config::Config::read_package_configs(
+            workspace.root(),
+            workspace.packages(),
+            args.profile.as_deref(),
+        )? {
+            let package_options = Options::new(&args, &package_config)?;
+            options.per_package.insert(package_name, package_options);
+        }
+    }
     debug!(?options);
+    if let Some(Command::Operators(operators_args)) = &args.command {
+        print!("{}", list_operators(&options, operators_args));
+        return Ok(());
+    }
+    console.set_progress_style(options.progress_style);
     let package_filter = if !args.mutate_packages.is_empty() {
         PackageFilter::explicit(&args.mutate_packages)
     } else if args.workspace {
@@ -482,6 +1021,18 @@ fn main() -> Result<()> {
         .unwrap_or_else(|| workspace.root().to_owned());
 
     let mut discovered = workspace.discover(&package_filter, &options, &console)?;
+    if let Some(Command::Worker) = &args.command {
+        return remote::serve_worker(discovered.mutants, &workspace, &options, &console);
+    }
+    if let Some(Command::Replay(replay_args)) = &args.command {
+        exit(replay(
+            discovered.mutants,
+            replay_args,
+            &workspace,
+            options,
+            &console,
+        )?);
+    }
 
     let previously_caught = if args.iterate {
         let previously_caught = load_previously_caught(&output_parent_dir)?;
@@ -504,22 +1055,90 @@ fn main() -> Result<()> {
     if let Some(in_diff) = &args.in_diff {
         mutants = diff_filter(
             mutants,
-            &read_to_string(in_diff).context("Failed to read filter diff")?,
+            &read_diff(in_diff, workspace.root()).context("Failed to read filter diff")?,
         )?;
     }
+    if args.blame_since.is_some() || !args.blame_author.is_empty() {
+        let filter = BlameFilter {
+            since: args.blame_since.clone(),
+            authors: args.blame_author.clone(),
+        };
+        mutants = blame_filter(mutants, workspace.root(), &filter)?;
+    }
     if let Some(shard) = &args.shard {
+        if args.list {
+            let counts = shard.counts(&mutants);
+            info!(
+                "Shard {}/{} has {} of {} mutants; the shards have {} mutants",
+                shard.k,
+                shard.n,
+                counts[shard.k],
+                mutants.len(),
+                counts
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            );
+        }
         mutants = shard.select(mutants);
     }
     if args.list {
         print!("{}", list_mutants(&mutants, &options));
     } else {
-        let output_dir = OutputDir::new(&output_parent_dir)?;
+        let output_dir = if args.resume {
+            let mut output_dir = OutputDir::resume(&output_parent_dir)?;
+            let resumed = load_resumed(output_dir.path(), mutants)?;
+            info!(
+                "Resuming with {} mutants left to test",
+                resumed.remaining.len()
+            );
+            mutants = resumed.remaining;
+            output_dir.add_earlier_outcomes(resumed.finished)?;
+            // The mutants are already sampled and in the order they were being tested.
+            options.shuffle = false;
+            options.genre_sample_ratio = None;
+            options.max_mutants_per_genre = None;
+            output_dir
+        } else {
+            OutputDir::new(&output_parent_dir)?
+        };
         if let Some(previously_caught) = previously_caught {
             output_dir.write_previously_caught(&previously_caught)?;
         }
         console.set_debug_log(output_dir.open_debug_log()?);
+        if let Some(events_file) = &options.events_file {
+            console.set_event_log(EventLog::create(events_file)?);
+        }
+        let sccache = Sccache::start(&options)?;
         let lab_outcome = test_mutants(mutants, &workspace, output_dir, &options, &console)?;
-        exit(lab_outcome.exit_code());
+        if let Some(sccache) = &sccache {
+            sccache.finish();
+        }
+        notify_finished(&lab_outcome, workspace.root(), &options);
+        if let Some(history_file) = &options.history_file {
+            append_history(history_file, workspace.root(), &lab_outcome)?;
+        }
+        let baseline_path = args
+            .missed_baseline
+            .unwrap_or_else(|| workspace.root().join(MISSED_BASELINE_TOML));
+        let code =
+            apply_missed_baseline(&lab_outcome, &baseline_path, args.update_missed_baseline)?;
+        let code = apply_minimum_score(&lab_outcome, &options, code);
+        if args.open {
+            report::open_html_report(&output_parent_dir.join(OUTDIR_NAME), workspace.root())?;
+        }
+        if !args.watch {
+            exit(code);
+        }
+        watch(
+            &workspace,
+            &package_filter,
+            &options,
+            &console,
+            &output_parent_dir,
+            discovered.files,
+        )?;
     }
     Ok(())
 }
@@ -0,0 +1,204 @@
+// Copyright 2025 Martin Pool
+
+//! A baseline file listing missed mutants that are accepted, so that they don't fail
+//! the run.
+//!
+//! This lets a team adopt cargo-mutants on an existing codebase by blocking new missed
+//! mutants, without first fixing all the existing ones, and then ratchet down the
+//! baseline as tests improve.
+//!
+//! Mutants are identified by their name without the line and column, so that entries
+//! still match when unrelated edits move the code around.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::fs::{read_to_string, write};
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::exit_code;
+use crate::outcome::{LabOutcome, SummaryOutcome};
+use crate::{Context, Result, Scenario};
+
+/// The default name of the baseline file, in the workspace root.
+pub const MISSED_BASELINE_TOML: &str = "mutants-baseline.toml";
+
+const HEADER: &str = "\
+# Missed mutants that are accepted and don't fail cargo-mutants.
+#
+# Regenerate this file with `cargo mutants --update-missed-baseline`.
+
+";
+
+/// Return the exit code for a run, taking into account the baseline file if it exists,
+/// and first rewriting it if `update` is set.
+pub fn apply_missed_baseline(
+    lab_outcome: &LabOutcome,
+    path: &Utf8Path,
+    update: bool,
+) -> Result<i32> {
+    let baseline = MissedBaseline::read(path)?;
+    if update {
+        let updated = baseline.unwrap_or_default().updated(lab_outcome);
+        updated.write(path)?;
+        info!(
+            "Wrote {} accepted missed mutants to {path}",
+            updated.missed.len()
+        );
+        Ok(updated.exit_code(lab_outcome))
+    } else if let Some(baseline) = baseline {
+        Ok(baseline.exit_code(lab_outcome))
+    } else {
+        Ok(lab_outcome.exit_code())
+    }
+}
+
+/// The contents of a missed-mutant baseline file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct MissedBaseline {
+    /// Names of accepted missed mutants, like `src/lib.rs: replace foo -> bool with true`.
+    #[serde(default)]
+    pub missed: BTreeSet<String>,
+}
+
+impl MissedBaseline {
+    /// Read a baseline file, or return None if it doesn't exist.
+    pub fn read(path: &Utf8Path) -> Result<Option<MissedBaseline>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let toml = read_to_string(path).with_context(|| format!("read {path}"))?;
+        toml::from_str(&toml)
+            .with_context(|| format!("parse missed baseline {path}"))
+            .map(Some)
+    }
+
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        // Write each mutant on its own line so that the file diffs well.
+        let mut toml = HEADER.to_owned();
+        toml.push_str("missed = [\n");
+        for name in &self.missed {
+            writeln!(toml, "    {},", toml::Value::from(name.as_str())).unwrap();
+        }
+        toml.push_str("]\n");
+        write(path, toml).with_context(|| format!("write missed baseline {path}"))
+    }
+
+    /// Update the baseline from the outcome of a run.
+    ///
+    /// Entries for mutants that were tested and not missed are removed, entries for
+    /// mutants that weren't tested in this run are kept, and newly missed mutants are
+    /// added.
+    #[must_use]
+    pub fn updated(&self, lab_outcome: &LabOutcome) -> MissedBaseline {
+        let tested = tested_mutant_names(lab_outcome);
+        let mut missed: BTreeSet<String> = self
+            .missed
+            .iter()
+            .filter(|name| !tested.contains(*name))
+            .cloned()
+            .collect();
+        missed.extend(missed_mutant_names(lab_outcome));
+        MissedBaseline { missed }
+    }
+
+    /// Return the exit code for a run, not counting accepted missed mutants as problems.
+    pub fn exit_code(&self, lab_outcome: &LabOutcome) -> i32 {
+        let code = lab_outcome.exit_code();
+        let missed = missed_mutant_names(lab_outcome);
+        let (accepted, new): (Vec<String>, Vec<String>) = missed
+            .into_iter()
+            .partition(|name| self.missed.contains(name));
+        if !accepted.is_empty() {
+            info!(
+                "{} missed mutants are accepted by the baseline",
+                accepted.len()
+            );
+        }
+        for name in &new {
+            warn!("Missed mutant is not in the baseline: {name}");
+        }
+        let tested = tested_mutant_names(lab_outcome);
+        let n_stale = self
+            .missed
+            .iter()
+            .filter(|name| tested.contains(*name) && !accepted.contains(*name))
+            .count();
+        if n_stale > 0 {
+            info!(
+                "{n_stale} mutants in the baseline are now caught; \
+                remove them with --update-missed-baseline"
+            );
+        }
+        if code == exit_code::FOUND_PROBLEMS && new.is_empty() {
+            exit_code::SUCCESS
+        } else {
+            code
+        }
+    }
+}
+
+/// Names of all the mutants tested in this run, without line numbers.
+fn tested_mutant_names(lab_outcome: &LabOutcome) -> BTreeSet<String> {
+    lab_outcome
+        .outcomes
+        .iter()
+        .filter_map(|outcome| match &outcome.scenario {
+            Scenario::Mutant(mutant) => Some(mutant.name(false)),
+            Scenario::Baseline => None,
+        })
+        .collect()
+}
+
+/// Names of the missed mutants in this run, without line numbers.
+fn missed_mutant_names(lab_outcome: &LabOutcome) -> BTreeSet<String> {
+    lab_outcome
+        .outcomes
+        .iter()
+        .filter(|outcome| outcome.summary() == SummaryOutcome::MissedMutant)
+        .filter_map(|outcome| match &outcome.scenario {
+            Scenario::Mutant(mutant) => Some(mutant.name(false)),
+            Scenario::Baseline => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use camino::Utf8PathBuf;
+
+    use super::*;
+
+    #[test]
+    fn write_and_read_baseline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = Utf8PathBuf::try_from(tmp.path().join(MISSED_BASELINE_TOML)).unwrap();
+        assert_eq!(MissedBaseline::read(&path).unwrap(), None);
+        let baseline = MissedBaseline {
+            missed: [
+                "src/lib.rs: replace foo -> bool with true",
+                "src/lib.rs: replace \"a\" with \"b\" in quote",
+            ]
+            .into_iter()
+            .map(ToOwned::to_owned)
+            .collect(),
+        };
+        baseline.write(&path).unwrap();
+        let written = read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Missed mutants"));
+        assert!(written.contains("\n    \"src/lib.rs: replace foo -> bool with true\",\n"));
+        assert_eq!(MissedBaseline::read(&path).unwrap(), Some(baseline));
+    }
+
+    #[test]
+    fn empty_baseline() {
+        let baseline: MissedBaseline = toml::from_str("").unwrap();
+        assert!(baseline.missed.is_empty());
+        assert!(toml::from_str::<MissedBaseline>("caught = []").is_err());
+    }
+}
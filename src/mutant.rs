@@ -8,8 +8,9 @@ use std::sync::Arc;
 use anyhow::Result;
 use console::{style, StyledObject};
 use serde::ser::{SerializeStruct, Serializer};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use similar::TextDiff;
+use strum::{Display, EnumString, VariantNames};
 use tracing::trace;
 
 use crate::build_dir::BuildDir;
@@ -18,8 +19,25 @@ use crate::source::SourceFile;
 use crate::span::Span;
 use crate::MUTATION_MARKER_COMMENT;
 
+/// The default number of lines of context in mutant diffs.
+pub const DEFAULT_DIFF_CONTEXT: usize = 8;
+
 /// Various broad categories of mutants.
-#[derive(Clone, Eq, PartialEq, Debug, Serialize)]
+#[derive(
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Debug,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    VariantNames,
+)]
+#[strum(ascii_case_insensitive)]
 pub enum Genre {
     /// Replace the body of a function with a fixed value.
     FnValue,
@@ -30,6 +48,75 @@ pub enum Genre {
     MatchArm,
     /// Replace the expression of a match arm guard with a fixed value.
     MatchArmGuard,
+    /// Replace arithmetic methods like `checked_add` with their counterpart, like `checked_sub`.
+    ArithmeticMethod,
+    /// Negate the condition of an `if` or `while` expression.
+    NegateCondition,
+    /// Delete a statement that calls a function or method for its side effects.
+    DeleteStatement,
+    /// Replace `Option` and `Result` combinators, like `unwrap_or(x)` with `unwrap_or_default()`.
+    Combinator,
+    /// Delete iterator adapters like `.rev()` and `.take(n)`, or swap `.min()` and `.max()`.
+    IteratorAdapter,
+    /// Swap the operands of a non-commutative binary operator, like `a - b` to `b - a`.
+    SwapOperands,
+    /// Add or subtract one from an index, like `v[i]` to `v[i + 1]`, or swap `first()`
+    /// and `last()`.
+    Index,
+    /// Replace the amount of a shift, like `n` in `a << n`, with one more or one less.
+    ShiftAmount,
+    /// Replace an exclusive range `a..b` with an inclusive range `a..=b`, and vice versa.
+    Range,
+    /// Replace the `?` operator with `.unwrap()`, so that errors are no longer propagated.
+    TryOperator,
+    /// Swap two arguments of the same type in a function or method call.
+    SwapArguments,
+    /// Force the pattern in an `if let` to match or not match, or make a `let ... else`
+    /// always diverge.
+    LetPattern,
+    /// Replace the body of a closure with a value of the right type.
+    ClosureValue,
+    /// Delete a `return` from an `if` without an `else`, so that the function continues.
+    EarlyReturn,
+    /// Delete the `else` branch of an `if` expression whose value is `()`.
+    DeleteElse,
+    /// Swap `break` and `continue`, or delete the label from `break 'label`.
+    LoopControl,
+    /// Change the type of an `as` cast between integers or floats, or swap it with
+    /// `T::try_from(x).unwrap()`.
+    Cast,
+    /// Delete `.await` from a statement that discards the future's output, so that the
+    /// future is never run.
+    Await,
+    /// Swap adjacent branches of a biased `select!`, changing which is preferred.
+    SelectBranch,
+    /// Replace the duration passed to a `timeout` function or method with zero or the
+    /// maximum.
+    Timeout,
+    /// A mutant generated by an external plugin.
+    Plugin,
+    /// Replace a numeric or boolean literal in the initializer of a `const` or `static` item.
+    ConstValue,
+    /// Flip a `true` or `false` literal passed as an argument or struct field.
+    BooleanLiteral,
+    /// Replace an integer or float literal with a nearby value, like `3` with `4`.
+    ///
+    /// This is off by default because it generates many mutants.
+    NumericLiteral,
+    /// Replace a non-empty string literal with `""` or `"xyzzy"`.
+    ///
+    /// This is off by default because it generates many mutants.
+    StringLiteral,
+}
+
+impl Genre {
+    /// True if mutants of this genre are only generated when explicitly enabled.
+    pub fn is_opt_in(self) -> bool {
+        matches!(
+            self,
+            Genre::Cast | Genre::ConstValue | Genre::NumericLiteral | Genre::StringLiteral
+        )
+    }
 }
 
 /// A mutation applied to source code.
@@ -53,6 +140,26 @@ pub struct Mutant {
 
     /// What general category of mutant this is.
     pub genre: Genre,
+
+    /// If this mutates code generated by a macro, the mutation within the expansion.
+    ///
+    /// In this case `span` covers the macro invocation, and `replacement` is the code with
+    /// the invocation replaced by its mutated expansion.
+    pub expansion: Option<Arc<Expansion>>,
+
+    /// A short description of the change, used in the name instead of the original text
+    /// for mutants of large spans like a whole `if let` expression.
+    pub description: Option<String>,
+}
+
+/// A mutation within the expansion of a macro.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Expansion {
+    /// The macro that generated the code, like `#[derive(PartialEq)]`.
+    pub macro_name: String,
+
+    /// The mutant within the expanded code, which is used to describe the change.
+    pub mutant: Mutant,
 }
 
 /// The function containing a mutant.
@@ -64,6 +171,11 @@ pub struct Function {
     #[allow(clippy::struct_field_names)]
     pub function_name: String,
 
+    /// The module containing the function, like `mycrate::foo::tests`, including
+    /// any inline `mod` blocks.
+    #[serde(skip)]
+    pub module_path: String,
+
     /// The return type of the function, including a leading "-> ", as a fragment of Rust syntax.
     ///
     /// Empty if the function has no return type (i.e. returns `()`).
@@ -71,6 +183,29 @@ pub struct Function {
 
     /// The span (line/column range) of the entire function.
     pub span: Span,
+
+    /// The size and complexity of the function, used to filter mutants.
+    #[serde(skip)]
+    pub size: FunctionSize,
+}
+
+/// How big and complex is a function?
+#[derive(Eq, PartialEq, Debug, Default, Clone, Copy)]
+pub struct FunctionSize {
+    /// The number of lines from the `fn` keyword to the closing brace.
+    pub lines: usize,
+
+    /// The cyclomatic complexity of the body: one more than the number of branches.
+    pub complexity: usize,
+}
+
+impl Function {
+    /// The return type without the leading "-> ", or `()` if there is none.
+    pub fn return_type_without_arrow(&self) -> &str {
+        self.return_type
+            .strip_prefix("->")
+            .map_or("()", str::trim_start)
+    }
 }
 
 impl Mutant {
@@ -92,6 +227,17 @@ impl Mutant {
             .collect::<String>()
     }
 
+    /// The module containing the mutant, like `mycrate::foo::tests`.
+    ///
+    /// Mutants outside any function, such as in derived impls, are in the module of
+    /// their file.
+    pub fn module_path(&self) -> String {
+        self.function.as_ref().map_or_else(
+            || self.source_file.module_path(),
+            |function| function.module_path.clone(),
+        )
+    }
+
     pub fn name(&self, show_line_col: bool) -> String {
         let mut v = Vec::new();
         v.push(self.source_file.tree_relative_slashes());
@@ -134,6 +280,12 @@ impl Mutant {
         fn s<S: ToString>(s: S) -> StyledObject<String> {
             style(s.to_string())
         }
+        if let Some(expansion) = &self.expansion {
+            let mut v = expansion.mutant.styled_parts();
+            v.push(s(" expanded from "));
+            v.push(s(&expansion.macro_name).magenta());
+            return v;
+        }
         let mut v: Vec<StyledObject<String>> = Vec::new();
         match self.genre {
             Genre::FnValue => {
@@ -157,16 +309,34 @@ impl Mutant {
             Genre::MatchArm => {
                 v.push(s("delete match arm"));
             }
+            Genre::LetPattern | Genre::DeleteElse => {
+                let description = self
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| one_line(&self.original_text()));
+                v.push(s(description).yellow());
+                if let Some(function) = &self.function {
+                    v.push(s(" in "));
+                    v.push(s(&function.function_name).bright().magenta());
+                }
+            }
+            Genre::SelectBranch => {
+                v.push(s("swap select branches"));
+                if let Some(function) = &self.function {
+                    v.push(s(" in "));
+                    v.push(s(&function.function_name).bright().magenta());
+                }
+            }
             _ => {
                 if self.replacement.is_empty() {
                     v.push(s("delete "));
                 } else {
                     v.push(s("replace "));
                 }
-                v.push(s(self.original_text()).yellow());
+                v.push(s(one_line(&self.original_text())).yellow());
                 if !self.replacement.is_empty() {
                     v.push(s(" with "));
-                    v.push(s(&self.replacement).bright().yellow());
+                    v.push(s(one_line(&self.replacement)).bright().yellow());
                 }
                 if let Some(function) = &self.function {
                     v.push(s(" in "));
@@ -191,12 +361,17 @@ impl Mutant {
     /// The mutated text must be passed in because we should have already computed
     /// it, and don't want to pointlessly recompute it here.
     pub fn diff(&self, mutated_code: &str) -> String {
+        self.diff_with_context(mutated_code, DEFAULT_DIFF_CONTEXT)
+    }
+
+    /// Return a unified diff for the mutant, with some number of lines of context.
+    pub fn diff_with_context(&self, mutated_code: &str, context_lines: usize) -> String {
         let old_label = self.source_file.tree_relative_slashes();
         // There shouldn't be any newlines, but just in case...
         let new_label = self.describe_change().replace('\n', " ");
         TextDiff::from_lines(self.source_file.code(), mutated_code)
             .unified_diff()
-            .context_radius(8)
+            .context_radius(context_lines)
             .header(&old_label, &new_label)
             .to_string()
     }
@@ -229,16 +404,28 @@ impl Mutant {
     }
 }
 
+/// Collapse runs of whitespace, including newlines, into single spaces, so that
+/// source fragments spanning several lines can be shown in a one-line description.
+pub(crate) fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
 impl fmt::Debug for Mutant {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // Custom implementation to show spans more concisely
-        f.debug_struct("Mutant")
-            .field("function", &self.function)
+        let mut d = f.debug_struct("Mutant");
+        d.field("function", &self.function)
             .field("replacement", &self.replacement)
             .field("genre", &self.genre)
             .field("span", &self.span)
-            .field("package_name", &self.source_file.package.name)
-            .finish()
+            .field("package_name", &self.source_file.package.name);
+        if let Some(expansion) = &self.expansion {
+            d.field("expansion", expansion);
+        }
+        if let Some(description) = &self.description {
+            d.field("description", description);
+        }
+        d.finish()
     }
 }
 
@@ -248,13 +435,19 @@ impl Serialize for Mutant {
         S: Serializer,
     {
         // custom serialize to omit inessential info
-        let mut ss = serializer.serialize_struct("Mutant", 7)?;
+        let mut ss = serializer.serialize_struct("Mutant", 10)?;
         ss.serialize_field("package", &self.source_file.package.name)?;
         ss.serialize_field("file", &self.source_file.tree_relative_slashes())?;
+        ss.serialize_field("module_path", &self.module_path())?;
         ss.serialize_field("function", &self.function.as_ref().map(Arc::as_ref))?;
         ss.serialize_field("span", &self.span)?;
+        ss.serialize_field("byte_span", &self.span.byte_range(self.source_file.code()))?;
+        ss.serialize_field("lines", &self.span.line_count())?;
         ss.serialize_field("replacement", &self.replacement)?;
         ss.serialize_field("genre", &self.genre)?;
+        if let Some(expansion) = &self.expansion {
+            ss.serialize_field("expanded_from", &expansion.macro_name)?;
+        }
         ss.end()
     }
 }
@@ -278,7 +471,7 @@ mod test {
             .discover(&PackageFilter::All, &options, &Console::new())
             .unwrap()
             .mutants;
-        assert_eq!(mutants.len(), 5);
+        assert_eq!(mutants.len(), 7);
         assert_eq!(
             format!("{:#?}", mutants[0]),
             indoc! {
@@ -286,8 +479,13 @@ mod test {
                     function: Some(
                         Function {
                             function_name: "main",
+                            module_path: "factorial",
                             return_type: "",
                             span: Span(1, 1, 5, 2),
+                            size: FunctionSize {
+                                lines: 5,
+                                complexity: 2,
+                            },
                         },
                     ),
                     replacement: "()",
@@ -302,14 +500,19 @@ mod test {
             "src/bin/factorial.rs:2:5: replace main with ()"
         );
         assert_eq!(
-            format!("{:#?}", mutants[1]),
+            format!("{:#?}", mutants[2]),
             indoc! { r#"
                 Mutant {
                     function: Some(
                         Function {
                             function_name: "factorial",
+                            module_path: "factorial",
                             return_type: "-> u32",
                             span: Span(7, 1, 13, 2),
+                            size: FunctionSize {
+                                lines: 7,
+                                complexity: 2,
+                            },
                         },
                     ),
                     replacement: "0",
@@ -320,15 +523,15 @@ mod test {
             }
         );
         assert_eq!(
-            mutants[1].name(false),
+            mutants[2].name(false),
             "src/bin/factorial.rs: replace factorial -> u32 with 0"
         );
         assert_eq!(
-            mutants[1].name(true),
+            mutants[2].name(true),
             "src/bin/factorial.rs:8:5: replace factorial -> u32 with 0"
         );
         assert_eq!(
-            mutants[2].name(true),
+            mutants[3].name(true),
             "src/bin/factorial.rs:8:5: replace factorial -> u32 with 1"
         );
     }
@@ -346,8 +549,12 @@ mod test {
             descriptions,
             [
                 "replace controlled_loop with ()",
+                "replace should_stop() with !(should_stop()) in controlled_loop",
+                "replace break with continue in controlled_loop",
+                "replace start.elapsed() > Duration::from_secs(60 * 5) with !(start.elapsed() > Duration::from_secs(60 * 5)) in controlled_loop",
                 "replace > with == in controlled_loop",
                 "replace > with < in controlled_loop",
+                "replace > with >= in controlled_loop",
                 "replace * with + in controlled_loop",
                 "replace * with / in controlled_loop",
             ]
@@ -371,6 +578,37 @@ mod test {
         assert_eq!(mutants, []);
     }
 
+    #[test]
+    fn json_includes_module_bytes_and_lines() {
+        let code = indoc! { r"
+            mod inner {
+                pub fn is_big(x: u32) -> bool {
+                    x > 10
+                }
+            }
+        " };
+        let mutants = mutate_source_str(code, &Options::default()).unwrap();
+        let json = serde_json::to_value(&mutants).unwrap();
+        let comparison = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|m| m["replacement"] == "==")
+            .unwrap();
+        assert_eq!(
+            comparison["module_path"],
+            "cargo_mutants_testdata_internal::inner"
+        );
+        assert_eq!(comparison["function"]["function_name"], "inner::is_big");
+        assert_eq!(comparison["genre"], "BinaryOperator");
+        assert_eq!(
+            comparison["byte_span"],
+            serde_json::json!({ "start": 58, "end": 59 })
+        );
+        assert_eq!(&code[58..59], ">");
+        assert_eq!(comparison["lines"], 1);
+    }
+
     #[test]
     fn mutate_factorial() -> Result<()> {
         let temp = copy_of_testdata("factorial");
@@ -378,7 +616,7 @@ mod test {
         let mutants = Workspace::open(tree_path)?
             .discover(&PackageFilter::All, &Options::default(), &Console::new())?
             .mutants;
-        assert_eq!(mutants.len(), 5);
+        assert_eq!(mutants.len(), 7);
 
         let mutated_code = mutants[0].mutated_code();
         assert_eq!(mutants[0].function.as_ref().unwrap().function_name, "main");
@@ -406,9 +644,9 @@ mod test {
             }
         );
 
-        let mutated_code = mutants[1].mutated_code();
+        let mutated_code = mutants[2].mutated_code();
         assert_eq!(
-            mutants[1].function.as_ref().unwrap().function_name,
+            mutants[2].function.as_ref().unwrap().function_name,
             "factorial"
         );
         assert_eq!(
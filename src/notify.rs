@@ -0,0 +1,126 @@
+// Copyright 2025 Martin Pool
+
+//! Notifications that a run has finished, for `--notify` and `--notify-webhook`.
+//!
+//! Runs can take hours, so these tell people to come back and look at the results.
+//! Failing to send a notification is only a warning: the results are already written.
+
+#![warn(clippy::pedantic)]
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use camino::Utf8Path;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::exit_code;
+use crate::options::Options;
+use crate::outcome::LabOutcome;
+use crate::scores::{format_score, Tally};
+
+/// How long to wait for the webhook to respond.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The JSON body posted to the webhook.
+///
+/// `text` is understood by Slack and other chat services' incoming webhooks; the other
+/// fields are the same as in the history file.
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    text: &'a str,
+    root: &'a Utf8Path,
+    total_mutants: usize,
+    caught: usize,
+    missed: usize,
+    timeout: usize,
+    unviable: usize,
+    score: Option<f64>,
+}
+
+/// Send the notifications configured in the options.
+pub fn notify_finished(lab_outcome: &LabOutcome, workspace_root: &Utf8Path, options: &Options) {
+    if !options.notify && options.notify_webhook.is_none() {
+        return;
+    }
+    let text = notification_text(lab_outcome, workspace_root);
+    if options.notify {
+        show_desktop_notification(&text);
+    }
+    if let Some(url) = &options.notify_webhook {
+        let payload = WebhookPayload {
+            text: &text,
+            root: workspace_root,
+            total_mutants: lab_outcome.total_mutants,
+            caught: lab_outcome.caught,
+            missed: lab_outcome.missed,
+            timeout: lab_outcome.timeout,
+            unviable: lab_outcome.unviable,
+            score: Tally::from(lab_outcome).score(),
+        };
+        post_webhook(url, &payload);
+    }
+}
+
+/// Describe the results in one line, like "mytree: 5 mutants tested, score 80.0%, 1 missed".
+fn notification_text(lab_outcome: &LabOutcome, workspace_root: &Utf8Path) -> String {
+    let name = workspace_root
+        .file_name()
+        .unwrap_or(workspace_root.as_str());
+    if lab_outcome.exit_code() == exit_code::BASELINE_FAILED {
+        return format!("{name}: tests failed in the unmutated tree");
+    }
+    let mut text = format!(
+        "{name}: {} mutants tested, score {}, {} missed",
+        lab_outcome.total_mutants,
+        format_score(Tally::from(lab_outcome).score()),
+        lab_outcome.missed
+    );
+    if lab_outcome.timeout > 0 {
+        write!(text, ", {} timeouts", lab_outcome.timeout).unwrap();
+    }
+    text
+}
+
+fn show_desktop_notification(text: &str) {
+    debug!(?text, "Show desktop notification");
+    if let Err(err) = notify_rust::Notification::new()
+        .summary("cargo-mutants finished")
+        .body(text)
+        .show()
+    {
+        warn!("Failed to show desktop notification: {err}");
+    }
+}
+
+fn post_webhook(url: &str, payload: &WebhookPayload) {
+    debug!(?url, ?payload, "Post webhook");
+    let json = serde_json::to_string(payload).expect("serialize webhook payload");
+    if let Err(err) = ureq::post(url)
+        .timeout(WEBHOOK_TIMEOUT)
+        .set("Content-Type", "application/json")
+        .send_string(&json)
+    {
+        warn!("Failed to post notification to {url}: {err}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_has_score_and_missed_count() {
+        let lab_outcome = LabOutcome {
+            total_mutants: 6,
+            caught: 4,
+            missed: 1,
+            timeout: 1,
+            ..LabOutcome::default()
+        };
+        assert_eq!(
+            notification_text(&lab_outcome, Utf8Path::new("/src/mytree")),
+            "mytree: 6 mutants tested, score 66.6%, 1 missed, 1 timeouts"
+        );
+    }
+}
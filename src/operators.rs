@@ -0,0 +1,264 @@
+// Copyright 2025 Martin Pool
+
+//! Describe the genres of mutants that can be generated, for `cargo mutants operators`.
+
+#![warn(clippy::pedantic)]
+
+use std::fmt::Write;
+
+use serde_json::{json, Value};
+use strum::VariantNames;
+
+use crate::mutant::Genre;
+use crate::options::Options;
+
+/// How to list the operators.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+pub struct OperatorsArgs {
+    /// Output json.
+    #[arg(long)]
+    json: bool,
+}
+
+/// A short description of a genre, and an example of the code before and after mutation.
+struct Description {
+    description: &'static str,
+    example: Option<(&'static str, &'static str)>,
+}
+
+/// Return the description of a genre.
+///
+/// This matches every genre, so that new genres can't be added without describing them.
+#[allow(clippy::too_many_lines)] // long but pretty straightforward
+fn describe(genre: Genre) -> Description {
+    let (description, example) = match genre {
+        Genre::FnValue => (
+            "Replace the body of a function with a fixed value of its return type.",
+            Some((
+                "fn is_valid(n: usize) -> bool { n < LIMIT }",
+                "fn is_valid(n: usize) -> bool { true }",
+            )),
+        ),
+        Genre::BinaryOperator => (
+            "Replace a binary operator with another, like `<` with `<=`.",
+            Some(("n < LIMIT", "n <= LIMIT")),
+        ),
+        Genre::UnaryOperator => (
+            "Delete a unary operator, like `!` or `-`.",
+            Some(("!done", "done")),
+        ),
+        Genre::MatchArm => (
+            "Delete a match arm, when there's a wildcard arm to fall back to.",
+            Some(("match c { 'a' => 1, _ => 0 }", "match c { _ => 0 }")),
+        ),
+        Genre::MatchArmGuard => (
+            "Replace the guard of a match arm with `true` or `false`.",
+            Some(("Some(n) if n > 0 => n,", "Some(n) if true => n,")),
+        ),
+        Genre::ArithmeticMethod => (
+            "Replace an arithmetic method with its counterpart, like `checked_add` with `checked_sub`.",
+            Some(("a.checked_add(b)", "a.checked_sub(b)")),
+        ),
+        Genre::NegateCondition => (
+            "Negate the condition of an `if` or `while` expression.",
+            Some(("if v.is_empty() {", "if !(v.is_empty()) {")),
+        ),
+        Genre::DeleteStatement => (
+            "Delete a statement that calls a function or method for its side effects.",
+            Some(("v.push(x); notify(x);", "v.push(x);")),
+        ),
+        Genre::Combinator => (
+            "Replace an `Option` or `Result` combinator with another.",
+            Some(("x.unwrap_or(7)", "x.unwrap_or_default()")),
+        ),
+        Genre::IteratorAdapter => (
+            "Delete an iterator adapter, or swap `.min()` and `.max()`.",
+            Some(("v.iter().rev().collect()", "v.iter().collect()")),
+        ),
+        Genre::SwapOperands => (
+            "Swap the operands of a non-commutative binary operator.",
+            Some(("a - b", "b - a")),
+        ),
+        Genre::Index => (
+            "Add or subtract one from an index, or swap `first()` and `last()`.",
+            Some(("v[i]", "v[i + 1]")),
+        ),
+        Genre::ShiftAmount => (
+            "Add or subtract one from the amount of a shift.",
+            Some(("a << n", "a << (n + 1)")),
+        ),
+        Genre::Range => (
+            "Swap an exclusive range with an inclusive range.",
+            Some(("&v[0..2]", "&v[0..=2]")),
+        ),
+        Genre::TryOperator => (
+            "Replace the `?` operator with `.unwrap()`.",
+            Some(("s.parse()?", "s.parse().unwrap()")),
+        ),
+        Genre::SwapArguments => (
+            "Swap two arguments of the same type in a call.",
+            Some(("divide(a, b)", "divide(b, a)")),
+        ),
+        Genre::LetPattern => (
+            "Force the pattern in an `if let` to match or not, or make a `let ... else` always diverge.",
+            Some(("if let Some(n) = x { n } else { 0 }", "{ 0 }")),
+        ),
+        Genre::ClosureValue => (
+            "Replace the body of a closure with a value of the right type.",
+            Some((
+                "v.sort_by(|a, b| b.cmp(a))",
+                "v.sort_by(|a, b| std::cmp::Ordering::Less)",
+            )),
+        ),
+        Genre::EarlyReturn => (
+            "Delete a `return` from an `if` without an `else`.",
+            Some(("if n == 0 { return 1; }", "if n == 0 {}")),
+        ),
+        Genre::DeleteElse => (
+            "Delete the `else` branch of an `if` whose value is `()`.",
+            Some((
+                "if n > 1 { v.push(1); } else { v.push(2); }",
+                "if n > 1 { v.push(1); }",
+            )),
+        ),
+        Genre::LoopControl => (
+            "Swap `break` and `continue`, or delete the label from `break 'label`.",
+            Some(("continue", "break")),
+        ),
+        Genre::Cast => (
+            "Change the type of an `as` cast between numbers.",
+            Some(("x as u64", "x as u32")),
+        ),
+        Genre::Await => (
+            "Delete `.await` from a statement that discards the future's output.",
+            Some(("flush().await;", "flush();")),
+        ),
+        Genre::SelectBranch => (
+            "Swap adjacent branches of a biased `select!`.",
+            Some((
+                "biased; m = rx.recv() => handle(m), _ = stop => return,",
+                "biased; _ = stop => return, m = rx.recv() => handle(m),",
+            )),
+        ),
+        Genre::Timeout => (
+            "Replace the duration passed to a timeout with zero or the maximum.",
+            Some((
+                "timeout(Duration::from_secs(5), f)",
+                "timeout(::std::time::Duration::ZERO, f)",
+            )),
+        ),
+        Genre::Plugin => ("A mutant generated by an external plugin.", None),
+        Genre::ConstValue => (
+            "Replace a numeric or boolean literal in a `const` or `static` initializer.",
+            Some(("const LIMIT: usize = 10;", "const LIMIT: usize = 11;")),
+        ),
+        Genre::BooleanLiteral => (
+            "Flip a `true` or `false` literal passed as an argument or struct field.",
+            Some(("v.push(true)", "v.push(false)")),
+        ),
+        Genre::NumericLiteral => (
+            "Replace an integer or float literal with a nearby value.",
+            Some(("x + 3", "x + 4")),
+        ),
+        Genre::StringLiteral => (
+            "Replace a non-empty string literal with `\"\"` or `\"xyzzy\"`.",
+            Some(("\"hello\"", "\"xyzzy\"")),
+        ),
+    };
+    Description {
+        description,
+        example,
+    }
+}
+
+/// All the genres, in the order they're declared.
+fn all_genres() -> impl Iterator<Item = Genre> {
+    Genre::VARIANTS
+        .iter()
+        .map(|name| name.parse().expect("parse genre name"))
+}
+
+/// Describe whether a genre is enabled by the options.
+fn status(genre: Genre, options: &Options) -> &'static str {
+    if options.allows_genre(genre) {
+        "enabled"
+    } else if genre.is_opt_in()
+        && options.genres.is_empty()
+        && !options.exclude_genres.contains(&genre)
+    {
+        "off by default"
+    } else {
+        "disabled"
+    }
+}
+
+/// List the genres, with their descriptions and examples, and whether they're enabled by
+/// the current options.
+pub fn list_operators(options: &Options, args: &OperatorsArgs) -> String {
+    if args.json {
+        let list: Vec<Value> = all_genres()
+            .map(|genre| {
+                let Description {
+                    description,
+                    example,
+                } = describe(genre);
+                json!({
+                    "genre": genre.to_string(),
+                    "description": description,
+                    "before": example.map(|(before, _)| before),
+                    "after": example.map(|(_, after)| after),
+                    "enabled": options.allows_genre(genre),
+                    "opt_in": genre.is_opt_in(),
+                })
+            })
+            .collect();
+        return serde_json::to_string_pretty(&list).expect("Serialize operators") + "\n";
+    }
+    let mut s = String::new();
+    for genre in all_genres() {
+        let Description {
+            description,
+            example,
+        } = describe(genre);
+        writeln!(s, "{genre}: {}", status(genre, options)).unwrap();
+        writeln!(s, "    {description}").unwrap();
+        if let Some((before, after)) = example {
+            writeln!(s, "    - {before}").unwrap();
+            writeln!(s, "    + {after}").unwrap();
+        }
+        s.push('\n');
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn every_genre_is_listed_with_its_status() {
+        let options = Options {
+            enable_genres: vec![Genre::Cast],
+            exclude_genres: vec![Genre::FnValue],
+            ..Options::default()
+        };
+        let text = list_operators(&options, &OperatorsArgs { json: false });
+        assert_eq!(text.matches("\n\n").count(), Genre::VARIANTS.len());
+        assert!(text.starts_with(
+            "FnValue: disabled\n    Replace the body of a function with a fixed value of its return type.\n    - fn is_valid"
+        ));
+        assert!(text.contains("\nBinaryOperator: enabled\n"));
+        assert!(text.contains("\nCast: enabled\n"));
+        assert!(text.contains("\nStringLiteral: off by default\n"));
+        assert!(
+            text.contains("\nPlugin: enabled\n    A mutant generated by an external plugin.\n\n")
+        );
+
+        let json: Vec<Value> =
+            serde_json::from_str(&list_operators(&options, &OperatorsArgs { json: true })).unwrap();
+        assert_eq!(json.len(), Genre::VARIANTS.len());
+        assert_eq!(json[0]["genre"], "FnValue");
+        assert_eq!(json[0]["enabled"], false);
+        assert_eq!(json[1]["before"], "n < LIMIT");
+    }
+}
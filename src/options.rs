@@ -10,22 +10,27 @@
 
 #![warn(clippy::pedantic)]
 
+use std::collections::BTreeMap;
 use std::env;
 #[cfg(test)]
 use std::ffi::OsString;
 use std::time::Duration;
 
+use anyhow::ensure;
 use camino::{Utf8Path, Utf8PathBuf};
 use globset::GlobSet;
 use regex::RegexSet;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
-use syn::Expr;
+use syn::{Expr, Path};
 use tracing::warn;
 
-use crate::config::Config;
+use crate::config::{Config, TimeoutOverrideConfig};
+use crate::fnvalue::ErrorValues;
 use crate::glob::build_glob_set;
-use crate::mutant::Mutant;
+use crate::hooks::Hooks;
+use crate::line_range::LineRange;
+use crate::mutant::{FunctionSize, Genre, Mutant};
 use crate::{Args, BaselineStrategy, Context, Phase, Result, ValueEnum};
 
 /// Options for mutation testing, based on both command-line arguments and the
@@ -42,15 +47,28 @@ pub struct Options {
     /// Don't run the tests, just see if each mutant builds.
     pub check_only: bool,
 
+    /// Check all mutants before building and testing any of them.
+    pub check_first: bool,
+
+    /// Don't test mutants that build to the same test binaries as the unmutated tree.
+    pub detect_equivalent: bool,
+
     /// Copy `.git` and other VCS directories to build directories.
     pub copy_vcs: bool,
 
+    /// Report mutants that this coverage file shows are never executed as missed,
+    /// without testing them.
+    pub coverage: Option<Utf8PathBuf>,
+
     /// Don't copy files matching gitignore patterns to build directories.
     pub gitignore: bool,
 
     /// Don't copy at all; run tests in the source directory.
     pub in_place: bool,
 
+    /// Reuse outcomes of unchanged mutants from earlier runs.
+    pub incremental: bool,
+
     /// Run a jobserver to limit concurrency between child processes.
     pub jobserver: bool,
 
@@ -60,6 +78,17 @@ pub struct Options {
     /// Don't delete scratch directories.
     pub leak_dirs: bool,
 
+    /// Run rustc through sccache.
+    pub sccache: bool,
+
+    /// Absolute path of a directory for cargo's intermediate build files, shared by all
+    /// the build directories.
+    pub shared_target_dir: Option<Utf8PathBuf>,
+
+    /// Absolute path of a directory to make the build directories in, instead of the
+    /// system temporary directory.
+    pub scratch_dir: Option<Utf8PathBuf>,
+
     /// The time limit for test tasks, if set.
     ///
     /// If this is not set by the user it's None, in which case there is no time limit
@@ -70,6 +99,12 @@ pub struct Options {
     /// The time multiplier for test tasks, if set (relative to baseline test duration).
     pub test_timeout_multiplier: Option<f64>,
 
+    /// Find which tests cover each mutant with cargo-llvm-cov, and run only those.
+    pub test_impact: bool,
+
+    /// Run only the tests that cover each mutant according to this test map.
+    pub test_map: Option<Utf8PathBuf>,
+
     /// Which packages to test for a given mutant.
     ///
     /// Comes from `--test-workspace` etc.
@@ -88,6 +123,9 @@ pub struct Options {
     /// The minimum test timeout, as a floor on the autoset value.
     pub minimum_test_timeout: Duration,
 
+    /// Timeouts for mutants in source files matching globs, in order of precedence.
+    pub timeout_overrides: Vec<TimeoutOverride>,
+
     pub print_caught: bool,
     pub print_unviable: bool,
 
@@ -96,6 +134,12 @@ pub struct Options {
     /// Show logs even from mutants that were caught, or source/unmutated builds.
     pub show_all_logs: bool,
 
+    /// Show the full-screen terminal interface while testing.
+    pub tui: bool,
+
+    /// The number of lines of each running scenario's log to show under the progress bar.
+    pub show_output_lines: usize,
+
     /// List mutants with line and column numbers.
     pub show_line_col: bool,
 
@@ -105,21 +149,62 @@ pub struct Options {
     /// interesting results.
     pub shuffle: bool,
 
+    /// Seed for the random number generator used to shuffle and sample mutants.
+    ///
+    /// If not set, a random seed is chosen.
+    pub seed: Option<u64>,
+
+    /// Test at most this many mutants of each genre.
+    pub max_mutants_per_genre: Option<usize>,
+
+    /// Test this fraction of the mutants of each genre.
+    pub genre_sample_ratio: Option<f64>,
+
+    /// Skip items carrying any of these attributes, in addition to `#[mutants::skip]`.
+    ///
+    /// Each is either an attribute path like `generated`, which matches regardless of the
+    /// attribute's arguments, or a whole attribute like `inline(always)`.
+    pub skip_attrs: Vec<String>,
+
     /// Don't mutate arguments to functions or methods matching any of these name.
     ///
     /// This matches as a string against the last component of the path, so should not include
     /// `::`.
     pub skip_calls: Vec<String>,
 
+    /// Run these programs to generate additional mutants.
+    pub plugins: Vec<String>,
+
+    /// Check batches of mutants before testing them, to quickly discard unviable mutants.
+    pub prescreen: bool,
+
+    /// Build the mutants of each file together, selected at runtime by an environment
+    /// variable.
+    pub schemata: bool,
+
     /// Cargo profile.
     pub profile: Option<String>,
 
+    /// Hosts to test mutants on over SSH, as well as locally, for each worker.
+    pub ssh_workers: Vec<String>,
+
+    /// Directory on SSH worker hosts holding the copy of the tree, if not the default.
+    pub remote_dir: Option<String>,
+
+    /// Shell command that runs cargo-mutants on SSH worker hosts.
+    pub remote_command: String,
+
     /// Additional arguments for every cargo invocation.
     pub additional_cargo_args: Vec<String>,
 
     /// Additional arguments to `cargo test`.
     pub additional_cargo_test_args: Vec<String>,
 
+    /// Environment variables for cargo build and test subprocesses.
+    ///
+    /// Later entries override earlier entries of the same name.
+    pub env: Vec<(String, String)>,
+
     /// Selection of features for cargo.
     pub features: super::Features,
 
@@ -129,32 +214,126 @@ pub struct Options {
     /// Files to exclude.
     pub exclude_globset: Option<GlobSet>,
 
+    /// Don't mutate packages whose names match these globs.
+    pub exclude_packages: Vec<String>,
+
     /// Mutants to examine, as a regexp matched against the full name.
     pub examine_names: RegexSet,
 
     /// Mutants to skip, as a regexp matched against the full name.
     pub exclude_names: RegexSet,
 
+    /// Mutate only functions whose return types match these regexps.
+    pub return_types: RegexSet,
+
+    /// Include only mutants that intersect these line ranges.
+    pub line_ranges: Vec<LineRange>,
+
+    /// Include only mutants in functions whose size and complexity are within these limits.
+    pub function_size_limits: FunctionSizeLimits,
+
     /// Create `mutants.out` within this directory (by default, the source directory).
     pub output_in_dir: Option<Utf8PathBuf>,
 
+    /// Generate mutants only in public items.
+    pub only_pub: bool,
+
     /// Run this many `cargo build` or `cargo test` tasks in parallel.
     pub jobs: Option<usize>,
 
+    /// Expand macros with rustc, and mutate the code generated by derives.
+    pub expand_macros: bool,
+
     /// Insert these values as errors from functions returning `Result`.
     pub error_values: Vec<String>,
 
+    /// Insert these values as errors from functions returning `Result` with these error
+    /// types, instead of `error_values`.
+    pub error_values_by_type: BTreeMap<String, String>,
+
+    /// Generate mutants of these opt-in genres, in addition to the default genres.
+    pub enable_genres: Vec<Genre>,
+
+    /// Generate only mutants of these genres, if non-empty.
+    pub genres: Vec<Genre>,
+
+    /// Don't generate mutants of these genres.
+    pub exclude_genres: Vec<Genre>,
+
+    /// Add and subtract these amounts from numeric literals; if empty, 1.
+    pub numeric_literal_deltas: Vec<u64>,
+
     /// Show ANSI colors.
     pub colors: Colors,
 
+    /// How to print results, such as with annotations for GitHub Actions.
+    pub output_format: OutputFormat,
+
+    /// How to show progress while testing.
+    pub progress_style: ProgressStyle,
+
+    /// Write a stream of JSON events to this file.
+    pub events_file: Option<Utf8PathBuf>,
+
+    /// Append a summary of each run to this file.
+    pub history_file: Option<Utf8PathBuf>,
+
+    /// Shell commands to run before and after each scenario, and the whole run.
+    pub hooks: Hooks,
+
+    /// Fail with a distinct exit code if the mutation score is below this percentage,
+    /// and otherwise don't fail because of missed mutants or timeouts.
+    pub minimum_score: Option<f64>,
+
+    /// Show a desktop notification when the run finishes.
+    pub notify: bool,
+
+    /// Post a JSON summary to this URL when the run finishes.
+    pub notify_webhook: Option<String>,
+
+    /// How to count mutants that timed out in the score, by default as missed.
+    pub score_timeouts: Option<ScoreAs>,
+
+    /// How to count unviable mutants in the score, by default excluding them.
+    pub score_unviable: Option<ScoreAs>,
+
     /// List mutants in json, etc.
     pub emit_json: bool,
 
     /// Emit diffs showing just what changed.
     pub emit_diffs: bool,
 
+    /// Lines of context around each change in listed diffs.
+    pub diff_context: usize,
+
     /// The tool to use to run tests.
     pub test_tool: TestTool,
+
+    /// Options for workspace members that have their own config file, by package name.
+    pub per_package: BTreeMap<String, Options>,
+}
+
+/// Timeouts for mutants in source files matching a glob.
+#[derive(Debug, Clone)]
+pub struct TimeoutOverride {
+    pub globset: GlobSet,
+    pub test_timeout: Option<Duration>,
+    pub build_timeout: Option<Duration>,
+}
+
+impl TimeoutOverride {
+    fn new(config: &TimeoutOverrideConfig) -> Result<TimeoutOverride> {
+        let timeout = |secs: Option<f64>| {
+            secs.map(Duration::try_from_secs_f64)
+                .transpose()
+                .with_context(|| format!("Invalid timeout for {:?}", config.path))
+        };
+        Ok(TimeoutOverride {
+            globset: build_glob_set(&[&config.path])?.expect("one glob"),
+            test_timeout: timeout(config.test_timeout)?,
+            build_timeout: timeout(config.build_timeout)?,
+        })
+    }
 }
 
 /// Which packages should be tested for a given mutant?
@@ -172,7 +351,9 @@ pub enum TestPackages {
 }
 
 /// Choice of tool to use to run tests.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumString, Display, Deserialize)]
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, EnumString, Display, Deserialize, Serialize,
+)]
 #[strum(serialize_all = "snake_case")]
 #[serde(rename_all = "snake_case")]
 pub enum TestTool {
@@ -184,6 +365,63 @@ pub enum TestTool {
     Nextest,
 }
 
+/// Which items to mutate, by their visibility.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, EnumString, Display, Deserialize, Serialize,
+)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    /// Mutate all items, the default.
+    #[default]
+    All,
+
+    /// Mutate only public items.
+    Pub,
+}
+
+/// How to count mutants with some outcome in the mutation score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString, Display, Deserialize, Serialize)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreAs {
+    /// Count them as caught.
+    Caught,
+
+    /// Count them as missed.
+    Missed,
+
+    /// Leave them out of the score.
+    Excluded,
+}
+
+/// Parse an environment variable setting like `NAME=value`.
+pub fn parse_env_var(s: &str) -> Result<(String, String)> {
+    let (name, value) = s
+        .split_once('=')
+        .with_context(|| format!("environment variable {s:?} should be like NAME=value"))?;
+    ensure!(
+        !name.is_empty(),
+        "environment variable name is empty in {s:?}"
+    );
+    Ok((name.to_owned(), value.to_owned()))
+}
+
+/// Choose an explicit timeout from the command line or the config.
+///
+/// A multiplier on the command line overrides an explicit timeout in the config.
+fn explicit_timeout(
+    arg_secs: Option<f64>,
+    arg_multiplier: Option<f64>,
+    config_secs: Option<f64>,
+) -> Result<Option<Duration>> {
+    arg_secs
+        .or(config_secs.filter(|_| arg_multiplier.is_none()))
+        .map(Duration::try_from_secs_f64)
+        .transpose()
+        .map_err(anyhow::Error::from)
+}
+
 /// Join two slices into a new vector.
 fn join_slices(a: &[String], b: &[String]) -> Vec<String> {
     a.iter().chain(b).cloned().collect()
@@ -228,8 +466,37 @@ impl Colors {
     }
 }
 
+/// How to print the results of testing each mutant.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, ValueEnum)]
+#[strum(serialize_all = "snake_case")]
+pub enum OutputFormat {
+    /// Plain text for a terminal or log.
+    #[default]
+    Text,
+    /// Also print GitHub Actions annotations for missed mutants, and write a job summary.
+    Github,
+}
+
+/// How to show progress while testing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, ValueEnum)]
+#[strum(serialize_all = "snake_case")]
+pub enum ProgressStyle {
+    /// An animated progress bar, redrawn in place on the terminal.
+    #[default]
+    Fancy,
+    /// A line of text with the counts so far, every few seconds, for terminals and logs
+    /// that can't redraw lines.
+    Plain,
+    /// Events as lines of JSON, and the counts so far every few seconds, for other
+    /// programs such as editors to follow.
+    Json,
+    /// No progress, and only a one-line summary after testing, for CI logs.
+    Quiet,
+}
+
 impl Options {
     /// Build options by merging command-line args and config file.
+    #[allow(clippy::too_many_lines)] // long but pretty straightforward
     pub(crate) fn new(args: &Args, config: &Config) -> Result<Options> {
         if args.no_copy_target {
             warn!("--no-copy-target is deprecated and has no effect; target/ is never copied");
@@ -241,6 +508,32 @@ impl Options {
                 .unwrap_or(20f64),
         );
 
+        let minimum_score = args.minimum_score.or(config.minimum_score);
+        if let Some(minimum_score) = minimum_score {
+            ensure!(
+                (0.0..=100.0).contains(&minimum_score),
+                "minimum score {minimum_score} should be a percentage between 0 and 100"
+            );
+        }
+
+        // Cargo runs in the build directories, so directories it uses must be absolute.
+        let absolute = |dir: Option<&Utf8PathBuf>| -> Result<Option<Utf8PathBuf>> {
+            match dir {
+                Some(dir) => Ok(Some(
+                    Utf8PathBuf::try_from(std::env::current_dir()?)
+                        .context("current directory is not UTF-8")?
+                        .join(dir),
+                )),
+                None => Ok(None),
+            }
+        };
+        let shared_target_dir = absolute(
+            args.shared_target_dir
+                .as_ref()
+                .or(config.shared_target_dir.as_ref()),
+        )?;
+        let scratch_dir = absolute(args.scratch_dir.as_ref().or(config.scratch_dir.as_ref()))?;
+
         // If either command line argument is set, it overrides the config.
         let test_package = if args.test_workspace == Some(true) {
             TestPackages::Workspace
@@ -282,58 +575,188 @@ impl Options {
                 &config.additional_cargo_test_args,
             ),
             baseline: args.baseline,
-            build_timeout: args.build_timeout.map(Duration::from_secs_f64),
+            build_timeout: explicit_timeout(
+                args.build_timeout,
+                args.build_timeout_multiplier,
+                config.build_timeout,
+            )
+            .context("Invalid build timeout")?,
             build_timeout_multiplier: args
                 .build_timeout_multiplier
                 .or(config.build_timeout_multiplier),
             cap_lints: args.cap_lints.unwrap_or(config.cap_lints),
+            check_first: args.check_first || config.check_first,
             check_only: args.check,
             colors: args.colors,
+            output_format: args.output_format,
+            // An explicit --tui or --progress-style wins over quiet progress set in the
+            // environment.
+            progress_style: match args.progress_style {
+                Some(progress_style) => progress_style,
+                None if args.quiet_progress && !args.tui => ProgressStyle::Quiet,
+                None => ProgressStyle::default(),
+            },
+            events_file: args.events_file.clone(),
+            history_file: args.history_file.clone().or(config.history_file.clone()),
+            hooks: Hooks {
+                before_run: args.before_run.clone().or(config.hooks.before_run.clone()),
+                after_run: args.after_run.clone().or(config.hooks.after_run.clone()),
+                before_scenario: args
+                    .before_scenario
+                    .clone()
+                    .or(config.hooks.before_scenario.clone()),
+                after_scenario: args
+                    .after_scenario
+                    .clone()
+                    .or(config.hooks.after_scenario.clone()),
+            },
+            minimum_score,
+            notify: args.notify || config.notify,
+            notify_webhook: args
+                .notify_webhook
+                .clone()
+                .or(config.notify_webhook.clone()),
+            score_timeouts: args.score_timeouts.or(config.score_timeouts),
+            score_unviable: args.score_unviable.or(config.score_unviable),
+            detect_equivalent: args.detect_equivalent || config.detect_equivalent,
             copy_vcs: args.copy_vcs.or(config.copy_vcs).unwrap_or(false),
+            coverage: args.coverage.clone(),
             emit_json: args.json,
+            env: config
+                .env
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .chain(args.env.iter().cloned())
+                .collect(),
             emit_diffs: args.diff,
+            diff_context: args.diff_context,
+            enable_genres: args
+                .enable_genre
+                .iter()
+                .chain(&config.enable_genres)
+                .copied()
+                .chain(config.genres.switched(true))
+                .collect(),
             error_values: join_slices(&args.error, &config.error_values),
+            error_values_by_type: config.error_values_by_type.clone(),
+            expand_macros: args.expand_macros || config.expand_macros,
+            exclude_genres: args
+                .exclude_genre
+                .iter()
+                .chain(&config.exclude_genres)
+                .copied()
+                .chain(config.genres.switched(false))
+                .collect(),
             examine_names: RegexSet::new(or_slices(&args.examine_re, &config.examine_re))
                 .context("Failed to compile examine_re regex")?,
             exclude_names: RegexSet::new(or_slices(&args.exclude_re, &config.exclude_re))
                 .context("Failed to compile exclude_re regex")?,
+            return_types: RegexSet::new(or_slices(&args.return_type, &config.return_type))
+                .context("Failed to compile return_type regex")?,
+            line_ranges: args.lines.clone(),
+            function_size_limits: FunctionSizeLimits {
+                min_complexity: args.min_complexity.or(config.min_complexity),
+                max_complexity: args.max_complexity.or(config.max_complexity),
+                min_lines: args.min_function_lines.or(config.min_function_lines),
+                max_lines: args.max_function_lines.or(config.max_function_lines),
+            },
             examine_globset: build_glob_set(or_slices(&args.file, &config.examine_globs))?,
             exclude_globset: build_glob_set(or_slices(&args.exclude, &config.exclude_globs))?,
+            exclude_packages: join_slices(&args.exclude_package, &config.exclude_packages),
             features: args.features.clone(),
+            genres: or_slices(&args.genre, config.genres.list()).to_vec(),
+            genre_sample_ratio: args.genre_sample_ratio.or(config.genre_sample_ratio),
             gitignore: args.gitignore,
             in_place: args.in_place,
+            incremental: args.incremental || config.incremental,
             jobs: args.jobs,
             jobserver: args.jobserver,
             jobserver_tasks: args.jobserver_tasks,
             leak_dirs: args.leak_dirs,
+            sccache: args.sccache || config.sccache,
+            shared_target_dir,
+            scratch_dir,
+            max_mutants_per_genre: args.max_mutants_per_genre.or(config.max_mutants_per_genre),
             minimum_test_timeout,
+            numeric_literal_deltas: config
+                .genres
+                .settings(Genre::NumericLiteral)
+                .map(|settings| settings.deltas.clone())
+                .unwrap_or_default(),
             output_in_dir: args.output.clone().or(config.output.clone()),
+            only_pub: args.only_pub || config.visibility == Some(Visibility::Pub),
+            plugins: join_slices(&args.plugin, &config.plugins),
+            prescreen: args.prescreen || config.prescreen,
+            schemata: args.schemata || config.schemata,
+            ssh_workers: join_slices(&args.ssh_worker, &config.ssh_workers),
+            remote_dir: args.remote_dir.clone().or(config.remote_dir.clone()),
+            remote_command: args
+                .remote_command
+                .clone()
+                .or(config.remote_command.clone())
+                .unwrap_or_else(|| "cargo mutants".to_owned()),
             print_caught: args.caught,
             print_unviable: args.unviable,
-            profile: args.profile.as_ref().or(config.profile.as_ref()).cloned(),
+            profile: if config.named_profile.is_some() {
+                // `--profile` selected a profile from the config, not a cargo profile.
+                config.profile.clone()
+            } else {
+                args.profile.as_ref().or(config.profile.as_ref()).cloned()
+            },
+            seed: args.seed.or(config.seed),
             shuffle: !args.no_shuffle,
             show_line_col: args.line_col,
             show_times: !args.no_times,
             show_all_logs: args.all_logs,
+            show_output_lines: args.show_output.unwrap_or(1),
+            tui: args.tui,
+            skip_attrs: join_slices(&args.skip_attr, &config.skip_attrs),
             skip_calls,
+            test_impact: args.test_impact || config.test_impact,
+            test_map: args.test_map.clone(),
             test_package,
-            test_timeout: args.timeout.map(Duration::from_secs_f64),
+            test_timeout: explicit_timeout(
+                args.timeout,
+                args.timeout_multiplier,
+                config.test_timeout,
+            )
+            .context("Invalid test timeout")?,
             test_timeout_multiplier: args.timeout_multiplier.or(config.timeout_multiplier),
             test_tool: args.test_tool.or(config.test_tool).unwrap_or_default(),
+            timeout_overrides: config
+                .timeout_overrides
+                .iter()
+                .map(TimeoutOverride::new)
+                .collect::<Result<_>>()?,
+            per_package: BTreeMap::new(),
         };
+        if let Some(ratio) = options.genre_sample_ratio {
+            ensure!(
+                ratio > 0.0 && ratio <= 1.0,
+                "genre_sample_ratio must be greater than 0 and at most 1: got {ratio}"
+            );
+        }
+        ensure!(
+            !options.numeric_literal_deltas.contains(&0),
+            "NumericLiteral deltas must be greater than 0"
+        );
         if let Some(jobs) = options.jobs {
             if jobs >= 8 {
                 warn!("--jobs={jobs} is probably too high and may overload your machine: each job runs a separate `cargo` process, and cargo may internally start many threads and subprocesses; values <= 8 are usually safe");
             }
         }
-        options.error_values.iter().for_each(|e| {
-            if e.starts_with("Err(") {
-                warn!(
-                    "error_value option gives the value of the error, and probably should not start with Err(: got {}",
-                    e
-                );
-            }
-        });
+        options
+            .error_values
+            .iter()
+            .chain(options.error_values_by_type.values())
+            .for_each(|e| {
+                if e.starts_with("Err(") {
+                    warn!(
+                        "error_value option gives the value of the error, and probably should not start with Err(: got {}",
+                        e
+                    );
+                }
+            });
         Ok(options)
     }
 
@@ -364,15 +787,28 @@ impl Options {
         }
     }
 
-    /// Return the syn ASTs for the error values, which should be inserted as return values
-    /// from functions returning `Result`.
-    pub(crate) fn parsed_error_exprs(&self) -> Result<Vec<Expr>> {
-        self.error_values
-            .iter()
-            .map(|e| {
-                syn::parse_str(e).with_context(|| format!("Failed to parse error value {e:?}"))
-            })
-            .collect()
+    /// Parse the error values, which should be inserted as return values from functions
+    /// returning `Result`.
+    pub(crate) fn parsed_error_values(&self) -> Result<ErrorValues> {
+        let parse_value = |e: &String| -> Result<Expr> {
+            syn::parse_str(e).with_context(|| format!("Failed to parse error value {e:?}"))
+        };
+        Ok(ErrorValues {
+            default: self
+                .error_values
+                .iter()
+                .map(parse_value)
+                .collect::<Result<_>>()?,
+            by_type: self
+                .error_values_by_type
+                .iter()
+                .map(|(error_type, value)| -> Result<(Path, Expr)> {
+                    let path = syn::parse_str(error_type)
+                        .with_context(|| format!("Failed to parse error type {error_type:?}"))?;
+                    Ok((path, parse_value(value)?))
+                })
+                .collect::<Result<_>>()?,
+        })
     }
 
     /// True if the options allow mutants to be generated from the given path.
@@ -390,11 +826,84 @@ impl Options {
                 .is_some_and(|g| g.is_match(path))
     }
 
+    /// Return the first timeout override matching a source file, if any.
+    pub fn timeout_override(&self, path: &Utf8Path) -> Option<&TimeoutOverride> {
+        self.timeout_overrides
+            .iter()
+            .find(|timeout_override| timeout_override.globset.is_match(path))
+    }
+
+    /// True if mutants of this genre should be generated.
+    ///
+    /// Genres named in `genres` are generated even if they're opt-in.
+    pub fn allows_genre(&self, genre: Genre) -> bool {
+        if self.exclude_genres.contains(&genre) {
+            false
+        } else if !self.genres.is_empty() {
+            self.genres.contains(&genre)
+        } else {
+            !genre.is_opt_in() || self.enable_genres.contains(&genre)
+        }
+    }
+
+    /// The amounts to add and subtract from numeric literals.
+    pub fn numeric_literal_deltas(&self) -> &[u64] {
+        if self.numeric_literal_deltas.is_empty() {
+            &[1]
+        } else {
+            &self.numeric_literal_deltas
+        }
+    }
+
+    /// Return the options for a package, which may come from its own config file.
+    pub fn for_package(&self, package_name: &str) -> &Options {
+        self.per_package.get(package_name).unwrap_or(self)
+    }
+
     /// True if the options allow this mutant to be tested.
     pub fn allows_mutant(&self, mutant: &Mutant) -> bool {
         let name = mutant.name(true);
         (self.examine_names.is_empty() || self.examine_names.is_match(&name))
             && (self.exclude_names.is_empty() || !self.exclude_names.is_match(&name))
+            && (self.return_types.is_empty()
+                || mutant.function.as_ref().is_some_and(|function| {
+                    self.return_types
+                        .is_match(function.return_type_without_arrow())
+                }))
+            && (self.line_ranges.is_empty()
+                || self
+                    .line_ranges
+                    .iter()
+                    .any(|range| range.intersects(&mutant.span)))
+            && (self.function_size_limits.is_unlimited()
+                || mutant
+                    .function
+                    .as_ref()
+                    .is_some_and(|function| self.function_size_limits.allows(function.size)))
+    }
+}
+
+/// Limits on the size and complexity of functions to mutate.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FunctionSizeLimits {
+    pub min_complexity: Option<usize>,
+    pub max_complexity: Option<usize>,
+    pub min_lines: Option<usize>,
+    pub max_lines: Option<usize>,
+}
+
+impl FunctionSizeLimits {
+    fn is_unlimited(&self) -> bool {
+        *self == FunctionSizeLimits::default()
+    }
+
+    /// True if a function of this size is within the limits.
+    fn allows(&self, size: FunctionSize) -> bool {
+        let within = |value: usize, min: Option<usize>, max: Option<usize>| {
+            min.map_or(true, |min| value >= min) && max.map_or(true, |max| value <= max)
+        };
+        within(size.complexity, self.min_complexity, self.max_complexity)
+            && within(size.lines, self.min_lines, self.max_lines)
     }
 }
 
@@ -438,6 +947,137 @@ mod test {
         assert_eq!(options.test_tool, TestTool::Nextest);
     }
 
+    #[test]
+    fn minimum_score_from_args_and_config() {
+        let config = Config::from_str(indoc! { r#"
+            minimum_score = 80
+            score_timeouts = "excluded"
+        "# })
+        .unwrap();
+        let args = Args::parse_from(["mutants", "--score-unviable", "caught"]);
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.minimum_score, Some(80.0));
+        assert_eq!(options.score_timeouts, Some(ScoreAs::Excluded));
+        assert_eq!(options.score_unviable, Some(ScoreAs::Caught));
+
+        let args = Args::parse_from(["mutants", "--minimum-score", "101"]);
+        assert!(Options::new(&args, &Config::default()).is_err());
+    }
+
+    #[test]
+    fn enable_genres_from_args_and_config() {
+        let options = Options::default();
+        assert!(options.allows_genre(Genre::FnValue));
+        assert!(!options.allows_genre(Genre::NumericLiteral));
+
+        let args = Args::parse_from(["mutants", "--enable-genre=NumericLiteral"]);
+        let options = Options::new(&args, &Config::default()).unwrap();
+        assert_eq!(options.enable_genres, [Genre::NumericLiteral]);
+        assert!(options.allows_genre(Genre::NumericLiteral));
+        assert!(options.allows_genre(Genre::BinaryOperator));
+
+        let args = Args::parse_from(["mutants"]);
+        let config = Config::from_str(r#"enable_genres = ["NumericLiteral"]"#).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert!(options.allows_genre(Genre::NumericLiteral));
+    }
+
+    #[test]
+    fn select_genres_from_args_and_config() {
+        let args = Args::parse_from([
+            "mutants",
+            "--genre=BinaryOperator,NumericLiteral",
+            "--genre",
+            "FnValue",
+        ]);
+        let options = Options::new(&args, &Config::default()).unwrap();
+        assert!(options.allows_genre(Genre::BinaryOperator));
+        assert!(options.allows_genre(Genre::NumericLiteral));
+        assert!(options.allows_genre(Genre::FnValue));
+        assert!(!options.allows_genre(Genre::UnaryOperator));
+
+        let config = Config::from_str(indoc! { r#"
+            genres = ["UnaryOperator"]
+            exclude_genres = ["FnValue"]
+        "#})
+        .unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert!(
+            !options.allows_genre(Genre::UnaryOperator),
+            "genres from args override the config"
+        );
+        assert!(!options.allows_genre(Genre::FnValue));
+        assert!(options.allows_genre(Genre::BinaryOperator));
+
+        let args = Args::parse_from(["mutants", "--exclude-genre=DeleteStatement"]);
+        let options = Options::new(&args, &config).unwrap();
+        assert!(options.allows_genre(Genre::UnaryOperator));
+        assert!(!options.allows_genre(Genre::BinaryOperator));
+        assert!(!options.allows_genre(Genre::DeleteStatement));
+        assert!(!options.allows_genre(Genre::FnValue));
+    }
+
+    #[test]
+    fn genres_table_from_config() {
+        let config = Config::from_str(indoc! { r"
+            [genres]
+            FnValue = false
+
+            [genres.NumericLiteral]
+            enabled = true
+            deltas = [2, 100]
+        "})
+        .unwrap();
+        let args = Args::parse_from(["mutants", "--exclude-genre=BinaryOperator"]);
+        let options = Options::new(&args, &config).unwrap();
+        assert!(options.genres.is_empty());
+        assert!(!options.allows_genre(Genre::FnValue));
+        assert!(!options.allows_genre(Genre::BinaryOperator));
+        assert!(options.allows_genre(Genre::NumericLiteral));
+        assert!(options.allows_genre(Genre::UnaryOperator));
+        assert_eq!(options.numeric_literal_deltas(), [2, 100]);
+
+        let options = Options::from_arg_strs(["mutants"]);
+        assert_eq!(options.numeric_literal_deltas(), [1]);
+
+        let config = Config::from_str("[genres.NumericLiteral]\ndeltas = [0]").unwrap();
+        assert!(Options::new(&args, &config).is_err());
+    }
+
+    #[test]
+    fn genre_sampling_from_args_and_config() {
+        let config = Config::from_str(indoc! { r"
+            max_mutants_per_genre = 100
+            genre_sample_ratio = 0.5
+            seed = 1234
+        "})
+        .unwrap();
+        let args = Args::parse_from(["mutants"]);
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.max_mutants_per_genre, Some(100));
+        assert_eq!(options.genre_sample_ratio, Some(0.5));
+        assert_eq!(options.seed, Some(1234));
+
+        let args = Args::parse_from([
+            "mutants",
+            "--max-mutants-per-genre=7",
+            "--genre-sample-ratio=0.25",
+            "--seed=99",
+        ]);
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.max_mutants_per_genre, Some(7));
+        assert_eq!(options.genre_sample_ratio, Some(0.25));
+        assert_eq!(options.seed, Some(99));
+
+        for bad_ratio in ["0", "1.5", "-0.5"] {
+            let args = Args::parse_from([
+                "mutants".to_owned(),
+                format!("--genre-sample-ratio={bad_ratio}"),
+            ]);
+            assert!(Options::new(&args, &Config::default()).is_err());
+        }
+    }
+
     #[test]
     fn options_from_baseline_arg() {
         let args = Args::parse_from(["mutants", "--baseline", "skip"]);
@@ -493,7 +1133,7 @@ mod test {
             "--timeout-multiplier=2.0",
             "--build-timeout-multiplier=1.0",
         ]);
-        let config = Config::read_file(config_file.path()).unwrap();
+        let config = Config::read_file(config_file.path(), None).unwrap();
         let options = Options::new(&args, &config).unwrap();
 
         assert_eq!(options.test_timeout_multiplier, Some(2.0));
@@ -529,12 +1169,31 @@ mod test {
         let mut config_file = NamedTempFile::new().unwrap();
         config_file.write_all(config.as_bytes()).unwrap();
         let args = Args::try_parse_from(["mutants"]).unwrap();
-        let config = Config::read_file(config_file.path()).unwrap();
+        let config = Config::read_file(config_file.path(), None).unwrap();
         let options = Options::new(&args, &config).unwrap();
         assert_eq!(options.test_tool, TestTool::Nextest);
         assert!(options.cap_lints);
     }
 
+    #[test]
+    fn only_pub_from_arg_or_config() {
+        let args = Args::try_parse_from(["mutants"]).unwrap();
+        let options = Options::new(&args, &Config::default()).unwrap();
+        assert!(!options.only_pub);
+
+        let config = Config::from_str(r#"visibility = "pub""#).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert!(options.only_pub);
+
+        let config = Config::from_str(r#"visibility = "all""#).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert!(!options.only_pub);
+
+        let args = Args::try_parse_from(["mutants", "--only-pub"]).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert!(options.only_pub);
+    }
+
     #[test]
     fn features_arg() {
         let args = Args::try_parse_from(["mutants", "--features", "nice,shiny features"]).unwrap();
@@ -675,6 +1334,38 @@ mod test {
             let options = Options::new(&args, &Config::default()).unwrap();
             assert_eq!(options.colors.forced_value(), None);
         }
+
+        #[test]
+        fn quiet_progress_from_env() {
+            use std::env::{set_var,remove_var};
+
+            let progress_style = |args: &[&str]| {
+                Options::new(&Args::parse_from(args), &Config::default())
+                    .unwrap()
+                    .progress_style
+            };
+
+            remove_var("CARGO_MUTANTS_QUIET_PROGRESS");
+            assert_eq!(progress_style(&["mutants"]), ProgressStyle::Fancy);
+            assert_eq!(progress_style(&["mutants", "--quiet-progress"]), ProgressStyle::Quiet);
+            // An explicit style wins, so that JSON events aren't silently lost.
+            assert_eq!(
+                progress_style(&["mutants", "--quiet-progress", "--progress-style=json"]),
+                ProgressStyle::Json
+            );
+
+            set_var("CARGO_MUTANTS_QUIET_PROGRESS", "1");
+            assert_eq!(progress_style(&["mutants"]), ProgressStyle::Quiet);
+            assert_eq!(progress_style(&["mutants", "--tui"]), ProgressStyle::Fancy);
+            assert_eq!(
+                progress_style(&["mutants", "--progress-style=plain"]),
+                ProgressStyle::Plain
+            );
+
+            set_var("CARGO_MUTANTS_QUIET_PROGRESS", "false");
+            assert_eq!(progress_style(&["mutants"]), ProgressStyle::Fancy);
+            remove_var("CARGO_MUTANTS_QUIET_PROGRESS");
+        }
     }
 
     #[test]
@@ -695,11 +1386,58 @@ mod test {
             "#};
         let mut config_file = NamedTempFile::new().unwrap();
         config_file.write_all(config.as_bytes()).unwrap();
-        let config = Config::read_file(config_file.path()).unwrap();
+        let config = Config::read_file(config_file.path(), None).unwrap();
         let options = Options::new(&args, &config).unwrap();
         assert_eq!(options.profile.unwrap(), "mutants");
     }
 
+    #[test]
+    fn named_config_profile_is_not_passed_to_cargo() {
+        let config = indoc! { r"
+                [profile.quick]
+                timeout_multiplier = 1.0
+            "};
+        let mut config_file = NamedTempFile::new().unwrap();
+        config_file.write_all(config.as_bytes()).unwrap();
+
+        let args = Args::parse_from(["mutants", "--profile=quick"]);
+        let config = Config::read_file(config_file.path(), args.profile.as_deref()).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.profile, None);
+        assert_eq!(options.test_timeout_multiplier, Some(1.0));
+
+        // A profile not defined in the config is a cargo profile.
+        let args = Args::parse_from(["mutants", "--profile=release"]);
+        let config = Config::read_file(config_file.path(), args.profile.as_deref()).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.profile.as_deref(), Some("release"));
+        assert_eq!(options.test_timeout_multiplier, None);
+    }
+
+    #[test]
+    fn env_from_args_follows_config() {
+        let args = Args::parse_from(["mutants", "--env", "B=from args", "--env=C=x=y", "--env=D="]);
+        let config = Config::from_str(indoc! { r#"
+                [env]
+                A = "1"
+                B = "from config"
+            "#})
+        .unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(
+            options.env,
+            [
+                ("A".to_owned(), "1".to_owned()),
+                ("B".to_owned(), "from config".to_owned()),
+                ("B".to_owned(), "from args".to_owned()),
+                ("C".to_owned(), "x=y".to_owned()),
+                ("D".to_owned(), String::new()),
+            ]
+        );
+        assert!(Args::try_parse_from(["mutants", "--env=A"]).is_err());
+        assert!(Args::try_parse_from(["mutants", "--env==1"]).is_err());
+    }
+
     #[test]
     fn test_workspace_arg_true() {
         let args = Args::parse_from(["mutants", "--test-workspace=true"]);
@@ -836,6 +1574,19 @@ mod test {
         );
     }
 
+    #[test]
+    fn skip_attrs_from_args_and_config() {
+        let args = Args::try_parse_from(["mutants", "--skip-attr=inline(always)"]).unwrap();
+        let config = Config::from_str(
+            r#"
+            skip_attrs = ["generated"]
+        "#,
+        )
+        .unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.skip_attrs, ["inline(always)", "generated"]);
+    }
+
     #[test]
     fn config_skip_calls_default_off() {
         // You can configure off the default `with_capacity` skip_calls
@@ -7,12 +7,12 @@ use std::fs::read_to_string;
 use std::time::{Duration, Instant};
 
 use anyhow::Context;
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use humantime::format_duration;
 use output::ScenarioOutput;
 use serde::ser::SerializeStruct;
-use serde::Serialize;
 use serde::Serializer;
+use serde::{Deserialize, Serialize};
 use tracing::warn;
 
 use crate::console::plural;
@@ -30,7 +30,7 @@ use crate::{exit_code, output, Options, Result, Scenario};
 /// 3. `cargo tests` -- do the tests pass?
 ///
 /// Some scenarios such as freshening the tree don't run the tests.
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
 pub enum Phase {
     Check,
     Build,
@@ -65,6 +65,10 @@ pub struct LabOutcome {
     pub timeout: usize,
     pub unviable: usize,
     pub success: usize,
+    /// Mutants that built to the same binaries as the unmutated tree, with `--detect-equivalent`.
+    pub equivalent: usize,
+    /// Missed mutants that weren't tested because `--coverage` showed that no tests execute them.
+    pub uncovered: usize,
 }
 
 impl LabOutcome {
@@ -78,9 +82,15 @@ impl LabOutcome {
             self.total_mutants += 1;
             match outcome.summary() {
                 SummaryOutcome::CaughtMutant => self.caught += 1,
-                SummaryOutcome::MissedMutant => self.missed += 1,
+                SummaryOutcome::MissedMutant => {
+                    self.missed += 1;
+                    if outcome.uncovered {
+                        self.uncovered += 1;
+                    }
+                }
                 SummaryOutcome::Timeout => self.timeout += 1,
                 SummaryOutcome::Unviable => self.unviable += 1,
+                SummaryOutcome::Equivalent => self.equivalent += 1,
                 SummaryOutcome::Success => self.success += 1,
                 SummaryOutcome::Failure => {
                     // We don't expect to see failures that don't fit into the other categories.
@@ -109,6 +119,26 @@ impl LabOutcome {
         }
     }
 
+    /// Describe how many mutants timed out in each phase, like "1 build, 2 test".
+    fn timeouts_by_phase(&self) -> String {
+        [Phase::Check, Phase::Build, Phase::Test]
+            .into_iter()
+            .filter_map(|phase| {
+                let count = self
+                    .outcomes
+                    .iter()
+                    .filter(|outcome| {
+                        outcome.scenario.is_mutant()
+                            && outcome.summary() == SummaryOutcome::Timeout
+                            && outcome.timeout_phase() == Some(phase)
+                    })
+                    .count();
+                (count > 0).then(|| format!("{count} {phase}"))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
     /// Return an overall summary, to show at the end of the program.
     pub fn summary_string(&self, start_time: Instant, options: &Options) -> String {
         let mut s = Vec::new();
@@ -121,7 +151,12 @@ impl LabOutcome {
         }
         s.push(": ".into());
         let mut by_outcome: Vec<String> = Vec::new();
-        if self.missed != 0 {
+        if self.uncovered != 0 {
+            by_outcome.push(format!(
+                "{} missed ({} uncovered)",
+                self.missed, self.uncovered
+            ));
+        } else if self.missed != 0 {
             by_outcome.push(format!("{} missed", self.missed));
         }
         if self.caught != 0 {
@@ -131,7 +166,14 @@ impl LabOutcome {
             by_outcome.push(format!("{} unviable", self.unviable));
         }
         if self.timeout != 0 {
-            by_outcome.push(format!("{} timeouts", self.timeout));
+            by_outcome.push(format!(
+                "{} timeouts ({})",
+                self.timeout,
+                self.timeouts_by_phase()
+            ));
+        }
+        if self.equivalent != 0 {
+            by_outcome.push(format!("{} equivalent", self.equivalent));
         }
         if self.success != 0 {
             by_outcome.push(format!("{} succeeded", self.success));
@@ -156,6 +198,10 @@ pub struct ScenarioOutcome {
     pub scenario: Scenario,
     /// For each phase, the duration and the cargo result.
     phase_results: Vec<PhaseResult>,
+    /// True if the mutant built to the same binaries as the unmutated tree, so wasn't tested.
+    equivalent: bool,
+    /// True if coverage showed that no tests execute the mutant, so it wasn't tested.
+    uncovered: bool,
 }
 
 impl Serialize for ScenarioOutcome {
@@ -182,6 +228,36 @@ impl ScenarioOutcome {
             diff_path: scenario_output.diff_path.clone(),
             scenario,
             phase_results: Vec::new(),
+            equivalent: false,
+            uncovered: false,
+        }
+    }
+
+    /// The outcome of a mutant that wasn't tested because no tests execute it.
+    pub fn uncovered(scenario_output: &ScenarioOutput, scenario: Scenario) -> ScenarioOutcome {
+        ScenarioOutcome {
+            uncovered: true,
+            ..ScenarioOutcome::new(scenario_output, scenario)
+        }
+    }
+
+    /// Recreate the outcome of a scenario that finished in an earlier run, for `--resume`.
+    pub fn from_earlier_run(
+        output_dir: &Utf8Path,
+        log_path: Utf8PathBuf,
+        diff_path: Option<Utf8PathBuf>,
+        scenario: Scenario,
+        phase_results: Vec<PhaseResult>,
+        equivalent: bool,
+    ) -> ScenarioOutcome {
+        ScenarioOutcome {
+            output_dir: output_dir.to_owned(),
+            log_path,
+            diff_path,
+            scenario,
+            phase_results,
+            equivalent,
+            uncovered: false,
         }
     }
 
@@ -189,10 +265,24 @@ impl ScenarioOutcome {
         self.phase_results.push(phase_result);
     }
 
+    /// Record that the mutant built to the same binaries as the unmutated tree.
+    pub fn set_equivalent(&mut self) {
+        self.equivalent = true;
+    }
+
     pub fn get_log_content(&self) -> Result<String> {
         read_to_string(self.output_dir.join(&self.log_path)).context("read log file")
     }
 
+    /// Read the diff of the mutant, if this is a mutant scenario.
+    pub fn get_diff_content(&self) -> Result<String> {
+        let diff_path = self
+            .diff_path
+            .as_ref()
+            .context("the baseline has no diff")?;
+        read_to_string(self.output_dir.join(diff_path)).context("read diff file")
+    }
+
     pub fn last_phase(&self) -> Phase {
         self.phase_results.last().unwrap().phase
     }
@@ -227,6 +317,14 @@ impl ScenarioOutcome {
             .any(|pr| pr.process_status.is_timeout())
     }
 
+    /// Return the phase that timed out, if any.
+    pub fn timeout_phase(&self) -> Option<Phase> {
+        self.phase_results
+            .iter()
+            .find(|pr| pr.process_status.is_timeout())
+            .map(|pr| pr.phase)
+    }
+
     pub fn check_or_build_failed(&self) -> bool {
         self.phase_results
             .iter()
@@ -261,8 +359,12 @@ impl ScenarioOutcome {
                 }
             }
             Scenario::Mutant(_) => {
-                if self.check_or_build_failed() {
+                if self.uncovered {
+                    SummaryOutcome::MissedMutant
+                } else if self.check_or_build_failed() {
                     SummaryOutcome::Unviable
+                } else if self.equivalent {
+                    SummaryOutcome::Equivalent
                 } else if self.has_timeout() {
                     SummaryOutcome::Timeout
                 } else if self.mutant_caught() {
@@ -314,13 +416,14 @@ impl Serialize for PhaseResult {
 }
 
 /// Overall summary outcome for one mutant.
-#[derive(Debug, Clone, Eq, PartialEq, Serialize, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Hash)]
 #[allow(clippy::module_name_repetitions)]
 pub enum SummaryOutcome {
     Success,
     CaughtMutant,
     MissedMutant,
     Unviable,
+    Equivalent,
     Failure,
     Timeout,
 }
@@ -354,6 +457,8 @@ mod test {
                     argv: vec!["cargo".into(), "test".into()],
                 },
             ],
+            equivalent: false,
+            uncovered: false,
         };
         assert_eq!(
             outcome.phase_result(Phase::Build),
@@ -382,4 +487,30 @@ mod test {
         );
         assert_eq!(outcome.phase_result(Phase::Check), None);
     }
+
+    #[test]
+    fn find_timeout_phase() {
+        let mut outcome = ScenarioOutcome {
+            output_dir: "output".into(),
+            log_path: "log".into(),
+            diff_path: None,
+            scenario: Scenario::Baseline,
+            phase_results: vec![PhaseResult {
+                phase: Phase::Build,
+                duration: Duration::from_secs(2),
+                process_status: Exit::Success,
+                argv: vec!["cargo".into(), "build".into()],
+            }],
+            equivalent: false,
+            uncovered: false,
+        };
+        assert_eq!(outcome.timeout_phase(), None);
+        outcome.phase_results.push(PhaseResult {
+            phase: Phase::Test,
+            duration: Duration::from_secs(30),
+            process_status: Exit::Timeout,
+            argv: vec!["cargo".into(), "test".into()],
+        });
+        assert_eq!(outcome.timeout_phase(), Some(Phase::Test));
+    }
 }
@@ -9,6 +9,7 @@ use std::path::Path;
 use std::thread::sleep;
 use std::time::Duration;
 
+use anyhow::ensure;
 use camino::{Utf8Path, Utf8PathBuf};
 use fs2::FileExt;
 use path_slash::PathExt;
@@ -17,16 +18,32 @@ use time::format_description::well_known::Rfc3339;
 use time::OffsetDateTime;
 use tracing::{info, trace};
 
-use crate::outcome::{LabOutcome, SummaryOutcome};
-use crate::{check_interrupted, Context, Mutant, Result, Scenario, ScenarioOutcome};
+use crate::cache::{CachedOutcome, OutcomeCache};
+use crate::outcome::{LabOutcome, PhaseResult, SummaryOutcome};
+use crate::schema::SCHEMA_VERSION;
+use crate::scores::Scores;
+use crate::timings::{Timings, TIMINGS_JSON};
+use crate::{check_interrupted, Context, Mutant, Options, Result, Scenario, ScenarioOutcome};
 
-const OUTDIR_NAME: &str = "mutants.out";
+pub const OUTDIR_NAME: &str = "mutants.out";
 const ROTATED_NAME: &str = "mutants.out.old";
 const LOCK_JSON: &str = "lock.json";
+pub const MUTANTS_JSON: &str = "mutants.json";
 const LOCK_POLL: Duration = Duration::from_millis(100);
 static CAUGHT_TXT: &str = "caught.txt";
 static PREVIOUSLY_CAUGHT_TXT: &str = "previously_caught.txt";
 static UNVIABLE_TXT: &str = "unviable.txt";
+static EQUIVALENT_TXT: &str = "equivalent.txt";
+static UNCOVERED_TXT: &str = "uncovered.txt";
+
+/// `outcomes.json`, with the version of its structure.
+#[derive(Serialize)]
+struct VersionedLabOutcome<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    lab_outcome: &'a LabOutcome,
+    scores: Scores,
+}
 
 /// The contents of a `lock.json` written into the output directory and used as
 /// a lock file to ensure that two cargo-mutants invocations don't try to write
@@ -99,11 +116,21 @@ pub struct OutputDir {
     /// A file holding a list of mutants where testing timed out, as text, one per line.
     timeout_list: File,
     unviable_list: File,
+    /// A file holding a list of mutants that built to the same binaries as the unmutated tree.
+    equivalent_list: File,
     /// The accumulated overall lab outcome.
     pub lab_outcome: LabOutcome,
     /// Log filenames which have already been used, and the number of times that each
     /// basename has been used.
     used_log_names: HashMap<String, usize>,
+    /// Timings read from the previous output directory, if there was one.
+    previous_timings: Timings,
+    /// Timings of the mutants tested in this run.
+    timings: Timings,
+    /// True if this directory is from an interrupted run that's being resumed.
+    resumed: bool,
+    /// Outcomes of mutants from previous runs.
+    previous_cache: OutcomeCache,
 }
 
 impl OutputDir {
@@ -113,7 +140,7 @@ impl OutputDir {
     /// with `--output`.
     ///
     /// If the directory already exists, it's rotated to `mutants.out.old`. If that directory
-    /// exists, it's deleted.
+    /// exists, it's deleted. Timings are carried over from the existing directory.
     ///
     /// If the directory already exists and `lock.json` exists and is locked, this waits for
     /// the lock to be released. The returned `OutputDir` holds a lock for its lifetime.
@@ -123,6 +150,8 @@ impl OutputDir {
                 .with_context(|| format!("create output parent directory {in_dir:?}"))?;
         }
         let output_dir = in_dir.join(OUTDIR_NAME);
+        let previous_timings = Timings::load(&output_dir);
+        let previous_cache = OutcomeCache::load(&output_dir);
         if output_dir.exists() {
             LockFile::acquire_lock(output_dir.as_ref())?;
             // Now release the lock for a bit while we move the directory. This might be
@@ -144,7 +173,46 @@ impl OutputDir {
         create_dir(&log_dir).with_context(|| format!("create log directory {:?}", &log_dir))?;
         let diff_dir = output_dir.join("diff");
         create_dir(diff_dir).context("create diff dir")?;
+        OutputDir::open(
+            output_dir,
+            lock_file,
+            previous_timings,
+            previous_cache,
+            false,
+        )
+    }
 
+    /// Open an existing `mutants.out` directory from an interrupted run, to add the
+    /// outcomes of the remaining mutants, for `--resume`.
+    ///
+    /// The directory isn't rotated, and the list of mutants in it is kept.
+    pub fn resume(in_dir: &Utf8Path) -> Result<OutputDir> {
+        let output_dir = in_dir.join(OUTDIR_NAME);
+        ensure!(
+            output_dir.join(MUTANTS_JSON).is_file(),
+            "No interrupted run to resume in {output_dir}"
+        );
+        let lock_file =
+            LockFile::acquire_lock(output_dir.as_std_path()).context("lock output directory")?;
+        let previous_timings = Timings::load(&output_dir);
+        let previous_cache = OutcomeCache::load(&output_dir);
+        OutputDir::open(
+            output_dir,
+            lock_file,
+            previous_timings,
+            previous_cache,
+            true,
+        )
+    }
+
+    /// Open the list files in a new or resumed output directory.
+    fn open(
+        output_dir: Utf8PathBuf,
+        lock_file: File,
+        previous_timings: Timings,
+        previous_cache: OutcomeCache,
+        resumed: bool,
+    ) -> Result<OutputDir> {
         // Create text list files.
         let mut list_file_options = OpenOptions::new();
         list_file_options.create(true).append(true);
@@ -160,6 +228,9 @@ impl OutputDir {
         let timeout_list = list_file_options
             .open(output_dir.join("timeout.txt"))
             .context("create timeout.txt")?;
+        let equivalent_list = list_file_options
+            .open(output_dir.join(EQUIVALENT_TXT))
+            .context("create equivalent.txt")?;
         Ok(OutputDir {
             path: output_dir,
             lab_outcome: LabOutcome::new(),
@@ -168,7 +239,12 @@ impl OutputDir {
             caught_list,
             timeout_list,
             unviable_list,
+            equivalent_list,
             used_log_names: HashMap::new(),
+            previous_timings,
+            timings: Timings::default(),
+            resumed,
+            previous_cache,
         })
     }
 
@@ -178,22 +254,45 @@ impl OutputDir {
             Scenario::Baseline => "baseline".into(),
             Scenario::Mutant(mutant) => mutant.log_file_name_base(),
         };
-        let basename = match self.used_log_names.entry(scenario_name.clone()) {
-            Entry::Occupied(mut e) => {
-                let index = e.get_mut();
-                *index += 1;
-                format!("{scenario_name}_{index:03}")
-            }
-            Entry::Vacant(e) => {
-                e.insert(0);
-                scenario_name
+        let basename = self.unused_log_name(&scenario_name);
+        ScenarioOutput::new(
+            &self.path,
+            &basename,
+            scenario.is_mutant(),
+            &scenario.to_string(),
+        )
+    }
+
+    /// Create a log file for work that isn't a scenario, such as a hook that runs before
+    /// the whole run, or building a schema of mutants.
+    pub fn start_named_log(&mut self, name: &str) -> Result<ScenarioOutput> {
+        let basename = self.unused_log_name(name);
+        ScenarioOutput::new(&self.path, &basename, false, name)
+    }
+
+    /// Return a log file basename that hasn't been used yet, by adding a number to
+    /// `name` if it has.
+    fn unused_log_name(&mut self, name: &str) -> String {
+        loop {
+            let basename = match self.used_log_names.entry(name.to_owned()) {
+                Entry::Occupied(mut e) => {
+                    let index = e.get_mut();
+                    *index += 1;
+                    format!("{name}_{index:03}")
+                }
+                Entry::Vacant(e) => {
+                    e.insert(0);
+                    name.to_owned()
+                }
+            };
+            // A resumed run may already have logs with this name.
+            if !self.path.join(format!("log/{basename}.log")).exists() {
+                return basename;
             }
-        };
-        ScenarioOutput::new(&self.path, scenario, &basename)
+        }
     }
 
     /// Return the path of the `mutants.out` directory.
-    #[allow(unused)]
     pub fn path(&self) -> &Utf8Path {
         &self.path
     }
@@ -204,29 +303,132 @@ impl OutputDir {
     fn write_lab_outcome(&self) -> Result<()> {
         serde_json::to_writer_pretty(
             BufWriter::new(File::create(self.path.join("outcomes.json"))?),
-            &self.lab_outcome,
+            &VersionedLabOutcome {
+                schema_version: SCHEMA_VERSION,
+                lab_outcome: &self.lab_outcome,
+                scores: Scores::new(&self.lab_outcome),
+            },
         )
         .context("write outcomes.json")
     }
 
+    /// Add the outcomes of scenarios that finished before a resumed run was interrupted.
+    pub fn add_earlier_outcomes(&mut self, outcomes: Vec<ScenarioOutcome>) -> Result<()> {
+        for outcome in outcomes {
+            self.lab_outcome.add(outcome);
+        }
+        self.write_lab_outcome()
+    }
+
     /// Add the result of testing one scenario.
     pub fn add_scenario_outcome(&mut self, scenario_outcome: &ScenarioOutcome) -> Result<()> {
         self.lab_outcome.add(scenario_outcome.to_owned());
         self.write_lab_outcome()?;
         let scenario = &scenario_outcome.scenario;
         if let Scenario::Mutant(mutant) = scenario {
-            let file = match scenario_outcome.summary() {
-                SummaryOutcome::MissedMutant => &mut self.missed_list,
-                SummaryOutcome::CaughtMutant => &mut self.caught_list,
-                SummaryOutcome::Timeout => &mut self.timeout_list,
-                SummaryOutcome::Unviable => &mut self.unviable_list,
-                _ => return Ok(()),
-            };
-            writeln!(file, "{}", mutant.name(true)).context("write to list file")?;
+            self.add_timing(mutant, scenario_outcome)?;
+            self.add_to_list(mutant, &scenario_outcome.summary())?;
         }
         Ok(())
     }
 
+    /// Add outcomes reused from the cache of an earlier run, for `--incremental`.
+    ///
+    /// Each mutant gets a diff, and a log saying that its outcome came from the cache.
+    pub fn add_cached_outcomes(&mut self, cached: Vec<(Mutant, CachedOutcome)>) -> Result<()> {
+        for (mutant, cached) in cached {
+            let diff = mutant.diff(&mutant.mutated_code());
+            let scenario = Scenario::Mutant(mutant);
+            let mut scenario_output = self.start_scenario(&scenario)?;
+            scenario_output.write_diff(&diff)?;
+            scenario_output.message(&format!(
+                "{:?} in an earlier run, and not tested again",
+                cached.summary
+            ))?;
+            let outcome = ScenarioOutcome::from_earlier_run(
+                &self.path,
+                scenario_output.log_path().to_owned(),
+                scenario_output.diff_path.clone(),
+                scenario,
+                cached.phase_results.iter().map(PhaseResult::from).collect(),
+                cached.summary == SummaryOutcome::Equivalent,
+            );
+            if let Scenario::Mutant(mutant) = &outcome.scenario {
+                self.add_to_list(mutant, &cached.summary)?;
+            }
+            self.lab_outcome.add(outcome);
+        }
+        self.write_lab_outcome()
+    }
+
+    /// Record mutants as missed without testing them, because coverage shows that no tests
+    /// execute them, and list them in `uncovered.txt` as well as `missed.txt`.
+    pub fn add_uncovered_outcomes(&mut self, mutants: Vec<Mutant>) -> Result<()> {
+        let mut uncovered_list = String::new();
+        for mutant in mutants {
+            uncovered_list.push_str(&mutant.name(true));
+            uncovered_list.push('\n');
+            let diff = mutant.diff(&mutant.mutated_code());
+            let scenario = Scenario::Mutant(mutant);
+            let mut scenario_output = self.start_scenario(&scenario)?;
+            scenario_output.write_diff(&diff)?;
+            scenario_output
+                .message("no tests execute this code, so it's missed without testing")?;
+            let outcome = ScenarioOutcome::uncovered(&scenario_output, scenario);
+            if let Scenario::Mutant(mutant) = &outcome.scenario {
+                self.add_to_list(mutant, &SummaryOutcome::MissedMutant)?;
+            }
+            self.lab_outcome.add(outcome);
+        }
+        write(self.path.join(UNCOVERED_TXT), uncovered_list).context("write uncovered.txt")?;
+        self.write_lab_outcome()
+    }
+
+    /// Add a mutant to the text file listing mutants with its outcome.
+    fn add_to_list(&mut self, mutant: &Mutant, summary: &SummaryOutcome) -> Result<()> {
+        let file = match summary {
+            SummaryOutcome::MissedMutant => &mut self.missed_list,
+            SummaryOutcome::CaughtMutant => &mut self.caught_list,
+            SummaryOutcome::Timeout => &mut self.timeout_list,
+            SummaryOutcome::Unviable => &mut self.unviable_list,
+            SummaryOutcome::Equivalent => &mut self.equivalent_list,
+            _ => return Ok(()),
+        };
+        writeln!(file, "{}", mutant.name(true)).context("write to list file")
+    }
+
+    /// Return the outcome of an identical mutant from an earlier run, if it's cached.
+    pub fn cached_outcome(&self, mutant: &Mutant, options: &Options) -> Option<&CachedOutcome> {
+        self.previous_cache.get(mutant, options)
+    }
+
+    /// Write the cache of outcomes from this and earlier runs.
+    pub fn write_cache(&self, options: &Options) -> Result<()> {
+        self.previous_cache
+            .updated_by(&self.lab_outcome, options)
+            .write(&self.path)
+    }
+
+    /// Timings of mutants from previous runs.
+    pub fn previous_timings(&self) -> &Timings {
+        &self.previous_timings
+    }
+
+    /// Record how long a mutant took, and write the timings from this and previous runs.
+    fn add_timing(&mut self, mutant: &Mutant, scenario_outcome: &ScenarioOutcome) -> Result<()> {
+        let secs = scenario_outcome
+            .phase_results()
+            .iter()
+            .map(|pr| pr.duration.as_secs_f64())
+            .sum();
+        self.timings.add(mutant, secs);
+        serde_json::to_writer_pretty(
+            BufWriter::new(File::create(self.path.join(TIMINGS_JSON))?),
+            &self.previous_timings.updated_by(&self.timings),
+        )
+        .context("write timings.json")
+    }
+
     pub fn open_debug_log(&self) -> Result<File> {
         let debug_log_path = self.path.join("debug.log");
         OpenOptions::new()
@@ -236,9 +438,14 @@ impl OutputDir {
             .with_context(|| format!("open {debug_log_path}"))
     }
 
+    /// Write the list of mutants to be tested, unless the run is resumed, in which case
+    /// the list from the interrupted run is kept so that it can be resumed again.
     pub fn write_mutants_list(&self, mutants: &[Mutant]) -> Result<()> {
+        if self.resumed {
+            return Ok(());
+        }
         serde_json::to_writer_pretty(
-            BufWriter::new(File::create(self.path.join("mutants.json"))?),
+            BufWriter::new(File::create(self.path.join(MUTANTS_JSON))?),
             mutants,
         )
         .context("write mutants.json")
@@ -296,14 +503,14 @@ pub struct ScenarioOutput {
 }
 
 impl ScenarioOutput {
-    fn new(output_dir: &Utf8Path, scenario: &Scenario, basename: &str) -> Result<Self> {
+    fn new(output_dir: &Utf8Path, basename: &str, has_diff: bool, title: &str) -> Result<Self> {
         let log_path = Utf8PathBuf::from(format!("log/{basename}.log"));
         let log_file = File::options()
             .append(true)
             .create_new(true)
             .read(true)
             .open(output_dir.join(&log_path))?;
-        let diff_path = if scenario.is_mutant() {
+        let diff_path = if has_diff {
             Some(Utf8PathBuf::from(format!("diff/{basename}.diff")))
         } else {
             None
@@ -314,7 +521,7 @@ impl ScenarioOutput {
             log_file,
             diff_path,
         };
-        scenario_output.message(&scenario.to_string())?;
+        scenario_output.message(title)?;
         Ok(scenario_output)
     }
 
@@ -432,6 +639,7 @@ mod test {
                 "mutants.out",
                 "mutants.out/caught.txt",
                 "mutants.out/diff",
+                "mutants.out/equivalent.txt",
                 "mutants.out/lock.json",
                 "mutants.out/log",
                 "mutants.out/missed.txt",
@@ -2,6 +2,7 @@
 
 //! Discover and represent cargo packages within a workspace.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
 
 use camino::{Utf8Path, Utf8PathBuf};
@@ -30,6 +31,15 @@ pub struct Package {
     /// The top source files for this package, relative to the workspace root,
     /// like `["src/lib.rs"]`.
     pub top_sources: Vec<Utf8PathBuf>,
+
+    /// The features defined by this package, mapped to the features they enable.
+    #[serde(skip)]
+    pub features: BTreeMap<String, Vec<String>>,
+
+    /// Features of this package turned on by other packages in the workspace that
+    /// depend on it, including `"default"` if they use the default features.
+    #[serde(skip)]
+    pub requested_features: BTreeSet<String>,
 }
 
 /// Read `cargo-metadata` parsed output, and produce our package representation.
@@ -38,7 +48,11 @@ pub fn packages_from_metadata(metadata: &cargo_metadata::Metadata) -> Vec<Arc<Pa
         .workspace_packages()
         .into_iter()
         .sorted_by_key(|p| &p.name)
-        .filter_map(|p| Package::from_cargo_metadata(p, &metadata.workspace_root))
+        .filter_map(|p| {
+            let mut package = Package::from_cargo_metadata(p, &metadata.workspace_root)?;
+            package.requested_features = requested_features(metadata, &p.name);
+            Some(package)
+        })
         .map(Arc::new)
         .collect()
 }
@@ -69,6 +83,12 @@ impl Package {
             top_sources: package_top_sources(workspace_root, package_metadata),
             version: package_metadata.version.to_string(),
             relative_dir,
+            features: package_metadata
+                .features
+                .iter()
+                .map(|(name, implied)| (name.clone(), implied.clone()))
+                .collect(),
+            requested_features: BTreeSet::new(),
         })
     }
 
@@ -77,6 +97,23 @@ impl Package {
     }
 }
 
+/// Find the features of a package requested by other workspace packages that depend on it.
+fn requested_features(metadata: &cargo_metadata::Metadata, name: &str) -> BTreeSet<String> {
+    let mut features = BTreeSet::new();
+    for dependency in metadata
+        .workspace_packages()
+        .into_iter()
+        .flat_map(|p| &p.dependencies)
+        .filter(|d| d.name == name)
+    {
+        features.extend(dependency.features.iter().cloned());
+        if dependency.uses_default_features {
+            features.insert("default".to_owned());
+        }
+    }
+    features
+}
+
 /// Find all the files that are named in the `path` of targets in a
 /// Cargo manifest, if the kind of the target is one that we should mutate.
 ///
@@ -152,6 +189,8 @@ impl PackageSelection {
             version: version.to_string(),
             relative_dir: relative_dir.into(),
             top_sources: vec![top_source.into()],
+            features: BTreeMap::new(),
+            requested_features: BTreeSet::new(),
         })])
     }
 }
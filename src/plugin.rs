@@ -0,0 +1,388 @@
+// Copyright 2025 Martin Pool
+
+//! Generate additional mutants from external plugin programs.
+//!
+//! A plugin is an executable that reads a JSON description of a source file on stdin,
+//! including the text of every function that cargo-mutants would mutate, and writes to
+//! stdout a JSON array of the mutants it wants to add. Each mutant gives the span of
+//! source text to replace and the replacement text.
+//!
+//! This lets domain-specific operators be written in any language, and using any
+//! parser (including `syn`), without being built in to cargo-mutants. Plugins are
+//! programs speaking JSON over source text, rather than WASM modules or dynamic
+//! libraries receiving the `syn` AST, because `syn`'s types aren't a stable ABI.
+
+#![warn(clippy::pedantic)]
+
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::thread::{self, sleep};
+use std::time::Duration;
+
+use anyhow::{bail, ensure};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::mutant::Function;
+use crate::process::{Exit, Process, WAIT_POLL_INTERVAL};
+use crate::source::SourceFile;
+use crate::span::{LineColumn, Span};
+use crate::{Context, Genre, Mutant, Result};
+
+/// How long a plugin may take to generate mutants for one file.
+const PLUGIN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The description of a source file sent to the plugin.
+#[derive(Serialize)]
+struct PluginRequest<'a> {
+    /// The name of the package containing the file.
+    package: &'a str,
+    /// The path of the file relative to the workspace, with forward slashes.
+    path: String,
+    /// The full text of the file.
+    code: &'a str,
+    functions: Vec<PluginFunction<'a>>,
+}
+
+/// A function that may be mutated by the plugin.
+#[derive(Serialize)]
+struct PluginFunction<'a> {
+    #[serde(flatten)]
+    function: &'a Function,
+    /// The text of the whole function, including its signature.
+    code: String,
+}
+
+/// A mutant proposed by a plugin.
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct PluginMutant {
+    /// The region of the file to replace.
+    span: Span,
+    /// The new text.
+    replacement: String,
+}
+
+/// Run each plugin on a source file, and return the mutants they generate.
+///
+/// Mutants are only accepted if they fall inside one of the given functions, which
+/// are those that cargo-mutants would itself mutate: this means that plugins
+/// respect `#[mutants::skip]`, `#[cfg(test)]` and similar exclusions.
+pub fn plugin_mutants(
+    plugins: &[String],
+    source_file: &SourceFile,
+    functions: &[Arc<Function>],
+) -> Result<Vec<Mutant>> {
+    if plugins.is_empty() || functions.is_empty() {
+        return Ok(Vec::new());
+    }
+    let code = source_file.code();
+    let request = PluginRequest {
+        package: &source_file.package.name,
+        path: source_file.tree_relative_slashes(),
+        code,
+        functions: functions
+            .iter()
+            .map(|function| PluginFunction {
+                function,
+                code: function.span.extract(code),
+            })
+            .collect(),
+    };
+    let request_json = serde_json::to_string(&request).expect("Serialize plugin request");
+    let mut mutants = Vec::new();
+    for plugin in plugins {
+        let Some(stdout) = run_plugin(plugin, &request_json, PLUGIN_TIMEOUT)
+            .with_context(|| format!("Failed to run plugin {plugin:?} on {}", request.path))?
+        else {
+            warn!(
+                "Plugin {plugin:?} timed out after {}s on {}; skipping its mutants for this file",
+                PLUGIN_TIMEOUT.as_secs(),
+                request.path
+            );
+            continue;
+        };
+        for plugin_mutant in parse_plugin_output(&stdout)
+            .with_context(|| format!("Failed to parse output of plugin {plugin:?}"))?
+        {
+            let Some(function) = innermost_function(functions, &plugin_mutant.span) else {
+                warn!(
+                    ?plugin,
+                    path = request.path,
+                    span = ?plugin_mutant.span,
+                    "Plugin mutant is not inside a function that can be mutated; skipping it"
+                );
+                continue;
+            };
+            mutants.push(Mutant {
+                source_file: source_file.clone(),
+                function: Some(Arc::clone(function)),
+                span: plugin_mutant.span,
+                replacement: plugin_mutant.replacement,
+                genre: Genre::Plugin,
+                expansion: None,
+                description: None,
+            });
+        }
+    }
+    Ok(mutants)
+}
+
+/// Run one plugin, feeding it the request on stdin, and return its stdout, or None if
+/// it didn't finish within the timeout.
+fn run_plugin(plugin: &str, request_json: &str, timeout: Duration) -> Result<Option<String>> {
+    debug!(?plugin, "run plugin");
+    let mut process = Process::spawn(
+        Command::new(plugin)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped()),
+        Some(timeout),
+    )
+    .context("Spawn plugin")?;
+    let child = process.child_mut();
+    let mut stdin = child.stdin.take().expect("Plugin stdin is piped");
+    let mut stdout = child.stdout.take().expect("Plugin stdout is piped");
+    let mut stderr = child.stderr.take().expect("Plugin stderr is piped");
+    // Write and read from other threads so that a plugin that writes before it's finished
+    // reading can't deadlock on a full pipe.
+    let (exit, write_result, stdout, stderr) = thread::scope(|scope| -> Result<_> {
+        let writer = scope.spawn(move || stdin.write_all(request_json.as_bytes()));
+        let stdout_reader = scope.spawn(move || {
+            let mut buf = Vec::new();
+            stdout.read_to_end(&mut buf).map(|_| buf)
+        });
+        let stderr_reader = scope.spawn(move || {
+            let mut buf = Vec::new();
+            stderr.read_to_end(&mut buf).map(|_| buf)
+        });
+        let exit = loop {
+            if let Some(exit) = process.poll().context("Wait for plugin")? {
+                break exit;
+            }
+            sleep(WAIT_POLL_INTERVAL);
+        };
+        // The plugin might reasonably exit without reading all its input, so write
+        // errors are only reported if it also failed.
+        let write_result = writer.join().expect("Join plugin stdin writer");
+        let stdout = stdout_reader.join().expect("Join plugin stdout reader");
+        let stderr = stderr_reader.join().expect("Join plugin stderr reader");
+        Ok((exit, write_result, stdout, stderr))
+    })?;
+    match exit {
+        Exit::Success => (),
+        Exit::Timeout => return Ok(None),
+        _ => {
+            if let Err(err) = write_result {
+                warn!(?plugin, "Failed to write request to plugin: {err}");
+            }
+            bail!(
+                "Plugin exited with {exit:?}: {}",
+                String::from_utf8_lossy(&stderr.unwrap_or_default()).trim()
+            );
+        }
+    }
+    let stdout = stdout.context("Read plugin output")?;
+    String::from_utf8(stdout)
+        .map(Some)
+        .context("Plugin output is not UTF-8")
+}
+
+/// Parse the JSON array of mutants written by a plugin.
+fn parse_plugin_output(stdout: &str) -> Result<Vec<PluginMutant>> {
+    let mutants: Vec<PluginMutant> = serde_json::from_str(stdout)?;
+    for mutant in &mutants {
+        let Span { start, end } = mutant.span;
+        ensure!(
+            start.line >= 1 && start.column >= 1,
+            "Plugin mutant span starts before the start of the file: {:?}",
+            mutant.span
+        );
+        ensure!(
+            !is_after(start, end),
+            "Plugin mutant span ends before it starts: {:?}",
+            mutant.span
+        );
+    }
+    Ok(mutants)
+}
+
+/// Find the innermost (that is, the last-starting) function that contains the span.
+fn innermost_function<'f>(
+    functions: &'f [Arc<Function>],
+    span: &Span,
+) -> Option<&'f Arc<Function>> {
+    functions
+        .iter()
+        .filter(|function| {
+            !is_after(function.span.start, span.start) && !is_after(span.end, function.span.end)
+        })
+        .max_by_key(|function| (function.span.start.line, function.span.start.column))
+}
+
+/// True if `a` is strictly after `b`.
+fn is_after(a: LineColumn, b: LineColumn) -> bool {
+    (a.line, a.column) > (b.line, b.column)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::mutant::FunctionSize;
+    use crate::visit::mutate_source_str;
+    use crate::Options;
+
+    fn function(name: &str, span: Span) -> Arc<Function> {
+        Arc::new(Function {
+            function_name: name.to_owned(),
+            module_path: "small".to_owned(),
+            return_type: String::new(),
+            span,
+            size: FunctionSize::default(),
+        })
+    }
+
+    #[test]
+    fn parse_output() {
+        let mutants = parse_plugin_output(indoc! { r#"
+            [
+                {
+                    "span": {"start": {"line": 2, "column": 5}, "end": {"line": 2, "column": 10}},
+                    "replacement": "a + 1"
+                }
+            ]
+        "#})
+        .unwrap();
+        assert_eq!(
+            mutants,
+            [PluginMutant {
+                span: Span::quad(2, 5, 2, 10),
+                replacement: "a + 1".to_owned(),
+            }]
+        );
+        assert_eq!(parse_plugin_output("[]").unwrap(), []);
+    }
+
+    #[test]
+    fn reject_bad_output() {
+        assert!(parse_plugin_output("").is_err());
+        assert!(parse_plugin_output(r#"[{"replacement": "x"}]"#).is_err());
+        assert!(parse_plugin_output(
+            r#"[{"span": {"start": {"line": 3, "column": 1}, "end": {"line": 2, "column": 1}}, "replacement": "x"}]"#
+        )
+        .is_err());
+        assert!(parse_plugin_output(
+            r#"[{"span": {"start": {"line": 0, "column": 1}, "end": {"line": 2, "column": 1}}, "replacement": "x"}]"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn find_innermost_function() {
+        let functions = [
+            function("outer", Span::quad(1, 1, 10, 2)),
+            function("inner", Span::quad(3, 5, 5, 6)),
+            function("other", Span::quad(12, 1, 14, 2)),
+        ];
+        let name = |span| innermost_function(&functions, &span).map(|f| &f.function_name[..]);
+        assert_eq!(name(Span::quad(2, 5, 2, 10)), Some("outer"));
+        assert_eq!(name(Span::quad(4, 9, 4, 12)), Some("inner"));
+        assert_eq!(name(Span::quad(3, 5, 5, 6)), Some("inner"));
+        assert_eq!(name(Span::quad(4, 9, 7, 1)), Some("outer"));
+        assert_eq!(name(Span::quad(13, 1, 13, 4)), Some("other"));
+        assert_eq!(name(Span::quad(11, 1, 11, 4)), None);
+    }
+
+    /// Write an executable shell script to use as a plugin.
+    #[cfg(unix)]
+    fn write_plugin(dir: &tempfile::TempDir, script: &str) -> String {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.path().join("plugin.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path.to_str().unwrap().to_owned()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn mutants_from_plugin() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = write_plugin(
+            &dir,
+            indoc! { r#"
+                cat >/dev/null
+                echo '[
+                    {"span": {"start": {"line": 2, "column": 5}, "end": {"line": 2, "column": 10}},
+                     "replacement": "a * b"},
+                    {"span": {"start": {"line": 7, "column": 1}, "end": {"line": 7, "column": 6}},
+                     "replacement": "const B: u32 = 2;"}
+                ]'
+            "#},
+        );
+        let options = Options {
+            plugins: vec![plugin],
+            genres: vec![Genre::Plugin],
+            ..Options::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn add(a: u32, b: u32) -> u32 {
+                    a + b
+                }
+
+                #[cfg(test)]
+                fn unused() {}
+                const A: u32 = 1;
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants.iter().map(|m| m.name(true)).collect::<Vec<_>>(),
+            ["src/main.rs:2:5: replace a + b with a * b in add"],
+            "the mutant outside a function is skipped"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn plugin_failure_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = write_plugin(&dir, "echo 'not today' >&2\nexit 3\n");
+        let options = Options {
+            plugins: vec![plugin],
+            ..Options::default()
+        };
+        let err = mutate_source_str("fn a() -> u32 { 1 }", &options).unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("not today"), "{message}");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn plugin_that_hangs_is_stopped_by_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = write_plugin(
+            &dir,
+            "cat >/dev/null
+sleep 60
+echo '[]'
+",
+        );
+        let start = std::time::Instant::now();
+        let stdout = run_plugin(&plugin, "{}", Duration::from_millis(500)).unwrap();
+        assert_eq!(stdout, None);
+        assert!(start.elapsed() < Duration::from_secs(30));
+        let plugin = write_plugin(
+            &dir,
+            "cat >/dev/null
+echo '[]'
+",
+        );
+        let stdout = run_plugin(&plugin, "{}", Duration::from_secs(30)).unwrap();
+        assert_eq!(stdout.as_deref(), Some("[]\n"));
+    }
+}
@@ -0,0 +1,337 @@
+// Copyright 2025 Martin Pool
+
+//! Screen out unviable mutants cheaply before building and testing them.
+//!
+//! Mutants in different files are applied together in batches, and each batch is
+//! checked with a single `cargo check --message-format=json`. Compiler errors are
+//! attributed back to the mutant in the same file, and those mutants are recorded as
+//! unviable without spending a full build on each of them.
+//!
+//! Mutants that can't be confidently blamed for an error are tested as usual, so
+//! screening only ever removes mutants from the queue.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use camino::Utf8PathBuf;
+use itertools::Itertools;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use crate::build_dir::BuildDir;
+use crate::cargo::{cargo_argv, cargo_env};
+use crate::console::Console;
+use crate::interrupt::check_interrupted;
+use crate::outcome::{Phase, PhaseResult, ScenarioOutcome};
+use crate::output::OutputDir;
+use crate::package::{Package, PackageSelection};
+use crate::process::Exit;
+use crate::scenario::Scenario;
+use crate::{Context, Mutant, Options, Result};
+
+/// The largest number of mutants applied together in one batch.
+///
+/// Larger batches need fewer checks, but are more likely to contain a mutant whose
+/// errors stop the compiler before it reports errors from the others.
+const MAX_BATCH_SIZE: usize = 32;
+
+/// Check mutants in batches, record the outcome of those that are unviable, and
+/// return the mutants that still need to be built and tested.
+///
+/// The remaining mutants are returned in their original order.
+pub fn prescreen(
+    mutants: Vec<Mutant>,
+    build_dir: &BuildDir,
+    output_mutex: &Mutex<OutputDir>,
+    options: &Options,
+    console: &Console,
+) -> Result<Vec<Mutant>> {
+    let n_mutants = mutants.len();
+    let mut unviable = vec![false; n_mutants];
+    for batch in make_batches(&mutants) {
+        let batch_mutants = batch.iter().map(|&i| &mutants[i]).collect_vec();
+        let errors = check_batch(&batch_mutants, build_dir, options)?;
+        for (&i, mutant) in batch.iter().zip(&batch_mutants) {
+            let file = &mutant.source_file.tree_relative_path;
+            if let Some(file_errors) = errors.errors_by_file.get(file) {
+                if blames_mutant(file_errors) {
+                    unviable[i] = true;
+                    record_unviable(
+                        mutant,
+                        file_errors,
+                        &errors,
+                        build_dir,
+                        output_mutex,
+                        options,
+                        console,
+                    )?;
+                }
+            }
+        }
+    }
+    let n_unviable = unviable.iter().filter(|u| **u).count();
+    info!("Prescreening found {n_unviable} of {n_mutants} mutants unviable");
+    Ok(mutants
+        .into_iter()
+        .zip(unviable)
+        .filter_map(|(mutant, unviable)| (!unviable).then_some(mutant))
+        .collect())
+}
+
+/// Group mutants into batches that can be applied to the tree together.
+///
+/// Each batch has at most one mutant from any file, so that errors in a file can be
+/// attributed to that mutant, and so that the mutants can't overlap.
+///
+/// Returns the indexes of the mutants in each batch.
+fn make_batches(mutants: &[Mutant]) -> Vec<Vec<usize>> {
+    let mut batches: Vec<Vec<usize>> = Vec::new();
+    for (i, mutant) in mutants.iter().enumerate() {
+        let path = &mutant.source_file.tree_relative_path;
+        if let Some(batch) = batches.iter_mut().find(|batch| {
+            batch.len() < MAX_BATCH_SIZE
+                && !batch
+                    .iter()
+                    .any(|&j| mutants[j].source_file.tree_relative_path == *path)
+        }) {
+            batch.push(i);
+        } else {
+            batches.push(vec![i]);
+        }
+    }
+    batches
+}
+
+/// The errors from checking one batch.
+struct BatchErrors {
+    errors_by_file: HashMap<Utf8PathBuf, Vec<CompilerError>>,
+    argv: Vec<String>,
+    process_status: Exit,
+    start: Instant,
+}
+
+/// An error reported by the compiler, with the file of its primary span.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompilerError {
+    /// The error code, like `E0308`, or the name of a lint that was denied.
+    code: Option<String>,
+    /// The message as rendered for a terminal.
+    rendered: String,
+}
+
+/// Apply a batch of mutants, run `cargo check`, and revert them.
+fn check_batch(
+    mutants: &[&Mutant],
+    build_dir: &BuildDir,
+    options: &Options,
+) -> Result<BatchErrors> {
+    let start = Instant::now();
+    debug!(n_mutants = mutants.len(), "check batch");
+    for mutant in mutants {
+        mutant.apply(build_dir, &mutant.mutated_code())?;
+    }
+    let packages: Vec<Arc<Package>> = mutants
+        .iter()
+        .map(|m| Arc::clone(&m.source_file.package))
+        .sorted_by_key(|p| p.name.clone())
+        .unique()
+        .collect();
+    let mut argv = cargo_argv(&PackageSelection::Explicit(packages), Phase::Check, options);
+    argv.push("--message-format=json".to_owned());
+    let mut command = Command::new(&argv[0]);
+    command
+        .args(&argv[1..])
+        .envs(cargo_env(options))
+        .current_dir(build_dir.path());
+    let output = command.output();
+    for mutant in mutants {
+        mutant.revert(build_dir)?;
+    }
+    let output = output.with_context(|| format!("Failed to run {argv:?}"))?;
+    check_interrupted()?;
+    let process_status = match output.status.code() {
+        Some(0) => Exit::Success,
+        Some(code) => Exit::Failure(code),
+        None => Exit::Other,
+    };
+    Ok(BatchErrors {
+        errors_by_file: parse_errors(&String::from_utf8_lossy(&output.stdout)),
+        argv,
+        process_status,
+        start,
+    })
+}
+
+/// A line of cargo's JSON output.
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<DiagnosticMessage>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticMessage {
+    level: String,
+    code: Option<DiagnosticCode>,
+    #[serde(default)]
+    spans: Vec<DiagnosticSpan>,
+    #[serde(default)]
+    rendered: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct DiagnosticSpan {
+    file_name: String,
+    is_primary: bool,
+}
+
+/// Collect errors from cargo's JSON messages, grouped by the file of their primary span.
+///
+/// Errors with no primary span, such as "aborting due to previous error", are skipped.
+fn parse_errors(json_lines: &str) -> HashMap<Utf8PathBuf, Vec<CompilerError>> {
+    let mut errors: HashMap<Utf8PathBuf, Vec<CompilerError>> = HashMap::new();
+    for line in json_lines.lines() {
+        let Ok(CargoMessage {
+            reason,
+            message: Some(message),
+        }) = serde_json::from_str::<CargoMessage>(line)
+        else {
+            continue;
+        };
+        if reason != "compiler-message" || message.level != "error" {
+            continue;
+        }
+        let Some(span) = message.spans.iter().find(|span| span.is_primary) else {
+            continue;
+        };
+        errors
+            .entry(Utf8PathBuf::from(span.file_name.replace('\\', "/")))
+            .or_default()
+            .push(CompilerError {
+                code: message.code.map(|code| code.code),
+                rendered: message.rendered.unwrap_or_default(),
+            });
+    }
+    errors
+}
+
+/// True if these errors, all in the file of one mutant, mean that mutant is unviable.
+///
+/// Errors from denied lints, like `dead_code`, might be caused by a mutant in another
+/// file, so the mutant is only blamed if all the errors are compiler errors with codes
+/// like `E0308`.
+fn blames_mutant(errors: &[CompilerError]) -> bool {
+    !errors.is_empty()
+        && errors.iter().all(|error| {
+            error.code.as_ref().is_some_and(|code| {
+                code.starts_with('E') && code[1..].chars().all(|c| c.is_ascii_digit())
+            })
+        })
+}
+
+/// Record the outcome of a mutant found to be unviable by prescreening.
+fn record_unviable(
+    mutant: &Mutant,
+    file_errors: &[CompilerError],
+    batch: &BatchErrors,
+    build_dir: &BuildDir,
+    output_mutex: &Mutex<OutputDir>,
+    options: &Options,
+    console: &Console,
+) -> Result<()> {
+    let scenario = Scenario::Mutant(mutant.clone());
+    let mut output_dir = output_mutex.lock().expect("lock output dir");
+    let mut scenario_output = output_dir.start_scenario(&scenario)?;
+    scenario_output.write_diff(&mutant.diff(&mutant.mutated_code()))?;
+    scenario_output.message(&format!(
+        "unviable in prescreening batch: {}",
+        batch.argv.join(" ")
+    ))?;
+    for error in file_errors {
+        scenario_output.message(&error.rendered)?;
+    }
+    let mut outcome = ScenarioOutcome::new(&scenario_output, scenario.clone());
+    outcome.add_phase_result(PhaseResult {
+        phase: Phase::Check,
+        duration: batch.start.elapsed(),
+        process_status: if batch.process_status.is_failure() {
+            batch.process_status
+        } else {
+            warn!("cargo check reported errors but did not fail");
+            Exit::Failure(1)
+        },
+        argv: batch.argv.clone(),
+    });
+    output_dir.add_scenario_outcome(&outcome)?;
+    drop(output_dir);
+    console.scenario_finished(build_dir.path(), &scenario, &outcome, options);
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::source::SourceFile;
+    use crate::visit::mutate_source_str;
+
+    #[test]
+    fn batches_have_one_mutant_per_file() {
+        let mut mutants =
+            mutate_source_str("fn f(a: u32) -> u32 { a + 1 }", &Options::default()).unwrap();
+        assert_eq!(mutants.len(), 4);
+        let mut other = mutants[0].clone();
+        other.source_file = SourceFile {
+            tree_relative_path: "src/other.rs".into(),
+            ..other.source_file.clone()
+        };
+        mutants.insert(1, other);
+        assert_eq!(
+            make_batches(&mutants),
+            [vec![0, 1], vec![2], vec![3], vec![4]]
+        );
+    }
+
+    #[test]
+    fn parse_errors_from_cargo_json() {
+        let errors = parse_errors(indoc! { r#"
+            {"reason":"compiler-artifact","package_id":"x"}
+            {"reason":"compiler-message","message":{"level":"error","code":{"code":"E0308","explanation":null},"spans":[{"file_name":"src/lib.rs","is_primary":true}],"rendered":"error[E0308]: mismatched types"}}
+            {"reason":"compiler-message","message":{"level":"warning","code":null,"spans":[{"file_name":"src/a.rs","is_primary":true}],"rendered":"warning: unused"}}
+            {"reason":"compiler-message","message":{"level":"error","code":{"code":"dead_code","explanation":null},"spans":[{"file_name":"src/b.rs","is_primary":false},{"file_name":"src\\c.rs","is_primary":true}],"rendered":"error: function is never used"}}
+            {"reason":"compiler-message","message":{"level":"error","code":null,"spans":[],"rendered":"error: aborting due to 2 previous errors"}}
+            not json
+            {"reason":"build-finished","success":false}
+        "#});
+        assert_eq!(
+            errors.keys().sorted().collect_vec(),
+            [
+                &Utf8PathBuf::from("src/c.rs"),
+                &Utf8PathBuf::from("src/lib.rs")
+            ]
+        );
+        assert_eq!(
+            errors[&Utf8PathBuf::from("src/lib.rs")],
+            [CompilerError {
+                code: Some("E0308".to_owned()),
+                rendered: "error[E0308]: mismatched types".to_owned(),
+            }]
+        );
+        assert!(blames_mutant(&errors[&Utf8PathBuf::from("src/lib.rs")]));
+        assert!(
+            !blames_mutant(&errors[&Utf8PathBuf::from("src/c.rs")]),
+            "denied lints might be caused by other mutants"
+        );
+        assert!(!blames_mutant(&[]));
+    }
+}
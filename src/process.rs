@@ -15,7 +15,7 @@ use std::time::{Duration, Instant};
 
 use anyhow::Context;
 use camino::Utf8Path;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, span, trace, Level};
 
 use crate::console::Console;
@@ -24,7 +24,7 @@ use crate::output::ScenarioOutput;
 use crate::Result;
 
 /// How frequently to check if a subprocess finished.
-const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+pub const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 #[cfg(windows)]
 mod windows;
@@ -75,7 +75,6 @@ impl Process {
         jobserver: Option<&jobserver::Client>,
         scenario_output: &mut ScenarioOutput,
     ) -> Result<Process> {
-        let start = Instant::now();
         let quoted_argv = quote_argv(argv);
         scenario_output.message(&quoted_argv)?;
         debug!(%quoted_argv, "start process");
@@ -91,10 +90,16 @@ impl Process {
         if let Some(js) = jobserver {
             js.configure(&mut command);
         }
-        configure_command(&mut command);
-        let child = command
-            .spawn()
-            .with_context(|| format!("failed to spawn {}", argv.join(" ")))?;
+        Process::spawn(&mut command, timeout)
+            .with_context(|| format!("failed to spawn {}", argv.join(" ")))
+    }
+
+    /// Start a command whose input and output are set up by the caller, so that it can
+    /// be polled with a timeout and terminated along with its children.
+    pub fn spawn(command: &mut Command, timeout: Option<Duration>) -> Result<Process> {
+        let start = Instant::now();
+        configure_command(command);
+        let child = command.spawn()?;
         Ok(Process {
             child,
             start,
@@ -102,6 +107,11 @@ impl Process {
         })
     }
 
+    /// The child process, for example to take its piped input and output.
+    pub fn child_mut(&mut self) -> &mut Child {
+        &mut self.child
+    }
+
     /// Check if the child process has finished; if so, return its status.
     #[mutants::skip] // It's hard to avoid timeouts if this never works...
     pub fn poll(&mut self) -> Result<Option<Exit>> {
@@ -140,7 +150,7 @@ impl Process {
 }
 
 /// The result of running a single child process.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Exit {
     /// Exited with status 0.
     Success,
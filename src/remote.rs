@@ -0,0 +1,396 @@
+// Copyright 2025 Martin Pool
+
+//! Test mutants on other machines over SSH, for `--ssh-worker`.
+//!
+//! The coordinator copies the source tree to each host with rsync, and then starts a
+//! `cargo mutants worker` process there for each `--ssh-worker`. The worker's standard
+//! input and output carry one JSON message per line:
+//!
+//! 1. The coordinator sends its version and its own command-line arguments, so that
+//!    the worker finds the same mutants with the same options.
+//! 2. The worker replies that it's ready, once it's found the mutants in its copy.
+//! 3. The coordinator sends one mutant at a time, identified by its file, span, and
+//!    replacement, with its timeouts, and the worker replies with the results of each
+//!    phase and the log.
+//!
+//! The worker tests each mutant in its own build directory, with the usual hooks and
+//! timeouts, and the coordinator records the outcomes in its own `mutants.out` as they
+//! arrive, so that the results look the same as a local run. The baseline is tested
+//! only by the coordinator.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::{BTreeSet, HashMap};
+use std::env;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure};
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use tempfile::TempDir;
+use tracing::{debug, info};
+
+use crate::lab::test_each_mutant;
+use crate::outcome::{PhaseResult, SummaryOutcome};
+use crate::output::{OutputDir, ScenarioOutput};
+use crate::resume::{MutantKey, PhaseResultRecord};
+use crate::timeouts::Timeouts;
+use crate::workspace::Workspace;
+use crate::{Console, Context, Mutant, Options, Result, VERSION};
+
+/// The program used to run commands on remote hosts.
+const SSH: &str = "ssh";
+
+/// The program used to copy the tree to remote hosts.
+const RSYNC: &str = "rsync";
+
+/// The first message from the coordinator to a worker.
+#[derive(Debug, Serialize, Deserialize)]
+struct Hello {
+    version: String,
+    /// The coordinator's arguments, starting with `mutants`.
+    args: Vec<String>,
+}
+
+/// A request from the coordinator to test one mutant.
+#[derive(Debug, Serialize, Deserialize)]
+struct Request {
+    mutant: MutantKey,
+    /// Timeouts in seconds.
+    build_timeout: Option<f64>,
+    test_timeout: Option<f64>,
+}
+
+/// A message from a worker to the coordinator.
+#[derive(Debug, Serialize, Deserialize)]
+enum Reply {
+    /// The worker found this many mutants and is ready for requests.
+    Ready { mutants: usize },
+    /// The worker tested the requested mutant.
+    Tested {
+        summary: SummaryOutcome,
+        phase_results: Vec<PhaseResultRecord>,
+        log: String,
+    },
+}
+
+/// The outcome of testing a mutant on a remote worker.
+pub struct RemoteOutcome {
+    pub summary: SummaryOutcome,
+    pub phase_results: Vec<PhaseResult>,
+    /// The log from the worker, including the output of cargo.
+    pub log: String,
+}
+
+/// A `cargo mutants worker` process running on a remote host.
+pub struct RemoteWorker {
+    host: String,
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    /// The local log that receives the worker's stderr.
+    log_path: String,
+}
+
+impl RemoteWorker {
+    /// Start a worker on a host that already has a copy of the tree, with its stderr
+    /// written to a local log, and wait for it to find the mutants.
+    pub fn start(
+        host: &str,
+        tree_root: &Utf8Path,
+        options: &Options,
+        log: &ScenarioOutput,
+    ) -> Result<RemoteWorker> {
+        let remote_command = format!(
+            "cd {} && {} worker",
+            shell_quote(&remote_dir(options, tree_root)),
+            options.remote_command
+        );
+        debug!(host, remote_command, "start remote worker");
+        let mut child = Command::new(SSH)
+            .args([host, &remote_command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(log.open_log_append()?)
+            .spawn()
+            .with_context(|| format!("Failed to run {SSH} {host}"))?;
+        let mut worker = RemoteWorker {
+            host: host.to_owned(),
+            stdin: child.stdin.take().expect("worker stdin"),
+            stdout: BufReader::new(child.stdout.take().expect("worker stdout")),
+            child,
+            log_path: log.output_dir.join(log.log_path()).into_string(),
+        };
+        worker.send(&Hello {
+            version: VERSION.to_owned(),
+            // The first argument is the program name.
+            args: env::args_os()
+                .skip(1)
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+        })?;
+        match worker.receive()? {
+            Reply::Ready { mutants } => {
+                debug!(host, mutants, "remote worker is ready");
+                Ok(worker)
+            }
+            reply @ Reply::Tested { .. } => {
+                bail!("Unexpected reply from worker on {host}: {reply:?}")
+            }
+        }
+    }
+
+    /// Test one mutant on the worker, and wait for the outcome.
+    pub fn test(&mut self, mutant: &Mutant, timeouts: Timeouts) -> Result<RemoteOutcome> {
+        self.send(&Request {
+            mutant: MutantKey::of(mutant),
+            build_timeout: timeouts.build.map(|t| t.as_secs_f64()),
+            test_timeout: timeouts.test.map(|t| t.as_secs_f64()),
+        })?;
+        match self.receive()? {
+            Reply::Tested {
+                summary,
+                phase_results,
+                log,
+            } => Ok(RemoteOutcome {
+                summary,
+                phase_results: phase_results.into_iter().map(PhaseResult::from).collect(),
+                log,
+            }),
+            reply @ Reply::Ready { .. } => {
+                bail!("Unexpected reply from worker on {}: {reply:?}", self.host)
+            }
+        }
+    }
+
+    /// Tell the worker there are no more mutants, and wait for it to exit.
+    pub fn finish(self) -> Result<()> {
+        let RemoteWorker {
+            host,
+            mut child,
+            stdin,
+            log_path,
+            ..
+        } = self;
+        drop(stdin);
+        let status = child.wait().context("wait for remote worker")?;
+        ensure!(
+            status.success(),
+            "Worker on {host} failed with {status}; see {log_path}"
+        );
+        Ok(())
+    }
+
+    fn send<T: Serialize>(&mut self, message: &T) -> Result<()> {
+        let mut line = serde_json::to_string(message)?;
+        line.push('\n');
+        self.stdin
+            .write_all(line.as_bytes())
+            .and_then(|()| self.stdin.flush())
+            .with_context(|| {
+                format!(
+                    "Failed to send to worker on {}; see {}",
+                    self.host, self.log_path
+                )
+            })
+    }
+
+    fn receive(&mut self) -> Result<Reply> {
+        let mut line = String::new();
+        let len = self
+            .stdout
+            .read_line(&mut line)
+            .with_context(|| format!("read from worker on {}", self.host))?;
+        if len == 0 {
+            let status = self.child.wait().context("wait for remote worker")?;
+            bail!(
+                "Worker on {} stopped with {status}; see {}",
+                self.host,
+                self.log_path
+            );
+        }
+        serde_json::from_str(&line)
+            .with_context(|| format!("parse message from worker on {}: {line:?}", self.host))
+    }
+}
+
+/// Copy the tree to each distinct SSH host, replacing any earlier copy there.
+pub fn sync_trees(tree_root: &Utf8Path, options: &Options) -> Result<()> {
+    let hosts: BTreeSet<&str> = options.ssh_workers.iter().map(String::as_str).collect();
+    let dir = remote_dir(options, tree_root);
+    info!(
+        "Copying the tree to {dir} on {}",
+        hosts.iter().copied().collect::<Vec<_>>().join(", ")
+    );
+    for host in hosts {
+        run_quietly(Command::new(SSH).args([host, &format!("mkdir -p {}", shell_quote(&dir))]))
+            .with_context(|| format!("Failed to create {dir} on {host}"))?;
+        run_quietly(
+            Command::new(RSYNC)
+                .args([
+                    "-a",
+                    "--delete",
+                    "--exclude=/target/",
+                    "--exclude=/mutants.out/",
+                    "--exclude=/mutants.out.old/",
+                    "-e",
+                    SSH,
+                ])
+                .arg(format!("{tree_root}/"))
+                .arg(format!("{host}:{dir}/")),
+        )
+        .with_context(|| format!("Failed to copy the tree to {host} with {RSYNC}"))?;
+    }
+    Ok(())
+}
+
+/// The directory on the remote hosts that holds the copy of the tree.
+///
+/// Relative paths are relative to the remote home directory.
+fn remote_dir(options: &Options, tree_root: &Utf8Path) -> String {
+    options.remote_dir.clone().unwrap_or_else(|| {
+        format!(
+            ".cache/cargo-mutants/{}",
+            tree_root.file_name().unwrap_or("tree")
+        )
+    })
+}
+
+fn run_quietly(command: &mut Command) -> Result<()> {
+    let output = command.output().context("Failed to start command")?;
+    if !output.status.success() {
+        bail!(
+            "{}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Quote a string to be used as one word in a POSIX shell command.
+fn shell_quote(s: &str) -> String {
+    if !s.is_empty()
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./,:=+@%".contains(c))
+    {
+        s.to_owned()
+    } else {
+        format!("'{}'", s.replace('\'', r"'\''"))
+    }
+}
+
+/// Read the coordinator's arguments, which configure a worker, from standard input.
+pub fn read_worker_args() -> Result<Vec<String>> {
+    let mut line = String::new();
+    io::stdin()
+        .lock()
+        .read_line(&mut line)
+        .context("read from coordinator")?;
+    let hello: Hello = serde_json::from_str(&line).context("parse message from coordinator")?;
+    ensure!(
+        hello.version == VERSION,
+        "The coordinator runs cargo-mutants {} but this worker has {VERSION}: install the same version on both",
+        hello.version
+    );
+    Ok(hello.args)
+}
+
+/// Test mutants as the coordinator requests them, as `cargo mutants worker`, until
+/// standard input is closed.
+pub fn serve_worker(
+    mutants: Vec<Mutant>,
+    workspace: &Workspace,
+    options: &Options,
+    console: &Console,
+) -> Result<()> {
+    let mut by_key: HashMap<MutantKey, Mutant> = mutants
+        .into_iter()
+        .map(|mutant| (MutantKey::of(&mutant), mutant))
+        .collect();
+    // Workers sharing a copy of the tree each have their own output, which isn't kept.
+    let output_tmp = TempDir::new().context("create temporary output directory")?;
+    let output_dir = OutputDir::new(
+        Utf8Path::from_path(output_tmp.path()).context("temporary directory path isn't UTF-8")?,
+    )?;
+    let mut stdout = io::stdout().lock();
+    let mut reply = |message: &Reply| -> Result<()> {
+        writeln!(stdout, "{}", serde_json::to_string(message)?)
+            .and_then(|()| stdout.flush())
+            .context("write to coordinator")
+    };
+    reply(&Reply::Ready {
+        mutants: by_key.len(),
+    })?;
+    let mut requests = io::stdin().lock().lines();
+    test_each_mutant(
+        workspace,
+        output_dir,
+        options,
+        console,
+        || {
+            let Some(line) = requests
+                .next()
+                .transpose()
+                .context("read from coordinator")?
+            else {
+                return Ok(None);
+            };
+            let request: Request =
+                serde_json::from_str(&line).context("parse request from coordinator")?;
+            let mutant = by_key.remove(&request.mutant).ok_or_else(|| {
+                anyhow!(
+                    "Requested mutant {:?} isn't in this worker's copy of the tree: \
+                    check that the tree was copied and the versions are the same",
+                    request.mutant
+                )
+            })?;
+            let timeouts = Timeouts {
+                build: request.build_timeout.map(Duration::from_secs_f64),
+                test: request.test_timeout.map(Duration::from_secs_f64),
+            };
+            Ok(Some((mutant, timeouts)))
+        },
+        |outcome| {
+            reply(&Reply::Tested {
+                summary: outcome.summary(),
+                phase_results: outcome
+                    .phase_results()
+                    .iter()
+                    .map(PhaseResultRecord::from)
+                    .collect(),
+                log: outcome.get_log_content()?,
+            })
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn quote_shell_words() {
+        assert_eq!(shell_quote("src/main.rs"), "src/main.rs");
+        assert_eq!(shell_quote("with space"), "'with space'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote("$HOME"), "'$HOME'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn default_remote_dir_is_named_after_the_tree() {
+        let options = Options::default();
+        assert_eq!(
+            remote_dir(&options, Utf8Path::new("/home/user/src/mytree")),
+            ".cache/cargo-mutants/mytree"
+        );
+        let options = Options::from_arg_strs(["mutants", "--remote-dir", "/scratch/tree"]);
+        assert_eq!(
+            remote_dir(&options, Utf8Path::new("/home/user/src/mytree")),
+            "/scratch/tree"
+        );
+    }
+}
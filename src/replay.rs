@@ -0,0 +1,125 @@
+// Copyright 2025 Martin Pool
+
+//! Test one mutant again, for `cargo mutants replay`.
+//!
+//! After seeing a missed mutant, the usual next step is to reproduce it, write a test, and
+//! check that the test catches it. To make that quick, the mutant is tested in a copy of the
+//! tree that shares cargo's intermediate build files with the source tree's `target`
+//! directory, as with `--shared-target-dir`, without first testing the unmutated tree, and
+//! its log is printed rather than written to `mutants.out`.
+//!
+//! The source tree itself is never mutated, because the user is probably editing it.
+
+#![warn(clippy::pedantic)]
+
+use anyhow::{anyhow, Context};
+
+use crate::console::Console;
+use crate::lab::test_mutants;
+use crate::options::Options;
+use crate::output::OutputDir;
+use crate::workspace::Workspace;
+use crate::{BaselineStrategy, Mutant, Result};
+
+/// Which mutant to test again.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+pub struct ReplayArgs {
+    /// The name of the mutant, like `src/lib.rs:2:7: replace < with <= in is_small`,
+    /// with or without the line and column.
+    name: String,
+}
+
+/// Test the named mutant in a copy of the source tree and print its log, returning the
+/// exit code.
+pub fn replay(
+    mutants: Vec<Mutant>,
+    args: &ReplayArgs,
+    workspace: &Workspace,
+    mut options: Options,
+    console: &Console,
+) -> Result<i32> {
+    let mutant = find_mutant(mutants, &args.name).ok_or_else(|| {
+        anyhow!(
+            "No mutant named {:?} in the current tree: see `cargo mutants --list`",
+            args.name
+        )
+    })?;
+    if options.shared_target_dir.is_none() {
+        options.shared_target_dir = Some(workspace.target_dir().to_owned());
+    }
+    options.baseline = BaselineStrategy::Skip;
+    options.print_caught = true;
+    options.print_unviable = true;
+    options.show_all_logs = true;
+    options.genre_sample_ratio = None;
+    options.max_mutants_per_genre = None;
+    // Keep the results of the last full run in `mutants.out`.
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("cargo-mutants-replay-")
+        .tempdir()
+        .context("create temp dir")?;
+    let output_dir = OutputDir::new(tmp_dir.path().try_into().context("temp dir path")?)?;
+    let lab_outcome = test_mutants(vec![mutant], workspace, output_dir, &options, console)?;
+    Ok(lab_outcome.exit_code())
+}
+
+/// Find a mutant by its name, with the line and column, or without them as in
+/// `cargo mutants compare`, where repeated changes in one function are numbered like
+/// `(#2)`.
+fn find_mutant(mut mutants: Vec<Mutant>, name: &str) -> Option<Mutant> {
+    let name = name.trim();
+    let (base, occurrence) = match name.rsplit_once(" (#") {
+        Some((base, n)) => match n.strip_suffix(')').and_then(|n| n.parse::<usize>().ok()) {
+            Some(n) => (base, n),
+            None => (name, 1),
+        },
+        None => (name, 1),
+    };
+    if let Some(i) = mutants.iter().position(|m| m.name(true) == name) {
+        return Some(mutants.swap_remove(i));
+    }
+    mutants
+        .into_iter()
+        .filter(|m| m.name(false) == base)
+        .nth(occurrence.checked_sub(1)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::visit::mutate_source_str;
+
+    #[test]
+    fn find_by_name_with_or_without_line_and_column() {
+        let mutants = mutate_source_str(
+            indoc::indoc! { "
+                fn is_small(x: u32) -> bool {
+                    x < 4 && x < 8
+                }
+            " },
+            &Options::default(),
+        )
+        .unwrap();
+        let find = |name| find_mutant(mutants.clone(), name).map(|m| m.name(true));
+        assert_eq!(
+            find("src/main.rs:2:7: replace < with == in is_small").as_deref(),
+            Some("src/main.rs:2:7: replace < with == in is_small")
+        );
+        assert_eq!(
+            find("src/main.rs: replace < with == in is_small").as_deref(),
+            Some("src/main.rs:2:7: replace < with == in is_small")
+        );
+        assert_eq!(
+            find("src/main.rs: replace < with == in is_small (#2)").as_deref(),
+            Some("src/main.rs:2:16: replace < with == in is_small")
+        );
+        assert_eq!(
+            find("src/main.rs: replace < with == in is_small (#3)"),
+            None
+        );
+        assert_eq!(
+            find("src/main.rs: replace < with == in is_small (#0)"),
+            None
+        );
+    }
+}
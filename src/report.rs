@@ -0,0 +1,470 @@
+// Copyright 2025 Martin Pool
+
+//! Write reports from the outcomes in `mutants.out`, for people and for other tools.
+//!
+//! Reports are generated after a run, from `outcomes.json`, the diffs of each mutant,
+//! and the current source files.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{create_dir, read_to_string, remove_dir_all, write};
+use std::process::Command;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Deserialize;
+use tracing::warn;
+
+use crate::mutant::Genre;
+use crate::outcome::SummaryOutcome;
+use crate::scores::{format_score, Tally};
+use crate::span::Span;
+use crate::{Context, Result};
+
+mod badge;
+mod compare;
+mod gitlab;
+mod html;
+mod junit;
+mod markdown;
+mod merge;
+mod patches;
+mod sarif;
+mod show;
+mod triage;
+
+pub use compare::{compare, CompareArgs};
+pub use merge::{merge, MergeArgs};
+pub use show::{show, ShowArgs};
+pub use triage::triage;
+
+/// Which reports to write.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+#[group(required = true, multiple = true)]
+#[allow(clippy::struct_excessive_bools, clippy::doc_markdown)] // doc comments are help text
+pub struct ReportFormats {
+    /// Write a self-contained HTML report to `mutants.out/report.html`.
+    #[arg(long)]
+    html: bool,
+
+    /// Write the HTML report, and open it in the default web browser.
+    #[arg(long)]
+    open: bool,
+
+    /// Write each mutant as a test case in JUnit XML to `mutants.out/junit.xml`, for CI
+    /// test result views.
+    #[arg(long)]
+    junit: bool,
+
+    /// Write missed mutants as a GitLab Code Quality report to
+    /// `mutants.out/gl-code-quality-report.json`.
+    #[arg(long)]
+    gitlab_code_quality: bool,
+
+    /// Write a Markdown summary to `mutants.out/summary.md`, for pull request comments or
+    /// job summaries.
+    #[arg(long)]
+    markdown: bool,
+
+    /// Show the diffs of at most this many missed mutants in the Markdown summary.
+    #[arg(long, value_name = "N", default_value_t = 20, requires = "markdown")]
+    markdown_missed: usize,
+
+    /// Write a shields.io badge description with the mutation score to
+    /// `mutants.out/badge.json`.
+    #[arg(long)]
+    badge: bool,
+
+    /// Write a badge image with the mutation score to `mutants.out/badge.svg`.
+    #[arg(long)]
+    badge_svg: bool,
+
+    /// Write missed mutants as SARIF to `mutants.out/mutants.sarif`, for code scanning tools.
+    #[arg(long)]
+    sarif: bool,
+
+    /// Write a patch for each missed mutant to `mutants.out/patches/`, and all of them to
+    /// `mutants.out/missed.patch`.
+    #[arg(long)]
+    patches: bool,
+}
+
+/// One scenario outcome, as read back from `outcomes.json`.
+#[derive(Debug, Deserialize)]
+struct OutcomeRecord {
+    scenario: ScenarioRecord,
+    summary: SummaryOutcome,
+    log_path: Option<Utf8PathBuf>,
+    diff_path: Option<Utf8PathBuf>,
+    #[serde(default)]
+    phase_results: Vec<PhaseResultRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhaseResultRecord {
+    /// Duration of this phase in seconds.
+    duration: f64,
+}
+
+#[derive(Debug, Deserialize)]
+enum ScenarioRecord {
+    Baseline,
+    Mutant(MutantRecord),
+}
+
+/// A mutant, as serialized in `outcomes.json`.
+#[derive(Debug, Deserialize)]
+struct MutantRecord {
+    package: String,
+    file: String,
+    #[serde(default)]
+    module_path: String,
+    function: Option<FunctionRecord>,
+    span: Span,
+    replacement: String,
+    genre: Genre,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionRecord {
+    function_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LabOutcomeRecord {
+    outcomes: Vec<OutcomeRecord>,
+}
+
+/// A mutant to show in reports, with its outcome and diff.
+struct ReportMutant {
+    mutant: MutantRecord,
+    outcome: SummaryOutcome,
+    diff: Option<String>,
+    /// The path of the diff within the output directory, like `diff/src_lib.rs_line_2_col_7.diff`.
+    diff_path: Option<Utf8PathBuf>,
+    /// The path of the log within the output directory.
+    log_path: Option<Utf8PathBuf>,
+    /// Total time to build and test this mutant, in seconds.
+    duration: f64,
+}
+
+impl ReportMutant {
+    /// Describe the change made by this mutant, like "replace < with <= in `is_small`".
+    ///
+    /// This is taken from the header of the diff, which has the same description as
+    /// the lists of mutants in `mutants.out`.
+    fn description(&self) -> String {
+        self.diff
+            .as_deref()
+            .and_then(|diff| diff.lines().find_map(|line| line.strip_prefix("+++ ")))
+            .map_or_else(
+                || format!("replace with {}", self.mutant.replacement),
+                ToOwned::to_owned,
+            )
+    }
+
+    /// The name of the mutant without the line and column, as in the missed baseline,
+    /// like "src/lib.rs: replace < with <= in `is_small`".
+    fn name(&self) -> String {
+        format!("{}: {}", self.mutant.file, self.description())
+    }
+}
+
+/// A short lowercase name for an outcome, like "missed".
+fn outcome_name(outcome: &SummaryOutcome) -> &'static str {
+    match outcome {
+        SummaryOutcome::CaughtMutant => "caught",
+        SummaryOutcome::MissedMutant => "missed",
+        SummaryOutcome::Timeout => "timeout",
+        SummaryOutcome::Unviable => "unviable",
+        SummaryOutcome::Equivalent => "equivalent",
+        SummaryOutcome::Success => "succeeded",
+        SummaryOutcome::Failure => "failed",
+    }
+}
+
+/// The mutants tested in a run, grouped by source file, and the source code of those files.
+struct Outcomes {
+    /// Mutants by source file, in order of their position in the file.
+    by_file: BTreeMap<String, Vec<ReportMutant>>,
+    /// The content of each source file that could be read.
+    sources: BTreeMap<String, String>,
+}
+
+impl Tally {
+    fn count<'a>(mutants: impl IntoIterator<Item = &'a ReportMutant>) -> Tally {
+        let mut tally = Tally::default();
+        for rm in mutants {
+            tally.add(&rm.outcome);
+        }
+        tally
+    }
+}
+
+/// Read the outcomes from an output directory, and write the selected reports into it.
+///
+/// Source files are read from `source_root`, which should be the root of the tree
+/// that was tested.
+///
+/// Returns the paths of the reports.
+pub fn write_reports(
+    output_dir: &Utf8Path,
+    source_root: &Utf8Path,
+    formats: &ReportFormats,
+) -> Result<Vec<Utf8PathBuf>> {
+    let outcomes = read_outcomes(output_dir, source_root)?;
+    let mut reports = Vec::new();
+    if formats.html || formats.open {
+        reports.push((html::REPORT_HTML, html::render_report(&outcomes)));
+    }
+    if formats.junit {
+        reports.push((junit::JUNIT_XML, junit::render_junit(&outcomes)));
+    }
+    if formats.gitlab_code_quality {
+        reports.push((
+            gitlab::GL_CODE_QUALITY_REPORT,
+            gitlab::render_code_quality(&outcomes)?,
+        ));
+    }
+    if formats.markdown {
+        reports.push((
+            markdown::SUMMARY_MD,
+            markdown::render_summary(&outcomes, formats.markdown_missed),
+        ));
+    }
+    if formats.badge {
+        reports.push((badge::BADGE_JSON, badge::render_badge_json(&outcomes)?));
+    }
+    if formats.badge_svg {
+        reports.push((badge::BADGE_SVG, badge::render_badge_svg(&outcomes)));
+    }
+    if formats.sarif {
+        reports.push((sarif::MUTANTS_SARIF, sarif::render_sarif(&outcomes)?));
+    }
+    let mut paths = Vec::new();
+    if formats.patches {
+        let patches = patches::render_patches(&outcomes);
+        reports.push((
+            patches::MISSED_PATCH,
+            patches
+                .iter()
+                .map(|(_, patch)| patch.as_str())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ));
+        paths.push(write_patches(output_dir, &patches)?);
+    }
+    for (name, content) in reports {
+        let path = output_dir.join(name);
+        write(&path, content).with_context(|| format!("write {path}"))?;
+        paths.push(path);
+    }
+    if formats.open {
+        open_in_browser(&output_dir.join(html::REPORT_HTML));
+    }
+    Ok(paths)
+}
+
+/// Write the HTML report after a run, and open it in the default web browser.
+pub fn open_html_report(output_dir: &Utf8Path, source_root: &Utf8Path) -> Result<()> {
+    let outcomes = read_outcomes(output_dir, source_root)?;
+    let path = output_dir.join(html::REPORT_HTML);
+    write(&path, html::render_report(&outcomes)).with_context(|| format!("write {path}"))?;
+    open_in_browser(&path);
+    Ok(())
+}
+
+/// Open a file in the browser named by `$BROWSER`, or otherwise the platform's default.
+///
+/// Failing to start the browser is only a warning, since the report is still written.
+fn open_in_browser(path: &Utf8Path) {
+    let mut command = if let Some(browser) = env::var_os("BROWSER") {
+        Command::new(browser)
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command.arg(path);
+    match command.status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => warn!("Failed to open {path} with {command:?}: {status}"),
+        Err(err) => warn!("Failed to open {path} with {command:?}: {err}"),
+    }
+}
+
+/// Write patches into a fresh directory, so that none are left from earlier reports.
+fn write_patches(output_dir: &Utf8Path, patches: &[(String, String)]) -> Result<Utf8PathBuf> {
+    let patches_dir = output_dir.join(patches::PATCHES_DIR);
+    if patches_dir.exists() {
+        remove_dir_all(&patches_dir).with_context(|| format!("remove {patches_dir}"))?;
+    }
+    create_dir(&patches_dir).with_context(|| format!("create {patches_dir}"))?;
+    for (name, patch) in patches {
+        let path = patches_dir.join(name);
+        write(&path, patch).with_context(|| format!("write {path}"))?;
+    }
+    Ok(patches_dir)
+}
+
+fn read_outcomes(output_dir: &Utf8Path, source_root: &Utf8Path) -> Result<Outcomes> {
+    let mut by_file: BTreeMap<String, Vec<ReportMutant>> = BTreeMap::new();
+    for rm in read_mutants(&output_dir.join("outcomes.json"))? {
+        by_file.entry(rm.mutant.file.clone()).or_default().push(rm);
+    }
+    let mut sources = BTreeMap::new();
+    for (file, mutants) in &mut by_file {
+        mutants.sort_by_key(|rm| (rm.mutant.span.start.line, rm.mutant.span.start.column));
+        match read_to_string(source_root.join(file)) {
+            Ok(code) => {
+                sources.insert(file.clone(), code);
+            }
+            Err(err) => warn!("Failed to read source file {file}: {err}"),
+        }
+    }
+    Ok(Outcomes { by_file, sources })
+}
+
+/// Read the mutants from an `outcomes.json` file, with their diffs from the directory
+/// that contains it.
+fn read_mutants(outcomes_path: &Utf8Path) -> Result<Vec<ReportMutant>> {
+    let json = read_to_string(outcomes_path)
+        .with_context(|| format!("read {outcomes_path}: run cargo mutants first"))?;
+    let lab_outcome: LabOutcomeRecord =
+        serde_json::from_str(&json).with_context(|| format!("parse {outcomes_path}"))?;
+    let output_dir = outcomes_path.parent().unwrap_or(Utf8Path::new("."));
+    Ok(lab_outcome
+        .outcomes
+        .into_iter()
+        .filter_map(|outcome| {
+            let ScenarioRecord::Mutant(mutant) = outcome.scenario else {
+                return None;
+            };
+            let diff = outcome
+                .diff_path
+                .as_ref()
+                .and_then(|diff_path| read_to_string(output_dir.join(diff_path)).ok());
+            let duration = outcome.phase_results.iter().map(|pr| pr.duration).sum();
+            Some(ReportMutant {
+                mutant,
+                outcome: outcome.summary,
+                diff,
+                diff_path: outcome.diff_path,
+                log_path: outcome.log_path,
+                duration,
+            })
+        })
+        .collect())
+}
+
+/// Escape text for use in HTML or XML, either as element content or in a double-quoted
+/// attribute.
+fn escape_markup(s: &str) -> String {
+    let mut r = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => r.push_str("&amp;"),
+            '<' => r.push_str("&lt;"),
+            '>' => r.push_str("&gt;"),
+            '"' => r.push_str("&quot;"),
+            _ => r.push(c),
+        }
+    }
+    r
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    /// Make a mutant for tests that replaces the `<` in `    x < 4` on some line.
+    pub(super) fn report_mutant(
+        line: usize,
+        replacement: &str,
+        outcome: SummaryOutcome,
+    ) -> ReportMutant {
+        ReportMutant {
+            mutant: MutantRecord {
+                package: "small".to_owned(),
+                file: "src/lib.rs".to_owned(),
+                module_path: "small".to_owned(),
+                function: Some(FunctionRecord {
+                    function_name: "is_small".to_owned(),
+                }),
+                span: Span::quad(line, 7, line, 8),
+                replacement: replacement.to_owned(),
+                genre: Genre::BinaryOperator,
+            },
+            outcome,
+            diff: Some(format!(
+                "--- src/lib.rs\n+++ replace < with {replacement} in is_small\n-    x < 4\n+    x {replacement} 4\n"
+            )),
+            diff_path: Some(format!("diff/src_lib.rs_line_{line}_col_7.diff").into()),
+            log_path: Some(format!("log/src_lib.rs_line_{line}_col_7.log").into()),
+            duration: 1.5,
+        }
+    }
+
+    #[test]
+    fn read_outcomes_json() {
+        let json = indoc! { r#"
+            {"outcomes": [
+                {"scenario": "Baseline", "summary": "Success", "log_path": "log/baseline.log",
+                 "diff_path": null, "phase_results": []},
+                {"scenario": {"Mutant": {"package": "p", "file": "src/lib.rs",
+                    "function": {"function_name": "f", "return_type": "", "span":
+                        {"start": {"line": 1, "column": 1}, "end": {"line": 3, "column": 2}}},
+                    "span": {"start": {"line": 2, "column": 5}, "end": {"line": 2, "column": 6}},
+                    "replacement": "-", "genre": "BinaryOperator"}},
+                 "summary": "MissedMutant", "log_path": "log/m.log", "diff_path": "diff/m.diff",
+                 "phase_results": [
+                    {"phase": "Build", "duration": 1.5, "process_status": "Success", "argv": []},
+                    {"phase": "Test", "duration": 2.0, "process_status": "Success", "argv": []}
+                 ]}
+            ], "total_mutants": 1}
+        "# };
+        let lab_outcome: LabOutcomeRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(lab_outcome.outcomes.len(), 2);
+        let ScenarioRecord::Mutant(mutant) = &lab_outcome.outcomes[1].scenario else {
+            panic!("expected a mutant");
+        };
+        assert_eq!(mutant.file, "src/lib.rs");
+        assert_eq!(mutant.replacement, "-");
+        assert_eq!(
+            lab_outcome.outcomes[1].summary,
+            SummaryOutcome::MissedMutant
+        );
+        assert_eq!(lab_outcome.outcomes[1].phase_results.len(), 2);
+    }
+
+    #[test]
+    fn score_counts_timeouts_as_not_caught() {
+        let mutants = [
+            report_mutant(1, "<=", SummaryOutcome::CaughtMutant),
+            report_mutant(2, "<=", SummaryOutcome::CaughtMutant),
+            report_mutant(3, "<=", SummaryOutcome::MissedMutant),
+            report_mutant(4, "<=", SummaryOutcome::Timeout),
+            report_mutant(5, "<=", SummaryOutcome::Unviable),
+        ];
+        let tally = Tally::count(&mutants);
+        assert_eq!(tally.total, 5);
+        assert_eq!(format_score(tally.score()), "50.0%");
+        assert_eq!(format_score(Tally::count(&mutants[4..]).score()), "n/a");
+        assert_eq!(format_score(Some(99.99)), "99.9%");
+    }
+
+    #[test]
+    fn description_from_diff_header() {
+        let rm = report_mutant(2, "<=", SummaryOutcome::MissedMutant);
+        assert_eq!(rm.description(), "replace < with <= in is_small");
+        let rm = ReportMutant { diff: None, ..rm };
+        assert_eq!(rm.description(), "replace with <=");
+    }
+}
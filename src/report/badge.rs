@@ -0,0 +1,153 @@
+// Copyright 2025 Martin Pool
+
+//! A badge showing the mutation score.
+//!
+//! `badge.json` is read by the shields.io [endpoint badge], so a README can show the score
+//! from a file published by CI. `badge.svg` is a similar self-contained image, for
+//! places that can't reach shields.io.
+//!
+//! [endpoint badge]: https://shields.io/badges/endpoint-badge
+
+use serde_json::json;
+
+use super::{escape_markup, format_score, Outcomes, Tally};
+use crate::{Context, Result};
+
+/// The name of the badge description within the output directory.
+pub(super) const BADGE_JSON: &str = "badge.json";
+
+/// The name of the badge image within the output directory.
+pub(super) const BADGE_SVG: &str = "badge.svg";
+
+const LABEL: &str = "mutants";
+
+/// Named colors for increasing scores, with the lowest score that gets each, and the
+/// color's value in shields.io.
+const COLORS: &[(f64, &str, &str)] = &[
+    (0.0, "red", "#e05d44"),
+    (20.0, "orange", "#fe7d37"),
+    (40.0, "yellow", "#dfb317"),
+    (60.0, "yellowgreen", "#a4a61d"),
+    (75.0, "green", "#97ca00"),
+    (90.0, "brightgreen", "#4c1"),
+];
+
+/// The color for runs with no viable mutants.
+const NO_SCORE_COLOR: (&str, &str) = ("lightgrey", "#9f9f9f");
+
+fn color(score: Option<f64>) -> (&'static str, &'static str) {
+    score.map_or(NO_SCORE_COLOR, |score| {
+        COLORS
+            .iter()
+            .rev()
+            .find(|(min, _, _)| score >= *min)
+            .map_or(NO_SCORE_COLOR, |(_, name, hex)| (name, hex))
+    })
+}
+
+/// Render the shields.io endpoint description of the badge.
+pub(super) fn render_badge_json(outcomes: &Outcomes) -> Result<String> {
+    let score = Tally::count(outcomes.by_file.values().flatten()).score();
+    let badge = json!({
+        "schemaVersion": 1,
+        "label": LABEL,
+        "message": format_score(score),
+        "color": color(score).0,
+    });
+    serde_json::to_string_pretty(&badge).context("serialize badge")
+}
+
+/// Render the badge as an SVG image, in the flat style of shields.io.
+pub(super) fn render_badge_svg(outcomes: &Outcomes) -> String {
+    let score = Tally::count(outcomes.by_file.values().flatten()).score();
+    let message = format_score(score);
+    let fill = color(score).1;
+    let label_width = text_width(LABEL);
+    let message_width = text_width(&message);
+    let width = label_width + message_width;
+    let label_x = label_width / 2;
+    let message_x = label_width + message_width / 2;
+    let title = escape_markup(&format!("{LABEL}: {message}"));
+    format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{title}">
+<title>{title}</title>
+<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>
+<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>
+<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="#555"/><rect x="{label_width}" width="{message_width}" height="20" fill="{fill}"/><rect width="{width}" height="20" fill="url(#s)"/></g>
+<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">
+<text x="{label_x}" y="15" fill="#010101" fill-opacity=".3">{LABEL}</text><text x="{label_x}" y="14">{LABEL}</text>
+<text x="{message_x}" y="15" fill="#010101" fill-opacity=".3">{message}</text><text x="{message_x}" y="14">{message}</text>
+</g>
+</svg>
+"##
+    )
+}
+
+/// Estimate the width in pixels of a box holding some text, in 11px Verdana.
+fn text_width(text: &str) -> usize {
+    // Verdana's characters are about 7px wide at this size, and there's 5px padding on each side.
+    text.chars().count() * 7 + 10
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::outcome::SummaryOutcome;
+    use crate::report::test::report_mutant;
+
+    fn outcomes(outcomes: &[SummaryOutcome]) -> Outcomes {
+        Outcomes {
+            by_file: BTreeMap::from([(
+                "src/lib.rs".to_owned(),
+                outcomes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, outcome)| report_mutant(i + 1, "<=", outcome.clone()))
+                    .collect(),
+            )]),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn colors_by_score() {
+        assert_eq!(color(None).0, "lightgrey");
+        assert_eq!(color(Some(0.0)).0, "red");
+        assert_eq!(color(Some(59.9)).0, "yellow");
+        assert_eq!(color(Some(75.0)).0, "green");
+        assert_eq!(color(Some(100.0)).0, "brightgreen");
+    }
+
+    #[test]
+    fn badge_json() {
+        let outcomes = outcomes(&[
+            SummaryOutcome::CaughtMutant,
+            SummaryOutcome::CaughtMutant,
+            SummaryOutcome::CaughtMutant,
+            SummaryOutcome::MissedMutant,
+        ]);
+        let badge: serde_json::Value =
+            serde_json::from_str(&render_badge_json(&outcomes).unwrap()).unwrap();
+        assert_eq!(
+            badge,
+            json!({
+                "schemaVersion": 1,
+                "label": "mutants",
+                "message": "75.0%",
+                "color": "green"
+            })
+        );
+    }
+
+    #[test]
+    fn badge_svg() {
+        let svg = render_badge_svg(&outcomes(&[SummaryOutcome::MissedMutant]));
+        assert!(svg.starts_with(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"97\" height=\"20\" role=\"img\" aria-label=\"mutants: 0.0%\">"
+        ));
+        assert!(svg.contains("fill=\"#e05d44\""));
+        assert!(svg.contains("<text x=\"78\" y=\"14\">0.0%</text>"));
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright 2025 Martin Pool
+
+//! Compare the outcomes of two runs, for `cargo mutants compare`.
+//!
+//! Mutants are matched up by their file and description, without the line and column,
+//! so that edits elsewhere in the file don't make them look new. If the same change is
+//! made several times in one function, the occurrences are matched in order.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use camino::{Utf8Path, Utf8PathBuf};
+
+use super::{outcome_name, read_mutants, ReportMutant};
+use crate::console::plural;
+use crate::exit_code;
+use crate::outcome::SummaryOutcome;
+use crate::output::OUTDIR_NAME;
+use crate::Result;
+
+/// Which runs to compare.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+pub struct CompareArgs {
+    /// The earlier run: a `mutants.out` directory, a directory containing one, or an
+    /// `outcomes.json` file.
+    old: Utf8PathBuf,
+
+    /// The later run, in the same form.
+    new: Utf8PathBuf,
+}
+
+/// Mutants from one run, by their name without the line and column.
+type NamedMutants = BTreeMap<String, ReportMutant>;
+
+/// Compare two runs, print the differences, and return the exit code: failing if any
+/// mutants are missed in the new run that weren't missed in the old one.
+pub fn compare(args: &CompareArgs) -> Result<i32> {
+    let old = name_mutants(read_mutants(&outcomes_path(&args.old))?);
+    let new = name_mutants(read_mutants(&outcomes_path(&args.new))?);
+    let comparison = Comparison::new(&old, &new);
+    print!("{}", comparison.format());
+    Ok(if comparison.newly_missed.is_empty() {
+        exit_code::SUCCESS
+    } else {
+        exit_code::FOUND_PROBLEMS
+    })
+}
+
+/// Find `outcomes.json` from a path given on the command line.
+pub(super) fn outcomes_path(path: &Utf8Path) -> Utf8PathBuf {
+    if path.is_file() {
+        path.to_owned()
+    } else if path.join(OUTDIR_NAME).is_dir() {
+        path.join(OUTDIR_NAME).join("outcomes.json")
+    } else {
+        path.join("outcomes.json")
+    }
+}
+
+pub(super) fn name_mutants(mut mutants: Vec<ReportMutant>) -> NamedMutants {
+    mutants.sort_by_key(|rm| {
+        (
+            rm.mutant.file.clone(),
+            rm.mutant.span.start.line,
+            rm.mutant.span.start.column,
+        )
+    });
+    let mut named = NamedMutants::new();
+    for rm in mutants {
+        let base = rm.name();
+        let mut name = base.clone();
+        let mut occurrence = 1;
+        while named.contains_key(&name) {
+            occurrence += 1;
+            name = format!("{base} (#{occurrence})");
+        }
+        named.insert(name, rm);
+    }
+    named
+}
+
+/// The differences between two runs.
+#[derive(Default)]
+struct Comparison<'a> {
+    /// Mutants that are missed in the new run, and were either caught, not tested, or
+    /// didn't exist in the old run.
+    newly_missed: Vec<&'a ReportMutant>,
+    /// Mutants that were missed in the old run, and are caught in the new run.
+    newly_caught: Vec<&'a ReportMutant>,
+    /// Mutants in the new run that weren't in the old run.
+    added: Vec<&'a ReportMutant>,
+    /// Mutants in the old run that aren't in the new run.
+    removed: Vec<&'a ReportMutant>,
+}
+
+impl<'a> Comparison<'a> {
+    fn new(old: &'a NamedMutants, new: &'a NamedMutants) -> Comparison<'a> {
+        let mut comparison = Comparison::default();
+        for (name, rm) in new {
+            let old_outcome = old.get(name).map(|old_rm| &old_rm.outcome);
+            if old_outcome.is_none() {
+                comparison.added.push(rm);
+            }
+            match (old_outcome, &rm.outcome) {
+                (Some(SummaryOutcome::MissedMutant), SummaryOutcome::MissedMutant) => (),
+                (_, SummaryOutcome::MissedMutant) => comparison.newly_missed.push(rm),
+                (Some(SummaryOutcome::MissedMutant), SummaryOutcome::CaughtMutant) => {
+                    comparison.newly_caught.push(rm);
+                }
+                _ => (),
+            }
+        }
+        comparison.removed = old
+            .iter()
+            .filter(|(name, _)| !new.contains_key(*name))
+            .map(|(_, rm)| rm)
+            .collect();
+        comparison
+    }
+
+    fn format(&self) -> String {
+        let mut s = String::new();
+        for (title, mutants, show_outcome) in [
+            ("Newly missed", &self.newly_missed, false),
+            ("Newly caught", &self.newly_caught, false),
+            ("New mutants", &self.added, true),
+            ("Removed mutants", &self.removed, true),
+        ] {
+            if mutants.is_empty() {
+                continue;
+            }
+            writeln!(s, "{title}:").unwrap();
+            for rm in mutants {
+                let span = &rm.mutant.span;
+                write!(
+                    s,
+                    "  {}:{}:{}: {}",
+                    rm.mutant.file,
+                    span.start.line,
+                    span.start.column,
+                    rm.description()
+                )
+                .unwrap();
+                if show_outcome {
+                    write!(s, " ({})", outcome_name(&rm.outcome)).unwrap();
+                }
+                s.push('\n');
+            }
+            s.push('\n');
+        }
+        writeln!(
+            s,
+            "{} newly missed, {} newly caught, {} new, {} removed",
+            plural(self.newly_missed.len(), "mutant"),
+            self.newly_caught.len(),
+            self.added.len(),
+            self.removed.len(),
+        )
+        .unwrap();
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn repeated_changes_are_numbered() {
+        let named = name_mutants(vec![
+            report_mutant(8, "<=", SummaryOutcome::CaughtMutant),
+            report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+        ]);
+        assert_eq!(
+            named.keys().collect::<Vec<_>>(),
+            [
+                "src/lib.rs: replace < with <= in is_small",
+                "src/lib.rs: replace < with <= in is_small (#2)"
+            ]
+        );
+        assert_eq!(
+            named["src/lib.rs: replace < with <= in is_small"]
+                .mutant
+                .span
+                .start
+                .line,
+            2
+        );
+    }
+
+    #[test]
+    fn compare_runs() {
+        let old = name_mutants(vec![
+            report_mutant(2, "<=", SummaryOutcome::CaughtMutant),
+            report_mutant(2, ">", SummaryOutcome::MissedMutant),
+            report_mutant(2, "==", SummaryOutcome::MissedMutant),
+            report_mutant(2, "!=", SummaryOutcome::CaughtMutant),
+        ]);
+        // The code moved down a line, which shouldn't matter.
+        let new = name_mutants(vec![
+            report_mutant(3, "<=", SummaryOutcome::MissedMutant),
+            report_mutant(3, ">", SummaryOutcome::CaughtMutant),
+            report_mutant(3, "==", SummaryOutcome::MissedMutant),
+            report_mutant(3, ">=", SummaryOutcome::Unviable),
+        ]);
+        let comparison = Comparison::new(&old, &new);
+        assert_eq!(
+            comparison.format(),
+            indoc! { "
+                Newly missed:
+                  src/lib.rs:3:7: replace < with <= in is_small
+
+                Newly caught:
+                  src/lib.rs:3:7: replace < with > in is_small
+
+                New mutants:
+                  src/lib.rs:3:7: replace < with >= in is_small (unviable)
+
+                Removed mutants:
+                  src/lib.rs:2:7: replace < with != in is_small (caught)
+
+                1 mutant newly missed, 1 newly caught, 1 new, 1 removed
+            " }
+        );
+    }
+}
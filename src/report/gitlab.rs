@@ -0,0 +1,101 @@
+// Copyright 2025 Martin Pool
+
+//! Missed mutants as a GitLab Code Quality report.
+//!
+//! GitLab compares the findings in the report from a merge request with those from its
+//! target branch, and shows the new ones in the merge request and on its diff. Findings
+//! are matched up by their fingerprints, so these depend only on the file and the
+//! description of the mutant, not on its line number.
+
+use serde_json::{json, Value};
+
+use super::{Outcomes, ReportMutant};
+use crate::hash::fnv1a;
+use crate::outcome::SummaryOutcome;
+use crate::{Context, Result};
+
+/// The name of the Code Quality file within the output directory.
+pub(super) const GL_CODE_QUALITY_REPORT: &str = "gl-code-quality-report.json";
+
+/// Render missed mutants as a GitLab Code Quality report.
+pub(super) fn render_code_quality(outcomes: &Outcomes) -> Result<String> {
+    let mut findings = Vec::new();
+    for (file, mutants) in &outcomes.by_file {
+        let mut seen: Vec<String> = Vec::new();
+        for rm in mutants {
+            if rm.outcome != SummaryOutcome::MissedMutant {
+                continue;
+            }
+            let description = rm.description();
+            // The same change could be made in several places in one function; number
+            // them so that each has a distinct fingerprint.
+            let occurrence = seen.iter().filter(|d| **d == description).count();
+            findings.push(finding(file, rm, &description, occurrence));
+            seen.push(description);
+        }
+    }
+    serde_json::to_string_pretty(&findings).context("serialize Code Quality report")
+}
+
+fn finding(file: &str, rm: &ReportMutant, description: &str, occurrence: usize) -> Value {
+    let span = &rm.mutant.span;
+    let fingerprint = fnv1a(format!("{file}\n{description}\n{occurrence}").as_bytes());
+    json!({
+        "description": format!("Missed mutant: {description}"),
+        "check_name": "missed-mutant",
+        "fingerprint": format!("{fingerprint:016x}"),
+        "severity": "minor",
+        "location": {
+            "path": file,
+            "positions": {
+                "begin": { "line": span.start.line, "column": span.start.column },
+                "end": { "line": span.end.line, "column": span.end.column }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn only_missed_mutants_are_findings() {
+        let outcomes = Outcomes {
+            by_file: BTreeMap::from([(
+                "src/lib.rs".to_owned(),
+                vec![
+                    report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+                    report_mutant(2, ">", SummaryOutcome::CaughtMutant),
+                    report_mutant(8, "<=", SummaryOutcome::MissedMutant),
+                ],
+            )]),
+            sources: BTreeMap::new(),
+        };
+        let findings: Value =
+            serde_json::from_str(&render_code_quality(&outcomes).unwrap()).unwrap();
+        let findings = findings.as_array().unwrap();
+        assert_eq!(findings.len(), 2);
+        assert_eq!(
+            findings[0],
+            json!({
+                "description": "Missed mutant: replace < with <= in is_small",
+                "check_name": "missed-mutant",
+                "fingerprint": format!("{:016x}", fnv1a(b"src/lib.rs\nreplace < with <= in is_small\n0")),
+                "severity": "minor",
+                "location": {
+                    "path": "src/lib.rs",
+                    "positions": {
+                        "begin": { "line": 2, "column": 7 },
+                        "end": { "line": 2, "column": 8 }
+                    }
+                }
+            })
+        );
+        assert_eq!(findings[1]["location"]["positions"]["begin"]["line"], 8);
+        assert_ne!(findings[0]["fingerprint"], findings[1]["fingerprint"]);
+    }
+}
@@ -0,0 +1,17 @@
+// Show or hide mutants, and files with no shown mutants, by outcome.
+function applyFilters() {
+  const shown = new Set(
+    Array.from(document.querySelectorAll("#filters input:checked"), (input) => input.value),
+  );
+  for (const row of document.querySelectorAll("tr.mutant")) {
+    const outcome = row.classList[1];
+    row.classList.toggle("hidden", !shown.has(outcome));
+  }
+  for (const file of document.querySelectorAll("section.file")) {
+    const visible = file.querySelector("tr.mutant:not(.hidden)");
+    file.classList.toggle("hidden", visible === null);
+  }
+}
+for (const input of document.querySelectorAll("#filters input")) {
+  input.addEventListener("change", applyFilters);
+}
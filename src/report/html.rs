@@ -0,0 +1,242 @@
+// Copyright 2025 Martin Pool
+
+//! A self-contained HTML report.
+//!
+//! The report shows each source file that has mutants, with the mutants listed
+//! under the line where they start, their outcomes, and their diffs. Everything,
+//! including the styles and the script that filters mutants by outcome, is inlined
+//! so that the file can be opened directly or attached to a CI run.
+
+use std::fmt::Write;
+
+use super::{escape_markup, Outcomes, ReportMutant};
+use crate::outcome::SummaryOutcome;
+
+/// The name of the report file within the output directory.
+pub(super) const REPORT_HTML: &str = "report.html";
+
+/// Outcomes in order of how much attention they need, with their names in the report.
+const OUTCOME_CLASSES: &[(SummaryOutcome, &str)] = &[
+    (SummaryOutcome::MissedMutant, "missed"),
+    (SummaryOutcome::Timeout, "timeout"),
+    (SummaryOutcome::CaughtMutant, "caught"),
+    (SummaryOutcome::Unviable, "unviable"),
+    (SummaryOutcome::Equivalent, "equivalent"),
+    (SummaryOutcome::Success, "success"),
+    (SummaryOutcome::Failure, "failure"),
+];
+
+/// Render the whole report as HTML.
+pub(super) fn render_report(outcomes: &Outcomes) -> String {
+    let all_outcomes = outcomes.by_file.values().flatten().map(|rm| &rm.outcome);
+    let totals = count_outcomes(all_outcomes);
+    let total: usize = totals.iter().map(|(_, count)| count).sum();
+    let mut html = String::new();
+    html.push_str(concat!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n",
+        "<title>cargo-mutants report</title>\n<style>\n",
+        include_str!("html.css"),
+        "</style>\n</head>\n<body>\n<h1>cargo-mutants report</h1>\n",
+    ));
+    writeln!(
+        html,
+        "<p class=\"totals\">{total} mutants tested: {}</p>",
+        describe_counts(&totals)
+    )
+    .unwrap();
+    html.push_str("<form id=\"filters\">Show:\n");
+    for (class, count) in &totals {
+        writeln!(
+            html,
+            "<label class=\"{class}\"><input type=\"checkbox\" value=\"{class}\" checked> {class} ({count})</label>",
+        )
+        .unwrap();
+    }
+    html.push_str("</form>\n");
+    for (file, mutants) in &outcomes.by_file {
+        render_file(
+            &mut html,
+            file,
+            mutants,
+            outcomes.sources.get(file).map(String::as_str),
+        );
+    }
+    html.push_str("<script>\n");
+    html.push_str(include_str!("html.js"));
+    html.push_str("</script>\n</body>\n</html>\n");
+    html
+}
+
+/// Render the source of one file, with its mutants listed under the lines where they start.
+fn render_file(html: &mut String, file: &str, mutants: &[ReportMutant], code: Option<&str>) {
+    let counts = count_outcomes(mutants.iter().map(|rm| &rm.outcome));
+    let open = if counts.iter().any(|(class, _)| *class == "missed") {
+        " open"
+    } else {
+        ""
+    };
+    writeln!(
+        html,
+        "<section class=\"file\">\n<details{open}><summary><code>{}</code> {}</summary>\n<table class=\"source\">",
+        escape_markup(file),
+        describe_counts(&counts),
+    )
+    .unwrap();
+    let lines: Vec<&str> = code.map_or_else(Vec::new, |code| code.lines().collect());
+    let mut mutants = mutants.iter().peekable();
+    for (i, line) in lines.iter().enumerate() {
+        let line_no = i + 1;
+        let mut here = Vec::new();
+        while let Some(rm) = mutants.next_if(|rm| rm.mutant.span.start.line <= line_no) {
+            here.push(rm);
+        }
+        let line_class = here
+            .iter()
+            .map(|rm| outcome_rank(&rm.outcome))
+            .min()
+            .map_or("", |rank| OUTCOME_CLASSES[rank].1);
+        writeln!(
+            html,
+            "<tr class=\"line {line_class}\"><td class=\"lineno\">{line_no}</td><td class=\"code\">{}</td></tr>",
+            escape_markup(line)
+        )
+        .unwrap();
+        for rm in here {
+            render_mutant(html, rm, code);
+        }
+    }
+    // Mutants past the end of the source, or in a file that couldn't be read.
+    for rm in mutants {
+        render_mutant(html, rm, code);
+    }
+    html.push_str("</table>\n</details>\n</section>\n");
+}
+
+fn render_mutant(html: &mut String, rm: &ReportMutant, code: Option<&str>) {
+    let class = OUTCOME_CLASSES[outcome_rank(&rm.outcome)].1;
+    let mutant = &rm.mutant;
+    let start = mutant.span.start;
+    let original = code.map_or_else(String::new, |code| mutant.span.extract(code));
+    write!(
+        html,
+        "<tr class=\"mutant {class}\"><td></td><td><span class=\"badge\">{class}</span> {}:{} {}: replace <code>{}</code> with <code>{}</code>",
+        start.line,
+        start.column,
+        mutant.genre,
+        escape_markup(&abbreviate(&original)),
+        escape_markup(&mutant.replacement),
+    )
+    .unwrap();
+    if let Some(function) = &mutant.function {
+        write!(
+            html,
+            " in <code>{}</code>",
+            escape_markup(&function.function_name)
+        )
+        .unwrap();
+    }
+    if let Some(diff) = &rm.diff {
+        write!(
+            html,
+            "<details><summary>diff</summary><pre>{}</pre></details>",
+            escape_markup(diff)
+        )
+        .unwrap();
+    }
+    html.push_str("</td></tr>\n");
+}
+
+/// Count mutants by outcome, in the order of [`OUTCOME_CLASSES`], omitting outcomes
+/// with no mutants.
+fn count_outcomes<'a>(
+    outcomes: impl IntoIterator<Item = &'a SummaryOutcome>,
+) -> Vec<(&'static str, usize)> {
+    let mut counts = vec![0; OUTCOME_CLASSES.len()];
+    for outcome in outcomes {
+        counts[outcome_rank(outcome)] += 1;
+    }
+    OUTCOME_CLASSES
+        .iter()
+        .zip(counts)
+        .filter(|(_, count)| *count > 0)
+        .map(|((_, class), count)| (*class, count))
+        .collect()
+}
+
+fn describe_counts(counts: &[(&str, usize)]) -> String {
+    counts
+        .iter()
+        .map(|(class, count)| format!("{count} {class}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn outcome_rank(outcome: &SummaryOutcome) -> usize {
+    OUTCOME_CLASSES
+        .iter()
+        .position(|(o, _)| o == outcome)
+        .expect("all outcomes have a class")
+}
+
+/// Shorten the original code of a mutant to its first line, and at most 80 chars.
+fn abbreviate(code: &str) -> String {
+    let first_line = code.lines().next().unwrap_or_default();
+    let mut short: String = first_line.chars().take(80).collect();
+    if short.len() < code.len() {
+        short.push_str(" …");
+    }
+    short
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn render_mutants_under_their_lines() {
+        let code = indoc! { "
+            fn is_small(x: u32) -> bool {
+                x < 4
+            }
+        " };
+        let by_file = BTreeMap::from([(
+            "src/lib.rs".to_owned(),
+            vec![
+                report_mutant(2, ">", SummaryOutcome::CaughtMutant),
+                report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+            ],
+        )]);
+        let sources = BTreeMap::from([("src/lib.rs".to_owned(), code.to_owned())]);
+        let html = render_report(&Outcomes { by_file, sources });
+        assert!(html.contains("<p class=\"totals\">2 mutants tested: 1 missed, 1 caught</p>"));
+        assert!(html.contains(
+            "<details open><summary><code>src/lib.rs</code> 1 missed, 1 caught</summary>"
+        ));
+        // The line is marked with the most important outcome.
+        assert!(html.contains(
+            "<tr class=\"line missed\"><td class=\"lineno\">2</td><td class=\"code\">    x &lt; 4</td></tr>\n<tr class=\"mutant caught\">"
+        ));
+        assert!(html.contains(
+            "<span class=\"badge\">missed</span> 2:7 BinaryOperator: replace <code>&lt;</code> with <code>&lt;=</code> in <code>is_small</code>"
+        ));
+        assert!(html.contains(
+            "<pre>--- src/lib.rs\n+++ replace &lt; with &lt;= in is_small\n-    x &lt; 4\n+    x &lt;= 4\n</pre>"
+        ));
+        assert!(html.contains("<tr class=\"line \"><td class=\"lineno\">3</td>"));
+    }
+
+    #[test]
+    fn abbreviate_long_code() {
+        assert_eq!(abbreviate("a + b"), "a + b");
+        assert_eq!(abbreviate("{\n    a\n}"), "{ …");
+        assert_eq!(
+            abbreviate(&"x".repeat(100)),
+            format!("{} …", "x".repeat(80))
+        );
+    }
+}
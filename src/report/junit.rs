@@ -0,0 +1,172 @@
+// Copyright 2025 Martin Pool
+
+//! Mutants as test cases in `JUnit` XML.
+//!
+//! Most CI systems, including Jenkins, GitLab, and Buildkite, can show and trend
+//! `JUnit` files. Each source file becomes a test suite, and each mutant a test case
+//! in it: missed mutants are failures, timeouts are errors, unviable and equivalent
+//! mutants are skipped, and caught mutants pass.
+
+use std::fmt::Write;
+
+use super::{escape_markup, Outcomes, ReportMutant};
+use crate::outcome::SummaryOutcome;
+
+/// The name of the `JUnit` file within the output directory.
+pub(super) const JUNIT_XML: &str = "junit.xml";
+
+/// Counts of test cases by how they're shown in `JUnit`.
+#[derive(Debug, Default)]
+struct Counts {
+    tests: usize,
+    failures: usize,
+    errors: usize,
+    skipped: usize,
+    time: f64,
+}
+
+impl Counts {
+    fn count<'a>(mutants: impl IntoIterator<Item = &'a ReportMutant>) -> Counts {
+        let mut counts = Counts::default();
+        for rm in mutants {
+            counts.tests += 1;
+            counts.time += rm.duration;
+            match rm.outcome {
+                SummaryOutcome::MissedMutant => counts.failures += 1,
+                SummaryOutcome::Timeout | SummaryOutcome::Failure => counts.errors += 1,
+                SummaryOutcome::Unviable | SummaryOutcome::Equivalent => counts.skipped += 1,
+                SummaryOutcome::CaughtMutant | SummaryOutcome::Success => (),
+            }
+        }
+        counts
+    }
+
+    /// Format as the attributes shared by `testsuites` and `testsuite`.
+    fn attributes(&self) -> String {
+        format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{:.3}\"",
+            self.tests, self.failures, self.errors, self.skipped, self.time
+        )
+    }
+}
+
+/// Render all mutants as a `JUnit` XML document.
+pub(super) fn render_junit(outcomes: &Outcomes) -> String {
+    let totals = Counts::count(outcomes.by_file.values().flatten());
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    writeln!(
+        xml,
+        "<testsuites name=\"cargo-mutants\" {}>",
+        totals.attributes()
+    )
+    .unwrap();
+    for (file, mutants) in &outcomes.by_file {
+        let counts = Counts::count(mutants);
+        writeln!(
+            xml,
+            "  <testsuite name=\"{}\" {}>",
+            escape_markup(file),
+            counts.attributes()
+        )
+        .unwrap();
+        for rm in mutants {
+            render_testcase(&mut xml, file, rm);
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn render_testcase(xml: &mut String, file: &str, rm: &ReportMutant) {
+    let file = escape_markup(file);
+    let description = escape_markup(&rm.description());
+    // The line number is an attribute rather than part of the name, so that the
+    // same mutant can be trended across runs even when code above it moves.
+    write!(
+        xml,
+        "    <testcase name=\"{description}\" classname=\"{file}\" file=\"{file}\" line=\"{}\" time=\"{:.3}\"",
+        rm.mutant.span.start.line, rm.duration
+    )
+    .unwrap();
+    let (element, kind) = match rm.outcome {
+        SummaryOutcome::MissedMutant => ("failure", "missed"),
+        SummaryOutcome::Timeout => ("error", "timeout"),
+        SummaryOutcome::Failure => ("error", "failure"),
+        SummaryOutcome::Unviable => ("skipped", "unviable"),
+        SummaryOutcome::Equivalent => ("skipped", "equivalent"),
+        SummaryOutcome::CaughtMutant | SummaryOutcome::Success => {
+            xml.push_str("/>\n");
+            return;
+        }
+    };
+    xml.push_str(">\n");
+    if element == "skipped" {
+        writeln!(xml, "      <skipped message=\"{kind}\"/>").unwrap();
+    } else {
+        write!(
+            xml,
+            "      <{element} type=\"{kind}\" message=\"{kind}: {description}\">"
+        )
+        .unwrap();
+        if let Some(diff) = &rm.diff {
+            xml.push_str(&escape_markup(diff));
+        }
+        writeln!(xml, "</{element}>").unwrap();
+    }
+    xml.push_str("    </testcase>\n");
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use indoc::indoc;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn mutants_as_test_cases() {
+        let outcomes = Outcomes {
+            by_file: BTreeMap::from([(
+                "src/lib.rs".to_owned(),
+                vec![
+                    report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+                    report_mutant(2, ">", SummaryOutcome::CaughtMutant),
+                    report_mutant(2, "==", SummaryOutcome::Timeout),
+                    report_mutant(2, "!=", SummaryOutcome::Unviable),
+                ],
+            )]),
+            sources: BTreeMap::new(),
+        };
+        assert_eq!(
+            render_junit(&outcomes),
+            indoc! { r#"
+                <?xml version="1.0" encoding="UTF-8"?>
+                <testsuites name="cargo-mutants" tests="4" failures="1" errors="1" skipped="1" time="6.000">
+                  <testsuite name="src/lib.rs" tests="4" failures="1" errors="1" skipped="1" time="6.000">
+                    <testcase name="replace &lt; with &lt;= in is_small" classname="src/lib.rs" file="src/lib.rs" line="2" time="1.500">
+                      <failure type="missed" message="missed: replace &lt; with &lt;= in is_small">--- src/lib.rs
+                +++ replace &lt; with &lt;= in is_small
+                -    x &lt; 4
+                +    x &lt;= 4
+                </failure>
+                    </testcase>
+                    <testcase name="replace &lt; with &gt; in is_small" classname="src/lib.rs" file="src/lib.rs" line="2" time="1.500"/>
+                    <testcase name="replace &lt; with == in is_small" classname="src/lib.rs" file="src/lib.rs" line="2" time="1.500">
+                      <error type="timeout" message="timeout: replace &lt; with == in is_small">--- src/lib.rs
+                +++ replace &lt; with == in is_small
+                -    x &lt; 4
+                +    x == 4
+                </error>
+                    </testcase>
+                    <testcase name="replace &lt; with != in is_small" classname="src/lib.rs" file="src/lib.rs" line="2" time="1.500">
+                      <skipped message="unviable"/>
+                    </testcase>
+                  </testsuite>
+                </testsuites>
+            "# }
+        );
+    }
+}
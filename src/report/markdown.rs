@@ -0,0 +1,163 @@
+// Copyright 2025 Martin Pool
+
+//! A Markdown summary of the run.
+//!
+//! The summary has the mutation score, a table of outcomes for each package, and the
+//! diffs of the first missed mutants in collapsible sections, so that it can be pasted
+//! into a pull request comment or a CI job summary.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use super::{escape_markup, format_score, Outcomes, ReportMutant, Tally};
+use crate::console::plural;
+use crate::outcome::SummaryOutcome;
+
+/// The name of the summary file within the output directory.
+pub(super) const SUMMARY_MD: &str = "summary.md";
+
+/// Render the summary, including the diffs of at most `max_missed` missed mutants.
+pub(super) fn render_summary(outcomes: &Outcomes, max_missed: usize) -> String {
+    let all = Tally::count(outcomes.by_file.values().flatten());
+    let mut md = String::from("## cargo-mutants\n\n");
+    writeln!(
+        md,
+        "**Mutation score: {}** ({} of {} viable mutants caught)\n",
+        format_score(all.score()),
+        all.caught,
+        all.caught + all.missed + all.timeout,
+    )
+    .unwrap();
+
+    let mut by_package: BTreeMap<&str, Vec<&ReportMutant>> = BTreeMap::new();
+    for rm in outcomes.by_file.values().flatten() {
+        by_package.entry(&rm.mutant.package).or_default().push(rm);
+    }
+    md.push_str("| Package | Mutants | Caught | Missed | Timeouts | Unviable | Score |\n");
+    md.push_str("| --- | ---: | ---: | ---: | ---: | ---: | ---: |\n");
+    for (package, mutants) in by_package {
+        let tally = Tally::count(mutants);
+        writeln!(
+            md,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            escape_markup(package),
+            tally.total,
+            tally.caught,
+            tally.missed,
+            tally.timeout,
+            tally.unviable,
+            format_score(tally.score())
+        )
+        .unwrap();
+    }
+
+    let missed: Vec<&ReportMutant> = outcomes
+        .by_file
+        .values()
+        .flatten()
+        .filter(|rm| rm.outcome == SummaryOutcome::MissedMutant)
+        .collect();
+    if !missed.is_empty() {
+        md.push_str("\n### Missed mutants\n\n");
+        for rm in missed.iter().take(max_missed) {
+            render_missed(&mut md, rm);
+        }
+        let more = missed.len().saturating_sub(max_missed);
+        if more > 0 {
+            writeln!(
+                md,
+                "See `mutants.out/missed.txt` for {}.",
+                if more == missed.len() {
+                    plural(more, "missed mutant")
+                } else {
+                    plural(more, "more missed mutant")
+                },
+            )
+            .unwrap();
+        }
+    }
+    md
+}
+
+fn render_missed(md: &mut String, rm: &ReportMutant) {
+    let span = &rm.mutant.span;
+    writeln!(
+        md,
+        "<details>\n<summary><code>{}:{}:{}</code>: {}</summary>\n",
+        escape_markup(&rm.mutant.file),
+        span.start.line,
+        span.start.column,
+        escape_markup(&rm.description()),
+    )
+    .unwrap();
+    if let Some(diff) = &rm.diff {
+        // Use a longer fence if the diff itself contains a fence.
+        let fence = if diff.contains("```") { "````" } else { "```" };
+        writeln!(md, "{fence}diff\n{}\n{fence}\n", diff.trim_end()).unwrap();
+    }
+    md.push_str("</details>\n\n");
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    fn outcomes() -> Outcomes {
+        Outcomes {
+            by_file: BTreeMap::from([(
+                "src/lib.rs".to_owned(),
+                vec![
+                    report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+                    report_mutant(2, ">", SummaryOutcome::CaughtMutant),
+                    report_mutant(2, "==", SummaryOutcome::MissedMutant),
+                    report_mutant(2, "!=", SummaryOutcome::Unviable),
+                ],
+            )]),
+            sources: BTreeMap::new(),
+        }
+    }
+
+    #[test]
+    fn summary_with_missed_diffs() {
+        assert_eq!(
+            render_summary(&outcomes(), 1),
+            indoc! { r"
+                ## cargo-mutants
+
+                **Mutation score: 33.3%** (1 of 3 viable mutants caught)
+
+                | Package | Mutants | Caught | Missed | Timeouts | Unviable | Score |
+                | --- | ---: | ---: | ---: | ---: | ---: | ---: |
+                | small | 4 | 1 | 2 | 0 | 1 | 33.3% |
+
+                ### Missed mutants
+
+                <details>
+                <summary><code>src/lib.rs:2:7</code>: replace &lt; with &lt;= in is_small</summary>
+
+                ```diff
+                --- src/lib.rs
+                +++ replace < with <= in is_small
+                -    x < 4
+                +    x <= 4
+                ```
+
+                </details>
+
+                See `mutants.out/missed.txt` for 1 more missed mutant.
+            " }
+        );
+    }
+
+    #[test]
+    fn summary_without_diffs() {
+        let md = render_summary(&outcomes(), 0);
+        assert!(!md.contains("<details>"));
+        assert!(md.ends_with(
+            "### Missed mutants\n\nSee `mutants.out/missed.txt` for 2 missed mutants.\n"
+        ));
+    }
+}
@@ -0,0 +1,405 @@
+// Copyright 2025 Martin Pool
+
+//! Merge the results of several runs into one `mutants.out`, for `cargo mutants merge`.
+//!
+//! This is for shards, or runs on different machines, that each tested some of the
+//! mutants of the same tree. Mutants are matched up by their file, span, and replacement.
+//! A mutant that was tested in more than one run is an overlap, and is kept once. If the
+//! runs had different outcomes for it, that's also a conflict: it's reported, and the
+//! outcome from the run listed first is kept.
+//!
+//! The merged directory has an `outcomes.json` with the counts and scores of all the
+//! mutants, the lists of mutants by outcome, and the log and diff of each mutant, so that
+//! the other commands that read `mutants.out` work on it just as on a single run.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Write;
+use std::fs::{copy, create_dir, create_dir_all, read_to_string};
+
+use anyhow::ensure;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::compare::outcomes_path;
+use super::{outcome_name, OutcomeRecord, ReportMutant, ScenarioRecord};
+use crate::console::plural;
+use crate::exit_code;
+use crate::outcome::SummaryOutcome;
+use crate::output::OUTDIR_NAME;
+use crate::schema::SCHEMA_VERSION;
+use crate::scores::Scores;
+use crate::span::Span;
+use crate::{Context, Result};
+
+/// Which runs to merge.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+pub struct MergeArgs {
+    /// The runs to merge: `mutants.out` directories, directories containing one, or
+    /// `outcomes.json` files. Where runs overlap, the outcome from the first is kept.
+    #[arg(required = true)]
+    runs: Vec<Utf8PathBuf>,
+}
+
+/// `outcomes.json` for the merged runs, in the same form as for a single run.
+#[derive(Debug, Default, Serialize)]
+struct MergedOutcomes {
+    schema_version: u32,
+    outcomes: Vec<Value>,
+    total_mutants: usize,
+    missed: usize,
+    caught: usize,
+    timeout: usize,
+    unviable: usize,
+    success: usize,
+    equivalent: usize,
+    uncovered: usize,
+    scores: Scores,
+}
+
+/// The outcomes read from one run.
+struct Run {
+    /// The `mutants.out` directory, which log and diff paths are relative to.
+    dir: Utf8PathBuf,
+    /// Each outcome as it was written, and as parsed.
+    outcomes: Vec<(Value, OutcomeRecord)>,
+}
+
+/// Outcomes merged so far, and the files copied into the merged directory.
+struct Merger {
+    output_dir: Utf8PathBuf,
+    merged: MergedOutcomes,
+    /// The run and outcome of each mutant kept, by its file, span, and replacement.
+    kept: HashMap<(String, Span, String), (Utf8PathBuf, SummaryOutcome)>,
+    /// Lines for each list file, like `caught.txt`.
+    lists: BTreeMap<&'static str, String>,
+    /// Paths of logs and diffs already copied, relative to the output directory.
+    used_paths: HashSet<Utf8PathBuf>,
+    /// The number of mutants tested in more than one run.
+    overlaps: usize,
+    /// Descriptions of mutants with different outcomes in different runs.
+    conflicts: Vec<String>,
+    baseline_failed: bool,
+}
+
+/// Merge runs into a new `mutants.out` in `output_parent`, print a summary, and return
+/// the exit code that one run with all these outcomes would have had.
+pub fn merge(args: &MergeArgs, output_parent: &Utf8Path) -> Result<i32> {
+    let output_dir = output_parent.join(OUTDIR_NAME);
+    ensure!(
+        !output_dir.exists(),
+        "{output_dir} already exists: remove it or choose another --output directory"
+    );
+    let runs = args
+        .runs
+        .iter()
+        .map(|path| read_run(&outcomes_path(path)))
+        .collect::<Result<Vec<Run>>>()?;
+    create_dir_all(output_dir.join("log")).with_context(|| format!("create {output_dir}"))?;
+    create_dir(output_dir.join("diff")).with_context(|| format!("create {output_dir}/diff"))?;
+    let mut merger = Merger::new(output_dir);
+    for run in runs {
+        merger.add_run(run)?;
+    }
+    merger.write()?;
+    print!("{}", merger.format_summary(args.runs.len()));
+    Ok(merger.exit_code())
+}
+
+fn read_run(outcomes_path: &Utf8Path) -> Result<Run> {
+    let json = read_to_string(outcomes_path).with_context(|| format!("read {outcomes_path}"))?;
+    let mut value: Value =
+        serde_json::from_str(&json).with_context(|| format!("parse {outcomes_path}"))?;
+    let outcomes = match value["outcomes"].take() {
+        Value::Array(outcomes) => outcomes,
+        _ => Vec::new(),
+    };
+    Ok(Run {
+        dir: outcomes_path
+            .parent()
+            .unwrap_or(Utf8Path::new("."))
+            .to_owned(),
+        outcomes: outcomes
+            .into_iter()
+            .map(|outcome| {
+                let record = serde_json::from_value(outcome.clone())
+                    .with_context(|| format!("parse outcome in {outcomes_path}"))?;
+                Ok((outcome, record))
+            })
+            .collect::<Result<Vec<_>>>()?,
+    })
+}
+
+/// The list file for mutants with this outcome.
+fn list_name(outcome: &SummaryOutcome) -> Option<&'static str> {
+    match outcome {
+        SummaryOutcome::CaughtMutant => Some("caught.txt"),
+        SummaryOutcome::MissedMutant => Some("missed.txt"),
+        SummaryOutcome::Timeout => Some("timeout.txt"),
+        SummaryOutcome::Unviable => Some("unviable.txt"),
+        SummaryOutcome::Equivalent => Some("equivalent.txt"),
+        SummaryOutcome::Success | SummaryOutcome::Failure => None,
+    }
+}
+
+impl Merger {
+    fn new(output_dir: Utf8PathBuf) -> Merger {
+        let lists = [
+            "caught.txt",
+            "missed.txt",
+            "timeout.txt",
+            "unviable.txt",
+            "equivalent.txt",
+            "uncovered.txt",
+        ]
+        .into_iter()
+        .map(|name| (name, String::new()))
+        .collect();
+        Merger {
+            output_dir,
+            merged: MergedOutcomes {
+                schema_version: SCHEMA_VERSION,
+                ..MergedOutcomes::default()
+            },
+            kept: HashMap::new(),
+            lists,
+            used_paths: HashSet::new(),
+            overlaps: 0,
+            conflicts: Vec::new(),
+            baseline_failed: false,
+        }
+    }
+
+    fn add_run(&mut self, run: Run) -> Result<()> {
+        let has_baseline = self
+            .merged
+            .outcomes
+            .iter()
+            .any(|o| o["scenario"] == "Baseline");
+        for (mut value, record) in run.outcomes {
+            let mutant = match record.scenario {
+                ScenarioRecord::Baseline => {
+                    // Keep one baseline, and any that failed, since they stopped a run.
+                    let failed = record.summary != SummaryOutcome::Success;
+                    if has_baseline && !failed {
+                        continue;
+                    }
+                    self.baseline_failed |= failed;
+                    None
+                }
+                ScenarioRecord::Mutant(mutant) => {
+                    let key = (mutant.file.clone(), mutant.span, mutant.replacement.clone());
+                    if let Some((first_run, first_outcome)) = self.kept.get(&key) {
+                        self.overlaps += 1;
+                        if *first_outcome != record.summary {
+                            self.conflicts.push(format!(
+                                "{}:{}:{}: {} in {first_run}, {} in {}",
+                                mutant.file,
+                                mutant.span.start.line,
+                                mutant.span.start.column,
+                                outcome_name(first_outcome),
+                                outcome_name(&record.summary),
+                                run.dir,
+                            ));
+                        }
+                        continue;
+                    }
+                    self.kept
+                        .insert(key, (run.dir.clone(), record.summary.clone()));
+                    Some(mutant)
+                }
+            };
+            for field in ["log_path", "diff_path"] {
+                if let Some(path) = value[field].as_str() {
+                    let new_path = self.copy_file(&run.dir, Utf8Path::new(path))?;
+                    value[field] = Value::String(new_path.into_string());
+                }
+            }
+            if let Some(mutant) = mutant {
+                let diff = record
+                    .diff_path
+                    .as_ref()
+                    .and_then(|diff_path| read_to_string(run.dir.join(diff_path)).ok());
+                let uncovered = record.phase_results.is_empty();
+                self.add_mutant(
+                    &ReportMutant {
+                        mutant,
+                        outcome: record.summary,
+                        diff,
+                        diff_path: None,
+                        log_path: None,
+                        duration: 0.0,
+                    },
+                    uncovered,
+                );
+            }
+            self.merged.outcomes.push(value);
+        }
+        Ok(())
+    }
+
+    /// Count a mutant, and add it to the scores and the list for its outcome.
+    fn add_mutant(&mut self, rm: &ReportMutant, uncovered: bool) {
+        let merged = &mut self.merged;
+        merged.total_mutants += 1;
+        match rm.outcome {
+            SummaryOutcome::CaughtMutant => merged.caught += 1,
+            SummaryOutcome::MissedMutant => merged.missed += 1,
+            SummaryOutcome::Timeout => merged.timeout += 1,
+            SummaryOutcome::Unviable => merged.unviable += 1,
+            SummaryOutcome::Equivalent => merged.equivalent += 1,
+            SummaryOutcome::Success => merged.success += 1,
+            SummaryOutcome::Failure => (),
+        }
+        let name = format!(
+            "{}:{}:{}: {}\n",
+            rm.mutant.file,
+            rm.mutant.span.start.line,
+            rm.mutant.span.start.column,
+            rm.description()
+        );
+        if let Some(list) = list_name(&rm.outcome) {
+            self.lists
+                .get_mut(list)
+                .expect("list exists")
+                .push_str(&name);
+        }
+        if uncovered && rm.outcome == SummaryOutcome::MissedMutant {
+            merged.uncovered += 1;
+            self.lists
+                .get_mut("uncovered.txt")
+                .expect("list exists")
+                .push_str(&name);
+        }
+        let scores = &mut merged.scores;
+        for (tallies, name) in [
+            (&mut scores.packages, &rm.mutant.package),
+            (&mut scores.modules, &rm.mutant.module_path),
+            (&mut scores.files, &rm.mutant.file),
+        ] {
+            tallies.entry(name.clone()).or_default().add(&rm.outcome);
+        }
+    }
+
+    /// Copy a log or diff into the merged directory, adding a number to its name if a file
+    /// from another run already has that name, and return its new relative path.
+    fn copy_file(&mut self, run_dir: &Utf8Path, path: &Utf8Path) -> Result<Utf8PathBuf> {
+        let mut new_path = path.to_owned();
+        let mut index = 0;
+        while !self.used_paths.insert(new_path.clone()) {
+            index += 1;
+            let stem = path.file_stem().unwrap_or_default();
+            new_path = match path.extension() {
+                Some(extension) => path.with_file_name(format!("{stem}_{index:03}.{extension}")),
+                None => path.with_file_name(format!("{stem}_{index:03}")),
+            };
+        }
+        let from = run_dir.join(path);
+        let to = self.output_dir.join(&new_path);
+        copy(&from, &to).with_context(|| format!("copy {from} to {to}"))?;
+        Ok(new_path)
+    }
+
+    fn write(&self) -> Result<()> {
+        let outcomes_path = self.output_dir.join("outcomes.json");
+        let json =
+            serde_json::to_string_pretty(&self.merged).context("serialize merged outcomes")?;
+        std::fs::write(&outcomes_path, json).with_context(|| format!("write {outcomes_path}"))?;
+        for (name, content) in &self.lists {
+            let path = self.output_dir.join(name);
+            std::fs::write(&path, content).with_context(|| format!("write {path}"))?;
+        }
+        Ok(())
+    }
+
+    fn format_summary(&self, n_runs: usize) -> String {
+        let mut s = format!(
+            "Merged {} into {}\n",
+            plural(n_runs, "run"),
+            self.output_dir
+        );
+        match self.overlaps {
+            0 => (),
+            1 => writeln!(s, "1 mutant was tested in more than one run").unwrap(),
+            n => writeln!(s, "{n} mutants were tested in more than one run").unwrap(),
+        }
+        if !self.conflicts.is_empty() {
+            writeln!(s, "Conflicting outcomes, keeping the first:").unwrap();
+            for conflict in &self.conflicts {
+                writeln!(s, "  {conflict}").unwrap();
+            }
+        }
+        let merged = &self.merged;
+        let mut by_outcome = Vec::new();
+        if merged.uncovered > 0 {
+            by_outcome.push(format!(
+                "{} missed ({} uncovered)",
+                merged.missed, merged.uncovered
+            ));
+        } else if merged.missed > 0 {
+            by_outcome.push(format!("{} missed", merged.missed));
+        }
+        for (count, name) in [
+            (merged.caught, "caught"),
+            (merged.unviable, "unviable"),
+            (merged.timeout, "timeouts"),
+            (merged.equivalent, "equivalent"),
+            (merged.success, "succeeded"),
+        ] {
+            if count > 0 {
+                by_outcome.push(format!("{count} {name}"));
+            }
+        }
+        writeln!(
+            s,
+            "{} tested: {}",
+            plural(merged.total_mutants, "mutant"),
+            by_outcome.join(", ")
+        )
+        .unwrap();
+        s
+    }
+
+    /// The exit code for these outcomes, as from a single run.
+    fn exit_code(&self) -> i32 {
+        if self.baseline_failed {
+            exit_code::BASELINE_FAILED
+        } else if self.merged.timeout > 0 {
+            exit_code::TIMEOUT
+        } else if self.merged.missed > 0 {
+            exit_code::FOUND_PROBLEMS
+        } else {
+            exit_code::SUCCESS
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::write;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn colliding_file_names_are_numbered() {
+        let tmp = TempDir::new().unwrap();
+        let root = Utf8Path::from_path(tmp.path()).unwrap();
+        let run_dir = root.join("run");
+        create_dir_all(run_dir.join("log")).unwrap();
+        create_dir_all(root.join("merged/log")).unwrap();
+        write(run_dir.join("log/m.log"), "m").unwrap();
+        let mut merger = Merger::new(root.join("merged"));
+        for expected in ["log/m.log", "log/m_001.log", "log/m_002.log"] {
+            assert_eq!(
+                merger.copy_file(&run_dir, "log/m.log".into()).unwrap(),
+                expected
+            );
+            assert!(root.join("merged").join(expected).is_file());
+        }
+        assert!(merger
+            .copy_file(&run_dir, "log/missing.log".into())
+            .is_err());
+    }
+}
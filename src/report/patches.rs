@@ -0,0 +1,86 @@
+// Copyright 2025 Martin Pool
+
+//! Patches for missed mutants, so that a developer can apply one to their tree and
+//! write a test that catches it.
+//!
+//! The diffs in `mutants.out/diff` are labelled with the description of the mutant,
+//! which `git apply` and `patch` don't understand, so each patch here has the usual
+//! `a/` and `b/` file names, with the description on a line before the diff.
+
+use std::fmt::Write;
+
+use camino::Utf8Path;
+
+use super::{Outcomes, ReportMutant};
+use crate::outcome::SummaryOutcome;
+
+/// The name of the directory of patches within the output directory.
+pub(super) const PATCHES_DIR: &str = "patches";
+
+/// The name of the file with the patches of all the missed mutants.
+pub(super) const MISSED_PATCH: &str = "missed.patch";
+
+/// Render a patch for each missed mutant that has a diff, returning the patch file
+/// names and their content.
+pub(super) fn render_patches(outcomes: &Outcomes) -> Vec<(String, String)> {
+    outcomes
+        .by_file
+        .values()
+        .flatten()
+        .filter(|rm| rm.outcome == SummaryOutcome::MissedMutant)
+        .filter_map(|rm| {
+            let name = Utf8Path::new(rm.diff_path.as_ref()?)
+                .file_stem()?
+                .to_owned();
+            Some((format!("{name}.patch"), render_patch(rm)?))
+        })
+        .collect()
+}
+
+/// Render one mutant's diff as a patch against the unmutated tree.
+fn render_patch(rm: &ReportMutant) -> Option<String> {
+    let diff = rm.diff.as_deref()?;
+    let file = &rm.mutant.file;
+    let span = &rm.mutant.span;
+    let mut patch = String::new();
+    writeln!(
+        patch,
+        "Missed mutant: {file}:{}:{}: {}\n",
+        span.start.line,
+        span.start.column,
+        rm.description()
+    )
+    .unwrap();
+    writeln!(patch, "--- a/{file}\n+++ b/{file}").unwrap();
+    for line in diff
+        .lines()
+        .skip_while(|line| line.starts_with("--- ") || line.starts_with("+++ "))
+    {
+        writeln!(patch, "{line}").unwrap();
+    }
+    Some(patch)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn patch_has_file_names_and_description() {
+        let rm = report_mutant(2, "<=", SummaryOutcome::MissedMutant);
+        assert_eq!(
+            render_patch(&rm).unwrap(),
+            indoc! { "
+                Missed mutant: src/lib.rs:2:7: replace < with <= in is_small
+
+                --- a/src/lib.rs
+                +++ b/src/lib.rs
+                -    x < 4
+                +    x <= 4
+            " }
+        );
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright 2025 Martin Pool
+
+//! Missed mutants in the Static Analysis Results Interchange Format (SARIF).
+//!
+//! SARIF files can be uploaded to code scanning services such as GitHub code scanning,
+//! which then show the missed mutants as annotations on the source lines they mutate.
+//! Only missed mutants are included, because they're the ones that need attention.
+
+use serde_json::{json, Value};
+
+use super::{Outcomes, ReportMutant};
+use crate::outcome::SummaryOutcome;
+use crate::{Context, Result, VERSION};
+
+/// The name of the SARIF file within the output directory.
+pub(super) const MUTANTS_SARIF: &str = "mutants.sarif";
+
+/// The id of the one rule that results are reported against.
+const MISSED_MUTANT_RULE: &str = "missed-mutant";
+
+/// Render missed mutants as a SARIF 2.1.0 log.
+pub(super) fn render_sarif(outcomes: &Outcomes) -> Result<String> {
+    let results: Vec<Value> = outcomes
+        .by_file
+        .values()
+        .flatten()
+        .filter(|rm| rm.outcome == SummaryOutcome::MissedMutant)
+        .map(sarif_result)
+        .collect();
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "cargo-mutants",
+                    "version": VERSION,
+                    "informationUri": "https://mutants.rs/",
+                    "rules": [{
+                        "id": MISSED_MUTANT_RULE,
+                        "name": "MissedMutant",
+                        "shortDescription": {
+                            "text": "The code was changed and the tests still passed"
+                        },
+                        "helpUri": "https://mutants.rs/using-results.html",
+                        "defaultConfiguration": { "level": "warning" }
+                    }]
+                }
+            },
+            "results": results
+        }]
+    });
+    serde_json::to_string_pretty(&log).context("serialize SARIF")
+}
+
+fn sarif_result(rm: &ReportMutant) -> Value {
+    let mutant = &rm.mutant;
+    let description = rm.description();
+    json!({
+        "ruleId": MISSED_MUTANT_RULE,
+        "level": "warning",
+        "message": { "text": format!("Missed mutant: {description}") },
+        "locations": [{
+            "physicalLocation": {
+                "artifactLocation": {
+                    "uri": mutant.file,
+                    "uriBaseId": "%SRCROOT%"
+                },
+                "region": {
+                    "startLine": mutant.span.start.line,
+                    "startColumn": mutant.span.start.column,
+                    "endLine": mutant.span.end.line,
+                    "endColumn": mutant.span.end.column
+                }
+            }
+        }],
+        // Identify the mutant without its line number, so that results are matched up
+        // across runs even when code above it moves.
+        "partialFingerprints": {
+            "mutantDescription/v1": format!("{}: {description}", mutant.file)
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn only_missed_mutants_are_results() {
+        let outcomes = Outcomes {
+            by_file: BTreeMap::from([(
+                "src/lib.rs".to_owned(),
+                vec![
+                    report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+                    report_mutant(2, ">", SummaryOutcome::CaughtMutant),
+                ],
+            )]),
+            sources: BTreeMap::new(),
+        };
+        let sarif: Value = serde_json::from_str(&render_sarif(&outcomes).unwrap()).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "cargo-mutants");
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(
+            results[0],
+            json!({
+                "ruleId": "missed-mutant",
+                "level": "warning",
+                "message": { "text": "Missed mutant: replace < with <= in is_small" },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": "src/lib.rs", "uriBaseId": "%SRCROOT%" },
+                        "region": { "startLine": 2, "startColumn": 7, "endLine": 2, "endColumn": 8 }
+                    }
+                }],
+                "partialFingerprints": {
+                    "mutantDescription/v1": "src/lib.rs: replace < with <= in is_small"
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright 2025 Martin Pool
+
+//! Show everything known about one mutant from the last run, for `cargo mutants show`.
+//!
+//! Mutants can be named as they're printed and listed in `missed.txt`, with the line and
+//! column, or without them, as in `cargo mutants compare` and the missed baseline, which
+//! still matches after unrelated edits move the code around.
+
+use std::fmt::Write;
+
+use anyhow::bail;
+use camino::Utf8Path;
+
+use super::compare::name_mutants;
+use super::{outcome_name, read_mutants, ReportMutant};
+use crate::output::OUTDIR_NAME;
+use crate::Result;
+
+/// Which mutant to show.
+#[derive(clap::Args, Debug, PartialEq, Eq)]
+pub struct ShowArgs {
+    /// The name of the mutant, like `src/lib.rs:2:7: replace < with <= in is_small`,
+    /// with or without the line and column.
+    name: String,
+}
+
+/// Print the details and diff of a mutant from the last run in this directory.
+pub fn show(output_parent: &Utf8Path, args: &ShowArgs) -> Result<()> {
+    let output_dir = output_parent.join(OUTDIR_NAME);
+    let mutants = name_mutants(read_mutants(&output_dir.join("outcomes.json"))?);
+    let found = mutants.get(args.name.trim()).or_else(|| {
+        mutants
+            .values()
+            .find(|rm| name_with_line_col(rm) == args.name.trim())
+    });
+    let Some(rm) = found else {
+        bail!(
+            "No mutant named {:?} in {output_dir}/outcomes.json",
+            args.name
+        );
+    };
+    print!("{}", format_mutant(rm, &output_dir));
+    Ok(())
+}
+
+/// The name of a mutant as it's printed, like `src/lib.rs:2:7: replace < with <= in is_small`.
+fn name_with_line_col(rm: &ReportMutant) -> String {
+    format!(
+        "{}:{}:{}: {}",
+        rm.mutant.file,
+        rm.mutant.span.start.line,
+        rm.mutant.span.start.column,
+        rm.description()
+    )
+}
+
+pub(super) fn format_mutant(rm: &ReportMutant, output_dir: &Utf8Path) -> String {
+    let mut s = String::new();
+    writeln!(s, "{}", name_with_line_col(rm)).unwrap();
+    writeln!(
+        s,
+        "Outcome:  {} in {:.1}s",
+        outcome_name(&rm.outcome),
+        rm.duration
+    )
+    .unwrap();
+    writeln!(s, "Genre:    {}", rm.mutant.genre).unwrap();
+    writeln!(s, "Package:  {}", rm.mutant.package).unwrap();
+    if let Some(function) = &rm.mutant.function {
+        writeln!(s, "Function: {}", function.function_name).unwrap();
+    }
+    if let Some(log_path) = &rm.log_path {
+        writeln!(s, "Log:      {output_dir}/{log_path}").unwrap();
+    }
+    if let Some(diff_path) = &rm.diff_path {
+        writeln!(s, "Diff:     {output_dir}/{diff_path}").unwrap();
+    }
+    if let Some(diff) = &rm.diff {
+        writeln!(s).unwrap();
+        s.push_str(diff);
+    }
+    s
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::outcome::SummaryOutcome;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn format_missed_mutant() {
+        let rm = report_mutant(2, "<=", SummaryOutcome::MissedMutant);
+        assert_eq!(
+            format_mutant(&rm, Utf8Path::new("mutants.out")),
+            indoc! { "
+                src/lib.rs:2:7: replace < with <= in is_small
+                Outcome:  missed in 1.5s
+                Genre:    BinaryOperator
+                Package:  small
+                Function: is_small
+                Log:      mutants.out/log/src_lib.rs_line_2_col_7.log
+                Diff:     mutants.out/diff/src_lib.rs_line_2_col_7.diff
+
+                --- src/lib.rs
+                +++ replace < with <= in is_small
+                -    x < 4
+                +    x <= 4
+            " }
+        );
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright 2025 Martin Pool
+
+//! Step through the missed mutants from the last run and decide what to do about each,
+//! for `cargo mutants triage`.
+//!
+//! Accepted mutants are added to the missed baseline, so that they no longer fail the run.
+//! Mutants marked as to-do or as equivalent to the original code are recorded in
+//! `mutants-triage.toml` in the workspace root. Mutants with a decision in either file
+//! aren't asked about again, so triage can be stopped and resumed, and picks up only new
+//! missed mutants after later runs.
+//!
+//! Mutants are named without their line and column, as in the missed baseline.
+
+use std::collections::BTreeSet;
+use std::fmt::{self, Write as _};
+use std::fs::{read_to_string, write};
+use std::io::{self, BufRead, Write};
+
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use super::show::format_mutant;
+use super::{read_mutants, ReportMutant};
+use crate::missed_baseline::MissedBaseline;
+use crate::outcome::SummaryOutcome;
+use crate::output::OUTDIR_NAME;
+use crate::{Context, Result};
+
+/// The name of the file recording triage decisions, in the workspace root.
+const TRIAGE_TOML: &str = "mutants-triage.toml";
+
+const HEADER: &str = "\
+# Decisions about missed mutants, from `cargo mutants triage`.
+#
+# Accepted mutants are in the missed baseline instead.
+
+";
+
+const PROMPT: &str =
+    "Accept into the baseline, mark as todo, mark as equivalent, skip, or quit? [a/t/e/s/q] ";
+
+/// What to do about a missed mutant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    /// Accept it in the missed baseline, so that it doesn't fail the run.
+    Accepted,
+    /// A test should be written to catch it.
+    Todo,
+    /// It doesn't change the behavior of the code, so it can't be caught.
+    Equivalent,
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Decision::Accepted => "accepted",
+            Decision::Todo => "todo",
+            Decision::Equivalent => "equivalent",
+        })
+    }
+}
+
+/// The contents of the triage file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+struct TriageDecisions {
+    /// Names of missed mutants that should be caught by a new test.
+    #[serde(default)]
+    todo: BTreeSet<String>,
+    /// Names of missed mutants that are equivalent to the original code.
+    #[serde(default)]
+    equivalent: BTreeSet<String>,
+}
+
+impl TriageDecisions {
+    /// Read the triage file, or return no decisions if it doesn't exist.
+    fn read(path: &Utf8Path) -> Result<TriageDecisions> {
+        if !path.is_file() {
+            return Ok(TriageDecisions::default());
+        }
+        let toml = read_to_string(path).with_context(|| format!("read {path}"))?;
+        toml::from_str(&toml).with_context(|| format!("parse triage file {path}"))
+    }
+
+    fn write(&self, path: &Utf8Path) -> Result<()> {
+        // Write each mutant on its own line so that the file diffs well.
+        let mut toml = HEADER.to_owned();
+        for (key, names) in [("todo", &self.todo), ("equivalent", &self.equivalent)] {
+            writeln!(toml, "{key} = [").unwrap();
+            for name in names {
+                writeln!(toml, "    {},", toml::Value::from(name.as_str())).unwrap();
+            }
+            toml.push_str("]\n");
+        }
+        write(path, toml).with_context(|| format!("write triage file {path}"))
+    }
+}
+
+/// Decisions about missed mutants, kept in the missed baseline and the triage file.
+struct Triage {
+    baseline_path: Utf8PathBuf,
+    baseline: MissedBaseline,
+    decisions_path: Utf8PathBuf,
+    decisions: TriageDecisions,
+}
+
+impl Triage {
+    fn load(baseline_path: &Utf8Path, decisions_path: &Utf8Path) -> Result<Triage> {
+        Ok(Triage {
+            baseline_path: baseline_path.to_owned(),
+            baseline: MissedBaseline::read(baseline_path)?.unwrap_or_default(),
+            decisions_path: decisions_path.to_owned(),
+            decisions: TriageDecisions::read(decisions_path)?,
+        })
+    }
+
+    fn decision(&self, name: &str) -> Option<Decision> {
+        if self.baseline.missed.contains(name) {
+            Some(Decision::Accepted)
+        } else if self.decisions.todo.contains(name) {
+            Some(Decision::Todo)
+        } else if self.decisions.equivalent.contains(name) {
+            Some(Decision::Equivalent)
+        } else {
+            None
+        }
+    }
+
+    /// Record a decision, replacing any earlier one, and write it out straight away so
+    /// that it's kept if triage is interrupted.
+    fn record(&mut self, name: &str, decision: Decision) -> Result<()> {
+        let was_accepted = self.baseline.missed.remove(name);
+        self.decisions.todo.remove(name);
+        self.decisions.equivalent.remove(name);
+        match decision {
+            Decision::Accepted => self.baseline.missed.insert(name.to_owned()),
+            Decision::Todo => self.decisions.todo.insert(name.to_owned()),
+            Decision::Equivalent => self.decisions.equivalent.insert(name.to_owned()),
+        };
+        if was_accepted || decision == Decision::Accepted {
+            self.baseline.write(&self.baseline_path)?;
+        }
+        self.decisions.write(&self.decisions_path)
+    }
+
+    /// Ask about each missed mutant that doesn't have a decision yet, and return the
+    /// decisions that were made.
+    fn run(
+        &mut self,
+        mutants: &[ReportMutant],
+        output_dir: &Utf8Path,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<Vec<Decision>> {
+        let missed = mutants
+            .iter()
+            .filter(|rm| rm.outcome == SummaryOutcome::MissedMutant)
+            .sorted_by_key(|rm| {
+                (
+                    rm.mutant.file.clone(),
+                    rm.mutant.span.start.line,
+                    rm.mutant.span.start.column,
+                )
+            })
+            .unique_by(|rm| rm.name())
+            .collect_vec();
+        let undecided = missed
+            .iter()
+            .filter(|rm| self.decision(&rm.name()).is_none())
+            .collect_vec();
+        if missed.is_empty() {
+            writeln!(output, "No missed mutants to triage")?;
+            return Ok(Vec::new());
+        } else if undecided.is_empty() {
+            writeln!(
+                output,
+                "All {} missed mutants have already been triaged",
+                missed.len()
+            )?;
+            return Ok(Vec::new());
+        }
+        let mut made = Vec::new();
+        'mutants: for (i, rm) in undecided.iter().enumerate() {
+            writeln!(output, "\n[{}/{}]", i + 1, undecided.len())?;
+            write!(output, "{}", format_mutant(rm, output_dir))?;
+            let decision = loop {
+                write!(output, "\n{PROMPT}")?;
+                output.flush()?;
+                let mut answer = String::new();
+                if input.read_line(&mut answer)? == 0 {
+                    writeln!(output)?;
+                    break 'mutants;
+                }
+                match answer.trim() {
+                    "a" => break Decision::Accepted,
+                    "t" => break Decision::Todo,
+                    "e" => break Decision::Equivalent,
+                    "s" => continue 'mutants,
+                    "q" => break 'mutants,
+                    _ => writeln!(output, "Please answer a, t, e, s, or q")?,
+                }
+            };
+            self.record(&rm.name(), decision)?;
+            made.push(decision);
+        }
+        Ok(made)
+    }
+}
+
+/// Step through the undecided missed mutants from the last run, asking what to do about
+/// each one.
+pub fn triage(
+    output_parent: &Utf8Path,
+    workspace_root: &Utf8Path,
+    baseline_path: &Utf8Path,
+) -> Result<()> {
+    let output_dir = output_parent.join(OUTDIR_NAME);
+    let mutants = read_mutants(&output_dir.join("outcomes.json"))?;
+    let mut triage = Triage::load(baseline_path, &workspace_root.join(TRIAGE_TOML))?;
+    let made = triage.run(
+        &mutants,
+        &output_dir,
+        &mut io::stdin().lock(),
+        &mut io::stdout().lock(),
+    )?;
+    if !made.is_empty() {
+        let counts = [Decision::Accepted, Decision::Todo, Decision::Equivalent]
+            .into_iter()
+            .map(|decision| {
+                let n = made.iter().filter(|d| **d == decision).count();
+                format!("{n} {decision}")
+            })
+            .join(", ");
+        println!("Triaged {} mutants: {counts}", made.len());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::report::test::report_mutant;
+
+    #[test]
+    fn decisions_are_recorded_and_not_asked_again() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(tmp.path()).unwrap();
+        let baseline_path = dir.join("mutants-baseline.toml");
+        let decisions_path = dir.join(TRIAGE_TOML);
+        let mutants = [
+            report_mutant(2, "<=", SummaryOutcome::MissedMutant),
+            report_mutant(3, "==", SummaryOutcome::CaughtMutant),
+            report_mutant(4, ">", SummaryOutcome::MissedMutant),
+            report_mutant(5, ">=", SummaryOutcome::MissedMutant),
+            report_mutant(6, "!=", SummaryOutcome::MissedMutant),
+        ];
+        let mut triage = Triage::load(&baseline_path, &decisions_path).unwrap();
+        let mut output = Vec::new();
+        let made = triage
+            .run(
+                &mutants,
+                Utf8Path::new("mutants.out"),
+                &mut Cursor::new("x\na\ns\ne\n"),
+                &mut output,
+            )
+            .unwrap();
+        assert_eq!(made, [Decision::Accepted, Decision::Equivalent]);
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("\n[1/4]\nsrc/lib.rs:2:7: replace < with <= in is_small\n"));
+        assert!(output.contains("Please answer a, t, e, s, or q\n"));
+        assert!(output.contains("\n[4/4]\nsrc/lib.rs:6:7: "));
+
+        let baseline = MissedBaseline::read(&baseline_path).unwrap().unwrap();
+        assert_eq!(
+            baseline.missed.iter().collect_vec(),
+            ["src/lib.rs: replace < with <= in is_small"]
+        );
+        let decisions = TriageDecisions::read(&decisions_path).unwrap();
+        assert!(decisions.todo.is_empty());
+        assert_eq!(
+            decisions.equivalent.iter().collect_vec(),
+            ["src/lib.rs: replace < with >= in is_small"]
+        );
+
+        // The skipped mutants are asked about again; changing a decision moves it.
+        let mut triage = Triage::load(&baseline_path, &decisions_path).unwrap();
+        let mut output = Vec::new();
+        let made = triage
+            .run(
+                &mutants,
+                Utf8Path::new("mutants.out"),
+                &mut Cursor::new("t\n"),
+                &mut output,
+            )
+            .unwrap();
+        assert_eq!(made, [Decision::Todo]);
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("\n[1/2]\nsrc/lib.rs:4:7: "));
+        triage
+            .record("src/lib.rs: replace < with <= in is_small", Decision::Todo)
+            .unwrap();
+        assert!(MissedBaseline::read(&baseline_path)
+            .unwrap()
+            .unwrap()
+            .missed
+            .is_empty());
+        assert_eq!(
+            TriageDecisions::read(&decisions_path).unwrap().todo.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn nothing_to_triage() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Utf8Path::from_path(tmp.path()).unwrap();
+        let mut triage = Triage::load(&dir.join("baseline.toml"), &dir.join(TRIAGE_TOML)).unwrap();
+        let mut output = Vec::new();
+        let made = triage
+            .run(
+                &[report_mutant(2, "<=", SummaryOutcome::CaughtMutant)],
+                Utf8Path::new("mutants.out"),
+                &mut Cursor::new(""),
+                &mut output,
+            )
+            .unwrap();
+        assert!(made.is_empty());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "No missed mutants to triage\n"
+        );
+        assert!(!dir.join(TRIAGE_TOML).exists());
+    }
+}
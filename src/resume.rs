@@ -0,0 +1,198 @@
+// Copyright 2025 Martin Pool
+
+//! Continue a run that was interrupted, for `--resume`.
+//!
+//! `mutants.json` is written before testing starts, and `outcomes.json` is rewritten as
+//! each scenario finishes, so after an interruption or crash they record which mutants
+//! were going to be tested and which have already finished. A resumed run tests only the
+//! remaining mutants, in the same order, and adds their results to the same output
+//! directory, so that the final results cover the whole run.
+//!
+//! Mutants are matched to those in the current tree by their file, span, and
+//! replacement, so the source code and the options that select mutants shouldn't change
+//! before resuming. The mutant that was being tested when the run was interrupted is
+//! tested again from the start.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::{HashMap, HashSet};
+use std::fs::read_to_string;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+
+use crate::outcome::{PhaseResult, SummaryOutcome};
+use crate::output::MUTANTS_JSON;
+use crate::process::Exit;
+use crate::span::Span;
+use crate::{Context, Mutant, Phase, Result, Scenario, ScenarioOutcome};
+
+/// The mutants left to test, and the results already found, in an interrupted run.
+pub struct Resumed {
+    /// Mutants that haven't been tested yet, in their original order.
+    pub remaining: Vec<Mutant>,
+    /// Outcomes of the scenarios that finished, including the baseline if it passed.
+    pub finished: Vec<ScenarioOutcome>,
+}
+
+/// The fields that identify a mutant in `mutants.json` and `outcomes.json`.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct MutantKey {
+    file: String,
+    span: Span,
+    replacement: String,
+}
+
+impl MutantKey {
+    pub fn of(mutant: &Mutant) -> MutantKey {
+        MutantKey {
+            file: mutant.source_file.tree_relative_slashes(),
+            span: mutant.span,
+            replacement: mutant.replacement.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LabOutcomeRecord {
+    outcomes: Vec<OutcomeRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutcomeRecord {
+    scenario: ScenarioRecord,
+    summary: SummaryOutcome,
+    log_path: Utf8PathBuf,
+    diff_path: Option<Utf8PathBuf>,
+    phase_results: Vec<PhaseResultRecord>,
+}
+
+#[derive(Debug, Deserialize)]
+enum ScenarioRecord {
+    Baseline,
+    Mutant(MutantKey),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PhaseResultRecord {
+    phase: Phase,
+    /// Duration in seconds.
+    duration: f64,
+    process_status: Exit,
+    argv: Vec<String>,
+}
+
+impl From<&PhaseResult> for PhaseResultRecord {
+    fn from(phase_result: &PhaseResult) -> PhaseResultRecord {
+        PhaseResultRecord {
+            phase: phase_result.phase,
+            duration: phase_result.duration.as_secs_f64(),
+            process_status: phase_result.process_status,
+            argv: phase_result.argv.clone(),
+        }
+    }
+}
+
+impl From<PhaseResultRecord> for PhaseResult {
+    fn from(record: PhaseResultRecord) -> PhaseResult {
+        PhaseResult {
+            phase: record.phase,
+            duration: Duration::from_secs_f64(record.duration),
+            process_status: record.process_status,
+            argv: record.argv,
+        }
+    }
+}
+
+/// Read the mutants list and outcomes from an interrupted run in `output_dir`, and match
+/// them up with the mutants discovered in the current tree.
+pub fn load_resumed(output_dir: &Utf8Path, mutants: Vec<Mutant>) -> Result<Resumed> {
+    let mutants_path = output_dir.join(MUTANTS_JSON);
+    let listed: Vec<MutantKey> = serde_json::from_str(
+        &read_to_string(&mutants_path).with_context(|| format!("read {mutants_path}"))?,
+    )
+    .with_context(|| format!("parse {mutants_path}"))?;
+    // Outcomes are only written once the first scenario finishes.
+    let outcomes_path = output_dir.join("outcomes.json");
+    let records = if outcomes_path.is_file() {
+        serde_json::from_str::<LabOutcomeRecord>(
+            &read_to_string(&outcomes_path).with_context(|| format!("read {outcomes_path}"))?,
+        )
+        .with_context(|| format!("parse {outcomes_path}"))?
+        .outcomes
+    } else {
+        Vec::new()
+    };
+
+    let mut by_key: HashMap<MutantKey, Mutant> = mutants
+        .into_iter()
+        .map(|mutant| (MutantKey::of(&mutant), mutant))
+        .collect();
+    let listed = listed
+        .into_iter()
+        .map(|key| {
+            by_key.remove(&key).ok_or_else(|| {
+                anyhow!(
+                    "Mutant at {}:{} from the interrupted run isn't in the current tree: \
+                    the source or options may have changed, so start again without --resume",
+                    key.file,
+                    key.span.start.line,
+                )
+            })
+        })
+        .collect::<Result<Vec<Mutant>>>()?;
+
+    let positions: HashMap<MutantKey, usize> = listed
+        .iter()
+        .enumerate()
+        .map(|(i, mutant)| (MutantKey::of(mutant), i))
+        .collect();
+    let mut finished = Vec::new();
+    let mut finished_positions = HashSet::new();
+    for record in records {
+        let scenario = match record.scenario {
+            // A baseline that failed stopped the run, so it'll be tried again.
+            ScenarioRecord::Baseline if record.summary == SummaryOutcome::Success => {
+                Scenario::Baseline
+            }
+            ScenarioRecord::Baseline => continue,
+            // Mutants that weren't run, because coverage showed they're never executed,
+            // are classified again from the coverage.
+            ScenarioRecord::Mutant(_) if record.phase_results.is_empty() => continue,
+            ScenarioRecord::Mutant(key) => {
+                let i = *positions.get(&key).with_context(|| {
+                    format!(
+                        "Mutant at {}:{} in {outcomes_path} isn't in {MUTANTS_JSON}",
+                        key.file, key.span.start.line
+                    )
+                })?;
+                finished_positions.insert(i);
+                Scenario::Mutant(listed[i].clone())
+            }
+        };
+        finished.push(ScenarioOutcome::from_earlier_run(
+            output_dir,
+            record.log_path,
+            record.diff_path,
+            scenario,
+            record
+                .phase_results
+                .into_iter()
+                .map(PhaseResult::from)
+                .collect(),
+            record.summary == SummaryOutcome::Equivalent,
+        ));
+    }
+    let remaining = listed
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !finished_positions.contains(i))
+        .map(|(_, mutant)| mutant)
+        .collect();
+    Ok(Resumed {
+        remaining,
+        finished,
+    })
+}
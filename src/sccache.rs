@@ -0,0 +1,171 @@
+// Copyright 2025 Martin Pool
+
+//! Support for the [sccache](https://github.com/mozilla/sccache) compiler cache.
+//!
+//! With `--sccache`, cargo is run with `RUSTC_WRAPPER=sccache`. sccache is also detected
+//! if `RUSTC_WRAPPER` already names it. Either way, sccache is checked to be working
+//! before any builds start, since a wrapper that fails makes every build fail, and its
+//! statistics are compared at the start and end of the run to report how many
+//! compilations were cached.
+
+#![warn(clippy::pedantic)]
+
+use std::env;
+use std::process::Command;
+
+use anyhow::{bail, Context};
+use camino::Utf8Path;
+use serde_json::Value;
+use tracing::{debug, info, warn};
+
+use crate::{Options, Result};
+
+/// The name of the sccache program, found on the `PATH`.
+pub const SCCACHE: &str = "sccache";
+
+/// sccache in use for this run, with its statistics from the start of the run.
+#[derive(Debug)]
+pub struct Sccache {
+    program: String,
+    start_stats: CacheStats,
+}
+
+/// Counts of compilations found in and missing from the cache.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Sccache {
+    /// Find whether sccache is configured or already in use, and if so check that it
+    /// responds, and record its statistics at the start of the run.
+    pub fn start(options: &Options) -> Result<Option<Sccache>> {
+        let configured_wrapper = configured_wrapper(options);
+        let program = if options.sccache {
+            if let Some(wrapper) = configured_wrapper.filter(|w| !is_sccache(w)) {
+                warn!("RUSTC_WRAPPER={wrapper:?} is replaced by sccache because of --sccache");
+            }
+            SCCACHE.to_owned()
+        } else if let Some(wrapper) = configured_wrapper.filter(|w| is_sccache(w)) {
+            debug!(?wrapper, "detected sccache from RUSTC_WRAPPER");
+            wrapper
+        } else {
+            return Ok(None);
+        };
+        let start_stats = show_stats(&program).with_context(|| {
+            format!("sccache ({program}) isn't working: check that it's installed and on the PATH")
+        })?;
+        debug!(?start_stats, "sccache stats at start");
+        Ok(Some(Sccache {
+            program,
+            start_stats,
+        }))
+    }
+
+    /// Log how many compilations were found in the cache during the run.
+    pub fn finish(&self) {
+        let end_stats = match show_stats(&self.program) {
+            Ok(stats) => stats,
+            Err(err) => {
+                warn!("Failed to read sccache stats: {err:#}");
+                return;
+            }
+        };
+        let hits = end_stats.hits.saturating_sub(self.start_stats.hits);
+        let misses = end_stats.misses.saturating_sub(self.start_stats.misses);
+        if hits + misses == 0 {
+            warn!(
+                "sccache didn't cache any compilations: it can't cache incremental builds, \
+                so check that RUSTC_WRAPPER isn't overridden and that the profile doesn't use \
+                incremental compilation for dependencies"
+            );
+        } else {
+            #[allow(clippy::cast_precision_loss)]
+            let rate = hits as f64 * 100.0 / (hits + misses) as f64;
+            info!("sccache had {hits} cache hits and {misses} misses ({rate:.0}% hit rate)");
+        }
+    }
+}
+
+/// The `RUSTC_WRAPPER` set in the options' environment or our own, if any.
+fn configured_wrapper(options: &Options) -> Option<String> {
+    options
+        .env
+        .iter()
+        .rev()
+        .find(|(name, _)| name == "RUSTC_WRAPPER")
+        .map(|(_, value)| value.clone())
+        .or_else(|| env::var("RUSTC_WRAPPER").ok())
+        .filter(|wrapper| !wrapper.is_empty())
+}
+
+/// True if a `RUSTC_WRAPPER` value names sccache.
+fn is_sccache(wrapper: &str) -> bool {
+    Utf8Path::new(wrapper).file_stem() == Some(SCCACHE)
+}
+
+/// Ask sccache for its statistics, which also starts its server if it's not running.
+fn show_stats(program: &str) -> Result<CacheStats> {
+    let output = Command::new(program)
+        .args(["--show-stats", "--stats-format=json"])
+        .output()
+        .with_context(|| format!("Failed to run {program}"))?;
+    if !output.status.success() {
+        bail!(
+            "{program} --show-stats failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    parse_stats(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Count the cache hits and misses for all languages in sccache's JSON statistics.
+fn parse_stats(json: &str) -> Result<CacheStats> {
+    let value: Value = serde_json::from_str(json).context("parse sccache stats")?;
+    let stats = &value["stats"];
+    let total = |field: &str| -> u64 {
+        stats[field]["counts"]
+            .as_object()
+            .map(|counts| counts.values().filter_map(Value::as_u64).sum())
+            .unwrap_or_default()
+    };
+    Ok(CacheStats {
+        hits: total("cache_hits"),
+        misses: total("cache_misses"),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_sccache_stats() {
+        let json = r#"{
+            "stats": {
+                "compile_requests": 12,
+                "cache_hits": {"counts": {"Rust": 7, "C/C++": 1}, "adv_counts": {}},
+                "cache_misses": {"counts": {"Rust": 3}, "adv_counts": {}}
+            },
+            "cache_location": "Local disk: \"/home/user/.cache/sccache\""
+        }"#;
+        assert_eq!(
+            parse_stats(json).unwrap(),
+            CacheStats { hits: 8, misses: 3 }
+        );
+        assert_eq!(
+            parse_stats(r#"{"stats": {"cache_hits": {"counts": {}}}}"#).unwrap(),
+            CacheStats::default()
+        );
+        assert!(parse_stats("not json").is_err());
+    }
+
+    #[test]
+    fn recognize_sccache_wrapper() {
+        assert!(is_sccache("sccache"));
+        assert!(is_sccache("/usr/local/bin/sccache"));
+        assert!(is_sccache("sccache.exe"));
+        assert!(!is_sccache("/usr/bin/ccache"));
+    }
+}
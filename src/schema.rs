@@ -0,0 +1,301 @@
+// Copyright 2025 Martin Pool
+
+//! JSON Schemas for the JSON that cargo-mutants writes, printed by `cargo mutants schema`.
+//!
+//! The schemas describe `outcomes.json`, and the lists of mutants in `mutants.json` and
+//! from `--list --json`. Tools that read these files can check [`SCHEMA_VERSION`], which
+//! is written into `outcomes.json`.
+//!
+//! Fields may be added without changing the version, so readers should ignore fields
+//! they don't know. Removing, renaming, or changing the meaning of a field increments
+//! the version.
+
+#![warn(clippy::pedantic)]
+
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+use strum::VariantNames;
+
+use crate::mutant::Genre;
+
+/// The version of the structure of the JSON output.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Which JSON output to describe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaName {
+    /// `mutants.out/outcomes.json`.
+    Outcomes,
+    /// `mutants.out/mutants.json`, and the output of `--list --json`.
+    Mutants,
+}
+
+/// Return the JSON Schema for one of the JSON outputs.
+pub fn schema(name: SchemaName) -> Value {
+    let (id, title, root) = match name {
+        SchemaName::Outcomes => (
+            "outcomes",
+            "cargo-mutants outcomes",
+            json!({
+                "type": "object",
+                "required": [
+                    "schema_version", "outcomes", "total_mutants", "missed", "caught",
+                    "timeout", "unviable", "success", "equivalent", "uncovered"
+                ],
+                "properties": {
+                    "schema_version": {
+                        "const": SCHEMA_VERSION,
+                        "description": "The version of this structure."
+                    },
+                    "outcomes": {
+                        "type": "array",
+                        "description": "The outcome of the baseline and of each mutant, in the order they finished.",
+                        "items": { "$ref": "#/$defs/ScenarioOutcome" }
+                    },
+                    "total_mutants": count("The number of mutants tested so far."),
+                    "missed": count("Mutants that were not caught by any test."),
+                    "caught": count("Mutants that were caught by a test."),
+                    "timeout": count("Mutants whose build or tests exceeded the timeout."),
+                    "unviable": count("Mutants that failed to build."),
+                    "success": count("Mutants that built successfully, with `--check` or with no tests run."),
+                    "equivalent": count("Mutants that built to the same binaries as the unmutated tree, with `--detect-equivalent`."),
+                    "uncovered": count("Missed mutants that weren't tested because `--coverage` showed that no tests execute them; these are also counted as missed."),
+                    "scores": {
+                        "type": "object",
+                        "description": "Counts and scores for each package, module, and file that has mutants.",
+                        "required": ["packages", "modules", "files"],
+                        "properties": {
+                            "packages": tallies("By package name."),
+                            "modules": tallies("By module path, like `mycrate::foo`, from the path of the source file."),
+                            "files": tallies("By source file, relative to the workspace root.")
+                        }
+                    }
+                }
+            }),
+        ),
+        SchemaName::Mutants => (
+            "mutants",
+            "cargo-mutants list of mutants",
+            json!({
+                "type": "array",
+                "items": { "$ref": "#/$defs/Mutant" }
+            }),
+        ),
+    };
+    let mut schema = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": format!("https://mutants.rs/schema/v{SCHEMA_VERSION}/{id}.json"),
+        "title": title,
+    });
+    let object = schema.as_object_mut().expect("schema is an object");
+    object.extend(root.as_object().expect("root schema is an object").clone());
+    let mut definitions = mutant_definitions();
+    definitions.extend(outcome_definitions());
+    object.insert("$defs".to_owned(), definitions.into());
+    schema
+}
+
+fn count(description: &str) -> Value {
+    json!({ "type": "integer", "minimum": 0, "description": description })
+}
+
+fn tallies(description: &str) -> Value {
+    json!({
+        "type": "object",
+        "description": description,
+        "additionalProperties": { "$ref": "#/$defs/Tally" }
+    })
+}
+
+/// Definitions of a mutant and its parts, used by all the schemas.
+fn mutant_definitions() -> Map<String, Value> {
+    let Value::Object(definitions) = json!({
+        "LineColumn": {
+            "type": "object",
+            "required": ["line", "column"],
+            "properties": {
+                "line": { "type": "integer", "minimum": 1, "description": "1-based line number." },
+                "column": { "type": "integer", "minimum": 1, "description": "1-based column, measured in chars." }
+            }
+        },
+        "Span": {
+            "type": "object",
+            "description": "A range of source code, from the start up to but not including the end.",
+            "required": ["start", "end"],
+            "properties": {
+                "start": { "$ref": "#/$defs/LineColumn" },
+                "end": { "$ref": "#/$defs/LineColumn" }
+            }
+        },
+        "ByteRange": {
+            "type": "object",
+            "description": "Byte offsets in the source file, from the start up to but not including the end, with line endings normalized to `\\n`.",
+            "required": ["start", "end"],
+            "properties": {
+                "start": { "type": "integer", "minimum": 0 },
+                "end": { "type": "integer", "minimum": 0 }
+            }
+        },
+        "Function": {
+            "type": "object",
+            "description": "The function containing a mutant.",
+            "required": ["function_name", "return_type", "span"],
+            "properties": {
+                "function_name": {
+                    "type": "string",
+                    "description": "The name of the function, including any containing modules, types, or traits."
+                },
+                "return_type": {
+                    "type": "string",
+                    "description": "The return type, including a leading `-> `, or empty if the function returns `()`."
+                },
+                "span": { "$ref": "#/$defs/Span" }
+            }
+        },
+        "Mutant": {
+            "type": "object",
+            "required": ["package", "file", "module_path", "function", "span", "byte_span", "lines", "replacement", "genre"],
+            "properties": {
+                "package": { "type": "string", "description": "The name of the package containing the mutant." },
+                "file": { "type": "string", "description": "The source file, relative to the workspace root, with `/` separators." },
+                "module_path": {
+                    "type": "string",
+                    "description": "The module containing the mutant, like `mycrate::foo::tests`, including inline `mod` blocks."
+                },
+                "function": {
+                    "oneOf": [{ "$ref": "#/$defs/Function" }, { "type": "null" }]
+                },
+                "span": { "$ref": "#/$defs/Span", "description": "The code that's replaced." },
+                "byte_span": { "$ref": "#/$defs/ByteRange", "description": "The code that's replaced, as byte offsets." },
+                "lines": { "type": "integer", "minimum": 1, "description": "The number of source lines touched by the replaced code." },
+                "replacement": { "type": "string", "description": "The code that's substituted." },
+                "genre": { "enum": Genre::VARIANTS },
+                "expanded_from": {
+                    "type": "string",
+                    "description": "If the mutant is in code expanded from a macro, the name of the macro."
+                },
+                "diff": {
+                    "type": "string",
+                    "description": "A diff of the mutation, in the output of `--list --json --diff`."
+                }
+            }
+        }
+    }) else {
+        unreachable!()
+    };
+    definitions
+}
+
+/// Definitions of the outcome of testing a scenario.
+fn outcome_definitions() -> Map<String, Value> {
+    let Value::Object(definitions) = json!({
+        "Scenario": {
+            "oneOf": [
+                { "const": "Baseline", "description": "The unmutated tree." },
+                {
+                    "type": "object",
+                    "required": ["Mutant"],
+                    "properties": { "Mutant": { "$ref": "#/$defs/Mutant" } }
+                }
+            ]
+        },
+        "Exit": {
+            "description": "How a child process finished.",
+            "oneOf": [
+                { "enum": ["Success", "Timeout", "Other"] },
+                {
+                    "type": "object",
+                    "required": ["Failure"],
+                    "properties": { "Failure": { "type": "integer", "description": "The exit code." } }
+                },
+                {
+                    "type": "object",
+                    "required": ["Signalled"],
+                    "properties": { "Signalled": { "type": "integer", "description": "The signal that killed the process." } }
+                }
+            ]
+        },
+        "PhaseResult": {
+            "type": "object",
+            "required": ["phase", "duration", "process_status", "argv"],
+            "properties": {
+                "phase": { "enum": ["Check", "Build", "Test"] },
+                "duration": { "type": "number", "minimum": 0, "description": "Seconds taken." },
+                "process_status": { "$ref": "#/$defs/Exit" },
+                "argv": { "type": "array", "items": { "type": "string" } }
+            }
+        },
+        "Tally": {
+            "type": "object",
+            "required": ["total", "caught", "missed", "timeout", "unviable", "equivalent", "score"],
+            "properties": {
+                "total": count("All the mutants tested."),
+                "caught": count("Mutants caught by a test."),
+                "missed": count("Mutants not caught by any test."),
+                "timeout": count("Mutants that exceeded the timeout."),
+                "unviable": count("Mutants that failed to build."),
+                "equivalent": count("Mutants equivalent to the unmutated tree."),
+                "score": {
+                    "type": ["number", "null"],
+                    "description": "The percentage of caught, missed, and timed out mutants that were caught, or null if there were none."
+                }
+            }
+        },
+        "ScenarioOutcome": {
+            "type": "object",
+            "required": ["scenario", "summary", "log_path", "diff_path", "phase_results"],
+            "properties": {
+                "scenario": { "$ref": "#/$defs/Scenario" },
+                "summary": {
+                    "enum": [
+                        "Success", "CaughtMutant", "MissedMutant", "Unviable", "Equivalent",
+                        "Failure", "Timeout"
+                    ]
+                },
+                "log_path": { "type": "string", "description": "The log file, relative to `mutants.out`." },
+                "diff_path": {
+                    "type": ["string", "null"],
+                    "description": "The diff of a mutant, relative to `mutants.out`."
+                },
+                "phase_results": { "type": "array", "items": { "$ref": "#/$defs/PhaseResult" } }
+            }
+        }
+    }) else {
+        unreachable!()
+    };
+    definitions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn outcomes_schema_has_version_and_definitions() {
+        let schema = schema(SchemaName::Outcomes);
+        assert_eq!(
+            schema["$id"],
+            format!("https://mutants.rs/schema/v{SCHEMA_VERSION}/outcomes.json")
+        );
+        assert_eq!(schema["type"], "object");
+        assert_eq!(
+            schema["properties"]["schema_version"]["const"],
+            SCHEMA_VERSION
+        );
+        assert_eq!(
+            schema["$defs"]["ScenarioOutcome"]["properties"]["scenario"]["$ref"],
+            "#/$defs/Scenario"
+        );
+    }
+
+    #[test]
+    fn mutants_schema_lists_all_genres() {
+        let schema = schema(SchemaName::Mutants);
+        assert_eq!(schema["items"]["$ref"], "#/$defs/Mutant");
+        let genres = schema["$defs"]["Mutant"]["properties"]["genre"]["enum"]
+            .as_array()
+            .unwrap();
+        assert_eq!(genres.len(), Genre::VARIANTS.len());
+        assert!(genres.contains(&json!("FnValue")));
+    }
+}
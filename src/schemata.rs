@@ -0,0 +1,225 @@
+// Copyright 2025 Martin Pool
+
+//! Mutant schemata: build many mutants of a file at once, for `--schemata`.
+//!
+//! Each mutant in a schema replaces its span with an `if` that checks the
+//! `CARGO_MUTANTS_ACTIVE_MUTANT` environment variable at runtime, choosing between the
+//! replacement and the original code. The tree is built once with all of them, and then
+//! the tests are run once for each mutant with the variable set to its number, without
+//! rebuilding.
+//!
+//! Only mutants whose span is a whole expression or function body can be guarded this
+//! way, and mutants in one schema mustn't overlap, except that mutants of the same span
+//! are chained. Other mutants are tested one at a time as usual.
+//!
+//! If any mutant in the schema doesn't build, the build fails for all of them. Then the
+//! schema is split in half and each half is tried again, until the mutants that don't
+//! build are tested on their own.
+
+#![warn(clippy::pedantic)]
+
+use std::fmt::Write;
+
+use crate::mutant::Genre;
+use crate::output::clean_filename;
+use crate::span::Span;
+use crate::{Mutant, MUTATION_MARKER_COMMENT};
+
+/// The environment variable that selects which mutant in a schema is active.
+pub const ACTIVE_MUTANT_ENV: &str = "CARGO_MUTANTS_ACTIVE_MUTANT";
+
+/// Mutants of one file that are built together.
+///
+/// Mutants are numbered from 1 in the order they're listed.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    pub mutants: Vec<Mutant>,
+}
+
+impl Schema {
+    /// The source code of the file with all the mutants guarded by runtime checks.
+    pub fn code(&self) -> String {
+        let original = self.mutants[0].source_file.code();
+        // Mutants of the same span share one chain of `if`s.
+        let mut sites: Vec<(Span, Vec<(usize, &Mutant)>)> = Vec::new();
+        for (i, mutant) in self.mutants.iter().enumerate() {
+            let id = i + 1;
+            match sites.iter_mut().find(|(span, _)| *span == mutant.span) {
+                Some((_, site_mutants)) => site_mutants.push((id, mutant)),
+                None => sites.push((mutant.span, vec![(id, mutant)])),
+            }
+        }
+        let mut sites = sites
+            .into_iter()
+            .map(|(span, site_mutants)| (span.byte_range(original), site_mutants))
+            .collect::<Vec<_>>();
+        // Replace from the end, so that earlier byte ranges stay valid.
+        sites.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        let mut code = original.to_owned();
+        for (range, site_mutants) in sites {
+            let mut guarded = String::new();
+            for (id, mutant) in site_mutants {
+                write!(
+                    guarded,
+                    "if ::std::env::var_os({ACTIVE_MUTANT_ENV:?}).map_or(false, |v| v == \"{id}\") \
+                    {{ {} {MUTATION_MARKER_COMMENT} }} else ",
+                    mutant.replacement
+                )
+                .unwrap();
+            }
+            write!(guarded, "{{ {} }}", &original[range.start..range.end]).unwrap();
+            code.replace_range(range.start..range.end, &guarded);
+        }
+        code
+    }
+
+    /// Split the schema into two halves, to find which mutants stop it building.
+    pub fn split(mut self) -> (Schema, Schema) {
+        let second = self.mutants.split_off(self.mutants.len() / 2);
+        (self, Schema { mutants: second })
+    }
+
+    /// A name for the log of building the schema.
+    pub fn log_name(&self) -> String {
+        format!(
+            "schema_{}",
+            clean_filename(&self.mutants[0].source_file.tree_relative_slashes())
+        )
+    }
+
+    /// True if the mutant can be built as part of this schema.
+    fn can_add(&self, mutant: &Mutant) -> bool {
+        let first = &self.mutants[0];
+        can_share_build(first)
+            && first.source_file.tree_relative_path == mutant.source_file.tree_relative_path
+            && self
+                .mutants
+                .iter()
+                .all(|other| other.span == mutant.span || !overlaps(&other.span, &mutant.span))
+    }
+}
+
+/// Group mutants into schemata of non-overlapping mutants in the same file.
+///
+/// Mutants that can't be guarded by a runtime check are each in a schema of their own,
+/// and are tested as usual. Schemata are in the order of their first mutant.
+pub fn plan_schemata(mutants: Vec<Mutant>) -> Vec<Schema> {
+    let mut schemata: Vec<Schema> = Vec::new();
+    for mutant in mutants {
+        if can_share_build(&mutant) {
+            if let Some(schema) = schemata.iter_mut().find(|schema| schema.can_add(&mutant)) {
+                schema.mutants.push(mutant);
+                continue;
+            }
+        }
+        schemata.push(Schema {
+            mutants: vec![mutant],
+        });
+    }
+    schemata
+}
+
+/// True if the mutant replaces a whole expression or function body, so that the
+/// original and the replacement can be chosen between by an `if` expression.
+fn can_share_build(mutant: &Mutant) -> bool {
+    mutant.expansion.is_none()
+        && matches!(
+            mutant.genre,
+            Genre::FnValue | Genre::ClosureValue | Genre::MatchArmGuard | Genre::NegateCondition
+        )
+}
+
+fn overlaps(a: &Span, b: &Span) -> bool {
+    let position = |lc: crate::span::LineColumn| (lc.line, lc.column);
+    position(a.start) < position(b.end) && position(b.start) < position(a.end)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::visit::mutate_source_str;
+    use crate::Options;
+
+    fn example_mutants() -> Vec<Mutant> {
+        mutate_source_str(
+            indoc! {"
+                fn is_big(x: u32) -> bool {
+                    if x > 10 {
+                        return true;
+                    }
+                    false
+                }
+
+                fn double(x: u32) -> u32 {
+                    x * 2
+                }
+            "},
+            &Options::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn nested_mutants_go_in_separate_schemata() {
+        let schemata = plan_schemata(example_mutants());
+        let genres = schemata
+            .iter()
+            .map(|schema| schema.mutants.iter().map(|m| m.genre).dedup().collect_vec())
+            .collect_vec();
+        assert_eq!(
+            genres,
+            [
+                vec![Genre::FnValue],
+                vec![Genre::NegateCondition],
+                vec![Genre::EarlyReturn],
+                vec![Genre::BinaryOperator],
+                vec![Genre::BinaryOperator],
+                vec![Genre::BinaryOperator],
+                vec![Genre::BinaryOperator],
+                vec![Genre::BinaryOperator],
+            ]
+        );
+        // Both functions' values are in the first schema.
+        assert_eq!(schemata[0].mutants.len(), 4);
+    }
+
+    #[test]
+    fn schema_code_guards_each_mutant() {
+        let schemata = plan_schemata(example_mutants());
+        let code = schemata[1].code();
+        assert_eq!(
+            code,
+            indoc! {r#"
+                fn is_big(x: u32) -> bool {
+                    if if ::std::env::var_os("CARGO_MUTANTS_ACTIVE_MUTANT").map_or(false, |v| v == "1") { !(x > 10) /* ~ changed by cargo-mutants ~ */ } else { x > 10 } {
+                        return true;
+                    }
+                    false
+                }
+
+                fn double(x: u32) -> u32 {
+                    x * 2
+                }
+            "#}
+        );
+    }
+
+    #[test]
+    fn split_schema_keeps_all_mutants() {
+        let schema = plan_schemata(example_mutants()).swap_remove(0);
+        let names = schema.mutants.iter().map(|m| m.name(true)).collect_vec();
+        let (a, b) = schema.split();
+        assert_eq!(a.mutants.len(), 2);
+        assert_eq!(
+            a.mutants
+                .iter()
+                .chain(&b.mutants)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            names
+        );
+    }
+}
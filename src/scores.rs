@@ -0,0 +1,335 @@
+// Copyright 2025 Martin Pool
+
+//! Mutation scores for the parts of the tree: each package, module, and file.
+//!
+//! Modules are named from the path of the file that contains them, so mutants in an
+//! inline `mod` block count towards the module of the file.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use serde::ser::{SerializeStruct, Serializer};
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::exit_code;
+use crate::options::{Options, ScoreAs};
+use crate::outcome::{LabOutcome, SummaryOutcome};
+
+/// The number of parts listed at each level in the console summary.
+const MAX_LISTED: usize = 5;
+
+/// Counts of mutants by outcome.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Tally {
+    pub total: usize,
+    pub caught: usize,
+    pub missed: usize,
+    pub timeout: usize,
+    pub unviable: usize,
+    pub equivalent: usize,
+}
+
+impl Tally {
+    /// Count one more mutant.
+    pub fn add(&mut self, outcome: &SummaryOutcome) {
+        self.total += 1;
+        match outcome {
+            SummaryOutcome::CaughtMutant => self.caught += 1,
+            SummaryOutcome::MissedMutant => self.missed += 1,
+            SummaryOutcome::Timeout => self.timeout += 1,
+            SummaryOutcome::Unviable => self.unviable += 1,
+            SummaryOutcome::Equivalent => self.equivalent += 1,
+            SummaryOutcome::Success | SummaryOutcome::Failure => (),
+        }
+    }
+
+    /// The number of mutants that built, and so could be caught.
+    pub fn viable(&self) -> usize {
+        self.caught + self.missed + self.timeout
+    }
+
+    /// The mutation score: the percentage of viable mutants that were caught.
+    ///
+    /// Timeouts count against the score, because they need attention just like missed
+    /// mutants. Unviable and equivalent mutants aren't counted. Returns None if there
+    /// were no viable mutants.
+    pub fn score(&self) -> Option<f64> {
+        self.score_counting(ScoreAs::Missed, ScoreAs::Excluded)
+    }
+
+    /// The mutation score, counting timeouts and unviable mutants as caught, missed, or
+    /// neither.
+    pub fn score_counting(&self, timeouts: ScoreAs, unviable: ScoreAs) -> Option<f64> {
+        let mut caught = self.caught;
+        let mut counted = self.caught + self.missed;
+        for (n, score_as) in [(self.timeout, timeouts), (self.unviable, unviable)] {
+            match score_as {
+                ScoreAs::Caught => {
+                    caught += n;
+                    counted += n;
+                }
+                ScoreAs::Missed => counted += n,
+                ScoreAs::Excluded => (),
+            }
+        }
+        #[allow(clippy::cast_precision_loss)]
+        (counted > 0).then(|| caught as f64 * 100.0 / counted as f64)
+    }
+}
+
+impl From<&LabOutcome> for Tally {
+    fn from(lab_outcome: &LabOutcome) -> Tally {
+        Tally {
+            total: lab_outcome.total_mutants,
+            caught: lab_outcome.caught,
+            missed: lab_outcome.missed,
+            timeout: lab_outcome.timeout,
+            unviable: lab_outcome.unviable,
+            equivalent: lab_outcome.equivalent,
+        }
+    }
+}
+
+impl Serialize for Tally {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut ss = serializer.serialize_struct("Tally", 7)?;
+        ss.serialize_field("total", &self.total)?;
+        ss.serialize_field("caught", &self.caught)?;
+        ss.serialize_field("missed", &self.missed)?;
+        ss.serialize_field("timeout", &self.timeout)?;
+        ss.serialize_field("unviable", &self.unviable)?;
+        ss.serialize_field("equivalent", &self.equivalent)?;
+        ss.serialize_field("score", &self.score())?;
+        ss.end()
+    }
+}
+
+/// Format a mutation score, rounded down so that a score of 100% means all mutants were caught.
+pub fn format_score(score: Option<f64>) -> String {
+    score.map_or_else(
+        || "n/a".to_owned(),
+        |score| format!("{:.1}%", (score * 10.0).floor() / 10.0),
+    )
+}
+
+/// Return the exit code for a run with `--minimum-score`, given the code it would
+/// otherwise have.
+///
+/// If the score meets the minimum, missed mutants and timeouts don't fail the run. If
+/// there's no minimum, or the run failed for some other reason, the code is unchanged.
+pub fn apply_minimum_score(lab_outcome: &LabOutcome, options: &Options, code: i32) -> i32 {
+    let Some(minimum) = options.minimum_score else {
+        return code;
+    };
+    if ![
+        exit_code::SUCCESS,
+        exit_code::FOUND_PROBLEMS,
+        exit_code::TIMEOUT,
+    ]
+    .contains(&code)
+    {
+        return code;
+    }
+    let score = Tally::from(lab_outcome).score_counting(
+        options.score_timeouts.unwrap_or(ScoreAs::Missed),
+        options.score_unviable.unwrap_or(ScoreAs::Excluded),
+    );
+    match score {
+        Some(score) if score < minimum => {
+            warn!(
+                "Mutation score {} is below the minimum of {minimum}%",
+                format_score(Some(score))
+            );
+            exit_code::SCORE_BELOW_MINIMUM
+        }
+        _ => {
+            info!(
+                "Mutation score {} meets the minimum of {minimum}%",
+                format_score(score)
+            );
+            exit_code::SUCCESS
+        }
+    }
+}
+
+/// Tallies of mutants in each package, module, and file.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
+pub struct Scores {
+    pub packages: BTreeMap<String, Tally>,
+    pub modules: BTreeMap<String, Tally>,
+    pub files: BTreeMap<String, Tally>,
+}
+
+impl Scores {
+    pub fn new(lab_outcome: &LabOutcome) -> Scores {
+        let mut scores = Scores::default();
+        for outcome in &lab_outcome.outcomes {
+            let Some(mutant) = outcome.scenario.mutant() else {
+                continue;
+            };
+            let summary = outcome.summary();
+            let file = mutant.source_file.tree_relative_slashes();
+            let module = mutant.source_file.module_path();
+            scores
+                .packages
+                .entry(mutant.source_file.package.name.clone())
+                .or_default()
+                .add(&summary);
+            scores.modules.entry(module).or_default().add(&summary);
+            scores.files.entry(file).or_default().add(&summary);
+        }
+        scores
+    }
+
+    /// Describe the packages, modules, and files with the lowest scores, for the console.
+    ///
+    /// Levels with only one part are left out, because its score is the same as the
+    /// overall score. Returns an empty string if every part has a perfect score.
+    pub fn lowest_summary(&self) -> String {
+        let mut s = String::new();
+        for (level, tallies) in [
+            ("package", &self.packages),
+            ("module", &self.modules),
+            ("file", &self.files),
+        ] {
+            if tallies.len() < 2 {
+                continue;
+            }
+            let mut imperfect: Vec<(&String, &Tally, f64)> = tallies
+                .iter()
+                .filter_map(|(name, tally)| Some((name, tally, tally.score()?)))
+                .filter(|(_, tally, _)| tally.caught < tally.viable())
+                .collect();
+            if imperfect.is_empty() {
+                continue;
+            }
+            imperfect.sort_by(|a, b| a.2.total_cmp(&b.2).then_with(|| a.0.cmp(b.0)));
+            writeln!(s, "Lowest scores by {level}:").unwrap();
+            for (name, tally, score) in imperfect.into_iter().take(MAX_LISTED) {
+                writeln!(
+                    s,
+                    "  {:>6}  {name} ({} of {} caught)",
+                    format_score(Some(score)),
+                    tally.caught,
+                    tally.viable()
+                )
+                .unwrap();
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn tally_score_and_json() {
+        let mut tally = Tally::default();
+        for outcome in [
+            SummaryOutcome::CaughtMutant,
+            SummaryOutcome::CaughtMutant,
+            SummaryOutcome::MissedMutant,
+            SummaryOutcome::Timeout,
+            SummaryOutcome::Unviable,
+        ] {
+            tally.add(&outcome);
+        }
+        assert_eq!(format_score(tally.score()), "50.0%");
+        assert_eq!(
+            serde_json::to_value(&tally).unwrap(),
+            serde_json::json!({
+                "total": 5, "caught": 2, "missed": 1, "timeout": 1, "unviable": 1,
+                "equivalent": 0, "score": 50.0
+            })
+        );
+        assert_eq!(format_score(Tally::default().score()), "n/a");
+    }
+
+    #[test]
+    fn score_counting_timeouts_and_unviable() {
+        let tally = Tally {
+            total: 10,
+            caught: 4,
+            missed: 2,
+            timeout: 2,
+            unviable: 2,
+            equivalent: 0,
+        };
+        let score = |timeouts, unviable| format_score(tally.score_counting(timeouts, unviable));
+        assert_eq!(score(ScoreAs::Missed, ScoreAs::Excluded), "50.0%");
+        assert_eq!(score(ScoreAs::Excluded, ScoreAs::Excluded), "66.6%");
+        assert_eq!(score(ScoreAs::Caught, ScoreAs::Caught), "80.0%");
+        assert_eq!(score(ScoreAs::Missed, ScoreAs::Missed), "40.0%");
+    }
+
+    #[test]
+    fn minimum_score_decides_exit_code() {
+        let lab_outcome = LabOutcome {
+            total_mutants: 4,
+            caught: 3,
+            missed: 1,
+            ..LabOutcome::default()
+        };
+        let mut options = Options::default();
+        assert_eq!(
+            apply_minimum_score(&lab_outcome, &options, exit_code::FOUND_PROBLEMS),
+            exit_code::FOUND_PROBLEMS
+        );
+        options.minimum_score = Some(75.0);
+        assert_eq!(
+            apply_minimum_score(&lab_outcome, &options, exit_code::FOUND_PROBLEMS),
+            exit_code::SUCCESS
+        );
+        assert_eq!(
+            apply_minimum_score(&lab_outcome, &options, exit_code::BASELINE_FAILED),
+            exit_code::BASELINE_FAILED
+        );
+        options.minimum_score = Some(80.0);
+        assert_eq!(
+            apply_minimum_score(&lab_outcome, &options, exit_code::FOUND_PROBLEMS),
+            exit_code::SCORE_BELOW_MINIMUM
+        );
+    }
+
+    #[test]
+    fn lowest_summary_skips_single_parts_and_perfect_scores() {
+        let tally = |caught, missed| Tally {
+            total: caught + missed,
+            caught,
+            missed,
+            ..Tally::default()
+        };
+        let scores = Scores {
+            packages: BTreeMap::from([("small".to_owned(), tally(3, 3))]),
+            modules: BTreeMap::from([
+                ("small".to_owned(), tally(1, 2)),
+                ("small::a".to_owned(), tally(2, 1)),
+                ("small::b".to_owned(), tally(0, 0)),
+            ]),
+            files: BTreeMap::from([
+                ("src/lib.rs".to_owned(), tally(1, 2)),
+                ("src/a.rs".to_owned(), tally(2, 0)),
+            ]),
+        };
+        assert_eq!(
+            scores.lowest_summary(),
+            indoc! { "
+                Lowest scores by module:
+                   33.3%  small (1 of 3 caught)
+                   66.6%  small::a (2 of 3 caught)
+                Lowest scores by file:
+                   33.3%  src/lib.rs (1 of 3 caught)
+            " }
+        );
+    }
+}
@@ -0,0 +1,133 @@
+// Copyright 2025 Martin Pool
+
+//! Put build directories in a scratch directory, such as a ramdisk, with `--scratch-dir`.
+//!
+//! Copying the tree and compiling it is partly bound by disk I/O, so on machines with slow
+//! disks it can help to build on a tmpfs like `/dev/shm`. Such filesystems are usually
+//! small, so before anything is copied the scratch directory is checked to be writable and
+//! to have room for all the build directories. If it doesn't, a warning is shown and the
+//! build directories go in the usual temporary directory instead.
+
+#![warn(clippy::pedantic)]
+
+use camino::{Utf8Path, Utf8PathBuf};
+use tracing::{debug, info, warn};
+
+use crate::console::format_mb;
+use crate::copy_tree::walk_tree;
+use crate::Options;
+
+/// The scratch directory to make build directories in, if one is configured and usable.
+///
+/// Each build directory is estimated to need as much space as the source tree, including
+/// any `target` directory in it, and there's one for each job.
+pub fn choose_scratch_dir(source: &Utf8Path, options: &Options) -> Option<Utf8PathBuf> {
+    let scratch_dir = options.scratch_dir.as_ref()?;
+    if options.in_place {
+        warn!("--scratch-dir has no effect with --in-place");
+        return None;
+    }
+    if let Err(err) = tempfile::Builder::new()
+        .prefix("cargo-mutants-check-")
+        .tempdir_in(scratch_dir)
+    {
+        warn!("Can't use scratch directory {scratch_dir}: {err}; using the default temporary directory");
+        return None;
+    }
+    let available = match fs2::available_space(scratch_dir) {
+        Ok(available) => available,
+        Err(err) => {
+            warn!("Can't find free space in scratch directory {scratch_dir}: {err}; using the default temporary directory");
+            return None;
+        }
+    };
+    let n_dirs = options.jobs.unwrap_or(1).max(1) as u64;
+    let needed = tree_size(source, options) * n_dirs;
+    debug!(%scratch_dir, available, needed, n_dirs, "checked scratch dir");
+    if needed > available {
+        warn!(
+            "Scratch directory {scratch_dir} has {} free, but {n_dirs} build directories may need about {}; using the default temporary directory",
+            format_mb(available),
+            format_mb(needed)
+        );
+        return None;
+    }
+    info!("Making build directories in {scratch_dir}");
+    Some(scratch_dir.clone())
+}
+
+/// The total size of the files that would be copied from a source tree, not following symlinks.
+///
+/// This skips the same files as [`walk_tree`], so a large VCS or ignored directory doesn't
+/// count against the scratch directory.
+fn tree_size(path: &Utf8Path, options: &Options) -> u64 {
+    walk_tree(path, options)
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(std::fs::Metadata::is_file)
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{create_dir, write};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn tree_size_counts_nested_files() {
+        let tmp = TempDir::new().unwrap();
+        let tree = Utf8Path::from_path(tmp.path()).unwrap();
+        let options = Options::default();
+        write(tree.join("a"), [0; 100]).unwrap();
+        create_dir(tree.join("sub")).unwrap();
+        write(tree.join("sub/b"), [0; 50]).unwrap();
+        assert_eq!(tree_size(tree, &options), 150);
+        assert_eq!(tree_size(&tree.join("missing"), &options), 0);
+    }
+
+    #[test]
+    fn tree_size_skips_excluded_dirs() {
+        let tmp = TempDir::new().unwrap();
+        let tree = Utf8Path::from_path(tmp.path()).unwrap();
+        write(tree.join("a"), [0; 100]).unwrap();
+        create_dir(tree.join(".git")).unwrap();
+        write(tree.join(".git/pack"), vec![0; 1_000_000]).unwrap();
+        create_dir(tree.join("mutants.out")).unwrap();
+        write(tree.join("mutants.out/log"), [0; 500]).unwrap();
+        assert_eq!(tree_size(tree, &Options::default()), 100);
+        let options = Options {
+            copy_vcs: true,
+            ..Default::default()
+        };
+        assert_eq!(tree_size(tree, &options), 1_000_100);
+    }
+
+    #[test]
+    fn unusable_scratch_dir_falls_back_to_default() {
+        let source = TempDir::new().unwrap();
+        let source = Utf8Path::from_path(source.path()).unwrap();
+        let options = Options::default();
+        assert_eq!(choose_scratch_dir(source, &options), None);
+
+        let scratch = TempDir::new().unwrap();
+        let scratch = Utf8Path::from_path(scratch.path()).unwrap();
+        let options = Options {
+            scratch_dir: Some(scratch.to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(
+            choose_scratch_dir(source, &options).as_deref(),
+            Some(scratch)
+        );
+
+        let options = Options {
+            scratch_dir: Some(scratch.join("missing")),
+            ..Default::default()
+        };
+        assert_eq!(choose_scratch_dir(source, &options), None);
+    }
+}
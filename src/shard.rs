@@ -1,11 +1,19 @@
-// Copyright 2023 Martin Pool
+// Copyright 2023-2025 Martin Pool
 
 //! Sharding parameters.
+//!
+//! Mutants are assigned to shards by a hash of their name, without the line and column,
+//! rather than by their position in the list. So, adding or changing code only moves
+//! the mutants in that code, and the other mutants stay in the same shard from run to
+//! run, which keeps the outcomes from earlier runs of each shard useful.
 
 use std::str::FromStr;
 
 use anyhow::{anyhow, ensure, Context, Error};
 
+use crate::hash::{fmix64, fnv1a};
+use crate::Mutant;
+
 /// Select mutants for a particular shard of the total list.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 pub struct Shard {
@@ -17,13 +25,29 @@ pub struct Shard {
 
 impl Shard {
     /// Select the mutants that should be run for this shard.
-    pub fn select<M, I: IntoIterator<Item = M>>(&self, mutants: I) -> Vec<M> {
+    pub fn select(&self, mutants: Vec<Mutant>) -> Vec<Mutant> {
         mutants
             .into_iter()
-            .enumerate()
-            .filter_map(|(i, m)| if i % self.n == self.k { Some(m) } else { None })
+            .filter(|mutant| shard_index(mutant, self.n) == self.k)
             .collect()
     }
+
+    /// Count the mutants in each of the `n` shards.
+    pub fn counts(&self, mutants: &[Mutant]) -> Vec<usize> {
+        let mut counts = vec![0; self.n];
+        for mutant in mutants {
+            counts[shard_index(mutant, self.n)] += 1;
+        }
+        counts
+    }
+}
+
+/// The shard, out of `n`, that contains a mutant.
+///
+/// The hash is stable, so that shards agree across machines and versions of Rust.
+fn shard_index(mutant: &Mutant, n: usize) -> usize {
+    let hash = fmix64(fnv1a(mutant.name(false).as_bytes()));
+    usize::try_from(hash % n as u64).expect("shard index fits in usize")
 }
 
 impl FromStr for Shard {
@@ -40,7 +64,11 @@ impl FromStr for Shard {
 
 #[cfg(test)]
 mod tests {
+    use indoc::indoc;
+
     use super::*;
+    use crate::visit::mutate_source_str;
+    use crate::Options;
 
     #[test]
     fn shard_from_str_valid_input() {
@@ -74,10 +102,49 @@ mod tests {
     }
 
     #[test]
-    fn shard_select() {
+    fn shard_select_divides_mutants_by_name() {
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn double(x: u32) -> u32 {
+                    x * 2
+                }
+
+                fn triple(x: u32) -> u32 {
+                    x * 3
+                }
+            "},
+            &Options::default(),
+        )
+        .unwrap();
+        let n = 3;
+        let shards = (0..n)
+            .map(|k| Shard { k, n }.select(mutants.clone()))
+            .collect::<Vec<_>>();
         assert_eq!(
-            Shard::from_str("1/4").unwrap().select(0..10).as_slice(),
-            &[1, 5, 9]
+            shards.iter().map(Vec::len).collect::<Vec<_>>(),
+            Shard { k: 0, n }.counts(&mutants)
         );
+        assert_eq!(shards.iter().map(Vec::len).sum::<usize>(), mutants.len());
+        // Moving the code to other lines doesn't change the shard of any mutant.
+        let moved = mutate_source_str(
+            indoc! {"
+                // A new comment.
+
+                fn double(x: u32) -> u32 {
+                    x * 2
+                }
+
+                fn triple(x: u32) -> u32 {
+                    x * 3
+                }
+            "},
+            &Options::default(),
+        )
+        .unwrap();
+        for (k, shard) in shards.iter().enumerate() {
+            let names =
+                |mutants: &[Mutant]| mutants.iter().map(|m| m.name(false)).collect::<Vec<_>>();
+            assert_eq!(names(&Shard { k, n }.select(moved.clone())), names(shard),);
+        }
     }
 }
@@ -7,6 +7,7 @@ use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use camino::{Utf8Path, Utf8PathBuf};
+use path_slash::PathExt;
 #[allow(unused_imports)]
 use tracing::{debug, info, warn};
 
@@ -97,6 +98,8 @@ impl SourceFile {
                 relative_dir: Utf8PathBuf::new(),
                 top_sources,
                 version: "0.1.0".to_owned(),
+                features: std::collections::BTreeMap::new(),
+                requested_features: std::collections::BTreeSet::new(),
             }),
             is_top,
         }
@@ -115,6 +118,22 @@ impl SourceFile {
         self.code.as_str()
     }
 
+    /// Name the module defined by this file, like `mycrate::foo::bar` for
+    /// `src/foo/bar.rs`.
+    pub fn module_path(&self) -> String {
+        let path = module_path(
+            &self.package.name,
+            &self.package.relative_dir,
+            &self.tree_relative_path,
+        );
+        if self.is_top {
+            // The top file of a target is the crate root, whatever it's called.
+            path.split("::").next().unwrap_or_default().to_owned()
+        } else {
+            path
+        }
+    }
+
     /// Format a location within this source file for display to the user
     pub fn format_source_location(&self, location: LineColumn) -> String {
         let source_file = self.tree_relative_slashes();
@@ -123,8 +142,38 @@ impl SourceFile {
     }
 }
 
+/// Name the module defined by a source file, like `mycrate::foo::bar` for
+/// `src/foo/bar.rs` or `src/foo/bar/mod.rs`, or `tool` for `src/bin/tool.rs`.
+///
+/// `package_dir` and `file` are relative to the workspace root.
+fn module_path(package_name: &str, package_dir: &Utf8Path, file: &Utf8Path) -> String {
+    let file = file.strip_prefix(package_dir).unwrap_or(file);
+    let file = file.strip_prefix("src").unwrap_or(file);
+    let mut parts = vec![package_name.replace('-', "_")];
+    parts.extend(
+        file.as_std_path()
+            .to_slash_lossy()
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .map(|part| part.strip_suffix(".rs").unwrap_or(part).to_owned()),
+    );
+    // Each binary in `src/bin` is a separate crate, named after the file or directory.
+    if parts.get(1).map(String::as_str) == Some("bin") && parts.len() > 2 {
+        parts.drain(..2);
+    }
+    if matches!(
+        parts.last().map(String::as_str),
+        Some("lib" | "main" | "mod")
+    ) && parts.len() > 1
+    {
+        parts.pop();
+    }
+    parts.join("::")
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::{BTreeMap, BTreeSet};
     use std::fs::File;
     use std::io::Write;
 
@@ -132,6 +181,33 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn module_paths() {
+        let root = Utf8Path::new("");
+        assert_eq!(module_path("small", root, "src/lib.rs".into()), "small");
+        assert_eq!(
+            module_path("cargo-mutants", root, "src/visit.rs".into()),
+            "cargo_mutants::visit"
+        );
+        assert_eq!(
+            module_path("a", "crates/a".into(), "crates/a/src/b/mod.rs".into()),
+            "a::b"
+        );
+        assert_eq!(
+            module_path("a", "crates/a".into(), "crates/a/src/b/c.rs".into()),
+            "a::b::c"
+        );
+        assert_eq!(module_path("a", root, "src/bin/tool.rs".into()), "tool");
+        assert_eq!(
+            module_path("a", root, "src/bin/tool/main.rs".into()),
+            "tool"
+        );
+        assert_eq!(
+            module_path("a", root, "src/bin/tool/args.rs".into()),
+            "tool::args"
+        );
+    }
+
     #[test]
     fn source_file_normalizes_crlf() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -146,6 +222,8 @@ mod test {
             relative_dir: Utf8PathBuf::from(""),
             top_sources: vec!["src/lib.rs".into()],
             version: "0.1.0".to_owned(),
+            features: BTreeMap::new(),
+            requested_features: BTreeSet::new(),
         };
         let source_file = SourceFile::load(temp_dir_path, Utf8Path::new(file_name), &package, true)
             .unwrap()
@@ -160,6 +238,8 @@ mod test {
             relative_dir: Utf8PathBuf::from(""),
             top_sources: vec!["src/lib.rs".into()],
             version: "0.1.0".to_owned(),
+            features: BTreeMap::new(),
+            requested_features: BTreeSet::new(),
         });
         let source_file = SourceFile::load(
             Utf8Path::new("unimportant"),
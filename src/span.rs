@@ -8,10 +8,10 @@
 
 use std::fmt;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 /// A (line, column) position in a source file.
-#[derive(Clone, Copy, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct LineColumn {
     /// 1-based line number.
     pub line: usize,
@@ -36,7 +36,7 @@ impl fmt::Debug for LineColumn {
 }
 
 /// A contiguous text span in a file.
-#[derive(Clone, Copy, Eq, PartialEq, Serialize)]
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Span {
     /// The *inclusive* position where the span starts.
     pub start: LineColumn,
@@ -44,6 +44,13 @@ pub struct Span {
     pub end: LineColumn,
 }
 
+/// A range of byte offsets in a file, from the start up to but not including the end.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: usize,
+    pub end: usize,
+}
+
 impl Span {
     #[allow(dead_code)]
     pub fn quad(
@@ -64,6 +71,41 @@ impl Span {
         }
     }
 
+    /// The number of lines that this span touches.
+    pub fn line_count(&self) -> usize {
+        self.end.line - self.start.line + 1
+    }
+
+    /// Return the byte offsets within `s` of the start and end of this span.
+    pub fn byte_range(&self, s: &str) -> ByteRange {
+        let mut start = None;
+        let mut line_no = 1;
+        let mut col_no = 1;
+        for (i, c) in s.char_indices() {
+            if start.is_none() && line_no == self.start.line && col_no == self.start.column {
+                start = Some(i);
+            }
+            if line_no == self.end.line && col_no == self.end.column {
+                return ByteRange {
+                    start: start.unwrap_or(i),
+                    end: i,
+                };
+            }
+            if c == '\n' {
+                line_no += 1;
+                col_no = 1;
+            } else if c == '\r' {
+                // counts as part of the last column, not a separate column
+            } else {
+                col_no += 1;
+            }
+        }
+        ByteRange {
+            start: start.unwrap_or(s.len()),
+            end: s.len(),
+        }
+    }
+
     /// Return the region of a multi-line string that this span covers.
     pub fn extract(&self, s: &str) -> String {
         let mut r = String::new();
@@ -221,6 +263,25 @@ mod test {
         assert_eq!(span.replace("hello", "x"), "hellox");
     }
 
+    #[test]
+    fn byte_range_matches_extract() {
+        let source = "fn café() {\n    wibble();\n}\n";
+        for span in [
+            Span::quad(1, 11, 3, 2),
+            Span::quad(2, 5, 2, 13),
+            Span::quad(1, 4, 1, 8),
+            Span::quad(4, 1, 4, 1),
+        ] {
+            let ByteRange { start, end } = span.byte_range(source);
+            assert_eq!(&source[start..end], span.extract(source), "{span:?}");
+        }
+        assert_eq!(
+            Span::quad(1, 11, 3, 2).byte_range(source),
+            ByteRange { start: 11, end: 28 }
+        );
+        assert_eq!(Span::quad(1, 11, 3, 2).line_count(), 3);
+    }
+
     #[test]
     fn cut_including_crlf() {
         let source = "fn foo() {\r\n    wibble();\r\n}\r\n//hey!\r\n";
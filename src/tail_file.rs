@@ -1,7 +1,8 @@
 // Copyright 2021-2024 Martin Pool
 
-//! Tail a log file: watch for new writes and return the last line.
+//! Tail a log file: watch for new writes and return the last lines.
 
+use std::collections::VecDeque;
 use std::fs::File;
 use std::io::Read;
 
@@ -9,24 +10,28 @@ use anyhow::Context;
 
 use crate::Result;
 
-/// Tail a log file, and return the last non-empty line seen.
+/// Tail a log file, and return the last non-empty lines seen.
 ///
 /// This assumes that the log file always receives whole lines as atomic writes, which
 /// is typical.  If the file is being written by a process that writes partial lines,
 /// this won't panic or error but it may not return whole correct lines.
 pub struct TailFile {
     file: File,
-    /// The last non-empty line we've seen in the file so far.
-    last_line_seen: String,
+    /// The last non-empty lines we've seen in the file so far, oldest first.
+    lines_seen: VecDeque<String>,
+    /// The number of lines to keep.
+    max_lines: usize,
     read_buf: Vec<u8>,
 }
 
 impl TailFile {
-    /// Watch lines appended to the given file, which should be open for reading.
-    pub fn new(file: File) -> TailFile {
+    /// Watch lines appended to the given file, which should be open for reading, keeping
+    /// up to `max_lines` of them.
+    pub fn new(file: File, max_lines: usize) -> TailFile {
         TailFile {
             file,
-            last_line_seen: String::new(),
+            lines_seen: VecDeque::new(),
+            max_lines: max_lines.max(1),
             read_buf: Vec::new(),
         }
     }
@@ -36,21 +41,36 @@ impl TailFile {
     ///
     /// Non-UTF8 content is lost.
     pub fn last_line(&mut self) -> Result<&str> {
+        self.read()?;
+        Ok(self.lines_seen.back().map_or("", String::as_str))
+    }
+
+    /// Return up to the configured number of last non-empty lines from this file, oldest
+    /// first.
+    pub fn last_lines(&mut self) -> Result<impl Iterator<Item = &str>> {
+        self.read()?;
+        Ok(self.lines_seen.iter().map(String::as_str))
+    }
+
+    /// Read anything appended to the file since the last read.
+    fn read(&mut self) -> Result<()> {
         self.read_buf.clear();
         let n_read = self
             .file
             .read_to_end(&mut self.read_buf)
             .context("Read tail of log file")?;
         if n_read > 0 {
-            if let Some(new_last) = String::from_utf8_lossy(&self.read_buf)
+            for line in String::from_utf8_lossy(&self.read_buf)
                 .lines()
                 .filter(|l| !l.trim().is_empty())
-                .last()
             {
-                new_last.clone_into(&mut self.last_line_seen);
+                if self.lines_seen.len() == self.max_lines {
+                    self.lines_seen.pop_front();
+                }
+                self.lines_seen.push_back(line.to_owned());
             }
         }
-        Ok(self.last_line_seen.as_str())
+        Ok(())
     }
 }
 
@@ -67,7 +87,7 @@ mod test {
         let mut tempfile = tempfile::NamedTempFile::new().unwrap();
         let path: Utf8PathBuf = tempfile.path().to_owned().try_into().unwrap();
         let reopened = File::open(&path).unwrap();
-        let mut tailer = TailFile::new(reopened);
+        let mut tailer = TailFile::new(reopened, 1);
 
         assert_eq!(
             tailer.last_line().unwrap(),
@@ -127,4 +147,26 @@ mod test {
         //     "the same line is continued and finished"
         // );
     }
+
+    #[test]
+    fn last_lines_of_file() {
+        let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+        let path: Utf8PathBuf = tempfile.path().to_owned().try_into().unwrap();
+        let mut tailer = TailFile::new(File::open(&path).unwrap(), 3);
+        assert_eq!(tailer.last_lines().unwrap().count(), 0);
+
+        tempfile.write_all(b"one\n\ntwo\n").unwrap();
+        assert_eq!(
+            tailer.last_lines().unwrap().collect::<Vec<_>>(),
+            ["one", "two"]
+        );
+
+        tempfile.write_all(b"three\nfour\n  \nfive\n").unwrap();
+        assert_eq!(
+            tailer.last_lines().unwrap().collect::<Vec<_>>(),
+            ["three", "four", "five"],
+            "only the last lines are kept, and blank lines are skipped"
+        );
+        assert_eq!(tailer.last_line().unwrap(), "five");
+    }
 }
@@ -0,0 +1,363 @@
+// Copyright 2025 Martin Pool
+
+//! Test-impact analysis: run only the tests that cover each mutant, for `--test-impact`
+//! and `--test-map`.
+//!
+//! With `--test-impact`, after the baseline passes, each test is run on its own under
+//! [cargo-llvm-cov](https://github.com/taiki-e/cargo-llvm-cov) in the baseline build
+//! directory, to find which functions it executes. The resulting map is written to
+//! `mutants.out/test_map.json`, and can be reused by later runs with `--test-map` as
+//! long as the tests haven't changed.
+//!
+//! Each mutant is then tested with only the tests that executed the function containing
+//! it, by passing their names to the test harness with `--exact`. A mutant that isn't
+//! reached by any test is run with a filter that matches no tests, so it's missed.
+//!
+//! Doctests aren't included in the map, and so they aren't run for any mutant.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeMap;
+use std::fs::{read_to_string, File};
+use std::io::{BufWriter, Write};
+use std::process::Command;
+
+use anyhow::bail;
+use camino::Utf8Path;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use crate::build_dir::BuildDir;
+use crate::cargo::{cargo_argv, cargo_bin, cargo_env};
+use crate::interrupt::check_interrupted;
+use crate::options::TestTool;
+use crate::output::ScenarioOutput;
+use crate::package::PackageSelection;
+use crate::{Context, Mutant, Options, Phase, Result};
+
+/// The name of the test map within the output directory.
+pub const TEST_MAP_JSON: &str = "test_map.json";
+
+/// A test name that shouldn't match any real test, used when no tests cover a mutant.
+const NO_TESTS_FILTER: &str = "cargo_mutants::no_tests_cover_this_mutant";
+
+/// The functions executed by each test.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestMap {
+    /// For each test name, the lines of the functions it executed.
+    pub tests: BTreeMap<String, Vec<CoveredFunction>>,
+}
+
+/// A function that was executed by a test.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CoveredFunction {
+    /// The path of the file relative to the tree, with forward slashes.
+    pub file: String,
+    /// The first line of the function's code.
+    pub start_line: usize,
+    /// The last line of the function's code.
+    pub end_line: usize,
+}
+
+impl TestMap {
+    pub fn load(path: &Utf8Path) -> Result<TestMap> {
+        serde_json::from_str(&read_to_string(path).with_context(|| format!("read {path}"))?)
+            .with_context(|| format!("parse test map {path}"))
+    }
+
+    pub fn write(&self, path: &Utf8Path) -> Result<()> {
+        serde_json::to_writer_pretty(
+            BufWriter::new(File::create(path).with_context(|| format!("create {path}"))?),
+            self,
+        )
+        .with_context(|| format!("write {path}"))
+    }
+
+    /// Find which functions each test executes, by running every test on its own under
+    /// cargo-llvm-cov in an unmutated build directory.
+    pub fn generate(
+        build_dir: &BuildDir,
+        packages: &PackageSelection,
+        options: &Options,
+        log: &mut ScenarioOutput,
+    ) -> Result<TestMap> {
+        // Tests are listed and run by the standard harness, even with nextest, since the
+        // names and filters are the same.
+        let options = Options {
+            test_tool: TestTool::Cargo,
+            ..options.clone()
+        };
+        let list_argv = cargo_argv(
+            packages,
+            Phase::Test,
+            &with_harness_args(&options, ["--list", "--format", "terse"]),
+        );
+        let names = parse_test_list(&run_captured(&list_argv, build_dir, &options, log)?);
+        info!(
+            "Finding the functions covered by each of {} tests with cargo-llvm-cov",
+            names.len()
+        );
+        let report_argv = [
+            cargo_bin(),
+            "llvm-cov".to_owned(),
+            "report".to_owned(),
+            "--json".to_owned(),
+        ];
+        let clean_argv = [
+            cargo_bin(),
+            "llvm-cov".to_owned(),
+            "clean".to_owned(),
+            "--profraw-only".to_owned(),
+        ];
+        let mut tests = BTreeMap::new();
+        for name in names {
+            let mut test_argv = cargo_argv(
+                packages,
+                Phase::Test,
+                &with_harness_args(&options, ["--exact", &name]),
+            );
+            // Replace `test` with `llvm-cov`, which runs the tests with coverage.
+            test_argv.splice(1..2, ["llvm-cov".to_owned(), "--no-report".to_owned()]);
+            run_captured(&test_argv, build_dir, &options, log)?;
+            let report = run_captured(&report_argv, build_dir, &options, log)?;
+            let functions = covered_functions(&report, build_dir.path())
+                .with_context(|| format!("parse cargo llvm-cov report for {name}"))?;
+            run_captured(&clean_argv, build_dir, &options, log)?;
+            tests.insert(name, functions);
+        }
+        Ok(TestMap { tests })
+    }
+
+    /// The names of the tests that executed the function containing a mutant.
+    pub fn tests_for(&self, mutant: &Mutant) -> Vec<&str> {
+        let file = mutant.source_file.tree_relative_slashes();
+        let line = mutant.span.start.line;
+        self.tests
+            .iter()
+            .filter(|(_, functions)| {
+                functions
+                    .iter()
+                    .any(|f| f.file == file && f.start_line <= line && line <= f.end_line)
+            })
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+}
+
+/// Return options that run only the named tests, or no tests if there are none.
+pub fn options_for_tests(options: &Options, tests: &[&str]) -> Options {
+    let filters = if tests.is_empty() {
+        vec![NO_TESTS_FILTER]
+    } else {
+        tests.to_vec()
+    };
+    with_harness_args(options, ["--exact"].into_iter().chain(filters))
+}
+
+/// Return options with more arguments for the test harness, after any that are
+/// already passed to it following `--`.
+fn with_harness_args<'a>(options: &Options, args: impl IntoIterator<Item = &'a str>) -> Options {
+    let mut options = options.clone();
+    let test_args = &mut options.additional_cargo_test_args;
+    if !test_args.iter().any(|arg| arg == "--") {
+        test_args.push("--".to_owned());
+    }
+    test_args.extend(args.into_iter().map(ToOwned::to_owned));
+    options
+}
+
+/// Run a command in the build directory, with its stderr in the log, and return its
+/// stdout.
+fn run_captured(
+    argv: &[String],
+    build_dir: &BuildDir,
+    options: &Options,
+    log: &mut ScenarioOutput,
+) -> Result<String> {
+    log.message(&argv.join(" "))?;
+    let output = Command::new(&argv[0])
+        .args(&argv[1..])
+        .envs(cargo_env(options))
+        .current_dir(build_dir.path())
+        .output()
+        .with_context(|| format!("Failed to run {argv:?}"))?;
+    log.open_log_append()?
+        .write_all(&output.stderr)
+        .context("write to log")?;
+    check_interrupted()?;
+    if !output.status.success() {
+        bail!(
+            "{} failed; see {}",
+            argv.join(" "),
+            log.output_dir.join(log.log_path())
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Parse the names of tests from `--list --format terse`, excluding doctests, which
+/// can't be run with coverage.
+fn parse_test_list(list: &str) -> Vec<String> {
+    list.lines()
+        .filter_map(|line| line.strip_suffix(": test"))
+        .filter(|name| !(name.contains(" - ") && name.contains("(line ")))
+        .map(ToOwned::to_owned)
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// Find the functions in the tree that were executed, from the JSON export of
+/// `cargo llvm-cov report --json`.
+fn covered_functions(json: &str, tree_root: &Utf8Path) -> Result<Vec<CoveredFunction>> {
+    let value: Value = serde_json::from_str(json)?;
+    let mut functions = Vec::new();
+    for function in value["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .flat_map(|data| data["functions"].as_array().into_iter().flatten())
+    {
+        if function["count"].as_u64().unwrap_or_default() == 0 {
+            continue;
+        }
+        let Some(file) = function["filenames"][0].as_str() else {
+            continue;
+        };
+        let Ok(relative) = Utf8Path::new(file).strip_prefix(tree_root) else {
+            // Probably in a dependency.
+            continue;
+        };
+        // Each region is `[line_start, column_start, line_end, column_end, count,
+        // file_id, expanded_file_id, kind]`; only those in the function's own file count.
+        let lines = function["regions"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_array)
+            .filter(|region| region.get(5).and_then(Value::as_u64) == Some(0))
+            .filter_map(|region| Some((region[0].as_u64()?, region[2].as_u64()?)))
+            .collect_vec();
+        let (Some(start_line), Some(end_line)) = (
+            lines.iter().map(|(start, _)| *start).min(),
+            lines.iter().map(|(_, end)| *end).max(),
+        ) else {
+            continue;
+        };
+        functions.push(CoveredFunction {
+            file: relative.as_str().replace('\\', "/"),
+            start_line: usize::try_from(start_line)?,
+            end_line: usize::try_from(end_line)?,
+        });
+    }
+    functions.sort();
+    functions.dedup();
+    Ok(functions)
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::visit::mutate_source_str;
+
+    #[test]
+    fn parse_terse_test_list() {
+        let list = indoc! {"
+            test::b_test: test
+            test::a_test: test
+            bench_something: bench
+
+            src/lib.rs - factorial (line 5): test
+            test::a_test: test
+        "};
+        assert_eq!(parse_test_list(list), ["test::a_test", "test::b_test"]);
+    }
+
+    #[test]
+    fn parse_llvm_cov_export() {
+        let json = r#"{
+            "data": [{
+                "functions": [
+                    {
+                        "name": "_RNvCs1_3lib9factorial",
+                        "count": 1,
+                        "filenames": ["/tmp/build/src/lib.rs"],
+                        "regions": [[4, 33, 6, 2, 1, 0, 0, 0], [5, 5, 9, 6, 5, 0, 0, 0], [7, 1, 7, 9, 1, 1, 0, 0]]
+                    },
+                    {
+                        "name": "_RNvCs1_3lib6unused",
+                        "count": 0,
+                        "filenames": ["/tmp/build/src/lib.rs"],
+                        "regions": [[12, 1, 14, 2, 0, 0, 0, 0]]
+                    },
+                    {
+                        "name": "_RNvCs2_4core3fmt",
+                        "count": 3,
+                        "filenames": ["/home/user/.cargo/registry/src/core.rs"],
+                        "regions": [[1, 1, 2, 2, 3, 0, 0, 0]]
+                    }
+                ]
+            }],
+            "type": "llvm.coverage.json.export"
+        }"#;
+        assert_eq!(
+            covered_functions(json, Utf8Path::new("/tmp/build")).unwrap(),
+            [CoveredFunction {
+                file: "src/lib.rs".to_owned(),
+                start_line: 4,
+                end_line: 9,
+            }]
+        );
+    }
+
+    #[test]
+    fn tests_for_mutant_in_covered_function() {
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn double(x: u32) -> u32 {
+                    x * 2
+                }
+
+                fn triple(x: u32) -> u32 {
+                    x * 3
+                }
+            "},
+            &Options::default(),
+        )
+        .unwrap();
+        let covered = |start_line, end_line| CoveredFunction {
+            // The path of source files made by `mutate_source_str`.
+            file: mutants[0].source_file.tree_relative_slashes(),
+            start_line,
+            end_line,
+        };
+        let map = TestMap {
+            tests: BTreeMap::from([
+                ("test_double".to_owned(), vec![covered(1, 3)]),
+                ("test_both".to_owned(), vec![covered(1, 3), covered(5, 7)]),
+            ]),
+        };
+        let double = mutants.iter().find(|m| m.span.start.line == 2).unwrap();
+        assert_eq!(map.tests_for(double), ["test_both", "test_double"]);
+        let triple = mutants.iter().find(|m| m.span.start.line == 6).unwrap();
+        assert_eq!(map.tests_for(triple), ["test_both"]);
+        assert!(TestMap::default().tests_for(triple).is_empty());
+    }
+
+    #[test]
+    fn options_for_tests_add_exact_filters() {
+        let options = Options::from_arg_strs(["mutants", "--", "--", "--test-threads=1"]);
+        assert_eq!(
+            options_for_tests(&options, &["a::b", "c"]).additional_cargo_test_args,
+            ["--", "--test-threads=1", "--exact", "a::b", "c"]
+        );
+        assert_eq!(
+            options_for_tests(&Options::default(), &[]).additional_cargo_test_args,
+            ["--", "--exact", NO_TESTS_FILTER]
+        );
+    }
+}
@@ -1,14 +1,23 @@
-// Copyright 2021-2024 Martin Pool
+// Copyright 2021-2025 Martin Pool
 
 //! Calculation of timeouts for the build and test phases.
+//!
+//! The test timeout is a multiple of how long the tests took in the baseline. In a
+//! workspace where each mutant runs only the tests of its own package, the time is
+//! taken from the test binaries of that package, so that mutants in a small package don't
+//! get the same timeout as those in the slowest.
 
+use std::collections::HashMap;
 use std::{cmp::max, time::Duration};
 
-use tracing::{info, warn};
+use camino::Utf8Path;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
 
 use crate::{
-    options::Options,
+    options::{Options, TimeoutOverride},
     outcome::{Phase, ScenarioOutcome},
+    package::Package,
 };
 
 #[derive(Debug, Copy, Clone)]
@@ -44,6 +53,26 @@ impl Timeouts {
             test: Some(test_timeout(None, options)),
         }
     }
+
+    /// Set the test timeout from how long the tests that a mutant will run took in the
+    /// baseline, unless there's an explicit test timeout.
+    pub fn for_test_duration(self, test_duration: Duration, options: &Options) -> Timeouts {
+        if options.test_timeout.is_some() {
+            return self;
+        }
+        Timeouts {
+            test: Some(multiplied_test_timeout(test_duration, options)),
+            ..self
+        }
+    }
+
+    /// Replace the timeouts that are set by an override for some source files.
+    pub fn with_override(self, timeout_override: &TimeoutOverride) -> Timeouts {
+        Timeouts {
+            build: timeout_override.build_timeout.or(self.build),
+            test: timeout_override.test_timeout.or(self.test),
+        }
+    }
 }
 
 const FALLBACK_TIMEOUT_SECS: u64 = 300;
@@ -55,13 +84,7 @@ fn test_timeout(baseline_duration: Option<Duration>, options: &Options) -> Durat
     if let Some(explicit) = options.test_timeout {
         explicit
     } else if let Some(baseline_duration) = baseline_duration {
-        let timeout = max(
-            options.minimum_test_timeout,
-            Duration::from_secs_f64(
-                (baseline_duration.as_secs_f64() * options.test_timeout_multiplier.unwrap_or(5.0))
-                    .ceil(),
-            ),
-        );
+        let timeout = multiplied_test_timeout(baseline_duration, options);
         if options.show_times {
             info!(
                 "Auto-set test timeout to {}",
@@ -78,6 +101,17 @@ fn test_timeout(baseline_duration: Option<Duration>, options: &Options) -> Durat
     }
 }
 
+/// The test timeout for tests that took `baseline_duration` in the baseline.
+fn multiplied_test_timeout(baseline_duration: Duration, options: &Options) -> Duration {
+    max(
+        options.minimum_test_timeout,
+        Duration::from_secs_f64(
+            (baseline_duration.as_secs_f64() * options.test_timeout_multiplier.unwrap_or(5.0))
+                .ceil(),
+        ),
+    )
+}
+
 fn build_timeout(baseline_duration: Option<Duration>, options: &Options) -> Option<Duration> {
     if let Some(t) = options.build_timeout {
         Some(t)
@@ -99,16 +133,275 @@ fn build_timeout(baseline_duration: Option<Duration>, options: &Options) -> Opti
     }
 }
 
+/// How long the tests of each package took in the baseline.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PackageTestDurations {
+    /// Time in the test phase that isn't reported by any test binary, such as starting
+    /// cargo and the test processes, which is added to the time for every package.
+    overhead: Duration,
+    /// The total time reported by the test binaries and doctests of each package.
+    by_package: HashMap<String, Duration>,
+}
+
+/// A line of cargo's JSON output describing a built target.
+#[derive(Deserialize)]
+struct CargoArtifact {
+    reason: String,
+    manifest_path: String,
+    target: CargoTarget,
+    #[serde(default)]
+    executable: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CargoTarget {
+    name: String,
+    kind: Vec<String>,
+}
+
+impl PackageTestDurations {
+    /// Find how long each package's tests took in the baseline, from the time reported by
+    /// each test binary in the baseline log.
+    ///
+    /// `artifacts_json` is cargo's JSON description of the test binaries, which is used to
+    /// find the package of each binary, and `build_dir` is where they were built. Returns
+    /// None if the time for the tests can't be found, for example with nextest, which
+    /// reports times in another format.
+    pub fn from_baseline(
+        baseline: &ScenarioOutcome,
+        artifacts_json: &str,
+        build_dir: &Utf8Path,
+        packages: &[&Package],
+    ) -> Option<PackageTestDurations> {
+        PackageTestDurations::from_log(
+            baseline.phase_result(Phase::Test)?.duration,
+            &baseline.get_log_content().ok()?,
+            artifacts_json,
+            build_dir,
+            packages,
+        )
+    }
+
+    /// Find how long each package's tests took, from the log of the baseline test phase
+    /// that took `total`.
+    fn from_log(
+        total: Duration,
+        log: &str,
+        artifacts_json: &str,
+        build_dir: &Utf8Path,
+        packages: &[&Package],
+    ) -> Option<PackageTestDurations> {
+        // Binaries and doctests are identified by their file name, or by "doctest " and
+        // the crate name, and mapped to the package name.
+        let mut package_of: HashMap<String, &str> = HashMap::new();
+        let mut by_package: HashMap<String, Duration> = HashMap::new();
+        for artifact in artifacts_json
+            .lines()
+            .filter_map(|line| serde_json::from_str::<CargoArtifact>(line).ok())
+            .filter(|artifact| artifact.reason == "compiler-artifact")
+        {
+            let manifest_path = Utf8Path::new(&artifact.manifest_path);
+            let Some(package) = packages.iter().find(|package| {
+                manifest_path.strip_prefix(build_dir).ok()
+                    == Some(&package.relative_dir.join("Cargo.toml"))
+            }) else {
+                continue;
+            };
+            by_package.entry(package.name.clone()).or_default();
+            if let Some(name) = artifact
+                .executable
+                .as_deref()
+                .and_then(|path| Utf8Path::new(path).file_name())
+            {
+                package_of.insert(name.to_owned(), &package.name);
+            }
+            if artifact.target.kind.iter().any(|kind| kind.contains("lib")) {
+                package_of.insert(
+                    format!("doctest {}", artifact.target.name.replace('-', "_")),
+                    &package.name,
+                );
+            }
+        }
+        let reported = parse_test_times(log);
+        if reported.is_empty() {
+            return None;
+        }
+        let mut total_reported = Duration::ZERO;
+        for (binary, duration) in reported {
+            total_reported += duration;
+            if let Some(package) = package_of.get(&binary) {
+                *by_package.entry((*package).to_owned()).or_default() += duration;
+            } else {
+                debug!(?binary, "test binary from baseline not found in artifacts");
+            }
+        }
+        Some(PackageTestDurations {
+            overhead: total.saturating_sub(total_reported),
+            by_package,
+        })
+    }
+
+    /// The time the tests of one package took in the baseline, if it's known.
+    pub fn for_package(&self, package_name: &str) -> Option<Duration> {
+        self.by_package
+            .get(package_name)
+            .map(|duration| self.overhead + *duration)
+    }
+}
+
+/// Find the time reported by each test binary and doctest in the log of `cargo test`.
+///
+/// Each binary is identified by its file name, and doctests by "doctest " and the crate
+/// name.
+fn parse_test_times(log: &str) -> Vec<(String, Duration)> {
+    let mut times = Vec::new();
+    let mut current = None;
+    for line in log.lines() {
+        let line = line.trim();
+        if let Some(running) = line.strip_prefix("Running ") {
+            // Either "Running `/path/deps/foo-1234 args`" with --verbose, or
+            // "Running unittests src/lib.rs (target/debug/deps/foo-1234)".
+            let path = if let Some(quoted) = running.strip_prefix('`') {
+                quoted.split(['`', ' ']).next()
+            } else {
+                running
+                    .rsplit_once('(')
+                    .and_then(|(_, path)| path.strip_suffix(')'))
+            };
+            current = path
+                .and_then(|path| Utf8Path::new(path).file_name())
+                .map(ToOwned::to_owned);
+        } else if let Some(crate_name) = line.strip_prefix("Doc-tests ") {
+            current = Some(format!("doctest {crate_name}"));
+        } else if line.starts_with("test result: ") {
+            let seconds = line
+                .rsplit_once("finished in ")
+                .and_then(|(_, time)| time.strip_suffix('s'))
+                .and_then(|time| time.parse::<f64>().ok());
+            if let (Some(binary), Some(seconds)) = (current.take(), seconds) {
+                times.push((binary, Duration::from_secs_f64(seconds)));
+            }
+        }
+    }
+    times
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::{BTreeMap, BTreeSet};
     use std::str::FromStr;
 
+    use camino::Utf8Path;
     use clap::Parser;
     use indoc::indoc;
 
     use super::*;
     use crate::{config::Config, Args};
 
+    #[test]
+    fn parse_times_from_test_log() {
+        let log = indoc! {"
+            *** cargo test --verbose --package=a@0.1.0
+                 Running `/b/target/debug/deps/a-1234`
+
+            running 1 test
+            test test::slow ... ok
+
+            test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 2.50s
+
+                 Running unittests src/lib.rs (target/debug/deps/b-5678)
+            test result: ok. 0 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.00s
+
+               Doc-tests a
+            test result: ok. 3 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.75s
+        "};
+        assert_eq!(
+            parse_test_times(log),
+            [
+                ("a-1234".to_owned(), Duration::from_millis(2500)),
+                ("b-5678".to_owned(), Duration::ZERO),
+                ("doctest a".to_owned(), Duration::from_millis(750)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_durations_by_package() {
+        let package = |name: &str, dir: &str| Package {
+            name: name.to_owned(),
+            version: "0.1.0".to_owned(),
+            relative_dir: dir.into(),
+            top_sources: Vec::new(),
+            features: BTreeMap::default(),
+            requested_features: BTreeSet::default(),
+        };
+        let slow = package("slow-pkg", "slow");
+        let fast = package("fast", "fast");
+        let artifacts_json = indoc! {r#"
+            {"reason":"compiler-artifact","manifest_path":"/b/slow/Cargo.toml","target":{"name":"slow_pkg","kind":["lib"]},"executable":null}
+            {"reason":"compiler-artifact","manifest_path":"/b/slow/Cargo.toml","target":{"name":"slow_pkg","kind":["lib"]},"executable":"/b/target/debug/deps/slow_pkg-1234"}
+            {"reason":"compiler-artifact","manifest_path":"/b/slow/Cargo.toml","target":{"name":"big","kind":["test"]},"executable":"/b/target/debug/deps/big-5678"}
+            {"reason":"compiler-artifact","manifest_path":"/b/fast/Cargo.toml","target":{"name":"fast","kind":["bin"]},"executable":"/b/target/debug/deps/fast-9abc"}
+            {"reason":"build-finished","success":true}
+        "#};
+        let log = indoc! {"
+                 Running `/b/target/debug/deps/fast-9abc`
+            test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.10s
+                 Running `/b/target/debug/deps/slow_pkg-1234`
+            test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 1.00s
+                 Running `/b/target/debug/deps/big-5678`
+            test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 5.00s
+               Doc-tests slow_pkg
+            test result: ok. 1 passed; 0 failed; 0 ignored; 0 measured; 0 filtered out; finished in 0.40s
+        "};
+        let durations = PackageTestDurations::from_log(
+            Duration::from_secs(7),
+            log,
+            artifacts_json,
+            Utf8Path::new("/b"),
+            &[&slow, &fast],
+        )
+        .unwrap();
+        // Half a second isn't reported by any binary, and is added to each package.
+        assert_eq!(
+            durations.for_package("slow-pkg"),
+            Some(Duration::from_millis(6900))
+        );
+        assert_eq!(
+            durations.for_package("fast"),
+            Some(Duration::from_millis(600))
+        );
+        assert_eq!(durations.for_package("other"), None);
+
+        let options = Options::from_arg_strs(["mutants", "--minimum-test-timeout=1"]);
+        let timeouts = Timeouts::without_baseline(&options);
+        assert_eq!(
+            timeouts
+                .for_test_duration(durations.for_package("fast").unwrap(), &options)
+                .test,
+            Some(Duration::from_secs(3))
+        );
+        let options = Options::from_arg_strs(["mutants", "--timeout=100"]);
+        assert_eq!(
+            timeouts
+                .for_test_duration(durations.for_package("fast").unwrap(), &options)
+                .test,
+            timeouts.test
+        );
+
+        // Without times in the log, as from nextest, nothing is known.
+        assert_eq!(
+            PackageTestDurations::from_log(
+                Duration::from_secs(7),
+                "",
+                artifacts_json,
+                Utf8Path::new("/b"),
+                &[&slow, &fast],
+            ),
+            None
+        );
+    }
+
     #[test]
     fn timeout_multiplier_from_option() {
         let options = Options::from_arg_strs(["mutants", "--timeout-multiplier", "1.5"]);
@@ -227,6 +520,126 @@ mod test {
         assert_eq!(options.build_timeout, Some(Duration::from_secs(4)));
     }
 
+    #[test]
+    fn test_timeout_alias_and_config() {
+        let args = Args::try_parse_from(["mutants", "--test-timeout=8"]).unwrap();
+        let options = Options::new(&args, &Config::default()).unwrap();
+        assert_eq!(options.test_timeout, Some(Duration::from_secs(8)));
+
+        let config = Config::from_str(indoc! {r#"
+            test_timeout = 30
+            build_timeout = 120
+        "#})
+        .unwrap();
+        let args = Args::try_parse_from(["mutants"]).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.test_timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.build_timeout, Some(Duration::from_secs(120)));
+        assert_eq!(
+            test_timeout(Some(Duration::from_secs(42)), &options),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            build_timeout(Some(Duration::from_secs(42)), &options),
+            Some(Duration::from_secs(120))
+        );
+
+        // A multiplier on the command line overrides the explicit timeouts from the config.
+        let args = Args::try_parse_from([
+            "mutants",
+            "--test-timeout-multiplier=2",
+            "--build-timeout-multiplier=3",
+        ])
+        .unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        assert_eq!(options.test_timeout, None);
+        assert_eq!(options.build_timeout, None);
+        assert_eq!(
+            test_timeout(Some(Duration::from_secs(42)), &options),
+            Duration::from_secs(84)
+        );
+        assert_eq!(
+            build_timeout(Some(Duration::from_secs(42)), &options),
+            Some(Duration::from_secs(126))
+        );
+    }
+
+    #[test]
+    fn timeout_overrides_by_path() {
+        let config = Config::from_str(indoc! {r#"
+            test_timeout = "1m"
+
+            [[timeout_overrides]]
+            path = "src/slow/**"
+            test_timeout = "10m"
+
+            [[timeout_overrides]]
+            path = "src/slow/**"
+            test_timeout = 1
+
+            [[timeout_overrides]]
+            path = "build_heavy.rs"
+            build_timeout = 90
+        "#})
+        .unwrap();
+        let args = Args::try_parse_from(["mutants"]).unwrap();
+        let options = Options::new(&args, &config).unwrap();
+        let timeouts = Timeouts::without_baseline(&options);
+        assert_eq!(timeouts.test, Some(Duration::from_secs(60)));
+        assert_eq!(timeouts.build, None);
+
+        assert!(options
+            .timeout_override(Utf8Path::new("src/fast.rs"))
+            .is_none());
+
+        // The first matching override is used.
+        let slow = timeouts.with_override(
+            options
+                .timeout_override(Utf8Path::new("src/slow/deep/mod.rs"))
+                .unwrap(),
+        );
+        assert_eq!(slow.test, Some(Duration::from_secs(600)));
+        assert_eq!(slow.build, None);
+
+        let build_heavy = timeouts.with_override(
+            options
+                .timeout_override(Utf8Path::new("src/gen/build_heavy.rs"))
+                .unwrap(),
+        );
+        assert_eq!(build_heavy.test, Some(Duration::from_secs(60)));
+        assert_eq!(build_heavy.build, Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn invalid_timeout_override_is_an_error() {
+        let err = Config::from_str(indoc! {r#"
+            [[timeout_overrides]]
+            path = "src/slow/**"
+            test_timeout = "ten minutes"
+        "#})
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("invalid duration \"ten minutes\""),
+            "{err:#}"
+        );
+        let err = Config::from_str(indoc! {r#"
+            [[timeout_overrides]]
+            test_timeout = 10
+        "#})
+        .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("missing field `path`"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn negative_timeout_in_config_is_an_error() {
+        let config = Config::from_str("build_timeout = -1.0").unwrap();
+        let args = Args::try_parse_from(["mutants"]).unwrap();
+        assert!(Options::new(&args, &config).is_err());
+    }
+
     #[test]
     fn no_default_build_timeout() {
         let args = Args::try_parse_from(["mutants"]).unwrap();
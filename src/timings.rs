@@ -0,0 +1,227 @@
+// Copyright 2025 Martin Pool
+
+//! Times taken to test mutants in previous runs, to estimate how long a run will take.
+//!
+//! Extrapolating from the first few mutants tested is misleading on large runs, because
+//! mutants in some files or packages take much longer to build and test than others. So
+//! each run records the mean time for the mutants in each file and package in
+//! `mutants.out/timings.json`. The next run reads it before rotating the output directory,
+//! and writes it again updated with the files it tested, so that timings carry over from
+//! run to run even if each run tests only some of the tree.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeMap;
+use std::fs::read_to_string;
+
+use camino::Utf8Path;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::mutant::Mutant;
+
+/// The name of the timings file within the output directory.
+pub const TIMINGS_JSON: &str = "timings.json";
+
+/// Mean times to test a mutant in each package and each source file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Timings {
+    /// Timings by package name.
+    pub packages: BTreeMap<String, Timing>,
+    /// Timings by source file path, relative to the tree, with forward slashes.
+    pub files: BTreeMap<String, Timing>,
+}
+
+/// The mean time to check, build, and test a mutant.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    /// The number of mutants that were tested.
+    pub mutants: usize,
+    /// The mean time per mutant, in seconds.
+    pub mean_secs: f64,
+}
+
+impl Timing {
+    #[allow(clippy::cast_precision_loss)]
+    fn add(&mut self, secs: f64) {
+        self.mutants += 1;
+        self.mean_secs += (secs - self.mean_secs) / self.mutants as f64;
+    }
+}
+
+impl Timings {
+    /// Read timings from an output directory, or return empty timings if there are none.
+    pub fn load(output_dir: &Utf8Path) -> Timings {
+        let path = output_dir.join(TIMINGS_JSON);
+        let Ok(json) = read_to_string(&path) else {
+            debug!(?path, "No previous timings");
+            return Timings::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|err| {
+            warn!("Failed to parse {path}: {err}");
+            Timings::default()
+        })
+    }
+
+    /// Record the time taken to test one mutant.
+    pub fn add(&mut self, mutant: &Mutant, secs: f64) {
+        self.packages
+            .entry(mutant.source_file.package.name.clone())
+            .or_default()
+            .add(secs);
+        self.files
+            .entry(mutant.source_file.tree_relative_slashes())
+            .or_default()
+            .add(secs);
+    }
+
+    /// Return these timings updated by those from a later run, which replace the timings
+    /// for any file or package it tested.
+    pub fn updated_by(&self, later: &Timings) -> Timings {
+        let mut timings = self.clone();
+        timings.packages.extend(later.packages.clone());
+        timings.files.extend(later.files.clone());
+        timings
+    }
+
+    /// Estimate how long a mutant will take, from the timing of its file, or its package
+    /// if that file hasn't been tested.
+    fn estimate(&self, mutant: &Mutant) -> Option<f64> {
+        self.files
+            .get(&mutant.source_file.tree_relative_slashes())
+            .or_else(|| self.packages.get(&mutant.source_file.package.name))
+            .map(|timing| timing.mean_secs)
+    }
+
+    /// The mean time across all the mutants in previous runs, if there were any.
+    #[allow(clippy::cast_precision_loss)]
+    fn overall_mean_secs(&self) -> Option<f64> {
+        let mutants: usize = self.packages.values().map(|timing| timing.mutants).sum();
+        (mutants > 0).then(|| {
+            self.packages
+                .values()
+                .map(|timing| timing.mean_secs * timing.mutants as f64)
+                .sum::<f64>()
+                / mutants as f64
+        })
+    }
+}
+
+/// An estimate of the time to test the mutants in this run, updated as they finish.
+#[derive(Debug, Clone)]
+pub struct Estimate {
+    timings: Timings,
+    /// The estimate for mutants in files and packages with no previous timings.
+    default_secs: f64,
+    /// Estimated time for all the mutants, in seconds of work.
+    total_secs: f64,
+    /// Estimated time for the mutants finished so far, in seconds of work.
+    done_secs: f64,
+    mutants_done: usize,
+    jobs: usize,
+}
+
+impl Estimate {
+    /// Start an estimate for testing these mutants with a number of parallel jobs, or
+    /// return None if there are no previous timings to estimate from.
+    pub fn new(mutants: &[Mutant], timings: &Timings, jobs: usize) -> Option<Estimate> {
+        let default_secs = timings.overall_mean_secs()?;
+        let total_secs = mutants
+            .iter()
+            .map(|mutant| timings.estimate(mutant).unwrap_or(default_secs))
+            .sum();
+        Some(Estimate {
+            timings: timings.clone(),
+            default_secs,
+            total_secs,
+            done_secs: 0.0,
+            mutants_done: 0,
+            jobs: jobs.max(1),
+        })
+    }
+
+    /// Update that a mutant has finished.
+    pub fn mutant_finished(&mut self, mutant: &Mutant) {
+        self.mutants_done += 1;
+        self.done_secs += self.timings.estimate(mutant).unwrap_or(self.default_secs);
+    }
+
+    /// Estimate the seconds to test the remaining mutants, given the time spent so far.
+    ///
+    /// Until each job has finished a couple of mutants this assumes the previous
+    /// timings are accurate; after that, they're scaled by how fast this run is going
+    /// compared to the previous runs.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn remaining_secs(&self, elapsed_secs: f64) -> f64 {
+        let remaining_secs = (self.total_secs - self.done_secs).max(0.0);
+        if self.mutants_done < 2 * self.jobs || self.done_secs <= 0.0 {
+            remaining_secs / self.jobs as f64
+        } else {
+            elapsed_secs * remaining_secs / self.done_secs
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mutant::Genre;
+    use crate::source::SourceFile;
+    use crate::span::Span;
+
+    fn mutant(package: &str, file: &str) -> Mutant {
+        Mutant {
+            source_file: SourceFile::for_tests(file, "", package, false),
+            function: None,
+            span: Span::quad(1, 1, 1, 2),
+            replacement: "()".to_owned(),
+            genre: Genre::FnValue,
+            expansion: None,
+            description: None,
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)] // these values are exact in binary
+    fn estimate_from_file_then_package_then_overall_mean() {
+        let mut timings = Timings::default();
+        timings.add(&mutant("a", "a/src/lib.rs"), 10.0);
+        timings.add(&mutant("a", "a/src/lib.rs"), 20.0);
+        timings.add(&mutant("b", "b/src/lib.rs"), 60.0);
+        assert_eq!(timings.files["a/src/lib.rs"].mean_secs, 15.0);
+        assert_eq!(timings.packages["a"].mutants, 2);
+        assert_eq!(timings.overall_mean_secs(), Some(30.0));
+
+        let mutants = [
+            mutant("a", "a/src/lib.rs"),
+            mutant("b", "b/src/new.rs"),
+            mutant("c", "c/src/lib.rs"),
+        ];
+        let mut estimate = Estimate::new(&mutants, &timings, 1).unwrap();
+        assert_eq!(estimate.remaining_secs(0.0), 15.0 + 60.0 + 30.0);
+        estimate.mutant_finished(&mutants[0]);
+        estimate.mutant_finished(&mutants[1]);
+        // This run is twice as fast as the previous timings.
+        assert_eq!(estimate.remaining_secs(37.5), 15.0);
+    }
+
+    #[test]
+    fn no_estimate_without_timings() {
+        assert!(Estimate::new(&[mutant("a", "src/lib.rs")], &Timings::default(), 1).is_none());
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn later_timings_replace_earlier() {
+        let mut earlier = Timings::default();
+        earlier.add(&mutant("a", "src/a.rs"), 10.0);
+        earlier.add(&mutant("a", "src/b.rs"), 20.0);
+        let mut later = Timings::default();
+        later.add(&mutant("a", "src/b.rs"), 40.0);
+        let timings = earlier.updated_by(&later);
+        assert_eq!(timings.files["src/a.rs"].mean_secs, 10.0);
+        assert_eq!(timings.files["src/b.rs"].mean_secs, 40.0);
+        assert_eq!(timings.packages["a"].mean_secs, 40.0);
+    }
+}
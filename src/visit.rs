@@ -9,12 +9,12 @@
 
 #![warn(clippy::pedantic)]
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::vec;
 
 use camino::{Utf8Path, Utf8PathBuf};
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Delimiter, Ident, TokenStream, TokenTree};
 use quote::{quote, ToTokens};
 use syn::ext::IdentExt;
 use syn::spanned::Spanned;
@@ -22,10 +22,15 @@ use syn::visit::Visit;
 use syn::{Attribute, BinOp, Block, Expr, ExprPath, File, ItemFn, ReturnType, Signature, UnOp};
 use tracing::{debug, debug_span, error, info, trace, trace_span, warn};
 
+use crate::cfg::{CfgContext, TargetCfgs};
 use crate::console::WalkProgress;
-use crate::fnvalue::return_type_replacements;
-use crate::mutant::Function;
+use crate::expand::{derived_impls, expand_package, DerivedImpls};
+use crate::fnvalue::{
+    add_type_params, result_error_path, return_type_replacements, ErrorValues, TypeParams,
+};
+use crate::mutant::{one_line, Expansion, Function, FunctionSize};
 use crate::package::Package;
+use crate::plugin::plugin_mutants;
 use crate::pretty::ToPrettyString;
 use crate::source::SourceFile;
 use crate::span::Span;
@@ -66,11 +71,20 @@ pub fn walk_tree(
 ) -> Result<Discovered> {
     let mut mutants = Vec::new();
     let mut files = Vec::new();
-    let error_exprs = options.parsed_error_exprs()?;
+    let target_cfgs = TargetCfgs::for_options(options);
     let progress = console.start_walk_tree();
     for package in packages {
-        let (mut package_mutants, mut package_files) =
-            walk_package(workspace_dir, package, &error_exprs, &progress, options)?;
+        let options = options.for_package(&package.name);
+        let error_values = options.parsed_error_values()?;
+        let cfg = CfgContext::new(target_cfgs.clone(), package, options);
+        let (mut package_mutants, mut package_files) = walk_package(
+            workspace_dir,
+            package,
+            &error_values,
+            &cfg,
+            &progress,
+            options,
+        )?;
         mutants.append(&mut package_mutants);
         files.append(&mut package_files);
     }
@@ -84,15 +98,28 @@ pub fn walk_tree(
 fn walk_package(
     workspace_dir: &Utf8Path,
     package: &Package,
-    error_exprs: &[Expr],
+    error_values: &ErrorValues,
+    cfg: &CfgContext,
     progress: &WalkProgress,
     options: &Options,
 ) -> Result<(Vec<Mutant>, Vec<SourceFile>)> {
     let mut mutants = Vec::new();
     let mut files = Vec::new();
-    let mut filename_queue =
-        VecDeque::from_iter(package.top_sources.iter().map(|p| (p.to_owned(), true)));
-    while let Some((path, package_top)) = filename_queue.pop_front() {
+    let mut error_enums = ErrorEnums::new();
+    let derived_impls = if options.expand_macros {
+        package_derived_impls(workspace_dir, package)
+    } else {
+        DerivedImpls::new()
+    };
+    // Files are queued with whether they're the top of the package, and whether they're
+    // inside a module that isn't `pub`.
+    let mut filename_queue = VecDeque::from_iter(
+        package
+            .top_sources
+            .iter()
+            .map(|p| (p.to_owned(), true, false)),
+    );
+    while let Some((path, package_top, in_private_mod)) = filename_queue.pop_front() {
         let Some(source_file) = SourceFile::load(workspace_dir, &path, package, package_top)?
         else {
             info!("Skipping source file outside of tree: {path:?}");
@@ -100,7 +127,15 @@ fn walk_package(
         };
         progress.increment_files(1);
         check_interrupted()?;
-        let (mut file_mutants, external_mods) = walk_file(&source_file, error_exprs, options)?;
+        let (mut file_mutants, external_mods, file_error_enums) = walk_file(
+            &source_file,
+            in_private_mod,
+            error_values,
+            &derived_impls,
+            cfg,
+            options,
+        )?;
+        merge_error_enums(&mut error_enums, file_error_enums);
         file_mutants.retain(|m| options.allows_mutant(m));
         progress.increment_mutants(file_mutants.len());
         // TODO: It would be better not to spend time generating mutants from
@@ -115,7 +150,8 @@ fn walk_package(
         // `--list-files`.
         for mod_namespace in &external_mods {
             if let Some(mod_path) = find_mod_source(workspace_dir, &source_file, mod_namespace) {
-                filename_queue.push_back((mod_path, false));
+                let private = in_private_mod || mod_namespace.parts.iter().any(|m| !m.public);
+                filename_queue.push_back((mod_path, false, private));
             }
         }
         if !options.allows_source_file_path(&source_file.tree_relative_path) {
@@ -124,34 +160,85 @@ fn walk_package(
         mutants.append(&mut file_mutants);
         files.push(source_file);
     }
+    // Enums can be defined in any file in the package, so these mutants can only be added
+    // once all the files have been visited.
+    let n_before = mutants.len();
+    let mutants = add_error_variant_mutants(mutants, &error_enums, options);
+    progress.increment_mutants(mutants.len() - n_before);
     Ok((mutants, files))
 }
 
+/// Expand macros in a package and find the impls generated by derive macros.
+///
+/// Failures are only warnings, because the package may have no library target, or
+/// the expanded code might not be parseable, and the other mutants are still useful.
+fn package_derived_impls(workspace_dir: &Utf8Path, package: &Package) -> DerivedImpls {
+    let _span = debug_span!("expand_macros", package = package.name).entered();
+    match expand_package(workspace_dir, package).and_then(|expanded| derived_impls(&expanded)) {
+        Ok(impls) => {
+            debug!(n_impls = impls.len(), "found derived impls");
+            impls
+        }
+        Err(err) => {
+            warn!(
+                "Failed to expand macros in package {}, so derived code won't be mutated: {err:#}",
+                package.name
+            );
+            DerivedImpls::new()
+        }
+    }
+}
+
 /// Find all possible mutants in a source file.
 ///
 /// Returns the mutants found, and the names of modules referenced by `mod` statements
 /// that should be visited later.
+///
+/// `in_private_mod` is true if the file is a module inside a module that isn't `pub`.
 fn walk_file(
     source_file: &SourceFile,
-    error_exprs: &[Expr],
+    in_private_mod: bool,
+    error_values: &ErrorValues,
+    derived_impls: &DerivedImpls,
+    cfg: &CfgContext,
     options: &Options,
-) -> Result<(Vec<Mutant>, Vec<ExternalModRef>)> {
+) -> Result<(Vec<Mutant>, Vec<ExternalModRef>, ErrorEnums)> {
     let _span = debug_span!("source_file", path = source_file.tree_relative_slashes()).entered();
     debug!("visit source file");
     let syn_file = syn::parse_str::<syn::File>(source_file.code())
         .with_context(|| format!("failed to parse {}", source_file.tree_relative_slashes()))?;
+    let mut signatures = SignatureCollector::default();
+    signatures.visit_file(&syn_file);
+    let mut enums = EnumCollector::default();
+    enums.visit_file(&syn_file);
     let mut visitor = DiscoveryVisitor {
-        error_exprs,
+        error_values,
+        derived_impls,
+        cfg,
+        fn_param_types: signatures.fn_param_types,
         external_mods: Vec::new(),
         mutants: Vec::new(),
         mod_namespace_stack: Vec::new(),
         namespace_stack: Vec::new(),
         fn_stack: Vec::new(),
+        functions: Vec::new(),
+        loop_labels: Vec::new(),
+        in_const_item: false,
+        generics_stack: Vec::new(),
+        impl_items_public: false,
+        in_private_mod,
         source_file: source_file.clone(),
         options,
     };
     visitor.visit_file(&syn_file);
-    Ok((visitor.mutants, visitor.external_mods))
+    if options.allows_genre(Genre::Plugin) {
+        visitor.mutants.extend(plugin_mutants(
+            &options.plugins,
+            source_file,
+            &visitor.functions,
+        )?);
+    }
+    Ok((visitor.mutants, visitor.external_mods, enums.error_enums))
 }
 
 /// For testing: parse and generate mutants from one single file provided as a string.
@@ -165,8 +252,15 @@ pub fn mutate_source_str(code: &str, options: &Options) -> Result<Vec<Mutant>> {
         "cargo-mutants-testdata-internal",
         true,
     );
-    let (mutants, _) = walk_file(&source_file, &options.parsed_error_exprs()?, options)?;
-    Ok(mutants)
+    let (mutants, _, error_enums) = walk_file(
+        &source_file,
+        false,
+        &options.parsed_error_values()?,
+        &DerivedImpls::new(),
+        &CfgContext::default(),
+        options,
+    )?;
+    Ok(add_error_variant_mutants(mutants, &error_enums, options))
 }
 
 /// Reference to an external module from a source file.
@@ -198,6 +292,8 @@ struct ModNamespace {
     path_attribute: Option<Utf8PathBuf>,
     /// Location of the module definition in the source file
     source_location: Span,
+    /// True if the module is declared `pub`.
+    public: bool,
 }
 
 impl ModNamespace {
@@ -209,6 +305,209 @@ impl ModNamespace {
     }
 }
 
+/// Parameter types of functions, keyed by the function name and whether it has a `self`
+/// receiver.
+///
+/// The value is None if there are several functions with the same key and different
+/// parameter types, in which case we can't tell which one is called.
+type FnParamTypes = HashMap<(String, bool), Option<Vec<String>>>;
+
+/// Visitor that collects the parameter types of all functions in a file, so that calls
+/// to them can be mutated by swapping same-typed arguments.
+#[derive(Default)]
+struct SignatureCollector {
+    fn_param_types: FnParamTypes,
+}
+
+impl SignatureCollector {
+    fn collect(&mut self, sig: &Signature) {
+        let mut has_receiver = false;
+        let mut types = Vec::new();
+        for input in &sig.inputs {
+            match input {
+                syn::FnArg::Receiver(_) => has_receiver = true,
+                syn::FnArg::Typed(pat_type) => types.push(pat_type.ty.to_pretty_string()),
+            }
+        }
+        self.fn_param_types
+            .entry((sig.ident.to_string(), has_receiver))
+            .and_modify(|existing| {
+                if existing.as_ref() != Some(&types) {
+                    *existing = None;
+                }
+            })
+            .or_insert_with(|| Some(types.clone()));
+    }
+}
+
+impl<'ast> Visit<'ast> for SignatureCollector {
+    fn visit_signature(&mut self, i: &'ast Signature) {
+        self.collect(i);
+    }
+}
+
+/// Visitor that measures the cyclomatic complexity of a function body, by counting the
+/// places where control flow branches.
+///
+/// Nested items are not counted, but closures are, since they're part of the function.
+struct ComplexityCounter {
+    complexity: usize,
+}
+
+impl<'ast> Visit<'ast> for ComplexityCounter {
+    fn visit_expr_if(&mut self, i: &'ast syn::ExprIf) {
+        self.complexity += 1;
+        syn::visit::visit_expr_if(self, i);
+    }
+
+    fn visit_expr_while(&mut self, i: &'ast syn::ExprWhile) {
+        self.complexity += 1;
+        syn::visit::visit_expr_while(self, i);
+    }
+
+    fn visit_expr_for_loop(&mut self, i: &'ast syn::ExprForLoop) {
+        self.complexity += 1;
+        syn::visit::visit_expr_for_loop(self, i);
+    }
+
+    fn visit_expr_match(&mut self, i: &'ast syn::ExprMatch) {
+        self.complexity += i.arms.len().saturating_sub(1);
+        syn::visit::visit_expr_match(self, i);
+    }
+
+    fn visit_arm(&mut self, i: &'ast syn::Arm) {
+        if i.guard.is_some() {
+            self.complexity += 1;
+        }
+        syn::visit::visit_arm(self, i);
+    }
+
+    fn visit_expr_binary(&mut self, i: &'ast syn::ExprBinary) {
+        if matches!(i.op, BinOp::And(_) | BinOp::Or(_)) {
+            self.complexity += 1;
+        }
+        syn::visit::visit_expr_binary(self, i);
+    }
+
+    fn visit_expr_try(&mut self, i: &'ast syn::ExprTry) {
+        self.complexity += 1;
+        syn::visit::visit_expr_try(self, i);
+    }
+
+    fn visit_item(&mut self, _i: &'ast syn::Item) {}
+}
+
+/// The unit-like variants of enums defined in a package, keyed by the enum name.
+///
+/// The value is None if there are several enums with the same name and different
+/// variants, in which case we can't tell which one is meant.
+type ErrorEnums = HashMap<String, Option<Vec<String>>>;
+
+/// Visitor that collects enums with unit-like variants, which might be used as
+/// error types.
+#[derive(Default)]
+struct EnumCollector {
+    error_enums: ErrorEnums,
+}
+
+impl<'ast> Visit<'ast> for EnumCollector {
+    fn visit_item_enum(&mut self, i: &'ast syn::ItemEnum) {
+        let variants: Vec<String> = i
+            .variants
+            .iter()
+            .filter(|variant| matches!(variant.fields, syn::Fields::Unit))
+            .map(|variant| variant.ident.to_string())
+            .collect();
+        if !variants.is_empty() {
+            merge_error_enums(
+                &mut self.error_enums,
+                HashMap::from([(i.ident.to_string(), Some(variants))]),
+            );
+        }
+        syn::visit::visit_item_enum(self, i);
+    }
+}
+
+/// Add enums from `new` into `error_enums`, forgetting any names that are ambiguous.
+fn merge_error_enums(error_enums: &mut ErrorEnums, new: ErrorEnums) {
+    for (name, variants) in new {
+        error_enums
+            .entry(name)
+            .and_modify(|existing| {
+                if *existing != variants {
+                    *existing = None;
+                }
+            })
+            .or_insert(variants);
+    }
+}
+
+/// Add mutants returning each unit-like variant of the error enum, from functions
+/// returning `Result<T, E>` where `E` is an enum defined in the same package.
+///
+/// The new mutants are inserted after the other mutants replacing the body of the
+/// same function.
+fn add_error_variant_mutants(
+    mutants: Vec<Mutant>,
+    error_enums: &ErrorEnums,
+    options: &Options,
+) -> Vec<Mutant> {
+    let mut result = Vec::with_capacity(mutants.len());
+    let mut mutants = mutants.into_iter().peekable();
+    while let Some(mutant) = mutants.next() {
+        let is_last_fn_value = mutant.genre == Genre::FnValue
+            && !mutants.peek().is_some_and(|next| {
+                next.genre == Genre::FnValue && next.function == mutant.function
+            });
+        let extra = if is_last_fn_value {
+            mutant
+                .function
+                .as_ref()
+                .map(|function| error_variant_replacements(&function.return_type, error_enums))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|replacement| Mutant {
+                    replacement,
+                    ..mutant.clone()
+                })
+                .filter(|m| options.allows_mutant(m))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        result.push(mutant);
+        result.extend(extra);
+    }
+    result
+}
+
+/// Return `Err(E::Variant)` for each unit-like variant of `E`, if the return type
+/// is `Result<T, E>` and `E` is a known enum.
+fn error_variant_replacements(return_type: &str, error_enums: &ErrorEnums) -> Vec<String> {
+    let Ok(ReturnType::Type(_, ty)) = syn::parse_str::<ReturnType>(return_type) else {
+        return Vec::new();
+    };
+    let Some(error_path) = result_error_path(&ty) else {
+        return Vec::new();
+    };
+    let Some(error_name) = error_path.segments.last() else {
+        return Vec::new();
+    };
+    if !error_name.arguments.is_none() {
+        return Vec::new();
+    }
+    let Some(Some(variants)) = error_enums.get(&error_name.ident.to_string()) else {
+        return Vec::new();
+    };
+    variants
+        .iter()
+        .map(|variant| {
+            let variant = Ident::new(variant, proc_macro2::Span::call_site());
+            quote! { Err(#error_path::#variant) }.to_pretty_string()
+        })
+        .collect()
+}
+
 /// `syn` visitor that recursively traverses the syntax tree, accumulating places
 /// that could be mutated.
 ///
@@ -243,31 +542,86 @@ struct DiscoveryVisitor<'o> {
     /// there are nested functions.
     fn_stack: Vec<Arc<Function>>,
 
+    /// All the functions entered so far, which can be mutated by plugins.
+    functions: Vec<Arc<Function>>,
+
+    /// The loops we're inside, within the innermost function or closure,
+    /// with their labels if any.
+    loop_labels: Vec<Option<String>>,
+
+    /// True while visiting the initializer of a `const` or `static` item.
+    in_const_item: bool,
+
+    /// The generics of the impls, traits and functions we're inside, used to find
+    /// replacement values for type parameters.
+    generics_stack: Vec<syn::Generics>,
+
+    /// True while visiting the items of a trait impl, or of a `pub` trait, whose
+    /// functions are public without being marked `pub`.
+    impl_items_public: bool,
+
+    /// True if this file is a module inside a module that isn't `pub`, as declared in
+    /// the files that include it.
+    in_private_mod: bool,
+
     /// The names from `mod foo;` statements that should be visited later,
     /// namespaced relative to the source file
     external_mods: Vec<ExternalModRef>,
 
-    /// Parsed error expressions, from the config file or command line.
-    error_exprs: &'o [Expr],
+    /// Parsed error values, from the config file or command line.
+    error_values: &'o ErrorValues,
+
+    /// Impls generated by derive macros in this package, if macros were expanded.
+    derived_impls: &'o DerivedImpls,
+
+    /// The cfgs known to be set or unset for this package.
+    cfg: &'o CfgContext,
+
+    /// Parameter types of functions defined in this file, used to find arguments
+    /// that can be swapped.
+    fn_param_types: FnParamTypes,
 
     options: &'o Options,
 }
 
 impl DiscoveryVisitor<'_> {
+    /// True if the attrs indicate that we should skip this node and everything inside it,
+    /// including if it's behind a `#[cfg]` that's inactive for this build.
+    fn attrs_excluded(&self, attrs: &[Attribute]) -> bool {
+        attrs_excluded(attrs)
+            || self.cfg.attrs_inactive(attrs)
+            || attrs
+                .iter()
+                .any(|attr| attr_is_configured_skip(&attr.meta, &self.options.skip_attrs))
+    }
+
     fn enter_function(
         &mut self,
-        function_name: &Ident,
-        return_type: &ReturnType,
+        sig: &Signature,
+        block: &Block,
         span: proc_macro2::Span,
     ) -> Arc<Function> {
-        self.namespace_stack.push(function_name.to_string());
+        self.namespace_stack.push(sig.ident.to_string());
         let full_function_name = self.namespace_stack.join("::");
+        let mut complexity = ComplexityCounter { complexity: 1 };
+        complexity.visit_block(block);
+        let module_path = std::iter::once(self.source_file.module_path())
+            .chain(self.mod_namespace_stack.iter().map(|m| m.name.clone()))
+            .collect::<Vec<_>>()
+            .join("::");
         let function = Arc::new(Function {
             function_name: full_function_name,
-            return_type: return_type.to_pretty_string(),
+            module_path,
+            return_type: sig.output.to_pretty_string(),
             span: span.into(),
+            size: FunctionSize {
+                lines: block.brace_token.span.close().end().line - sig.fn_token.span.start().line
+                    + 1,
+                complexity: complexity.complexity,
+            },
         });
         self.fn_stack.push(Arc::clone(&function));
+        self.functions.push(Arc::clone(&function));
         function
     }
 
@@ -284,19 +638,82 @@ impl DiscoveryVisitor<'_> {
 
     /// Record that we generated some mutants.
     fn collect_mutant(&mut self, span: Span, replacement: &TokenStream, genre: Genre) {
+        self.collect_mutant_text(span, replacement.to_pretty_string(), genre);
+    }
+
+    /// Record a mutant whose replacement is given as source text.
+    ///
+    /// This is useful when the replacement is built from the original text, so that
+    /// its formatting is preserved.
+    fn collect_mutant_text(&mut self, span: Span, replacement: String, genre: Genre) {
+        self.push_mutant(span, replacement, genre, None);
+    }
+
+    /// Record a mutant with a short description to use in its name, instead of the
+    /// original text.
+    fn collect_described_mutant(
+        &mut self,
+        span: Span,
+        replacement: String,
+        genre: Genre,
+        description: String,
+    ) {
+        self.push_mutant(span, replacement, genre, Some(description));
+    }
+
+    fn push_mutant(
+        &mut self,
+        span: Span,
+        replacement: String,
+        genre: Genre,
+        description: Option<String>,
+    ) {
+        if !self.options.allows_genre(genre) {
+            return;
+        }
         self.mutants.push(Mutant {
             source_file: self.source_file.clone(),
             function: self.fn_stack.last().cloned(),
             span,
-            replacement: replacement.to_pretty_string(),
+            replacement,
             genre,
+            expansion: None,
+            description,
         });
     }
 
+    /// True if `--only-pub` excludes an item with this visibility.
+    ///
+    /// Items nested inside a function are part of its body, and so are mutated if the
+    /// function is.
+    fn excluded_by_visibility(&self, vis: &syn::Visibility) -> bool {
+        self.options.only_pub
+            && self.fn_stack.is_empty()
+            && (!matches!(vis, syn::Visibility::Public(_)) || self.in_private_mod())
+    }
+
+    /// True if we're inside a module that isn't `pub`, in this file or in the files
+    /// that include it, so that even `pub` items aren't public.
+    ///
+    /// Items re-exported from private modules with `pub use` are still treated as private.
+    fn in_private_mod(&self) -> bool {
+        self.in_private_mod || self.mod_namespace_stack.iter().any(|m| !m.public)
+    }
+
+    /// The type parameters in scope, and their trait bounds.
+    fn type_params(&self) -> TypeParams {
+        let mut type_params = TypeParams::new();
+        for generics in &self.generics_stack {
+            add_type_params(&mut type_params, generics);
+        }
+        type_params
+    }
+
     fn collect_fn_mutants(&mut self, sig: &Signature, block: &Block) {
         if let Some(function) = self.fn_stack.last().cloned() {
             let body_span = function_body_span(block).expect("Empty function body");
-            let repls = return_type_replacements(&sig.output, self.error_exprs);
+            let error_exprs = self.error_values.for_return_type(&sig.output);
+            let repls = return_type_replacements(&sig.output, &error_exprs, &self.type_params());
             if repls.is_empty() {
                 debug!(
                     function_name = function.function_name,
@@ -328,807 +745,3785 @@ impl DiscoveryVisitor<'_> {
         }
     }
 
-    /// Call a function with a namespace pushed onto the stack.
-    ///
-    /// This is used when recursively descending into a namespace.
-    fn in_namespace<F, T>(&mut self, name: &str, f: F) -> T
-    where
-        F: FnOnce(&mut Self) -> T,
-    {
-        self.namespace_stack.push(name.to_owned());
-        let r = f(self);
-        assert_eq!(self.namespace_stack.pop().unwrap(), name);
-        r
-    }
-}
-
-impl<'ast> Visit<'ast> for DiscoveryVisitor<'_> {
-    fn visit_expr_call(&mut self, i: &'ast syn::ExprCall) {
-        let _span = trace_span!("expr_call", line = i.span().start().line).entered();
-        if attrs_excluded(&i.attrs) {
+    /// Generate mutants that swap pairs of arguments of the same type in a call to a
+    /// function defined in this file.
+    fn collect_swap_arguments(
+        &mut self,
+        name: &Ident,
+        has_receiver: bool,
+        args: &syn::punctuated::Punctuated<Expr, syn::Token![,]>,
+    ) {
+        let Some(Some(param_types)) = self
+            .fn_param_types
+            .get(&(name.to_string(), has_receiver))
+            .cloned()
+        else {
+            return;
+        };
+        if param_types.len() != args.len() {
             return;
         }
-        if let Expr::Path(ExprPath { path, .. }) = &*i.func {
-            debug!(path = path.to_pretty_string(), "visit call");
-            if let Some(hit) = self
-                .options
-                .skip_calls
-                .iter()
-                .find(|s| path_ends_with(path, s))
-            {
-                trace!("skip call to {hit}");
-                return;
+        let args: Vec<&Expr> = args.iter().collect();
+        let code = self.source_file.code().to_owned();
+        for i in 0..args.len() {
+            for j in (i + 1)..args.len() {
+                // `impl Trait` parameters can each be a different type.
+                if param_types[i] != param_types[j] || param_types[i].contains("impl ") {
+                    continue;
+                }
+                let first = Span::from(args[i].span());
+                let second = Span::from(args[j].span());
+                let first_text = first.extract(&code);
+                let second_text = second.extract(&code);
+                if first_text == second_text {
+                    continue;
+                }
+                let between = Span {
+                    start: first.end,
+                    end: second.start,
+                }
+                .extract(&code);
+                self.collect_mutant_text(
+                    Span {
+                        start: first.start,
+                        end: second.end,
+                    },
+                    format!("{second_text}{between}{first_text}"),
+                    Genre::SwapArguments,
+                );
             }
         }
-        syn::visit::visit_expr_call(self, i);
     }
 
-    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
-        let _span = trace_span!("expr_method_call", line = i.span().start().line).entered();
-        if attrs_excluded(&i.attrs) {
+    /// Generate mutants of `Option` and `Result` combinators.
+    ///
+    /// `map` and `and_then` are not swapped, because without type information the
+    /// result would almost always be unviable.
+    fn collect_combinator_mutants(&mut self, i: &syn::ExprMethodCall) {
+        if self.fn_stack.is_empty() {
             return;
         }
-        if let Some(hit) = self.options.skip_calls.iter().find(|s| i.method == s) {
-            trace!("skip method call to {hit}");
-            return;
+        let method_span: Span = i.method.span().into();
+        // From the method name through the closing parenthesis.
+        let call_span = Span {
+            start: method_span.start,
+            end: Span::from(i.paren_token.span.close()).end,
+        };
+        match i.method.to_string().as_str() {
+            "unwrap_or" | "unwrap_or_else" => {
+                self.collect_mutant(
+                    call_span,
+                    &quote! { unwrap_or_default() },
+                    Genre::Combinator,
+                );
+            }
+            "is_some" => self.collect_mutant(method_span, &quote! { is_none }, Genre::Combinator),
+            "is_none" => self.collect_mutant(method_span, &quote! { is_some }, Genre::Combinator),
+            "is_ok" => self.collect_mutant(method_span, &quote! { is_err }, Genre::Combinator),
+            "is_err" => self.collect_mutant(method_span, &quote! { is_ok }, Genre::Combinator),
+            "ok_or" if i.args.len() == 1 => {
+                let arg_span: Span = i.args[0].span().into();
+                for error_expr in &self.error_values.default {
+                    self.collect_mutant(arg_span, &error_expr.to_token_stream(), Genre::Combinator);
+                }
+            }
+            _ => (),
         }
-        syn::visit::visit_expr_method_call(self, i);
     }
 
-    /// Visit a source file.
-    fn visit_file(&mut self, i: &'ast File) {
-        // No trace here; it's created per file for the whole visitor
-        if attrs_excluded(&i.attrs) {
-            trace!("file excluded by attrs");
+    /// Generate mutants that delete iterator adapters that change the order or
+    /// number of items, or that swap minimum and maximum.
+    fn collect_iterator_adapter_mutants(&mut self, i: &syn::ExprMethodCall) {
+        if self.fn_stack.is_empty() {
             return;
         }
-        syn::visit::visit_file(self, i);
+        let method = i.method.to_string();
+        // `take()` with no arguments is also `Option::take` or `Cell::take`, which aren't
+        // iterator adapters, so the number of arguments is checked too.
+        match (method.as_str(), i.args.len()) {
+            ("rev", 0) | ("skip" | "take", 1) => {
+                // From the dot through the closing parenthesis.
+                let span = Span {
+                    start: Span::from(i.dot_token.span()).start,
+                    end: Span::from(i.paren_token.span.close()).end,
+                };
+                self.collect_mutant(span, &TokenStream::new(), Genre::IteratorAdapter);
+            }
+            ("min" | "max" | "min_by" | "max_by" | "min_by_key" | "max_by_key", _) => {
+                let swapped = if let Some(rest) = method.strip_prefix("min") {
+                    format!("max{rest}")
+                } else {
+                    method.replacen("max", "min", 1)
+                };
+                let swapped = Ident::new(&swapped, i.method.span());
+                self.collect_mutant(
+                    i.method.span().into(),
+                    &quote! { #swapped },
+                    Genre::IteratorAdapter,
+                );
+            }
+            _ => (),
+        }
     }
 
-    /// Visit top-level `fn foo()`.
-    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
-        let function_name = i.sig.ident.to_pretty_string();
-        let _span = trace_span!(
-            "fn",
-            line = i.sig.fn_token.span.start().line,
-            name = function_name
-        )
-        .entered();
-        trace!("visit fn");
-        if fn_sig_excluded(&i.sig) || attrs_excluded(&i.attrs) || block_is_empty(&i.block) {
+    /// Generate mutants that replace the body of a closure with each of the given values.
+    fn collect_closure_value_mutants(
+        &mut self,
+        closure: &syn::ExprClosure,
+        replacements: impl IntoIterator<Item = String>,
+    ) {
+        if self.fn_stack.is_empty() {
             return;
         }
-        let function = self.enter_function(&i.sig.ident, &i.sig.output, i.span());
-        self.collect_fn_mutants(&i.sig, &i.block);
-        syn::visit::visit_item_fn(self, i);
-        self.leave_function(function);
+        let span = match &*closure.body {
+            // Replace the contents of the block, as for function bodies.
+            Expr::Block(syn::ExprBlock { block, .. }) => match function_body_span(block) {
+                Some(span) => span,
+                None => return,
+            },
+            body => body.span().into(),
+        };
+        let original = span.extract(self.source_file.code());
+        for rep in replacements {
+            if rep != original {
+                self.collect_mutant_text(span, rep, Genre::ClosureValue);
+            }
+        }
     }
 
-    /// Visit `fn foo()` within an `impl`.
-    fn visit_impl_item_fn(&mut self, i: &'ast syn::ImplItemFn) {
-        // Don't look inside constructors (called "new") because there's often no good
-        // alternative.
-        let function_name = i.sig.ident.to_pretty_string();
-        let _span = trace_span!(
-            "fn",
-            line = i.sig.fn_token.span.start().line,
-            name = function_name
-        )
-        .entered();
-        if fn_sig_excluded(&i.sig)
-            || attrs_excluded(&i.attrs)
-            || i.sig.ident == "new"
-            || block_is_empty(&i.block)
-        {
-            return;
-        }
-        let function = self.enter_function(&i.sig.ident, &i.sig.output, i.span());
-        self.collect_fn_mutants(&i.sig, &i.block);
-        syn::visit::visit_impl_item_fn(self, i);
-        self.leave_function(function);
+    /// Visit a `const` or `static` item, while recording that we're inside it.
+    fn visit_const_initializer(&mut self, visit: impl FnOnce(&mut Self)) {
+        let outer = std::mem::replace(&mut self.in_const_item, true);
+        visit(self);
+        self.in_const_item = outer;
     }
 
-    /// Visit `fn foo() { ... }` within a trait, i.e. a default implementation of a function.
-    fn visit_trait_item_fn(&mut self, i: &'ast syn::TraitItemFn) {
-        let function_name = i.sig.ident.to_pretty_string();
-        let _span = trace_span!(
-            "fn",
-            line = i.sig.fn_token.span.start().line,
-            name = function_name
-        )
-        .entered();
-        if fn_sig_excluded(&i.sig) || attrs_excluded(&i.attrs) || i.sig.ident == "new" {
-            return;
+    /// Generate mutants for a literal in a `const` or `static` initializer:
+    /// numbers are replaced by their neighbors, and booleans are flipped.
+    fn collect_const_value_mutants(&mut self, lit: &syn::Lit) {
+        for rep in numeric_literal_replacements(lit, &[1]) {
+            self.collect_mutant_text(lit.span().into(), rep, Genre::ConstValue);
         }
-        if let Some(block) = &i.default {
-            if block_is_empty(block) {
-                return;
-            }
-            let function = self.enter_function(&i.sig.ident, &i.sig.output, i.span());
-            self.collect_fn_mutants(&i.sig, block);
-            syn::visit::visit_trait_item_fn(self, i);
-            self.leave_function(function);
+        if let syn::Lit::Bool(lit_bool) = lit {
+            let flipped = !lit_bool.value;
+            self.collect_mutant(
+                lit_bool.span().into(),
+                &quote! { #flipped },
+                Genre::ConstValue,
+            );
         }
     }
 
-    /// Visit `impl Foo { ...}` or `impl Debug for Foo { ... }`.
-    fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
-        if attrs_excluded(&i.attrs) {
+    /// Visit the body of a loop, while recording that we're inside it.
+    fn visit_loop(&mut self, label: Option<&syn::Label>, visit: impl FnOnce(&mut Self)) {
+        self.loop_labels
+            .push(label.map(|label| label.name.to_string()));
+        visit(self);
+        self.loop_labels.pop();
+    }
+
+    /// Generate mutants that delete the `else` branches of an `if` statement.
+    ///
+    /// Without type information, this is only done when the `if` is evidently of type `()`:
+    /// when it's followed by a semicolon or other statements, or when it's the last statement
+    /// in the body of a function returning `()`.
+    ///
+    /// In an `else if` chain, each `else` and everything after it can be deleted.
+    fn collect_delete_else(&mut self, block: &Block, stmt: &syn::Stmt) {
+        let syn::Stmt::Expr(Expr::If(expr_if), semi) = stmt else {
+            return;
+        };
+        let Some(function) = self.fn_stack.last() else {
+            return;
+        };
+        let is_last = block
+            .stmts
+            .last()
+            .is_some_and(|last| std::ptr::eq(last, stmt));
+        if semi.is_none()
+            && is_last
+            && !(function.return_type.is_empty()
+                && function.span.end == Span::from(block.span()).end)
+        {
+            trace!("not deleting else from if expression that might have a value");
             return;
         }
-        let type_name = i.self_ty.to_pretty_string();
-        let name = if let Some((_, trait_path, _)) = &i.trait_ {
-            if path_ends_with(trait_path, "Default") {
-                // Can't think of how to generate a viable different default.
+        let mut expr_if = expr_if;
+        while let Some((else_token, else_expr)) = &expr_if.else_branch {
+            if self.attrs_excluded(&expr_if.attrs) {
                 return;
             }
-            format!("<impl {trait} for {type_name}>", trait = trait_path.to_pretty_string())
-        } else {
-            type_name
-        };
-        self.in_namespace(&name, |v| syn::visit::visit_item_impl(v, i));
+            let span = Span {
+                start: Span::from(else_token.span).start,
+                end: Span::from(else_expr.span()).end,
+            };
+            let if_line = Span::from(expr_if.if_token.span).start.line;
+            self.collect_described_mutant(
+                span,
+                String::new(),
+                Genre::DeleteElse,
+                format!("delete else branch of if at line {if_line}"),
+            );
+            match &**else_expr {
+                Expr::If(next_if) => expr_if = next_if,
+                _ => break,
+            }
+        }
     }
 
-    /// Visit `trait Foo { ... }`
-    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
-        let name = i.ident.to_pretty_string();
-        let _span = trace_span!("trait", line = i.span().start().line, name).entered();
-        if attrs_excluded(&i.attrs) {
+    /// Generate a mutant that replaces `T::try_from(x).unwrap()` with `(x as T)`,
+    /// for integer types `T`.
+    fn collect_try_from_unwrap_mutant(&mut self, i: &syn::ExprMethodCall) {
+        if i.method != "unwrap" || !i.args.is_empty() || self.fn_stack.is_empty() {
             return;
         }
-        self.in_namespace(&name, |v| syn::visit::visit_item_trait(v, i));
+        let Expr::Call(syn::ExprCall { func, args, .. }) = &*i.receiver else {
+            return;
+        };
+        let Expr::Path(ExprPath { path, .. }) = &**func else {
+            return;
+        };
+        if path.segments.len() != 2 || path.segments[1].ident != "try_from" || args.len() != 1 {
+            return;
+        }
+        let type_name = path.segments[0].ident.to_string();
+        if !is_integer_type(&type_name) {
+            return;
+        }
+        let arg = &args[0];
+        let arg_span: Span = arg.span().into();
+        let mut arg_text = arg_span.extract(self.source_file.code());
+        if !matches!(
+            arg,
+            Expr::Call(_)
+                | Expr::Field(_)
+                | Expr::Index(_)
+                | Expr::Lit(_)
+                | Expr::MethodCall(_)
+                | Expr::Paren(_)
+                | Expr::Path(_)
+        ) {
+            // `as` binds more tightly than most operators.
+            arg_text = format!("({arg_text})");
+        }
+        self.collect_mutant_text(
+            i.span().into(),
+            format!("({arg_text} as {type_name})"),
+            Genre::Cast,
+        );
     }
 
-    /// Visit `mod foo { ... }` or `mod foo;`.
-    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
-        let mod_name = node.ident.unraw().to_string();
-        let _span = trace_span!("mod", line = node.mod_token.span.start().line, mod_name).entered();
-        if attrs_excluded(&node.attrs) {
-            trace!("mod excluded by attrs");
+    /// Generate a mutant that swaps the operands of a non-commutative operator.
+    ///
+    /// `a < b` and `a > b` are not swapped, because `b < a` is the same as replacing
+    /// the operator with `>`, which is already generated.
+    fn collect_swap_operands(&mut self, i: &syn::ExprBinary) {
+        if !matches!(
+            i.op,
+            BinOp::Sub(_) | BinOp::Div(_) | BinOp::Rem(_) | BinOp::Le(_) | BinOp::Ge(_)
+        ) {
+            return;
+        }
+        let code = self.source_file.code();
+        let operand_text = |expr: &Expr| {
+            let text = Span::from(expr.span()).extract(code);
+            if matches!(
+                expr,
+                Expr::Assign(_) | Expr::Binary(_) | Expr::Closure(_) | Expr::Range(_)
+            ) {
+                format!("({text})")
+            } else {
+                text
+            }
+        };
+        let left = operand_text(&i.left);
+        let right = operand_text(&i.right);
+        if left == right {
             return;
         }
+        let op = i.op.to_pretty_string();
+        self.collect_mutant_text(
+            i.span().into(),
+            format!("{right} {op} {left}"),
+            Genre::SwapOperands,
+        );
+    }
 
-        let source_location = Span::from(node.span());
+    /// Generate mutants that add one to and subtract one from an index.
+    ///
+    /// Only indexes that look like integers are mutated: ranges, references, and
+    /// non-integer literals are probably slicing or looking up keys in a map. An index
+    /// of literal `0` is not decremented, because that would fail to compile.
+    fn collect_index_mutants(&mut self, index: &Expr) {
+        if self.fn_stack.is_empty() {
+            return;
+        }
+        let span: Span = index.span().into();
+        match index {
+            Expr::Lit(syn::ExprLit {
+                lit: lit @ syn::Lit::Int(_),
+                ..
+            }) => {
+                if self.options.allows_genre(Genre::NumericLiteral) {
+                    // The same mutants will be generated from the literal.
+                    return;
+                }
+                for rep in numeric_literal_replacements(lit, &[1]) {
+                    self.collect_mutant_text(span, rep, Genre::Index);
+                }
+            }
+            Expr::Assign(_)
+            | Expr::Closure(_)
+            | Expr::Lit(_)
+            | Expr::Range(_)
+            | Expr::Reference(_) => {}
+            _ => {
+                let mut original = span.extract(self.source_file.code());
+                if matches!(index, Expr::Binary(_) | Expr::Cast(_) | Expr::Unary(_)) {
+                    // Operators like `&` and `<<` bind more loosely than `+`.
+                    original = format!("({original})");
+                }
+                self.collect_mutant_text(span, format!("{original} + 1"), Genre::Index);
+                self.collect_mutant_text(span, format!("{original} - 1"), Genre::Index);
+            }
+        }
+    }
 
-        // Extract path attribute value, if any (e.g. `#[path="..."]`)
-        let path_attribute = match find_path_attribute(&node.attrs) {
-            Ok(path) => path,
-            Err(path_attribute) => {
-                let definition_site = self
-                    .source_file
-                    .format_source_location(source_location.start);
-                error!(?path_attribute, ?definition_site, %mod_name, "invalid filesystem traversal in mod path attribute");
+    /// Generate a mutant that swaps `first()` and `last()`, or their `_mut` variants.
+    fn collect_first_last_mutant(&mut self, i: &syn::ExprMethodCall) {
+        if !i.args.is_empty() || self.fn_stack.is_empty() {
+            return;
+        }
+        let swapped = match i.method.to_string().as_str() {
+            "first" => "last",
+            "last" => "first",
+            "first_mut" => "last_mut",
+            "last_mut" => "first_mut",
+            _ => return,
+        };
+        self.collect_mutant_text(i.method.span().into(), swapped.to_owned(), Genre::Index);
+    }
+
+    /// Generate a mutant that flips a `true` or `false` literal.
+    ///
+    /// This is only called for arguments and struct fields: literals returned from
+    /// a function are already covered by replacing the function body.
+    fn collect_flip_bool(&mut self, expr: &Expr) {
+        if self.fn_stack.is_empty() {
+            return;
+        }
+        if let Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Bool(lit_bool),
+            ..
+        }) = expr
+        {
+            let flipped = !lit_bool.value;
+            self.collect_mutant(
+                lit_bool.span().into(),
+                &quote! { #flipped },
+                Genre::BooleanLiteral,
+            );
+        }
+    }
+
+    /// Generate mutants that shift by one more or one less.
+    fn collect_shift_amount_mutants(&mut self, amount: &Expr) {
+        let span: Span = amount.span().into();
+        if let Expr::Lit(syn::ExprLit { lit, .. }) = amount {
+            if self.options.allows_genre(Genre::NumericLiteral) {
+                // The same mutants will be generated from the literal.
                 return;
             }
-        };
-        let mod_namespace = ModNamespace {
-            name: mod_name,
-            path_attribute,
-            source_location,
-        };
-        self.mod_namespace_stack.push(mod_namespace.clone());
+            for rep in numeric_literal_replacements(lit, &[1]) {
+                self.collect_mutant_text(span, rep, Genre::ShiftAmount);
+            }
+        } else {
+            let original = span.extract(self.source_file.code());
+            for op in ["+", "-"] {
+                self.collect_mutant_text(span, format!("({original} {op} 1)"), Genre::ShiftAmount);
+            }
+        }
+    }
 
-        // If there's no content in braces, then this is a `mod foo;`
-        // statement referring to an external file. We remember the module
-        // name and then later look for the file.
-        if node.content.is_none() {
-            // If we're already inside `mod a { ... }` and see `mod b;` then
-            // remember [a, b] as an external module to visit later.
-            self.external_mods.push(ExternalModRef {
-                parts: self.mod_namespace_stack.clone(),
-            });
+    /// Generate a mutant that negates the condition of an `if` or `while`.
+    fn collect_negate_condition(&mut self, cond: &Expr) {
+        if expr_contains_let(cond) {
+            // `if let` and let chains can't be negated.
+            trace!("not negating condition containing let");
+            return;
         }
-        self.in_namespace(&mod_namespace.name, |v| syn::visit::visit_item_mod(v, node));
-        assert_eq!(self.mod_namespace_stack.pop(), Some(mod_namespace));
+        if matches!(
+            cond,
+            Expr::Unary(syn::ExprUnary {
+                op: UnOp::Not(_),
+                ..
+            })
+        ) {
+            // Deleting the `!` is already generated as a unary operator mutant.
+            return;
+        }
+        let span: Span = cond.span().into();
+        let original = span.extract(self.source_file.code());
+        self.collect_mutant_text(span, format!("!({original})"), Genre::NegateCondition);
     }
 
-    /// Visit `a op b` expressions.
-    fn visit_expr_binary(&mut self, i: &'ast syn::ExprBinary) {
-        let _span = trace_span!("binary", line = i.op.span().start().line).entered();
-        trace!("visit binary operator");
-        if attrs_excluded(&i.attrs) {
+    /// Generate mutants that force the pattern of an `if let` to match, or not to match.
+    ///
+    /// When forced to match, the pattern is bound with `let ... else` that panics if
+    /// the value doesn't match, and then the body is run. When forced not to match,
+    /// the whole expression is replaced by its `else` branch, or by an empty block.
+    fn collect_if_let_mutants(&mut self, i: &syn::ExprIf) {
+        let Expr::Let(expr_let) = &*i.cond else {
+            return;
+        };
+        if self.fn_stack.is_empty() {
             return;
         }
-        let replacements = match i.op {
-            // We don't generate `<=` from `==` because it can too easily go
-            // wrong with unsigned types compared to 0.
+        let code = self.source_file.code();
+        let pat = Span::from(expr_let.pat.span()).extract(code);
+        let mut scrutinee = Span::from(expr_let.expr.span()).extract(code);
+        if scrutinee.ends_with('}') {
+            // `let ... else` doesn't allow the expression to end with a brace.
+            scrutinee = format!("({scrutinee})");
+        }
+        let then_branch = Span::from(i.then_branch.span()).extract(code);
+        let (not_taken, not_taken_name) = match &i.else_branch {
+            Some((_, else_expr)) => (
+                Span::from(else_expr.span()).extract(code),
+                "its else branch",
+            ),
+            None => ("{}".to_owned(), "{}"),
+        };
+        let span: Span = i.span().into();
+        // The whole expression is replaced, so the name shows only the pattern.
+        let pat_name = one_line(&pat);
+        self.collect_described_mutant(
+            span,
+            format!("{{ let {pat} = {scrutinee} else {{ panic!() }}; {then_branch} }}"),
+            Genre::LetPattern,
+            format!("force if let {pat_name} branch taken"),
+        );
+        self.collect_described_mutant(
+            span,
+            not_taken,
+            Genre::LetPattern,
+            format!("replace if let {pat_name} with {not_taken_name}"),
+        );
+    }
 
-            // We try replacing logical ops with == and !=, which are effectively
-            // XNOR and XOR when applied to booleans. However, they're often unviable
-            // because they require parenthesis for disambiguation in many expressions.
-            BinOp::Eq(_) => vec![quote! { != }],
-            BinOp::Ne(_) => vec![quote! { == }],
-            BinOp::And(_) => vec![quote! { || }],
-            BinOp::Or(_) => vec![quote! { && }],
-            BinOp::Lt(_) => vec![quote! { == }, quote! {>}],
-            BinOp::Gt(_) => vec![quote! { == }, quote! {<}],
-            BinOp::Le(_) => vec![quote! {>}],
-            BinOp::Ge(_) => vec![quote! {<}],
-            BinOp::Add(_) => vec![quote! {-}, quote! {*}],
-            BinOp::AddAssign(_) => vec![quote! {-=}, quote! {*=}],
-            BinOp::Sub(_) | BinOp::Mul(_) => vec![quote! {+}, quote! {/}],
-            BinOp::SubAssign(_) | BinOp::MulAssign(_) => vec![quote! {+=}, quote! {/=}],
-            BinOp::Div(_) => vec![quote! {%}, quote! {*}],
-            BinOp::DivAssign(_) => vec![quote! {%=}, quote! {*=}],
-            BinOp::Rem(_) => vec![quote! {/}, quote! {+}],
-            BinOp::RemAssign(_) => vec![quote! {/=}, quote! {+=}],
-            BinOp::Shl(_) => vec![quote! {>>}],
-            BinOp::ShlAssign(_) => vec![quote! {>>=}],
-            BinOp::Shr(_) => vec![quote! {<<}],
-            BinOp::ShrAssign(_) => vec![quote! {<<=}],
-            BinOp::BitAnd(_) => vec![quote! {|}, quote! {^}],
-            BinOp::BitAndAssign(_) => vec![quote! {|=}, quote! {^=}],
-            BinOp::BitOr(_) => vec![quote! {&}, quote! {^}],
-            BinOp::BitOrAssign(_) => vec![quote! {&=}, quote! {^=}],
-            BinOp::BitXor(_) => vec![quote! {|}, quote! {&}],
-            BinOp::BitXorAssign(_) => vec![quote! {|=}, quote! {&=}],
-            _ => {
-                trace!(
-                    op = i.op.to_pretty_string(),
-                    "No mutants generated for this binary operator"
-                );
-                Vec::new()
-            }
+    /// Generate a mutant that makes a `let ... else` statement always diverge, by running
+    /// its `else` block first.
+    fn collect_let_else_mutant(&mut self, local: &syn::Local) {
+        let Some(syn::LocalInit {
+            diverge: Some((_, diverge)),
+            ..
+        }) = &local.init
+        else {
+            return;
         };
-        replacements
-            .into_iter()
-            .for_each(|rep| self.collect_mutant(i.op.span().into(), &rep, Genre::BinaryOperator));
-        syn::visit::visit_expr_binary(self, i);
+        if self.fn_stack.is_empty() {
+            return;
+        }
+        let code = self.source_file.code();
+        let span: Span = local.span().into();
+        let diverge = Span::from(diverge.span()).extract(code);
+        let original = span.extract(code);
+        let pat = one_line(&Span::from(local.pat.span()).extract(code));
+        self.collect_described_mutant(
+            span,
+            format!("{diverge} {original}"),
+            Genre::LetPattern,
+            format!("force let {pat} else branch taken"),
+        );
     }
 
-    fn visit_expr_unary(&mut self, i: &'ast syn::ExprUnary) {
-        let _span = trace_span!("unary", line = i.op.span().start().line).entered();
-        trace!("visit unary operator");
-        if attrs_excluded(&i.attrs) {
+    /// Generate mutants that delete `return` statements from guard clauses like
+    /// `if cond { return x; }`, so that the function continues instead.
+    ///
+    /// This is only done for `if` without an `else`, whose type is always `()`, so
+    /// that the code should still compile without the `return`.
+    fn collect_early_return_mutants(&mut self, i: &syn::ExprIf) {
+        if i.else_branch.is_some() || self.fn_stack.is_empty() {
             return;
         }
-        match i.op {
-            UnOp::Not(_) | UnOp::Neg(_) => {
-                self.collect_mutant(i.op.span().into(), &quote! {}, Genre::UnaryOperator);
+        for stmt in &i.then_branch.stmts {
+            if let syn::Stmt::Expr(Expr::Return(expr_return), _) = stmt {
+                if !self.attrs_excluded(&expr_return.attrs) {
+                    self.collect_mutant(
+                        stmt.span().into(),
+                        &TokenStream::new(),
+                        Genre::EarlyReturn,
+                    );
+                }
             }
-            _ => {
-                trace!(
-                    op = i.op.to_pretty_string(),
-                    "No mutants generated for this unary operator"
-                );
+        }
+    }
+
+    /// Generate a mutant that deletes a statement calling a function or method.
+    ///
+    /// Only statements terminated by a semicolon are deleted, because their value is
+    /// discarded and so the code should still compile without them.
+    fn collect_delete_statement(&mut self, block: &Block, stmt: &syn::Stmt) {
+        let syn::Stmt::Expr(expr, Some(_semi)) = stmt else {
+            return;
+        };
+        let call = match expr {
+            Expr::Try(try_expr) => &*try_expr.expr,
+            expr => expr,
+        };
+        match call {
+            Expr::Call(syn::ExprCall { attrs, func, .. }) => {
+                if self.attrs_excluded(attrs) {
+                    return;
+                }
+                if let Expr::Path(ExprPath { path, .. }) = &**func {
+                    if self
+                        .options
+                        .skip_calls
+                        .iter()
+                        .any(|s| path_ends_with(path, s))
+                    {
+                        return;
+                    }
+                }
+            }
+            Expr::MethodCall(syn::ExprMethodCall { attrs, method, .. }) => {
+                if self.attrs_excluded(attrs) || self.options.skip_calls.iter().any(|s| method == s)
+                {
+                    return;
+                }
             }
+            _ => return,
+        }
+        let Some(function) = self.fn_stack.last() else {
+            return;
         };
-        syn::visit::visit_expr_unary(self, i);
+        if block.stmts.len() == 1
+            && function.return_type.is_empty()
+            && function.span.end == Span::from(block.span()).end
+        {
+            // Deleting the only statement of a function returning `()` is the same
+            // as replacing the function body with `()`.
+            trace!("not deleting only statement in function body");
+            return;
+        }
+        self.collect_mutant(
+            stmt.span().into(),
+            &TokenStream::new(),
+            Genre::DeleteStatement,
+        );
     }
 
-    fn visit_expr_match(&mut self, i: &'ast syn::ExprMatch) {
-        let _span = trace_span!("match", line = i.span().start().line).entered();
+    /// Generate a mutant that deletes `.await` from a statement like `send(msg).await;`,
+    /// so that the future is created but never run.
+    ///
+    /// Only statements whose value is discarded are mutated, because the type of the
+    /// future's output isn't known, and otherwise it would probably be unviable.
+    fn collect_delete_await(&mut self, stmt: &syn::Stmt) {
+        let syn::Stmt::Expr(Expr::Await(expr_await), Some(_semi)) = stmt else {
+            return;
+        };
+        if self.attrs_excluded(&expr_await.attrs) || self.fn_stack.is_empty() {
+            return;
+        }
+        let span = Span {
+            start: Span::from(expr_await.dot_token.span).start,
+            end: Span::from(expr_await.await_token.span).end,
+        };
+        self.collect_mutant(span, &TokenStream::new(), Genre::Await);
+    }
 
-        // While it's not currently possible to annotate expressions with custom attributes, this
-        // limitation could be lifted in the future.
-        if attrs_excluded(&i.attrs) {
-            trace!("match excluded by attrs");
+    /// Generate mutants that swap each pair of adjacent branches in a biased `select!`.
+    ///
+    /// Without `biased;`, tokio's `select!` polls the branches in a random order, so
+    /// their order doesn't matter.
+    fn collect_select_branch_mutants(&mut self, name: &str, tokens: &TokenStream) {
+        let tokens = tokens.clone().into_iter().collect::<Vec<_>>();
+        let branch_tokens = match (name, &tokens[..]) {
+            ("select", [TokenTree::Ident(biased), TokenTree::Punct(semi), rest @ ..])
+                if biased == "biased" && semi.as_char() == ';' =>
+            {
+                rest
+            }
+            ("select_biased", rest) => rest,
+            _ => return,
+        };
+        let Some(branches) = select_branches(branch_tokens) else {
+            trace!("can't parse select branches");
             return;
+        };
+        let code = self.source_file.code();
+        let mutants = branches
+            .windows(2)
+            .map(|pair| {
+                let [(first, _), (second, second_has_comma)] = pair else {
+                    unreachable!()
+                };
+                let between = Span {
+                    start: first.end,
+                    end: second.start,
+                };
+                // Commas are optional after a handler in braces, so add them after both
+                // branches in their new positions to make sure they're still separated.
+                let replacement = format!(
+                    "{},{}{}{}",
+                    second.extract(code),
+                    between.extract(code).trim_start_matches(','),
+                    first.extract(code),
+                    if *second_has_comma { "" } else { "," },
+                );
+                let span = Span {
+                    start: first.start,
+                    end: second.end,
+                };
+                (span, replacement)
+            })
+            .collect::<Vec<_>>();
+        for (span, replacement) in mutants {
+            self.collect_mutant_text(span, replacement, Genre::SelectBranch);
         }
+    }
 
-        let has_catchall = i
-            .arms
-            .iter()
-            .any(|arm| matches!(arm.pat, syn::Pat::Wild(_)));
-        if has_catchall {
-            i.arms
-                .iter()
-                // Don't mutate the wild arm, because that will likely be unviable, and also
-                // skip it if a guard is present, because the replacement of the guard with 'false'
-                // below is logically equivalent to removing the arm.
-                .filter(|arm| !matches!(arm.pat, syn::Pat::Wild(_)) && arm.guard.is_none())
-                .for_each(|arm| {
-                    self.collect_mutant(arm.span().into(), &quote! {}, Genre::MatchArm);
-                });
-        } else {
-            trace!("match has no `_` pattern");
+    /// Generate mutants that replace the duration passed to `timeout`, like the first
+    /// argument of `tokio::time::timeout(duration, future)`, with zero and the maximum.
+    fn collect_timeout_mutants(&mut self, duration: &Expr) {
+        if self.fn_stack.is_empty() {
+            return;
+        }
+        for replacement in ["::std::time::Duration::ZERO", "::std::time::Duration::MAX"] {
+            self.collect_mutant_text(
+                duration.span().into(),
+                replacement.to_owned(),
+                Genre::Timeout,
+            );
         }
+    }
 
-        i.arms
-            .iter()
-            .flat_map(|arm| &arm.guard)
-            .for_each(|(_if, guard_expr)| {
-                self.collect_mutant(
-                    guard_expr.span().into(),
-                    &quote! { true },
-                    Genre::MatchArmGuard,
-                );
-                self.collect_mutant(
-                    guard_expr.span().into(),
-                    &quote! { false },
-                    Genre::MatchArmGuard,
-                );
-            });
+    /// Mutate the impls generated by `#[derive]` attributes on a type, if macros were
+    /// expanded.
+    ///
+    /// Each mutant replaces the type definition with a copy that doesn't derive the
+    /// trait, followed by the mutated impl.
+    fn collect_derive_mutants(&mut self, attrs: &[Attribute], ident: &Ident, item_span: Span) {
+        use syn::punctuated::Punctuated;
+        let derived_impls = self.derived_impls;
+        if derived_impls.is_empty() {
+            return;
+        }
+        let source_file = self.source_file.clone();
+        let code = source_file.code();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("derive")) {
+            let Ok(paths) =
+                attr.parse_args_with(Punctuated::<syn::Path, syn::Token![,]>::parse_terminated)
+            else {
+                continue;
+            };
+            let attr_span = Span::from(attr.span());
+            for (i, path) in paths.iter().enumerate() {
+                let Some(trait_name) = path.segments.last().map(|s| s.ident.to_string()) else {
+                    continue;
+                };
+                let Some(Some(impl_code)) = derived_impls.get(&(ident.to_string(), trait_name))
+                else {
+                    continue;
+                };
+                let macro_name = format!("#[derive({})]", path.to_pretty_string());
+                let impl_file = SourceFile {
+                    code: Arc::new(impl_code.clone()),
+                    ..source_file.clone()
+                };
+                let impl_mutants = match walk_file(
+                    &impl_file,
+                    self.in_private_mod(),
+                    self.error_values,
+                    &DerivedImpls::new(),
+                    self.cfg,
+                    self.options,
+                ) {
+                    Ok((mutants, _, _)) => mutants,
+                    Err(err) => {
+                        warn!("Failed to mutate expansion of {macro_name} on {ident}: {err:#}");
+                        continue;
+                    }
+                };
+                let other_paths: Vec<String> = paths
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| *j != i)
+                    .map(|(_, p)| p.to_pretty_string())
+                    .collect();
+                let new_attr = if other_paths.is_empty() {
+                    String::new()
+                } else {
+                    format!("#[derive({})]", other_paths.join(", "))
+                };
+                let before_attr = Span {
+                    start: item_span.start,
+                    end: attr_span.start,
+                }
+                .extract(code);
+                let after_attr = Span {
+                    start: attr_span.end,
+                    end: item_span.end,
+                }
+                .extract(code);
+                for impl_mutant in impl_mutants {
+                    self.mutants.push(Mutant {
+                        source_file: source_file.clone(),
+                        function: None,
+                        span: item_span,
+                        replacement: format!(
+                            "{before_attr}{new_attr}{after_attr}\n{}",
+                            impl_mutant.mutated_code()
+                        ),
+                        genre: impl_mutant.genre,
+                        expansion: Some(Arc::new(Expansion {
+                            macro_name: macro_name.clone(),
+                            mutant: impl_mutant,
+                        })),
+                        description: None,
+                    });
+                }
+            }
+        }
+    }
 
-        syn::visit::visit_expr_match(self, i);
+    /// Call a function with a namespace pushed onto the stack.
+    ///
+    /// This is used when recursively descending into a namespace.
+    fn in_namespace<F, T>(&mut self, name: &str, f: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        self.namespace_stack.push(name.to_owned());
+        let r = f(self);
+        assert_eq!(self.namespace_stack.pop().unwrap(), name);
+        r
     }
 }
 
-// Get the span of the block excluding the braces, or None if it is empty.
-fn function_body_span(block: &Block) -> Option<Span> {
-    Some(Span {
-        start: block.stmts.first()?.span().start().into(),
-        end: block.stmts.last()?.span().end().into(),
-    })
-}
-
-/// Find a new source file referenced by a `mod` statement.
-///
-/// Possibly, our heuristics just won't be able to find which file it is,
-/// in which case we return `Ok(None)`.
-fn find_mod_source(
-    tree_root: &Utf8Path,
-    parent: &SourceFile,
-    mod_namespace: &ExternalModRef,
-) -> Option<Utf8PathBuf> {
-    // First, work out whether the mod will be a sibling in the same directory, or
-    // in a child directory.
-    //
-    // 1. The parent is "src/foo.rs" and `mod bar` means "src/foo/bar.rs".
-    //
-    // 2. The parent is "src/lib.rs" (a target top file) and `mod bar` means "src/bar.rs".
-    //
-    // 3. The parent is "src/foo/mod.rs" and so `mod bar` means "src/foo/bar.rs".
-    //
-    // 4. A path attribute on a mod statement when there is no enclosing mod block
-    //     E.g. for parent file "src/a/parent_file.rs",
-    //     ```
-    //     // `path` is relative to the directory where the source file is located
-    //     #[path="foo_file.rs"] // resolves to: src/a/foo_file.rs
-    //     mod foo;
-    //
-    //     mod bar {
-    //         // `path` is relative to the directory of the enclosing module block
-    //         #[path="baz_file.rs"] // resolves to: src/a/parent_file/bar/baz_file.rs
-    //         mod baz;
-    //     }
-    //     ```
-    //
-    // Having determined the right directory then we can follow the path attribute, or
-    // if no path is specified, then look for either `foo.rs` or `foo/mod.rs`.
-
-    let (mod_child, mod_parents) = mod_namespace
-        .parts
-        .split_last()
-        .expect("mod namespace is empty");
+impl<'ast> Visit<'ast> for DiscoveryVisitor<'_> {
+    fn visit_expr_call(&mut self, i: &'ast syn::ExprCall) {
+        let _span = trace_span!("expr_call", line = i.span().start().line).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if let Expr::Path(ExprPath { path, .. }) = &*i.func {
+            debug!(path = path.to_pretty_string(), "visit call");
+            if let Some(hit) = self
+                .options
+                .skip_calls
+                .iter()
+                .find(|s| path_ends_with(path, s))
+            {
+                trace!("skip call to {hit}");
+                return;
+            }
+            if let Some(last) = path.segments.last() {
+                self.collect_swap_arguments(&last.ident, false, &i.args);
+            }
+            if path_ends_with(path, "timeout") && i.args.len() == 2 {
+                self.collect_timeout_mutants(&i.args[0]);
+            }
+        }
+        i.args.iter().for_each(|arg| self.collect_flip_bool(arg));
+        syn::visit::visit_expr_call(self, i);
+    }
 
-    // TODO: Beyond #115, we should probably remove all special handling of
-    // `mod.rs` here by remembering how we found this file, and whether it
-    // is above or inside the directory corresponding to its module?
+    fn visit_expr_method_call(&mut self, i: &'ast syn::ExprMethodCall) {
+        let _span = trace_span!("expr_method_call", line = i.span().start().line).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if let Some(hit) = self.options.skip_calls.iter().find(|s| i.method == s) {
+            trace!("skip method call to {hit}");
+            return;
+        }
+        self.collect_swap_arguments(&i.method, true, &i.args);
+        i.args.iter().for_each(|arg| self.collect_flip_bool(arg));
+        if i.method == "timeout" && i.args.len() == 1 {
+            self.collect_timeout_mutants(&i.args[0]);
+        }
+        self.collect_combinator_mutants(i);
+        self.collect_iterator_adapter_mutants(i);
+        self.collect_try_from_unwrap_mutant(i);
+        self.collect_first_last_mutant(i);
+        let closure_reps = closure_value_replacements(&i.method.to_string());
+        if !closure_reps.is_empty() {
+            for arg in &i.args {
+                if let Expr::Closure(closure) = arg {
+                    if matches!(closure.output, ReturnType::Default) {
+                        // Closures with a declared return type are mutated when they're visited.
+                        self.collect_closure_value_mutants(
+                            closure,
+                            closure_reps.iter().map(|rep| (*rep).to_owned()),
+                        );
+                    }
+                }
+            }
+        }
+        for rep in arithmetic_method_replacements(&i.method.to_string()) {
+            let rep = Ident::new(rep, i.method.span());
+            self.collect_mutant(
+                i.method.span().into(),
+                &quote! { #rep },
+                Genre::ArithmeticMethod,
+            );
+        }
+        syn::visit::visit_expr_method_call(self, i);
+    }
 
-    let parent_path = &parent.tree_relative_path;
-    let mut search_dir = if parent.is_top
-        || parent_path.ends_with("mod.rs")
-        // NOTE: Path attribute on a top-level `mod foo;` (no enclosing block)
-        //       ignores the parent module path
-        || (mod_child.path_attribute.is_some() && mod_parents.is_empty())
-    {
-        parent_path
-            .parent()
-            .expect("mod path has no parent")
-            .to_owned() // src/lib.rs -> src/
-    } else {
-        parent_path.with_extension("") // foo.rs -> foo/
-    };
+    /// Visit a source file.
+    fn visit_file(&mut self, i: &'ast File) {
+        // No trace here; it's created per file for the whole visitor
+        if self.attrs_excluded(&i.attrs) {
+            trace!("file excluded by attrs");
+            return;
+        }
+        syn::visit::visit_file(self, i);
+    }
 
-    search_dir.extend(mod_parents.iter().map(ModNamespace::get_filesystem_name));
+    /// Visit top-level `fn foo()`.
+    fn visit_item_fn(&mut self, i: &'ast ItemFn) {
+        let function_name = i.sig.ident.to_pretty_string();
+        let _span = trace_span!(
+            "fn",
+            line = i.sig.fn_token.span.start().line,
+            name = function_name
+        )
+        .entered();
+        trace!("visit fn");
+        if fn_sig_excluded(&i.sig)
+            || self.attrs_excluded(&i.attrs)
+            || block_is_empty(&i.block)
+            || self.excluded_by_visibility(&i.vis)
+        {
+            return;
+        }
+        let function = self.enter_function(&i.sig, &i.block, i.span());
+        self.generics_stack.push(i.sig.generics.clone());
+        self.collect_fn_mutants(&i.sig, &i.block);
+        syn::visit::visit_item_fn(self, i);
+        self.generics_stack.pop();
+        self.leave_function(function);
+    }
 
-    let mod_child_candidates = if let Some(filesystem_name) = &mod_child.path_attribute {
-        vec![search_dir.join(filesystem_name)]
-    } else {
-        [".rs", "/mod.rs"]
-            .iter()
-            .map(|tail| search_dir.join(mod_child.name.clone() + tail))
-            .collect()
-    };
+    /// Visit `fn foo()` within an `impl`.
+    fn visit_impl_item_fn(&mut self, i: &'ast syn::ImplItemFn) {
+        // Don't look inside constructors (called "new") because there's often no good
+        // alternative.
+        let function_name = i.sig.ident.to_pretty_string();
+        let _span = trace_span!(
+            "fn",
+            line = i.sig.fn_token.span.start().line,
+            name = function_name
+        )
+        .entered();
+        if fn_sig_excluded(&i.sig)
+            || self.attrs_excluded(&i.attrs)
+            || i.sig.ident == "new"
+            || block_is_empty(&i.block)
+            || (!self.impl_items_public && self.excluded_by_visibility(&i.vis))
+        {
+            return;
+        }
+        let function = self.enter_function(&i.sig, &i.block, i.span());
+        self.generics_stack.push(i.sig.generics.clone());
+        self.collect_fn_mutants(&i.sig, &i.block);
+        syn::visit::visit_impl_item_fn(self, i);
+        self.generics_stack.pop();
+        self.leave_function(function);
+    }
 
-    let mut tried_paths = Vec::new();
-    for relative_path in mod_child_candidates {
-        let full_path = tree_root.join(&relative_path);
-        if full_path.is_file() {
-            trace!("found submodule in {full_path}");
-            return Some(relative_path);
+    /// Visit `fn foo() { ... }` within a trait, i.e. a default implementation of a function.
+    fn visit_trait_item_fn(&mut self, i: &'ast syn::TraitItemFn) {
+        let function_name = i.sig.ident.to_pretty_string();
+        let _span = trace_span!(
+            "fn",
+            line = i.sig.fn_token.span.start().line,
+            name = function_name
+        )
+        .entered();
+        if fn_sig_excluded(&i.sig)
+            || self.attrs_excluded(&i.attrs)
+            || i.sig.ident == "new"
+            || (self.options.only_pub && !self.impl_items_public)
+        {
+            return;
+        }
+        if let Some(block) = &i.default {
+            if block_is_empty(block) {
+                return;
+            }
+            let function = self.enter_function(&i.sig, block, i.span());
+            self.generics_stack.push(i.sig.generics.clone());
+            self.collect_fn_mutants(&i.sig, block);
+            syn::visit::visit_trait_item_fn(self, i);
+            self.generics_stack.pop();
+            self.leave_function(function);
         }
-        tried_paths.push(full_path);
     }
-    let mod_name = &mod_child.name;
-    let definition_site = parent.format_source_location(mod_child.source_location.start);
-    warn!(?definition_site, %mod_name, ?tried_paths, "referent of mod not found");
-    None
-}
 
-/// True if the signature of a function is such that it should be excluded.
-fn fn_sig_excluded(sig: &syn::Signature) -> bool {
-    if sig.unsafety.is_some() {
-        trace!("Skip unsafe fn");
-        true
-    } else {
-        false
+    /// Visit `struct Foo { ... }`, to mutate code generated by its derives.
+    fn visit_item_struct(&mut self, i: &'ast syn::ItemStruct) {
+        if !self.attrs_excluded(&i.attrs) && !self.excluded_by_visibility(&i.vis) {
+            self.collect_derive_mutants(&i.attrs, &i.ident, i.span().into());
+        }
+        syn::visit::visit_item_struct(self, i);
     }
-}
 
-/// True if any of the attrs indicate that we should skip this node and everything inside it.
-///
-/// This checks for `#[cfg(test)]`, `#[test]`, and `#[mutants::skip]`.
-fn attrs_excluded(attrs: &[Attribute]) -> bool {
-    attrs
-        .iter()
-        .any(|attr| attr_is_cfg_test(attr) || attr_is_test(attr) || attr_is_mutants_skip(attr))
-}
+    /// Visit `enum Foo { ... }`, to mutate code generated by its derives.
+    fn visit_item_enum(&mut self, i: &'ast syn::ItemEnum) {
+        if !self.attrs_excluded(&i.attrs) && !self.excluded_by_visibility(&i.vis) {
+            self.collect_derive_mutants(&i.attrs, &i.ident, i.span().into());
+        }
+        syn::visit::visit_item_enum(self, i);
+    }
 
-/// True if the block (e.g. the contents of a function) is empty.
-fn block_is_empty(block: &syn::Block) -> bool {
-    block.stmts.is_empty()
-}
+    /// Visit `union Foo { ... }`, to mutate code generated by its derives.
+    fn visit_item_union(&mut self, i: &'ast syn::ItemUnion) {
+        if !self.attrs_excluded(&i.attrs) && !self.excluded_by_visibility(&i.vis) {
+            self.collect_derive_mutants(&i.attrs, &i.ident, i.span().into());
+        }
+        syn::visit::visit_item_union(self, i);
+    }
 
-/// True if the attribute looks like `#[cfg(test)]`, or has "test"
-/// anywhere in it.
-fn attr_is_cfg_test(attr: &Attribute) -> bool {
-    if !path_is(attr.path(), &["cfg"]) {
-        return false;
+    /// Visit `impl Foo { ...}` or `impl Debug for Foo { ... }`.
+    fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        let type_name = i.self_ty.to_pretty_string();
+        let name = if let Some((_, trait_path, _)) = &i.trait_ {
+            if path_ends_with(trait_path, "Default") {
+                // Can't think of how to generate a viable different default.
+                return;
+            }
+            format!("<impl {trait} for {type_name}>", trait = trait_path.to_pretty_string())
+        } else {
+            type_name
+        };
+        self.generics_stack.push(i.generics.clone());
+        let outer_public = std::mem::replace(&mut self.impl_items_public, i.trait_.is_some());
+        self.in_namespace(&name, |v| syn::visit::visit_item_impl(v, i));
+        self.impl_items_public = outer_public;
+        self.generics_stack.pop();
     }
-    let mut contains_test = false;
-    if let Err(err) = attr.parse_nested_meta(|meta| {
-        if meta.path.is_ident("test") {
-            contains_test = true;
+
+    /// Visit `trait Foo { ... }`
+    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
+        let name = i.ident.to_pretty_string();
+        let _span = trace_span!("trait", line = i.span().start().line, name).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
         }
-        Ok(())
-    }) {
-        debug!(
-            ?err,
-            attr = attr.to_pretty_string(),
-            "Attribute is in an unrecognized form so skipped",
-        );
-        return false;
+        self.generics_stack.push(i.generics.clone());
+        let public = matches!(i.vis, syn::Visibility::Public(_)) && !self.in_private_mod();
+        let outer_public = std::mem::replace(&mut self.impl_items_public, public);
+        self.in_namespace(&name, |v| syn::visit::visit_item_trait(v, i));
+        self.impl_items_public = outer_public;
+        self.generics_stack.pop();
     }
-    contains_test
-}
 
-/// True if the attribute is `#[test]`.
-fn attr_is_test(attr: &Attribute) -> bool {
-    attr.path().is_ident("test")
-}
+    /// Visit `mod foo { ... }` or `mod foo;`.
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        let mod_name = node.ident.unraw().to_string();
+        let _span = trace_span!("mod", line = node.mod_token.span.start().line, mod_name).entered();
+        if self.attrs_excluded(&node.attrs) {
+            trace!("mod excluded by attrs");
+            return;
+        }
 
-fn path_is(path: &syn::Path, idents: &[&str]) -> bool {
-    path.segments.iter().map(|ps| &ps.ident).eq(idents.iter())
-}
+        let source_location = Span::from(node.span());
 
-/// True if the path ends with this identifier.
-///
-/// This is used as a heuristic to match types without being sensitive to which
-/// module they are in, or to match functions without being sensitive to which
-/// type they might be associated with.
-///
-/// This does not check type arguments.
-fn path_ends_with(path: &syn::Path, ident: &str) -> bool {
-    path.segments.last().is_some_and(|s| s.ident == ident)
-}
+        // Extract path attribute value, if any (e.g. `#[path="..."]`)
+        let path_attribute = match find_path_attribute(&node.attrs) {
+            Ok(path) => path,
+            Err(path_attribute) => {
+                let definition_site = self
+                    .source_file
+                    .format_source_location(source_location.start);
+                error!(?path_attribute, ?definition_site, %mod_name, "invalid filesystem traversal in mod path attribute");
+                return;
+            }
+        };
+        let mod_namespace = ModNamespace {
+            name: mod_name,
+            path_attribute,
+            source_location,
+            public: matches!(node.vis, syn::Visibility::Public(_)),
+        };
+        self.mod_namespace_stack.push(mod_namespace.clone());
 
-/// True if the attribute contains `mutants::skip`.
-///
-/// This for example returns true for `#[mutants::skip]` or `#[cfg_attr(test, mutants::skip)]`.
-fn attr_is_mutants_skip(attr: &Attribute) -> bool {
-    if path_is(attr.path(), &["mutants", "skip"]) {
-        return true;
+        // If there's no content in braces, then this is a `mod foo;`
+        // statement referring to an external file. We remember the module
+        // name and then later look for the file.
+        if node.content.is_none() {
+            // If we're already inside `mod a { ... }` and see `mod b;` then
+            // remember [a, b] as an external module to visit later.
+            self.external_mods.push(ExternalModRef {
+                parts: self.mod_namespace_stack.clone(),
+            });
+        }
+        self.in_namespace(&mod_namespace.name, |v| syn::visit::visit_item_mod(v, node));
+        assert_eq!(self.mod_namespace_stack.pop(), Some(mod_namespace));
     }
-    if !path_is(attr.path(), &["cfg_attr"]) {
-        return false;
+
+    /// Visit `a op b` expressions.
+    fn visit_expr_binary(&mut self, i: &'ast syn::ExprBinary) {
+        let _span = trace_span!("binary", line = i.op.span().start().line).entered();
+        trace!("visit binary operator");
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        let replacements = match i.op {
+            // We don't generate `<=` from `==` because it can too easily go
+            // wrong with unsigned types compared to 0.
+
+            // We try replacing logical ops with == and !=, which are effectively
+            // XNOR and XOR when applied to booleans. However, they're often unviable
+            // because they require parenthesis for disambiguation in many expressions.
+            BinOp::Eq(_) => vec![quote! { != }],
+            BinOp::Ne(_) => vec![quote! { == }],
+            BinOp::And(_) => vec![quote! { || }],
+            BinOp::Or(_) => vec![quote! { && }],
+            // Comparisons are also moved across the boundary, e.g. `<` to `<=`,
+            // to catch off-by-one errors.
+            BinOp::Lt(_) => vec![quote! { == }, quote! {>}, quote! {<=}],
+            BinOp::Gt(_) => vec![quote! { == }, quote! {<}, quote! {>=}],
+            BinOp::Le(_) => vec![quote! {>}, quote! {<}],
+            BinOp::Ge(_) => vec![quote! {<}, quote! {>}],
+            BinOp::Add(_) => vec![quote! {-}, quote! {*}],
+            BinOp::AddAssign(_) => vec![quote! {-=}, quote! {*=}],
+            BinOp::Sub(_) | BinOp::Mul(_) => vec![quote! {+}, quote! {/}],
+            BinOp::SubAssign(_) | BinOp::MulAssign(_) => vec![quote! {+=}, quote! {/=}],
+            BinOp::Div(_) => vec![quote! {%}, quote! {*}],
+            BinOp::DivAssign(_) => vec![quote! {%=}, quote! {*=}],
+            BinOp::Rem(_) => vec![quote! {/}, quote! {+}],
+            BinOp::RemAssign(_) => vec![quote! {/=}, quote! {+=}],
+            BinOp::Shl(_) => vec![quote! {>>}],
+            BinOp::ShlAssign(_) => vec![quote! {>>=}],
+            BinOp::Shr(_) => vec![quote! {<<}],
+            BinOp::ShrAssign(_) => vec![quote! {<<=}],
+            BinOp::BitAnd(_) => vec![quote! {|}, quote! {^}],
+            BinOp::BitAndAssign(_) => vec![quote! {|=}, quote! {^=}],
+            BinOp::BitOr(_) => vec![quote! {&}, quote! {^}],
+            BinOp::BitOrAssign(_) => vec![quote! {&=}, quote! {^=}],
+            BinOp::BitXor(_) => vec![quote! {|}, quote! {&}],
+            BinOp::BitXorAssign(_) => vec![quote! {|=}, quote! {&=}],
+            _ => {
+                trace!(
+                    op = i.op.to_pretty_string(),
+                    "No mutants generated for this binary operator"
+                );
+                Vec::new()
+            }
+        };
+        for rep in replacements {
+            self.collect_mutant(i.op.span().into(), &rep, Genre::BinaryOperator);
+        }
+        if matches!(
+            i.op,
+            BinOp::Shl(_) | BinOp::Shr(_) | BinOp::ShlAssign(_) | BinOp::ShrAssign(_)
+        ) {
+            self.collect_shift_amount_mutants(&i.right);
+        }
+        self.collect_swap_operands(i);
+        syn::visit::visit_expr_binary(self, i);
     }
-    let mut skip = false;
-    if let Err(err) = attr.parse_nested_meta(|meta| {
-        if path_is(&meta.path, &["mutants", "skip"]) {
-            skip = true;
+
+    fn visit_expr_unary(&mut self, i: &'ast syn::ExprUnary) {
+        let _span = trace_span!("unary", line = i.op.span().start().line).entered();
+        trace!("visit unary operator");
+        if self.attrs_excluded(&i.attrs) {
+            return;
         }
-        Ok(())
-    }) {
-        debug!(
-            ?attr,
-            ?err,
-            "Attribute is not a path with attributes; skipping"
+        match i.op {
+            UnOp::Not(_) | UnOp::Neg(_) => {
+                self.collect_mutant(i.op.span().into(), &quote! {}, Genre::UnaryOperator);
+            }
+            _ => {
+                trace!(
+                    op = i.op.to_pretty_string(),
+                    "No mutants generated for this unary operator"
+                );
+            }
+        }
+        syn::visit::visit_expr_unary(self, i);
+    }
+
+    /// Visit a block of statements, such as a function body.
+    fn visit_block(&mut self, i: &'ast Block) {
+        for stmt in &i.stmts {
+            self.collect_delete_statement(i, stmt);
+            self.collect_delete_else(i, stmt);
+            self.collect_delete_await(stmt);
+        }
+        syn::visit::visit_block(self, i);
+    }
+
+    /// Visit `const` items.
+    fn visit_item_const(&mut self, i: &'ast syn::ItemConst) {
+        if self.attrs_excluded(&i.attrs) || self.excluded_by_visibility(&i.vis) {
+            return;
+        }
+        self.visit_const_initializer(|v| syn::visit::visit_item_const(v, i));
+    }
+
+    /// Visit `static` items.
+    fn visit_item_static(&mut self, i: &'ast syn::ItemStatic) {
+        if self.attrs_excluded(&i.attrs) || self.excluded_by_visibility(&i.vis) {
+            return;
+        }
+        self.visit_const_initializer(|v| syn::visit::visit_item_static(v, i));
+    }
+
+    /// Visit associated `const` items in an `impl`.
+    fn visit_impl_item_const(&mut self, i: &'ast syn::ImplItemConst) {
+        if self.attrs_excluded(&i.attrs)
+            || (!self.impl_items_public && self.excluded_by_visibility(&i.vis))
+        {
+            return;
+        }
+        self.visit_const_initializer(|v| syn::visit::visit_impl_item_const(v, i));
+    }
+
+    /// Visit `let` statements.
+    fn visit_local(&mut self, i: &'ast syn::Local) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.collect_let_else_mutant(i);
+        syn::visit::visit_local(self, i);
+    }
+
+    /// Visit numeric and string literals.
+    fn visit_expr_lit(&mut self, i: &'ast syn::ExprLit) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if self.in_const_item {
+            self.collect_const_value_mutants(&i.lit);
+        }
+        if self.fn_stack.is_empty() {
+            return;
+        }
+        if !self.in_const_item {
+            let deltas = self.options.numeric_literal_deltas();
+            for rep in numeric_literal_replacements(&i.lit, deltas) {
+                self.collect_mutant_text(i.lit.span().into(), rep, Genre::NumericLiteral);
+            }
+        }
+        if let syn::Lit::Str(lit_str) = &i.lit {
+            let value = lit_str.value();
+            if !value.is_empty() {
+                self.collect_mutant(lit_str.span().into(), &quote! { "" }, Genre::StringLiteral);
+                if value != "xyzzy" {
+                    self.collect_mutant(
+                        lit_str.span().into(),
+                        &quote! { "xyzzy" },
+                        Genre::StringLiteral,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Don't look inside attributes, which contain no code to mutate.
+    fn visit_attribute(&mut self, _i: &'ast Attribute) {}
+
+    /// Don't look inside patterns: mutating literals in patterns would too often
+    /// make the match non-exhaustive.
+    fn visit_pat(&mut self, _i: &'ast syn::Pat) {}
+
+    /// Visit `[T; N]` array types, but not the length, because a mutated length
+    /// would almost always be unviable.
+    fn visit_type_array(&mut self, i: &'ast syn::TypeArray) {
+        self.visit_type(&i.elem);
+    }
+
+    /// Visit `[x; N]` array expressions, but not the length, for the same reason as
+    /// array types.
+    fn visit_expr_repeat(&mut self, i: &'ast syn::ExprRepeat) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.visit_expr(&i.expr);
+    }
+
+    /// Visit `a..b` and `a..=b` range expressions.
+    fn visit_expr_range(&mut self, i: &'ast syn::ExprRange) {
+        let _span = trace_span!("range", line = i.span().start().line).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if !self.fn_stack.is_empty() {
+            match i.limits {
+                // `a..` has no end and so can't be made inclusive.
+                syn::RangeLimits::HalfOpen(dots) if i.end.is_some() => {
+                    self.collect_mutant(dots.span().into(), &quote! { ..= }, Genre::Range);
+                }
+                syn::RangeLimits::Closed(dots) => {
+                    self.collect_mutant(dots.span().into(), &quote! { .. }, Genre::Range);
+                }
+                syn::RangeLimits::HalfOpen(_) => (),
+            }
+        }
+        syn::visit::visit_expr_range(self, i);
+    }
+
+    /// Visit `Foo { field: value, .. }` struct expressions.
+    fn visit_expr_struct(&mut self, i: &'ast syn::ExprStruct) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        for field in &i.fields {
+            if !self.attrs_excluded(&field.attrs) {
+                self.collect_flip_bool(&field.expr);
+            }
+        }
+        syn::visit::visit_expr_struct(self, i);
+    }
+
+    /// Visit `expr?` expressions.
+    fn visit_expr_try(&mut self, i: &'ast syn::ExprTry) {
+        let _span = trace_span!("try", line = i.question_token.span.start().line).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if !self.fn_stack.is_empty() {
+            self.collect_mutant(
+                i.question_token.span.into(),
+                &quote! { .unwrap() },
+                Genre::TryOperator,
+            );
+        }
+        syn::visit::visit_expr_try(self, i);
+    }
+
+    /// Visit `if cond { ... }` expressions, including `else if`.
+    fn visit_expr_if(&mut self, i: &'ast syn::ExprIf) {
+        let _span = trace_span!("if", line = i.if_token.span.start().line).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.collect_negate_condition(&i.cond);
+        self.collect_if_let_mutants(i);
+        self.collect_early_return_mutants(i);
+        syn::visit::visit_expr_if(self, i);
+    }
+
+    /// Visit `while cond { ... }` loops.
+    fn visit_expr_while(&mut self, i: &'ast syn::ExprWhile) {
+        let _span = trace_span!("while", line = i.while_token.span.start().line).entered();
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.collect_negate_condition(&i.cond);
+        self.visit_loop(i.label.as_ref(), |v| syn::visit::visit_expr_while(v, i));
+    }
+
+    /// Visit `for pat in expr { ... }` loops.
+    fn visit_expr_for_loop(&mut self, i: &'ast syn::ExprForLoop) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.visit_loop(i.label.as_ref(), |v| {
+            syn::visit::visit_expr_for_loop(v, i);
+        });
+    }
+
+    /// Visit `loop { ... }`.
+    fn visit_expr_loop(&mut self, i: &'ast syn::ExprLoop) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.visit_loop(i.label.as_ref(), |v| syn::visit::visit_expr_loop(v, i));
+    }
+
+    /// Visit closures, which can't `break` out of loops around them.
+    fn visit_expr_closure(&mut self, i: &'ast syn::ExprClosure) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if matches!(i.output, ReturnType::Type(..)) {
+            let error_exprs = self.error_values.for_return_type(&i.output);
+            let reps: Vec<String> =
+                return_type_replacements(&i.output, &error_exprs, &self.type_params())
+                    .into_iter()
+                    .map(|rep| rep.to_pretty_string())
+                    .collect();
+            self.collect_closure_value_mutants(i, reps);
+        }
+        let outer_loops = std::mem::take(&mut self.loop_labels);
+        syn::visit::visit_expr_closure(self, i);
+        self.loop_labels = outer_loops;
+    }
+
+    /// Visit `async { ... }` blocks, which can't `break` out of loops around them.
+    fn visit_expr_async(&mut self, i: &'ast syn::ExprAsync) {
+        let outer_loops = std::mem::take(&mut self.loop_labels);
+        syn::visit::visit_expr_async(self, i);
+        self.loop_labels = outer_loops;
+    }
+
+    /// Visit `expr as Type` casts.
+    fn visit_expr_cast(&mut self, i: &'ast syn::ExprCast) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        if !self.fn_stack.is_empty() {
+            let type_name = i.ty.to_pretty_string();
+            for rep in cast_type_replacements(&type_name) {
+                self.collect_mutant_text(i.ty.span().into(), (*rep).to_owned(), Genre::Cast);
+            }
+            if is_integer_type(&type_name) {
+                let expr_span: Span = i.expr.span().into();
+                let expr_text = expr_span.extract(self.source_file.code());
+                self.collect_mutant_text(
+                    i.span().into(),
+                    format!("{type_name}::try_from({expr_text}).unwrap()"),
+                    Genre::Cast,
+                );
+            }
+        }
+        syn::visit::visit_expr_cast(self, i);
+    }
+
+    /// Visit `v[i]` index expressions.
+    fn visit_expr_index(&mut self, i: &'ast syn::ExprIndex) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        self.collect_index_mutants(&i.index);
+        syn::visit::visit_expr_index(self, i);
+    }
+
+    /// Visit a macro invocation, mutating its arguments if they're ordinary expressions.
+    ///
+    /// The macro's definition isn't known, so this is only a guess that the arguments
+    /// are evaluated as written, like those of `vec![]` or `format!()`.
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        let _span = trace_span!("macro", line = i.span().start().line).entered();
+        if self.fn_stack.is_empty() {
+            // Macros outside of functions are typically declaring items.
+            return;
+        }
+        let Some(name) = i.path.segments.last().map(|s| s.ident.to_string()) else {
+            return;
+        };
+        if UNMUTATED_MACROS.contains(&name.as_str()) || self.options.skip_calls.contains(&name) {
+            trace!(?name, "skip macro");
+            return;
+        }
+        self.collect_select_branch_mutants(&name, &i.tokens);
+        let Some(args) = macro_arg_exprs(&i.tokens) else {
+            trace!(?name, "macro arguments are not expressions");
+            return;
+        };
+        for arg in &args {
+            // String literals passed directly to macros are almost always format strings,
+            // and changing them just makes the call fail to compile.
+            if !matches!(
+                arg,
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(_),
+                    ..
+                })
+            ) {
+                self.visit_expr(arg);
+            }
+        }
+    }
+
+    /// Visit `break` and `break 'label`.
+    fn visit_expr_break(&mut self, i: &'ast syn::ExprBreak) {
+        if self.attrs_excluded(&i.attrs) {
+            return;
+        }
+        // A break with a value can only leave a `loop` or block, and it's unclear what to
+        // do with the value.
+        if i.expr.is_none() && self.fn_stack.last().is_some() {
+            if let Some(label) = &i.label {
+                let label_name = label.to_string();
+                if self.loop_labels.contains(&Some(label_name.clone())) {
+                    // Otherwise, it's the label of a block, which can't be continued.
+                    self.collect_mutant(
+                        i.span().into(),
+                        &quote! { continue #label },
+                        Genre::LoopControl,
+                    );
+                }
+                // An unlabeled break leaves the innermost loop, so this is only different if
+                // that loop has a different label.
+                if matches!(self.loop_labels.last(), Some(innermost) if innermost.as_ref() != Some(&label_name))
+                {
+                    self.collect_mutant(i.span().into(), &quote! { break }, Genre::LoopControl);
+                }
+            } else if !self.loop_labels.is_empty() {
+                self.collect_mutant(i.span().into(), &quote! { continue }, Genre::LoopControl);
+            }
+        }
+        syn::visit::visit_expr_break(self, i);
+    }
+
+    /// Visit `continue` and `continue 'label`.
+    fn visit_expr_continue(&mut self, i: &'ast syn::ExprContinue) {
+        if self.attrs_excluded(&i.attrs) || self.fn_stack.is_empty() {
+            return;
+        }
+        let label = &i.label;
+        self.collect_mutant(
+            i.span().into(),
+            &quote! { break #label },
+            Genre::LoopControl,
+        );
+    }
+
+    fn visit_expr_match(&mut self, i: &'ast syn::ExprMatch) {
+        let _span = trace_span!("match", line = i.span().start().line).entered();
+
+        // While it's not currently possible to annotate expressions with custom attributes, this
+        // limitation could be lifted in the future.
+        if self.attrs_excluded(&i.attrs) {
+            trace!("match excluded by attrs");
+            return;
+        }
+
+        let has_catchall = i
+            .arms
+            .iter()
+            .any(|arm| arm.guard.is_none() && pat_is_catchall(&arm.pat));
+        if has_catchall {
+            i.arms
+                .iter()
+                // Don't mutate the catch-all arm, because that will likely be unviable, and also
+                // skip it if a guard is present, because the replacement of the guard with 'false'
+                // below is logically equivalent to removing the arm.
+                .filter(|arm| !pat_is_catchall(&arm.pat) && arm.guard.is_none())
+                .for_each(|arm| {
+                    self.collect_mutant(arm.span().into(), &quote! {}, Genre::MatchArm);
+                });
+        } else {
+            trace!("match has no `_` pattern");
+        }
+
+        i.arms
+            .iter()
+            .flat_map(|arm| &arm.guard)
+            .for_each(|(_if, guard_expr)| {
+                // Replacing the guard with `true` is equivalent to removing it.
+                self.collect_mutant(
+                    guard_expr.span().into(),
+                    &quote! { true },
+                    Genre::MatchArmGuard,
+                );
+                self.collect_mutant(
+                    guard_expr.span().into(),
+                    &quote! { false },
+                    Genre::MatchArmGuard,
+                );
+            });
+
+        syn::visit::visit_expr_match(self, i);
+    }
+}
+
+/// True if a match arm pattern matches any value: either `_` or a binding like `other`.
+///
+/// Identifiers starting with an uppercase letter are assumed to be constants or unit
+/// enum variants, like `None`, rather than bindings.
+fn pat_is_catchall(pat: &syn::Pat) -> bool {
+    match pat {
+        syn::Pat::Wild(_) => true,
+        syn::Pat::Ident(pat_ident) => {
+            pat_ident.subpat.is_none()
+                && !pat_ident
+                    .ident
+                    .unraw()
+                    .to_string()
+                    .starts_with(|c: char| c.is_uppercase())
+        }
+        _ => false,
+    }
+}
+
+/// True if the expression is a `let` binding or a chain containing one.
+fn expr_contains_let(expr: &Expr) -> bool {
+    match expr {
+        Expr::Let(_) => true,
+        Expr::Binary(binary) => expr_contains_let(&binary.left) || expr_contains_let(&binary.right),
+        Expr::Paren(paren) => expr_contains_let(&paren.expr),
+        _ => false,
+    }
+}
+
+/// Return replacements for a numeric literal: the literal plus each delta, and minus
+/// each delta if that's not negative.
+///
+/// Any type suffix, like `u8`, is kept. Literals in other bases are not mutated,
+/// because rewriting them in decimal would obscure their meaning.
+fn numeric_literal_replacements(lit: &syn::Lit, deltas: &[u64]) -> Vec<String> {
+    let mut reps = Vec::new();
+    match lit {
+        syn::Lit::Int(lit_int) => {
+            let token = lit_int.token().to_string();
+            if ["0x", "0o", "0b"]
+                .iter()
+                .any(|prefix| token.starts_with(prefix))
+            {
+                return Vec::new();
+            }
+            let Ok(value) = lit_int.base10_parse::<u128>() else {
+                return Vec::new();
+            };
+            let suffix = lit_int.suffix();
+            for &delta in deltas {
+                if let Some(plus) = value.checked_add(delta.into()) {
+                    reps.push(format!("{plus}{suffix}"));
+                }
+                if let Some(minus) = value.checked_sub(delta.into()) {
+                    reps.push(format!("{minus}{suffix}"));
+                }
+            }
+        }
+        syn::Lit::Float(lit_float) => {
+            let Ok(value) = lit_float.base10_parse::<f64>() else {
+                return Vec::new();
+            };
+            let suffix = lit_float.suffix();
+            for &delta in deltas {
+                #[allow(clippy::cast_precision_loss)]
+                let delta = delta as f64;
+                reps.push(format!("{:?}{suffix}", value + delta));
+                if value >= delta {
+                    reps.push(format!("{:?}{suffix}", value - delta));
+                }
+            }
+        }
+        _ => {}
+    }
+    reps
+}
+
+/// Return the names of methods that can replace an arithmetic method like `checked_add`.
+///
+/// Checked, wrapping, saturating, and overflowing methods on integers are the
+/// method-call equivalents of the arithmetic operators, so they're swapped in the
+/// same way as binary operators.
+fn arithmetic_method_replacements(method: &str) -> &'static [&'static str] {
+    match method {
+        "checked_add" => &["checked_sub"],
+        "checked_sub" => &["checked_add"],
+        "checked_mul" => &["checked_div"],
+        "checked_div" => &["checked_mul"],
+        "wrapping_add" => &["wrapping_sub"],
+        "wrapping_sub" => &["wrapping_add"],
+        "wrapping_mul" => &["wrapping_div"],
+        "wrapping_div" => &["wrapping_mul"],
+        "saturating_add" => &["saturating_sub"],
+        "saturating_sub" => &["saturating_add"],
+        "saturating_mul" => &["saturating_div"],
+        "saturating_div" => &["saturating_mul"],
+        "overflowing_add" => &["overflowing_sub"],
+        "overflowing_sub" => &["overflowing_add"],
+        "overflowing_mul" => &["overflowing_div"],
+        "overflowing_div" => &["overflowing_mul"],
+        _ => &[],
+    }
+}
+
+/// Return replacement values for the body of a closure passed to a well-known method.
+///
+/// Without type information, this is only known for methods whose closures
+/// return `bool` or `Ordering`.
+fn closure_value_replacements(method: &str) -> &'static [&'static str] {
+    match method {
+        "all" | "any" | "filter" | "find" | "partition" | "position" | "retain" | "rposition"
+        | "skip_while" | "take_while" => &["true", "false"],
+        "binary_search_by" | "max_by" | "min_by" | "sort_by" | "sort_unstable_by" => &[
+            "std::cmp::Ordering::Less",
+            "std::cmp::Ordering::Equal",
+            "std::cmp::Ordering::Greater",
+        ],
+        _ => &[],
+    }
+}
+
+/// Return true if this is the name of a primitive integer type.
+fn is_integer_type(type_name: &str) -> bool {
+    [
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ]
+    .contains(&type_name)
+}
+
+/// Return replacements for the type in an `as` cast.
+///
+/// Integer types are replaced with the next narrower and next wider type of the
+/// same signedness, and with the type of the same width and opposite signedness,
+/// to simulate truncation and sign-extension bugs.
+fn cast_type_replacements(type_name: &str) -> &'static [&'static str] {
+    match type_name {
+        "u8" => &["u16", "i8"],
+        "u16" => &["u8", "u32", "i16"],
+        "u32" => &["u16", "u64", "i32"],
+        "u64" => &["u32", "u128", "i64"],
+        "u128" => &["u64", "i128"],
+        "usize" => &["isize"],
+        "i8" => &["i16", "u8"],
+        "i16" => &["i8", "i32", "u16"],
+        "i32" => &["i16", "i64", "u32"],
+        "i64" => &["i32", "i128", "u64"],
+        "i128" => &["i64", "u128"],
+        "isize" => &["usize"],
+        "f32" => &["f64"],
+        "f64" => &["f32"],
+        _ => &[],
+    }
+}
+
+// Get the span of the block excluding the braces, or None if it is empty.
+fn function_body_span(block: &Block) -> Option<Span> {
+    Some(Span {
+        start: block.stmts.first()?.span().start().into(),
+        end: block.stmts.last()?.span().end().into(),
+    })
+}
+
+/// Find a new source file referenced by a `mod` statement.
+///
+/// Possibly, our heuristics just won't be able to find which file it is,
+/// in which case we return `Ok(None)`.
+fn find_mod_source(
+    tree_root: &Utf8Path,
+    parent: &SourceFile,
+    mod_namespace: &ExternalModRef,
+) -> Option<Utf8PathBuf> {
+    // First, work out whether the mod will be a sibling in the same directory, or
+    // in a child directory.
+    //
+    // 1. The parent is "src/foo.rs" and `mod bar` means "src/foo/bar.rs".
+    //
+    // 2. The parent is "src/lib.rs" (a target top file) and `mod bar` means "src/bar.rs".
+    //
+    // 3. The parent is "src/foo/mod.rs" and so `mod bar` means "src/foo/bar.rs".
+    //
+    // 4. A path attribute on a mod statement when there is no enclosing mod block
+    //     E.g. for parent file "src/a/parent_file.rs",
+    //     ```
+    //     // `path` is relative to the directory where the source file is located
+    //     #[path="foo_file.rs"] // resolves to: src/a/foo_file.rs
+    //     mod foo;
+    //
+    //     mod bar {
+    //         // `path` is relative to the directory of the enclosing module block
+    //         #[path="baz_file.rs"] // resolves to: src/a/parent_file/bar/baz_file.rs
+    //         mod baz;
+    //     }
+    //     ```
+    //
+    // Having determined the right directory then we can follow the path attribute, or
+    // if no path is specified, then look for either `foo.rs` or `foo/mod.rs`.
+
+    let (mod_child, mod_parents) = mod_namespace
+        .parts
+        .split_last()
+        .expect("mod namespace is empty");
+
+    // TODO: Beyond #115, we should probably remove all special handling of
+    // `mod.rs` here by remembering how we found this file, and whether it
+    // is above or inside the directory corresponding to its module?
+
+    let parent_path = &parent.tree_relative_path;
+    let mut search_dir = if parent.is_top
+        || parent_path.ends_with("mod.rs")
+        // NOTE: Path attribute on a top-level `mod foo;` (no enclosing block)
+        //       ignores the parent module path
+        || (mod_child.path_attribute.is_some() && mod_parents.is_empty())
+    {
+        parent_path
+            .parent()
+            .expect("mod path has no parent")
+            .to_owned() // src/lib.rs -> src/
+    } else {
+        parent_path.with_extension("") // foo.rs -> foo/
+    };
+
+    search_dir.extend(mod_parents.iter().map(ModNamespace::get_filesystem_name));
+
+    let mod_child_candidates = if let Some(filesystem_name) = &mod_child.path_attribute {
+        vec![search_dir.join(filesystem_name)]
+    } else {
+        [".rs", "/mod.rs"]
+            .iter()
+            .map(|tail| search_dir.join(mod_child.name.clone() + tail))
+            .collect()
+    };
+
+    let mut tried_paths = Vec::new();
+    for relative_path in mod_child_candidates {
+        let full_path = tree_root.join(&relative_path);
+        if full_path.is_file() {
+            trace!("found submodule in {full_path}");
+            return Some(relative_path);
+        }
+        tried_paths.push(full_path);
+    }
+    let mod_name = &mod_child.name;
+    let definition_site = parent.format_source_location(mod_child.source_location.start);
+    warn!(?definition_site, %mod_name, ?tried_paths, "referent of mod not found");
+    None
+}
+
+/// True if the signature of a function is such that it should be excluded.
+fn fn_sig_excluded(sig: &syn::Signature) -> bool {
+    if sig.unsafety.is_some() {
+        trace!("Skip unsafe fn");
+        true
+    } else {
+        false
+    }
+}
+
+/// True if any of the attrs indicate that we should skip this node and everything inside it.
+///
+/// This checks for `#[cfg(test)]`, `#[test]`, and `#[mutants::skip]`.
+fn attrs_excluded(attrs: &[Attribute]) -> bool {
+    attrs
+        .iter()
+        .any(|attr| attr_is_cfg_test(attr) || attr_is_test(attr) || attr_is_mutants_skip(attr))
+}
+
+/// True if the block (e.g. the contents of a function) is empty.
+fn block_is_empty(block: &syn::Block) -> bool {
+    block.stmts.is_empty()
+}
+
+/// True if the attribute looks like `#[cfg(test)]`, or has "test"
+/// anywhere in it.
+fn attr_is_cfg_test(attr: &Attribute) -> bool {
+    if !path_is(attr.path(), &["cfg"]) {
+        return false;
+    }
+    let mut contains_test = false;
+    if let Err(err) = attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("test") {
+            contains_test = true;
+        }
+        Ok(())
+    }) {
+        debug!(
+            ?err,
+            attr = attr.to_pretty_string(),
+            "Attribute is in an unrecognized form so skipped",
+        );
+        return false;
+    }
+    contains_test
+}
+
+/// True if the attribute is `#[test]`.
+fn attr_is_test(attr: &Attribute) -> bool {
+    attr.path().is_ident("test")
+}
+
+fn path_is(path: &syn::Path, idents: &[&str]) -> bool {
+    path.segments.iter().map(|ps| &ps.ident).eq(idents.iter())
+}
+
+/// Macros whose arguments are never mutated, because they're not evaluated as
+/// written or are only used for logging.
+const UNMUTATED_MACROS: &[&str] = &[
+    "cfg",
+    "column",
+    "compile_error",
+    "concat",
+    "debug",
+    "env",
+    "error",
+    "file",
+    "include",
+    "include_bytes",
+    "include_str",
+    "info",
+    "line",
+    "log",
+    "macro_rules",
+    "matches",
+    "module_path",
+    "option_env",
+    "parse_quote",
+    "parse_quote_spanned",
+    "quote",
+    "quote_spanned",
+    "stringify",
+    "trace",
+    "warn",
+];
+
+/// Parse the arguments of a macro invocation as expressions separated by commas,
+/// or by semicolons as in `vec![0; n]`.
+///
+/// Returns None if the arguments are not all expressions.
+fn macro_arg_exprs(tokens: &TokenStream) -> Option<Vec<Expr>> {
+    use syn::parse::Parser;
+    use syn::punctuated::Punctuated;
+    if let Ok(args) = Punctuated::<Expr, syn::Token![,]>::parse_terminated.parse2(tokens.clone()) {
+        Some(args.into_iter().collect())
+    } else if let Ok(args) =
+        Punctuated::<Expr, syn::Token![;]>::parse_separated_nonempty.parse2(tokens.clone())
+    {
+        Some(args.into_iter().collect())
+    } else {
+        None
+    }
+}
+
+/// Find the spans of the branches of a `select!`, like `v = rx.recv() => handle(v),`,
+/// not including the comma that ends each one, and whether there is such a comma.
+///
+/// `else` branches are not included, because they only run when all the other branches
+/// are disabled, so their position doesn't matter.
+///
+/// Returns None if the branches can't be parsed.
+fn select_branches(tokens: &[TokenTree]) -> Option<Vec<(Span, bool)>> {
+    let is_punct = |tt: &TokenTree, c: char| matches!(tt, TokenTree::Punct(p) if p.as_char() == c);
+    let mut branches = Vec::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        let start = i;
+        // Find the `=>` that separates the pattern and future from the handler.
+        while !(is_punct(&tokens[i], '=') && tokens.get(i + 1).is_some_and(|tt| is_punct(tt, '>')))
+        {
+            i += 1;
+            if i >= tokens.len() {
+                return None;
+            }
+        }
+        i += 2;
+        let handler = tokens.get(i)?;
+        if matches!(handler, TokenTree::Group(group) if group.delimiter() == Delimiter::Brace) {
+            i += 1;
+        } else {
+            while i < tokens.len() && !is_punct(&tokens[i], ',') {
+                i += 1;
+            }
+        }
+        let end = i - 1;
+        let has_comma = tokens.get(i).is_some_and(|tt| is_punct(tt, ','));
+        if has_comma {
+            i += 1;
+        }
+        if !matches!(&tokens[start], TokenTree::Ident(ident) if ident == "else") {
+            let span = Span {
+                start: Span::from(tokens[start].span()).start,
+                end: Span::from(tokens[end].span()).end,
+            };
+            branches.push((span, has_comma));
+        }
+    }
+    Some(branches)
+}
+
+/// True if the path ends with this identifier.
+///
+/// This is used as a heuristic to match types without being sensitive to which
+/// module they are in, or to match functions without being sensitive to which
+/// type they might be associated with.
+///
+/// This does not check type arguments.
+fn path_ends_with(path: &syn::Path, ident: &str) -> bool {
+    path.segments.last().is_some_and(|s| s.ident == ident)
+}
+
+/// True if the attribute contains `mutants::skip`.
+///
+/// This for example returns true for `#[mutants::skip]` or `#[cfg_attr(test, mutants::skip)]`.
+fn attr_is_mutants_skip(attr: &Attribute) -> bool {
+    if path_is(attr.path(), &["mutants", "skip"]) {
+        return true;
+    }
+    if !path_is(attr.path(), &["cfg_attr"]) {
+        return false;
+    }
+    let mut skip = false;
+    if let Err(err) = attr.parse_nested_meta(|meta| {
+        if path_is(&meta.path, &["mutants", "skip"]) {
+            skip = true;
+        }
+        Ok(())
+    }) {
+        debug!(
+            ?attr,
+            ?err,
+            "Attribute is not a path with attributes; skipping"
+        );
+        return false;
+    }
+    skip
+}
+
+/// True if the attribute matches one of the `skip_attrs` configured by the user, either
+/// directly or inside `#[cfg_attr(...)]`.
+///
+/// Attributes are compared ignoring whitespace, and a pattern that's only a path, like
+/// `inline`, matches the attribute with any arguments.
+fn attr_is_configured_skip(meta: &syn::Meta, skip_attrs: &[String]) -> bool {
+    use syn::punctuated::Punctuated;
+    if skip_attrs.is_empty() {
+        return false;
+    }
+    let strip = |s: &str| s.chars().filter(|c| !c.is_whitespace()).collect::<String>();
+    let meta_text = strip(&meta.to_pretty_string());
+    let path_text = strip(&meta.path().to_pretty_string());
+    if skip_attrs.iter().any(|pattern| {
+        let pattern = strip(pattern);
+        let pattern = pattern
+            .strip_prefix("#[")
+            .and_then(|p| p.strip_suffix(']'))
+            .unwrap_or(&pattern);
+        pattern == meta_text || pattern == path_text
+    }) {
+        return true;
+    }
+    if let syn::Meta::List(list) = meta {
+        if list.path.is_ident("cfg_attr") {
+            if let Ok(args) =
+                list.parse_args_with(Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)
+            {
+                // The first argument is the predicate, and the rest are attributes.
+                return args
+                    .iter()
+                    .skip(1)
+                    .any(|inner| attr_is_configured_skip(inner, skip_attrs));
+            }
+        }
+    }
+    false
+}
+
+/// Finds the first path attribute (`#[path = "..."]`)
+///
+/// # Errors
+/// Returns an error if the path attribute contains a dubious path (leading `/`)
+fn find_path_attribute(attrs: &[Attribute]) -> std::result::Result<Option<Utf8PathBuf>, String> {
+    attrs
+        .iter()
+        .find_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(meta) if meta.path.is_ident("path") => {
+                let syn::Expr::Lit(expr_lit) = &meta.value else {
+                    return None;
+                };
+                let syn::Lit::Str(lit_str) = &expr_lit.lit else {
+                    return None;
+                };
+                let path = lit_str.value();
+
+                // refuse to follow absolute paths
+                if path.starts_with('/') {
+                    Some(Err(path))
+                } else {
+                    Some(Ok(Utf8PathBuf::from(path)))
+                }
+            }
+            _ => None,
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use indoc::indoc;
+    use itertools::Itertools;
+    use pretty_assertions::assert_eq;
+    use test_log::test;
+
+    use crate::test_util::copy_of_testdata;
+    use crate::workspace::{PackageFilter, Workspace};
+
+    use super::*;
+
+    #[test]
+    fn path_ends_with() {
+        use super::path_ends_with;
+        use syn::parse_quote;
+
+        let path = parse_quote! { foo::bar::baz };
+        assert!(path_ends_with(&path, "baz"));
+        assert!(!path_ends_with(&path, "bar"));
+        assert!(!path_ends_with(&path, "foo"));
+
+        let path = parse_quote! { baz };
+        assert!(path_ends_with(&path, "baz"));
+        assert!(!path_ends_with(&path, "bar"));
+
+        let path = parse_quote! { BTreeMap<K, V> };
+        assert!(path_ends_with(&path, "BTreeMap"));
+        assert!(!path_ends_with(&path, "V"));
+        assert!(!path_ends_with(&path, "K"));
+    }
+
+    /// We should not generate mutants that produce the same tokens as the
+    /// source.
+    #[test]
+    fn no_mutants_equivalent_to_source() {
+        let code = indoc! { "
+            fn always_true() -> bool { true }
+        "};
+        let source_file = SourceFile::for_tests("src/lib.rs", code, "unimportant", true);
+        let (mutants, _files, _enums) = walk_file(
+            &source_file,
+            false,
+            &ErrorValues::default(),
+            &DerivedImpls::new(),
+            &CfgContext::default(),
+            &Options::default(),
+        )
+        .expect("walk_file");
+        let mutant_names = mutants.iter().map(|m| m.name(false)).collect_vec();
+        // It would be good to suggest replacing this with 'false', breaking a key behavior,
+        // but bad to replace it with 'true', changing nothing.
+        assert_eq!(
+            mutant_names,
+            ["src/lib.rs: replace always_true -> bool with false"]
+        );
+    }
+
+    /// We don't visit functions inside files marked with `#![cfg(test)]`.
+    #[test]
+    fn no_mutants_in_files_with_inner_cfg_test_attribute() {
+        let options = Options::default();
+        let console = Console::new();
+        let tmp = copy_of_testdata("cfg_test_inner");
+        let workspace = Workspace::open(tmp.path()).unwrap();
+        let discovered = workspace
+            .discover(&PackageFilter::All, &options, &console)
+            .unwrap();
+        assert_eq!(discovered.mutants.as_slice(), &[]);
+    }
+
+    /// Helper function for `find_path_attribute` tests
+    fn run_find_path_attribute(
+        token_stream: &TokenStream,
+    ) -> std::result::Result<Option<Utf8PathBuf>, String> {
+        let token_string = token_stream.to_string();
+        let item_mod = syn::parse_str::<syn::ItemMod>(&token_string).unwrap_or_else(|err| {
+            panic!("Failed to parse test case token stream: {token_string}\n{err}")
+        });
+        find_path_attribute(&item_mod.attrs)
+    }
+
+    #[test]
+    fn find_path_attribute_on_module_item() {
+        let outer = run_find_path_attribute(&quote! {
+            #[path = "foo_file.rs"]
+            mod foo;
+        });
+        assert_eq!(outer, Ok(Some(Utf8PathBuf::from("foo_file.rs"))));
+
+        let inner = run_find_path_attribute(&quote! {
+            mod foo {
+                #![path = "foo_folder"]
+
+                #[path = "file_for_bar.rs"]
+                mod bar;
+            }
+        });
+        assert_eq!(inner, Ok(Some(Utf8PathBuf::from("foo_folder"))));
+    }
+
+    #[test]
+    fn reject_module_path_absolute() {
+        // dots are valid
+        let dots = run_find_path_attribute(&quote! {
+            #[path = "contains/../dots.rs"]
+            mod dots;
+        });
+        assert_eq!(dots, Ok(Some(Utf8PathBuf::from("contains/../dots.rs"))));
+
+        let dots_inner = run_find_path_attribute(&quote! {
+            mod dots_in_path {
+                #![path = "contains/../dots"]
+            }
+        });
+        assert_eq!(dots_inner, Ok(Some(Utf8PathBuf::from("contains/../dots"))));
+
+        let leading_slash = run_find_path_attribute(&quote! {
+            #[path = "/leading_slash.rs"]
+            mod dots;
+        });
+        assert_eq!(leading_slash, Err("/leading_slash.rs".to_owned()));
+
+        let allow_other_slashes = run_find_path_attribute(&quote! {
+            #[path = "foo/other/slashes/are/allowed.rs"]
+            mod dots;
+        });
+        assert_eq!(
+            allow_other_slashes,
+            Ok(Some(Utf8PathBuf::from("foo/other/slashes/are/allowed.rs")))
+        );
+
+        let leading_slash2 = run_find_path_attribute(&quote! {
+            #[path = "/leading_slash/../and_dots.rs"]
+            mod dots;
+        });
+        assert_eq!(
+            leading_slash2,
+            Err("/leading_slash/../and_dots.rs".to_owned())
+        );
+    }
+
+    /// Demonstrate that we can generate mutants from a string, without needing a whole tree.
+    #[test]
+    fn mutants_from_test_str() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn always_true() -> bool { true }
+            "},
+            &options,
+        )
+        .expect("walk_file_string");
+        assert_eq!(
+            mutants.iter().map(|m| m.name(false)).collect_vec(),
+            ["src/main.rs: replace always_true -> bool with false"]
+        );
+    }
+
+    #[test]
+    fn function_size_and_complexity() {
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn getter() -> u32 {
+                    1
+                }
+
+                fn branchy(a: Option<u32>, b: bool) -> Option<u32> {
+                    let a = a?;
+                    if b && a > 2 {
+                        return Some(a);
+                    }
+                    match a {
+                        0 => None,
+                        n if n > 10 => Some(n),
+                        _ => {
+                            fn nested(x: bool) -> bool { if x { x } else { !x } }
+                            Some(a)
+                        }
+                    }
+                }
+            "},
+            &Options::default(),
+        )
+        .unwrap();
+        let sizes = mutants
+            .iter()
+            .filter(|m| m.genre == Genre::FnValue)
+            .map(|m| {
+                let function = m.function.as_ref().unwrap();
+                (function.function_name.as_str(), function.size)
+            })
+            .dedup()
+            .collect_vec();
+        assert_eq!(
+            sizes,
+            [
+                (
+                    "getter",
+                    FunctionSize {
+                        lines: 3,
+                        complexity: 1
+                    }
+                ),
+                (
+                    "branchy",
+                    FunctionSize {
+                        lines: 14,
+                        // ?, if, &&, two extra match arms, and a guard
+                        complexity: 7
+                    }
+                ),
+                (
+                    "branchy::nested",
+                    FunctionSize {
+                        lines: 1,
+                        complexity: 2
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_by_function_size() {
+        let code = indoc! {"
+            fn getter() -> u32 {
+                1
+            }
+
+            fn check(a: u32) -> bool {
+                if a > 1 {
+                    a < 10
+                } else {
+                    false
+                }
+            }
+        "};
+        let function_names = |args: &[&str]| {
+            let options = Options::from_arg_strs(["mutants"].iter().chain(args));
+            mutate_source_str(code, &options)
+                .unwrap()
+                .iter()
+                .filter(|m| options.allows_mutant(m))
+                .map(|m| m.function.as_ref().unwrap().function_name.clone())
+                .dedup()
+                .collect_vec()
+        };
+        assert_eq!(function_names(&["--min-function-lines=4"]), ["check"]);
+        assert_eq!(function_names(&["--max-function-lines=3"]), ["getter"]);
+        assert_eq!(function_names(&["--min-complexity=2"]), ["check"]);
+        assert_eq!(function_names(&["--max-complexity=1"]), ["getter"]);
+        assert_eq!(
+            function_names(&["--max-complexity=1", "--min-function-lines=4"]),
+            [""; 0]
+        );
+    }
+
+    #[test]
+    fn skip_configured_attrs() {
+        let options = Options::from_arg_strs([
+            "mutants",
+            "--skip-attr",
+            "generated",
+            "--skip-attr",
+            "#[inline(always)]",
+        ]);
+        let mutants = mutate_source_str(
+            indoc! {"
+                #[generated(by = \"bindgen\")]
+                fn generated() -> bool { true }
+
+                #[inline(always)]
+                fn always_inlined() -> bool { true }
+
+                #[inline]
+                fn inlined() -> bool { true }
+
+                #[cfg_attr(fuzzing, generated)]
+                fn sometimes_generated() -> bool { true }
+            "},
+            &options,
+        )
+        .expect("walk_file_string");
+        assert_eq!(
+            mutants.iter().map(|m| m.name(false)).collect_vec(),
+            ["src/main.rs: replace inlined -> bool with false",]
+        );
+    }
+
+    /// Skip mutating arguments to a particular named function.
+    #[test]
+    fn skip_named_fn() {
+        let options = Options {
+            skip_calls: vec!["dont_touch_this".to_owned()],
+            ..Default::default()
+        };
+        let mut mutants = mutate_source_str(
+            indoc! {"
+                fn main() {
+                    dont_touch_this(2 + 3);
+                }
+            "},
+            &options,
+        )
+        .expect("walk_file_string");
+        // Ignore the main function itself
+        mutants.retain(|m| m.genre != Genre::FnValue);
+        assert_eq!(mutants, []);
+    }
+
+    #[test]
+    fn skip_with_capacity_by_default() {
+        let options = Options::from_arg_strs(["mutants"]);
+        let mut mutants = mutate_source_str(
+            indoc! {"
+                fn main() {
+                    let mut v = Vec::with_capacity(2 * 100);
+                }
+            "},
+            &options,
+        )
+        .expect("walk_file_string");
+        // Ignore the main function itself
+        mutants.retain(|m| m.genre != Genre::FnValue);
+        assert_eq!(mutants, []);
+    }
+
+    #[test]
+    fn mutate_vec_with_capacity_when_default_skips_are_turned_off() {
+        let options = Options::from_arg_strs(["mutants", "--skip-calls-defaults", "false"]);
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn main() {
+                    let mut _v = std::vec::Vec::<String>::with_capacity(2 * 100);
+                }
+            "},
+            &options,
+        )
+        .expect("walk_file_string");
+        dbg!(&mutants);
+        // The main fn plus two mutations of the `*` expression.
+        assert_eq!(mutants.len(), 3);
+    }
+
+    #[test]
+    fn skip_method_calls_by_name() {
+        let options = Options::from_arg_strs(["mutants", "--skip-calls", "dont_touch_this"]);
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn main() {
+                    let mut v = v::new();
+                    v.dont_touch_this(2 + 3);
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        dbg!(&mutants);
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|mutant| mutant.genre != Genre::FnValue)
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn mutant_name_includes_type_parameters() {
+        // From https://github.com/sourcefrog/cargo-mutants/issues/334
+        let options = Options::from_arg_strs(["mutants"]);
+        let mutants = mutate_source_str(
+            indoc! {r#"
+            impl AsRef<str> for Apath {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl From<Apath> for String {
+                fn from(a: Apath) -> String {
+                    a.0
+                }
+            }
+
+            impl<'a> From<&'a str> for Apath {
+                fn from(s: &'a str) -> Apath {
+                    assert!(Apath::is_valid(s), "invalid apath: {s:?}");
+                    Apath(s.to_string())
+                }
+            }
+            "#},
+            &options,
+        )
+        .unwrap();
+        dbg!(&mutants);
+        let mutant_names = mutants.iter().map(|m| m.name(false) + "\n").join("");
+        assert_eq!(
+            mutant_names,
+            indoc! {r#"
+                src/main.rs: replace <impl AsRef<str> for Apath>::as_ref -> &str with ""
+                src/main.rs: replace <impl AsRef<str> for Apath>::as_ref -> &str with "xyzzy"
+                src/main.rs: replace <impl From<Apath> for String>::from -> String with String::new()
+                src/main.rs: replace <impl From<Apath> for String>::from -> String with "xyzzy".into()
+                src/main.rs: replace <impl From<&'a str> for Apath>::from -> Apath with Default::default()
+            "#}
+        );
+    }
+
+    #[test]
+    fn mutate_comparison_boundaries() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: u32, b: u32) -> bool {
+                    a < b && a >= 1
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::BinaryOperator)
+                .map(Mutant::describe_change)
+                .collect_vec(),
+            [
+                "replace && with || in f",
+                "replace < with == in f",
+                "replace < with > in f",
+                "replace < with <= in f",
+                "replace >= with < in f",
+                "replace >= with > in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_logical_connectives_in_nested_expressions() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: bool, b: bool, c: bool) -> usize {
+                    let mut n = 0;
+                    if a && (b || c) {
+                        n += 1;
+                    }
+                    while n == 0 || !a {
+                        n = [a, b].iter().filter(|x| **x && c).count();
+                    }
+                    n
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::BinaryOperator)
+                .map(|m| m.name(true))
+                .filter(|name| name.contains("&&") || name.contains("||"))
+                .collect_vec(),
+            [
+                "src/main.rs:3:10: replace && with || in f",
+                "src/main.rs:3:16: replace || with && in f",
+                "src/main.rs:6:18: replace || with && in f",
+                "src/main.rs:7:42: replace && with || in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_arithmetic_methods() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: u32, b: u32) -> Option<u32> {
+                    let c = a.wrapping_mul(b).saturating_sub(1);
+                    c.checked_add(a.min(b))
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::ArithmeticMethod)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:31: replace saturating_sub with saturating_add in f",
+                "src/main.rs:2:15: replace wrapping_mul with wrapping_div in f",
+                "src/main.rs:3:7: replace checked_add with checked_sub in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn negate_if_and_while_conditions() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(mut a: u32, b: Option<u32>) -> u32 {
+                    while a < 10 {
+                        a += 1;
+                    }
+                    if let Some(b) = b {
+                        a += b;
+                    } else if a == 0 || is_even(a) {
+                        a = 1;
+                    }
+                    if !is_even(a) {
+                        a += 1;
+                    }
+                    a
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::NegateCondition)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:11: replace a < 10 with !(a < 10) in f",
+                "src/main.rs:7:15: replace a == 0 || is_even(a) with !(a == 0 || is_even(a)) in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn delete_statements_calling_functions() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(v: &mut Vec<u32>, w: &mut impl Write) -> io::Result<()> {
+                    let n = v.len();
+                    v.push(1);
+                    log(n);
+                    w.write_all(b\"hello\")?;
+                    v.len();
+                    println!(\"done\");
+                    Ok(())
+                }
+
+                fn g(v: &mut Vec<u32>) {
+                    v.clear();
+                }
+
+                fn h(v: &mut Vec<u32>) {
+                    if v.is_empty() {
+                        v.push(0);
+                    }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::DeleteStatement)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:3:5: delete v.push(1); in f",
+                "src/main.rs:4:5: delete log(n); in f",
+                "src/main.rs:5:5: delete w.write_all(b\"hello\")?; in f",
+                "src/main.rs:6:5: delete v.len(); in f",
+                "src/main.rs:17:9: delete v.push(0); in h",
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_literals_are_off_by_default() {
+        let mutants = mutate_source_str("fn f() -> u32 { 2 * 3 }", &Options::default()).unwrap();
+        assert!(!mutants.iter().any(|m| m.genre == Genre::NumericLiteral));
+    }
+
+    #[test]
+    fn mutate_numeric_literals() {
+        let options = Options {
+            enable_genres: vec![Genre::NumericLiteral],
+            ..Default::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {"
+                const LIMIT: usize = 10;
+
+                fn f(a: &[u8], x: f64) -> f64 {
+                    let buf = [0u8; 4];
+                    match a.len() {
+                        0 => 0.5,
+                        n => x * 2.5 + n as f64 + f64::from(a[1] & 0xff),
+                    }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::NumericLiteral)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:4:16: replace 0u8 with 1u8 in f",
+                "src/main.rs:6:14: replace 0.5 with 1.5 in f",
+                "src/main.rs:7:18: replace 2.5 with 3.5 in f",
+                "src/main.rs:7:18: replace 2.5 with 1.5 in f",
+                "src/main.rs:7:47: replace 1 with 2 in f",
+                "src/main.rs:7:47: replace 1 with 0 in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_literal_deltas_from_options() {
+        let options = Options {
+            enable_genres: vec![Genre::NumericLiteral],
+            numeric_literal_deltas: vec![1, 10],
+            ..Default::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(x: f64) -> f64 {
+                    x * 2.5 + 5u32 as f64
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::NumericLiteral)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:9: replace 2.5 with 3.5 in f",
+                "src/main.rs:2:9: replace 2.5 with 1.5 in f",
+                "src/main.rs:2:9: replace 2.5 with 12.5 in f",
+                "src/main.rs:2:15: replace 5u32 with 6u32 in f",
+                "src/main.rs:2:15: replace 5u32 with 4u32 in f",
+                "src/main.rs:2:15: replace 5u32 with 15u32 in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_string_literals() {
+        let options = Options {
+            enable_genres: vec![Genre::StringLiteral],
+            ..Default::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {r#"
+                fn f(headers: &mut Headers, name: &str) {
+                    #[doc = "not mutated"]
+                    const PREFIX: &str = "x-";
+                    headers.insert("content-type", "");
+                    if name == "xyzzy" {
+                        headers.insert(name, r"raw");
+                    }
+                }
+            "#},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::StringLiteral)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                r#"src/main.rs:3:26: replace "x-" with "" in f"#,
+                r#"src/main.rs:3:26: replace "x-" with "xyzzy" in f"#,
+                r#"src/main.rs:4:20: replace "content-type" with "" in f"#,
+                r#"src/main.rs:4:20: replace "content-type" with "xyzzy" in f"#,
+                r#"src/main.rs:5:16: replace "xyzzy" with "" in f"#,
+                r#"src/main.rs:6:30: replace r"raw" with "" in f"#,
+                r#"src/main.rs:6:30: replace r"raw" with "xyzzy" in f"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn swap_arguments_of_the_same_type() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn copy(dst: &mut [u8], src: &[u8], len: usize, offset: usize) {}
+
+                struct Point;
+
+                impl Point {
+                    fn new(x: i32, y: i32) -> Point { Point }
+                    fn moved(&self, dx: i32, dy: i32) -> Point { Point }
+                }
+
+                fn main() {
+                    copy(&mut a, &b, n, 0);
+                    let p = Point::new(1, 2);
+                    p.moved(3, 3);
+                    p.moved(dx, 4);
+                    other(1, 2);
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::SwapArguments)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:11:22: replace n, 0 with 0, n in main",
+                "src/main.rs:12:24: replace 1, 2 with 2, 1 in main",
+                "src/main.rs:14:13: replace dx, 4 with 4, dx in main",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_compound_assignments() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(mut count: u32, mut flags: u8) -> u32 {
+                    count += 1;
+                    count -= 2;
+                    count *= 3;
+                    count /= 4;
+                    flags |= 0x10;
+                    flags &= 0x0f;
+                    count
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::BinaryOperator)
+                .map(Mutant::describe_change)
+                .collect_vec(),
+            [
+                "replace += with -= in f",
+                "replace += with *= in f",
+                "replace -= with += in f",
+                "replace -= with /= in f",
+                "replace *= with += in f",
+                "replace *= with /= in f",
+                "replace /= with %= in f",
+                "replace /= with *= in f",
+                "replace |= with &= in f",
+                "replace |= with ^= in f",
+                "replace &= with |= in f",
+                "replace &= with ^= in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn replace_try_operator_with_unwrap() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(path: &Path) -> Result<usize> {
+                    let text = read_to_string(path)?;
+                    Ok(text.parse::<usize>()?.pow(2))
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::TryOperator)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:36: replace ? with .unwrap() in f",
+                "src/main.rs:3:29: replace ? with .unwrap() in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_combinators() {
+        let options = Options {
+            error_values: vec!["anyhow!(\"mutated\")".to_owned()],
+            ..Default::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {r#"
+                fn f(a: Option<u32>, b: Result<u32>) -> Result<u32> {
+                    if a.is_some() && b.is_err() {
+                        return Ok(a.unwrap_or(7));
+                    }
+                    let c = b.unwrap_or_else(|_| 0);
+                    a.map(|x| x + c).ok_or(anyhow!("missing"))
+                }
+            "#},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::Combinator)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:10: replace is_some with is_none in f",
+                "src/main.rs:2:25: replace is_err with is_ok in f",
+                "src/main.rs:3:21: replace unwrap_or(7) with unwrap_or_default() in f",
+                "src/main.rs:5:15: replace unwrap_or_else(|_| 0) with unwrap_or_default() in f",
+                r#"src/main.rs:6:28: replace anyhow!("missing") with anyhow!("mutated") in f"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_iterator_adapters() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(v: &[u32]) -> Option<u32> {
+                    let a = v.iter().rev().skip(1).take(3).max();
+                    let b = v.iter().min_by_key(|x| x.count_ones());
+                    a.minimum()
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::IteratorAdapter)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:44: replace max with min in f",
+                "src/main.rs:2:35: delete .take(3) in f",
+                "src/main.rs:2:27: delete .skip(1) in f",
+                "src/main.rs:2:21: delete .rev() in f",
+                "src/main.rs:3:22: replace min_by_key with max_by_key in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn take_without_arguments_is_not_an_iterator_adapter() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(opt: &mut Option<u32>, cell: &std::cell::Cell<u32>) -> u32 {
+                    opt.take().unwrap_or_default() + cell.take()
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert!(
+            !mutants.iter().any(|m| m.genre == Genre::IteratorAdapter),
+            "{:?}",
+            mutants.iter().map(|m| m.name(true)).collect_vec()
+        );
+    }
+
+    #[test]
+    fn mutate_range_limits() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(v: &[u32], n: usize) -> u32 {
+                    let mut sum = 0;
+                    for i in 0..n {
+                        sum += v[i];
+                    }
+                    match n {
+                        0..=3 => sum += v[1..=n].len(),
+                        _ => sum += v[n..].len(),
+                    }
+                    sum
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::Range)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:3:15: replace .. with ..= in f",
+                "src/main.rs:7:28: replace ..= with .. in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_bitwise_operators() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(flags: u8, mask: u8) -> u8 {
+                    (flags & mask) | (flags ^ 0x80)
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::BinaryOperator)
+                .map(Mutant::describe_change)
+                .collect_vec(),
+            [
+                "replace | with & in f",
+                "replace | with ^ in f",
+                "replace & with | in f",
+                "replace & with ^ in f",
+                "replace ^ with | in f",
+                "replace ^ with & in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_shift_amounts() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(mut a: u32, n: u32) -> u32 {
+                    a <<= 8;
+                    (a >> n) | (a << 0)
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| matches!(m.genre, Genre::ShiftAmount | Genre::BinaryOperator))
+                .map(Mutant::describe_change)
+                .collect_vec(),
+            [
+                "replace <<= with >>= in f",
+                "replace 8 with 9 in f",
+                "replace 8 with 7 in f",
+                "replace | with & in f",
+                "replace | with ^ in f",
+                "replace >> with << in f",
+                "replace n with (n + 1) in f",
+                "replace n with (n - 1) in f",
+                "replace << with >> in f",
+                "replace 0 with 1 in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn flip_boolean_arguments_and_fields() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f() -> Config {
+                    let verbose = true;
+                    open(path, false);
+                    builder.recursive(true);
+                    Config { verbose, strict: false }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::BooleanLiteral)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:3:16: replace false with true in f",
+                "src/main.rs:4:23: replace true with false in f",
+                "src/main.rs:5:31: replace false with true in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_async_code() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                async fn serve(rx: &mut Receiver<u32>, tx: &Sender<u32>) -> Result<u32> {
+                    tx.send(1).await;
+                    let n = rx.recv().await;
+                    flush().await?;
+                    let got = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await?;
+                    let conn = connect().timeout(LIMIT).await;
+                    tokio::select! {
+                        biased;
+                        v = rx.recv() => v,
+                        _ = tx.closed() => { None }
+                        _ = sleep(Duration::from_millis(10)) => None,
+                        else => None,
+                    };
+                    tokio::select! {
+                        v = rx.recv() => v,
+                        _ = tx.closed() => None,
+                    };
+                    Ok(n)
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        let names = |genre| {
+            mutants
+                .iter()
+                .filter(|m| m.genre == genre)
+                .map(|m| m.name(true))
+                .collect_vec()
+        };
+        assert_eq!(
+            names(Genre::Await),
+            ["src/main.rs:2:15: delete .await in serve"]
+        );
+        assert_eq!(
+            names(Genre::Timeout),
+            [
+                "src/main.rs:5:36: replace Duration::from_secs(5) with ::std::time::Duration::ZERO in serve",
+                "src/main.rs:5:36: replace Duration::from_secs(5) with ::std::time::Duration::MAX in serve",
+                "src/main.rs:6:34: replace LIMIT with ::std::time::Duration::ZERO in serve",
+                "src/main.rs:6:34: replace LIMIT with ::std::time::Duration::MAX in serve",
+            ]
+        );
+        let select_mutants = mutants
+            .iter()
+            .filter(|m| m.genre == Genre::SelectBranch)
+            .collect_vec();
+        assert_eq!(
+            select_mutants.iter().map(|m| m.name(true)).collect_vec(),
+            [
+                "src/main.rs:9:9: swap select branches in serve",
+                "src/main.rs:10:9: swap select branches in serve",
+            ]
+        );
+        assert_eq!(
+            select_mutants[0].replacement,
+            "_ = tx.closed() => { None },\n        v = rx.recv() => v,"
+        );
+        assert_eq!(
+            select_mutants[1].replacement,
+            "_ = sleep(Duration::from_millis(10)) => None,\n        _ = tx.closed() => { None }"
         );
-        return false;
     }
-    skip
-}
-
-/// Finds the first path attribute (`#[path = "..."]`)
-///
-/// # Errors
-/// Returns an error if the path attribute contains a dubious path (leading `/`)
-fn find_path_attribute(attrs: &[Attribute]) -> std::result::Result<Option<Utf8PathBuf>, String> {
-    attrs
-        .iter()
-        .find_map(|attr| match &attr.meta {
-            syn::Meta::NameValue(meta) if meta.path.is_ident("path") => {
-                let syn::Expr::Lit(expr_lit) = &meta.value else {
-                    return None;
-                };
-                let syn::Lit::Str(lit_str) = &expr_lit.lit else {
-                    return None;
-                };
-                let path = lit_str.value();
 
-                // refuse to follow absolute paths
-                if path.starts_with('/') {
-                    Some(Err(path))
-                } else {
-                    Some(Ok(Utf8PathBuf::from(path)))
+    #[test]
+    fn fn_value_of_type_param_uses_bounds() {
+        let options = Options {
+            genres: vec![Genre::FnValue],
+            ..Options::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {"
+                struct Cache<K, V> { k: K, v: V }
+                impl<K, V: Default + Clone> Cache<K, V> {
+                    fn get(&self) -> V { self.v.clone() }
+                    fn key(&self) -> K { todo!() }
+                    fn convert<T: From<u8>>(&self) -> Option<T> { None }
                 }
-            }
-            _ => None,
-        })
-        .transpose()
-}
-
-#[cfg(test)]
-mod test {
-    use indoc::indoc;
-    use itertools::Itertools;
-    use pretty_assertions::assert_eq;
-    use test_log::test;
-
-    use crate::test_util::copy_of_testdata;
-    use crate::workspace::{PackageFilter, Workspace};
-
-    use super::*;
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .map(|m| format!(
+                    "{}: {}",
+                    m.function.as_ref().unwrap().function_name,
+                    m.replacement
+                ))
+                .collect::<Vec<_>>(),
+            [
+                "Cache<K, V>::get: Default::default()",
+                "Cache<K, V>::convert: Some(<T as From<u8>>::from(0))",
+                "Cache<K, V>::convert: Some(<T as From<u8>>::from(1))",
+            ]
+        );
+    }
 
     #[test]
-    fn path_ends_with() {
-        use super::path_ends_with;
-        use syn::parse_quote;
-
-        let path = parse_quote! { foo::bar::baz };
-        assert!(path_ends_with(&path, "baz"));
-        assert!(!path_ends_with(&path, "bar"));
-        assert!(!path_ends_with(&path, "foo"));
-
-        let path = parse_quote! { baz };
-        assert!(path_ends_with(&path, "baz"));
-        assert!(!path_ends_with(&path, "bar"));
-
-        let path = parse_quote! { BTreeMap<K, V> };
-        assert!(path_ends_with(&path, "BTreeMap"));
-        assert!(!path_ends_with(&path, "V"));
-        assert!(!path_ends_with(&path, "K"));
+    fn only_pub_mutates_public_items() {
+        let options = Options {
+            only_pub: true,
+            genres: vec![Genre::FnValue],
+            ..Options::default()
+        };
+        let mutants = mutate_source_str(
+            indoc! {"
+                pub fn public() -> u32 {
+                    fn nested() -> bool { true }
+                    if nested() { 1 } else { 2 }
+                }
+                fn private() -> u32 { 1 }
+                pub(crate) fn crate_visible() -> u32 { 1 }
+                pub struct S;
+                impl S {
+                    pub fn method(&self) -> u32 { 1 }
+                    fn helper(&self) -> u32 { 1 }
+                }
+                impl Iterator for S {
+                    type Item = u32;
+                    fn next(&mut self) -> Option<u32> { None }
+                }
+                pub trait T {
+                    fn provided(&self) -> u32 { 1 }
+                }
+                trait Private {
+                    fn provided(&self) -> u32 { 1 }
+                }
+                mod internal {
+                    pub fn hidden() -> u32 { 1 }
+                    pub trait Hidden {
+                        fn provided(&self) -> u32 { 1 }
+                    }
+                    pub mod nested {
+                        pub fn also_hidden() -> u32 { 1 }
+                    }
+                }
+                pub mod api {
+                    pub fn exported() -> u32 { 1 }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .map(|m| m.function.as_ref().unwrap().function_name.as_str())
+                .unique()
+                .collect_vec(),
+            [
+                "public",
+                "public::nested",
+                "S::method",
+                "<impl Iterator for S>::next",
+                "T::provided",
+                "api::exported",
+            ]
+        );
     }
 
-    /// We should not generate mutants that produce the same tokens as the
-    /// source.
     #[test]
-    fn no_mutants_equivalent_to_source() {
-        let code = indoc! { "
-            fn always_true() -> bool { true }
-        "};
-        let source_file = SourceFile::for_tests("src/lib.rs", code, "unimportant", true);
-        let (mutants, _files) =
-            walk_file(&source_file, &[], &Options::default()).expect("walk_file");
-        let mutant_names = mutants.iter().map(|m| m.name(false)).collect_vec();
-        // It would be good to suggest replacing this with 'false', breaking a key behavior,
-        // but bad to replace it with 'true', changing nothing.
+    fn mutate_loop_control() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(v: &[Vec<u32>]) -> u32 {
+                    let mut n = 0;
+                    'outer: for row in v {
+                        for x in row {
+                            if *x == 0 {
+                                continue 'outer;
+                            } else if *x > 100 {
+                                break 'outer;
+                            } else if *x > 10 {
+                                break;
+                            }
+                            n += x;
+                        }
+                    }
+                    let found = 'search: {
+                        if n > 0 {
+                            break 'search true;
+                        }
+                        false
+                    };
+                    v.iter().for_each(|row| loop { if row.is_empty() { break } });
+                    n
+                }
+            "},
+            &options,
+        )
+        .unwrap();
         assert_eq!(
-            mutant_names,
-            ["src/lib.rs: replace always_true -> bool with false"]
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::LoopControl)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:6:17: replace continue 'outer with break 'outer in f",
+                "src/main.rs:8:17: replace break 'outer with continue 'outer in f",
+                "src/main.rs:8:17: replace break 'outer with break in f",
+                "src/main.rs:10:17: replace break with continue in f",
+                "src/main.rs:21:56: replace break with continue in f",
+            ]
         );
     }
 
-    /// We don't visit functions inside files marked with `#![cfg(test)]`.
     #[test]
-    fn no_mutants_in_files_with_inner_cfg_test_attribute() {
+    fn delete_else_branches() {
         let options = Options::default();
-        let console = Console::new();
-        let tmp = copy_of_testdata("cfg_test_inner");
-        let workspace = Workspace::open(tmp.path()).unwrap();
-        let discovered = workspace
-            .discover(&PackageFilter::All, &options, &console)
-            .unwrap();
-        assert_eq!(discovered.mutants.as_slice(), &[]);
-    }
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: u32) {
+                    if a == 0 {
+                        zero();
+                    } else if a == 1 {
+                        one();
+                    } else {
+                        many();
+                    }
+                    let b = if a == 0 { 1 } else { 2 };
+                    if a > b {
+                        big();
+                    } else {
+                        small();
+                    }
+                }
 
-    /// Helper function for `find_path_attribute` tests
-    fn run_find_path_attribute(
-        token_stream: &TokenStream,
-    ) -> std::result::Result<Option<Utf8PathBuf>, String> {
-        let token_string = token_stream.to_string();
-        let item_mod = syn::parse_str::<syn::ItemMod>(&token_string).unwrap_or_else(|err| {
-            panic!("Failed to parse test case token stream: {token_string}\n{err}")
-        });
-        find_path_attribute(&item_mod.attrs)
+                fn g(a: u32) -> u32 {
+                    if a > 0 { a } else { 1 }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::DeleteElse)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:4:7: delete else branch of if at line 2 in f",
+                "src/main.rs:6:7: delete else branch of if at line 4 in f",
+                "src/main.rs:12:7: delete else branch of if at line 10 in f",
+            ]
+        );
     }
 
     #[test]
-    fn find_path_attribute_on_module_item() {
-        let outer = run_find_path_attribute(&quote! {
-            #[path = "foo_file.rs"]
-            mod foo;
-        });
-        assert_eq!(outer, Ok(Some(Utf8PathBuf::from("foo_file.rs"))));
-
-        let inner = run_find_path_attribute(&quote! {
-            mod foo {
-                #![path = "foo_folder"]
-
-                #[path = "file_for_bar.rs"]
-                mod bar;
+    fn mutate_casts_only_when_enabled() {
+        let source = indoc! {"
+            fn f(x: u32, y: i64) -> u8 {
+                let z = y as f64;
+                u8::try_from(x + 1).unwrap() ^ (x as u8)
             }
-        });
-        assert_eq!(inner, Ok(Some(Utf8PathBuf::from("foo_folder"))));
+        "};
+        let mutants = mutate_source_str(source, &Options::default()).unwrap();
+        assert!(!mutants.iter().any(|m| m.genre == Genre::Cast));
+
+        let options = Options {
+            enable_genres: vec![Genre::Cast],
+            ..Default::default()
+        };
+        let mutants = mutate_source_str(source, &options).unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::Cast)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:18: replace f64 with f32 in f",
+                "src/main.rs:3:5: replace u8::try_from(x + 1).unwrap() with ((x + 1) as u8) in f",
+                "src/main.rs:3:42: replace u8 with u16 in f",
+                "src/main.rs:3:42: replace u8 with i8 in f",
+                "src/main.rs:3:37: replace x as u8 with u8::try_from(x).unwrap() in f",
+            ]
+        );
     }
 
     #[test]
-    fn reject_module_path_absolute() {
-        // dots are valid
-        let dots = run_find_path_attribute(&quote! {
-            #[path = "contains/../dots.rs"]
-            mod dots;
-        });
-        assert_eq!(dots, Ok(Some(Utf8PathBuf::from("contains/../dots.rs"))));
+    fn delete_unary_operators() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: &i32, b: bool) -> i32 {
+                    if !b {
+                        return -*a;
+                    }
+                    *a
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::UnaryOperator)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:8: delete ! in f",
+                "src/main.rs:3:16: delete - in f",
+            ]
+        );
+    }
 
-        let dots_inner = run_find_path_attribute(&quote! {
-            mod dots_in_path {
-                #![path = "contains/../dots"]
-            }
-        });
-        assert_eq!(dots_inner, Ok(Some(Utf8PathBuf::from("contains/../dots"))));
+    #[test]
+    fn mutate_if_let_and_let_else() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: Option<u32>, b: Option<u32>) -> u32 {
+                    let Some(b) = b else { return 0 };
+                    if let Some(c) = a.checked_add(b) {
+                        log(c);
+                    }
+                    if let Some(a) = a { a + b } else { b }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::LetPattern)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:5: force let Some(b) else branch taken in f",
+                "src/main.rs:3:5: force if let Some(c) branch taken in f",
+                "src/main.rs:3:5: replace if let Some(c) with {} in f",
+                "src/main.rs:6:5: force if let Some(a) branch taken in f",
+                "src/main.rs:6:5: replace if let Some(a) with its else branch in f",
+            ]
+        );
+        let let_pattern = mutants
+            .iter()
+            .filter(|m| m.genre == Genre::LetPattern)
+            .collect_vec();
+        assert_eq!(
+            let_pattern[3].replacement,
+            "{ let Some(a) = a else { panic!() }; { a + b } }"
+        );
+        assert_eq!(let_pattern[4].replacement, "{ b }");
+    }
 
-        let leading_slash = run_find_path_attribute(&quote! {
-            #[path = "/leading_slash.rs"]
-            mod dots;
-        });
-        assert_eq!(leading_slash, Err("/leading_slash.rs".to_owned()));
+    #[test]
+    fn return_each_unit_variant_of_error_enum() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                mod error {
+                    pub enum ParseError {
+                        Empty,
+                        TooLong,
+                        BadChar(char),
+                    }
+                }
 
-        let allow_other_slashes = run_find_path_attribute(&quote! {
-            #[path = "foo/other/slashes/are/allowed.rs"]
-            mod dots;
-        });
+                fn parse(s: &str) -> Result<u32, error::ParseError> {
+                    s.parse()
+                }
+            "},
+            &options,
+        )
+        .unwrap();
         assert_eq!(
-            allow_other_slashes,
-            Ok(Some(Utf8PathBuf::from("foo/other/slashes/are/allowed.rs")))
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::FnValue)
+                .map(|m| m.name(false))
+                .collect_vec(),
+            [
+                "src/main.rs: replace parse -> Result<u32, error::ParseError> with Ok(0)",
+                "src/main.rs: replace parse -> Result<u32, error::ParseError> with Ok(1)",
+                "src/main.rs: replace parse -> Result<u32, error::ParseError> with Err(error::ParseError::Empty)",
+                "src/main.rs: replace parse -> Result<u32, error::ParseError> with Err(error::ParseError::TooLong)",
+            ]
         );
+    }
 
-        let leading_slash2 = run_find_path_attribute(&quote! {
-            #[path = "/leading_slash/../and_dots.rs"]
-            mod dots;
-        });
+    #[test]
+    fn error_values_by_type_replace_default_error_values() {
+        let options = Options::from_arg_strs(["mutants", "--error", "\"mutated\".into()"]);
+        let options = Options {
+            error_values_by_type: [
+                ("anyhow::Error", "::anyhow::anyhow!(\"mutated\")"),
+                ("io::Error", "std::io::Error::other(\"mutated\")"),
+            ]
+            .into_iter()
+            .map(|(error_type, value)| (error_type.to_owned(), value.to_owned()))
+            .collect(),
+            ..options
+        };
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn a() -> Result<(), anyhow::Error> { Ok(()) }
+                fn b() -> Result<(), std::io::Error> { Ok(()) }
+                fn c() -> Result<(), String> { Ok(()) }
+            "},
+            &options,
+        )
+        .unwrap();
         assert_eq!(
-            leading_slash2,
-            Err("/leading_slash/../and_dots.rs".to_owned())
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::FnValue)
+                .map(|m| m.name(false))
+                .collect_vec(),
+            [
+                "src/main.rs: replace a -> Result<(), anyhow::Error> with Err(::anyhow::anyhow!(\"mutated\"))",
+                "src/main.rs: replace b -> Result<(), std::io::Error> with Err(std::io::Error::other(\"mutated\"))",
+                "src/main.rs: replace c -> Result<(), String> with Err(\"mutated\".into())",
+            ]
         );
     }
 
-    /// Demonstrate that we can generate mutants from a string, without needing a whole tree.
     #[test]
-    fn mutants_from_test_str() {
+    fn mutate_trait_default_method_bodies() {
         let options = Options::default();
         let mutants = mutate_source_str(
             indoc! {"
-                fn always_true() -> bool { true }
+                trait Shape {
+                    fn sides(&self) -> u32;
+
+                    fn is_polygon(&self) -> bool {
+                        self.sides() >= 3 && !self.is_curved()
+                    }
+
+                    #[mutants::skip]
+                    fn is_curved(&self) -> bool {
+                        self.sides() == 0
+                    }
+                }
             "},
             &options,
         )
-        .expect("walk_file_string");
+        .unwrap();
         assert_eq!(
-            mutants.iter().map(|m| m.name(false)).collect_vec(),
-            ["src/main.rs: replace always_true -> bool with false"]
+            mutants.iter().map(|m| m.name(true)).collect_vec(),
+            [
+                "src/main.rs:5:9: replace Shape::is_polygon -> bool with true",
+                "src/main.rs:5:9: replace Shape::is_polygon -> bool with false",
+                "src/main.rs:5:27: replace && with || in Shape::is_polygon",
+                "src/main.rs:5:22: replace >= with < in Shape::is_polygon",
+                "src/main.rs:5:22: replace >= with > in Shape::is_polygon",
+                "src/main.rs:5:9: replace self.sides() >= 3 with 3 >= self.sides() in Shape::is_polygon",
+                "src/main.rs:5:30: delete ! in Shape::is_polygon",
+            ]
         );
     }
 
-    /// Skip mutating arguments to a particular named function.
     #[test]
-    fn skip_named_fn() {
+    fn mutate_const_values_only_when_enabled() {
+        let source = indoc! {"
+            const LIMIT: usize = 100;
+            static VERBOSE: bool = false;
+            const TABLE: [u8; 2] = [0x10, 7];
+
+            struct S;
+            impl S {
+                const SCALE: f64 = 2.5;
+            }
+
+            fn f() -> usize {
+                const INNER: usize = 3;
+                INNER
+            }
+        "};
+        let mutants = mutate_source_str(source, &Options::default()).unwrap();
+        assert!(!mutants.iter().any(|m| m.genre == Genre::ConstValue));
+
         let options = Options {
-            skip_calls: vec!["dont_touch_this".to_owned()],
+            enable_genres: vec![Genre::ConstValue],
             ..Default::default()
         };
-        let mut mutants = mutate_source_str(
+        let mutants = mutate_source_str(source, &options).unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::ConstValue)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:1:22: replace 100 with 101",
+                "src/main.rs:1:22: replace 100 with 99",
+                "src/main.rs:2:24: replace false with true",
+                "src/main.rs:3:31: replace 7 with 8",
+                "src/main.rs:3:31: replace 7 with 6",
+                "src/main.rs:7:24: replace 2.5 with 3.5",
+                "src/main.rs:7:24: replace 2.5 with 1.5",
+                "src/main.rs:11:26: replace 3 with 4 in f",
+                "src/main.rs:11:26: replace 3 with 2 in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_closure_bodies() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
             indoc! {"
-                fn main() {
-                    dont_touch_this(2 + 3);
+                fn f(v: &mut Vec<u32>) -> usize {
+                    v.sort_by(|a, b| b.cmp(a));
+                    let is_big = |x: &u32| -> bool { *x > 10 };
+                    v.iter().filter(|x| **x % 2 == 0).map(|x| x + 1).count()
                 }
             "},
             &options,
         )
-        .expect("walk_file_string");
-        // Ignore the main function itself
-        mutants.retain(|m| m.genre != Genre::FnValue);
-        assert_eq!(mutants, []);
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| matches!(m.genre, Genre::ClosureValue | Genre::BinaryOperator))
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:22: replace b.cmp(a) with std::cmp::Ordering::Less in f",
+                "src/main.rs:2:22: replace b.cmp(a) with std::cmp::Ordering::Equal in f",
+                "src/main.rs:2:22: replace b.cmp(a) with std::cmp::Ordering::Greater in f",
+                "src/main.rs:3:38: replace *x > 10 with true in f",
+                "src/main.rs:3:38: replace *x > 10 with false in f",
+                "src/main.rs:3:41: replace > with == in f",
+                "src/main.rs:3:41: replace > with < in f",
+                "src/main.rs:3:41: replace > with >= in f",
+                "src/main.rs:4:25: replace **x % 2 == 0 with true in f",
+                "src/main.rs:4:25: replace **x % 2 == 0 with false in f",
+                "src/main.rs:4:33: replace == with != in f",
+                "src/main.rs:4:29: replace % with / in f",
+                "src/main.rs:4:29: replace % with + in f",
+                "src/main.rs:4:49: replace + with - in f",
+                "src/main.rs:4:49: replace + with * in f",
+            ]
+        );
     }
 
     #[test]
-    fn skip_with_capacity_by_default() {
-        let options = Options::from_arg_strs(["mutants"]);
-        let mut mutants = mutate_source_str(
+    fn swap_operands_of_non_commutative_operators() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
             indoc! {"
-                fn main() {
-                    let mut v = Vec::with_capacity(2 * 100);
+                fn f(a: u32, b: u32) -> bool {
+                    let d = a - b - 1;
+                    let q = a / (b + 1);
+                    a % a < b && d <= q * 2
                 }
             "},
             &options,
         )
-        .expect("walk_file_string");
-        // Ignore the main function itself
-        mutants.retain(|m| m.genre != Genre::FnValue);
-        assert_eq!(mutants, []);
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::SwapOperands)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:13: replace a - b - 1 with 1 - (a - b) in f",
+                "src/main.rs:2:13: replace a - b with b - a in f",
+                "src/main.rs:3:13: replace a / (b + 1) with (b + 1) / a in f",
+                "src/main.rs:4:18: replace d <= q * 2 with (q * 2) <= d in f",
+            ]
+        );
     }
 
     #[test]
-    fn mutate_vec_with_capacity_when_default_skips_are_turned_off() {
-        let options = Options::from_arg_strs(["mutants", "--skip-calls-defaults", "false"]);
+    fn mutate_indexes() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {r#"
+                fn f(v: &[u8], map: &HashMap<String, u8>, i: usize) -> u8 {
+                    let head = &v[..i];
+                    let x = v[0] + v[2] + v[i * 2] + map["key"] + map[&name()];
+                    x + v.first().unwrap() + head.last().unwrap()
+                }
+            "#},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::Index)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:3:15: replace 0 with 1 in f",
+                "src/main.rs:3:22: replace 2 with 3 in f",
+                "src/main.rs:3:22: replace 2 with 1 in f",
+                "src/main.rs:3:29: replace i * 2 with (i * 2) + 1 in f",
+                "src/main.rs:3:29: replace i * 2 with (i * 2) - 1 in f",
+                "src/main.rs:4:11: replace first with last in f",
+                "src/main.rs:4:35: replace last with first in f",
+            ]
+        );
+    }
+
+    #[test]
+    fn index_with_binary_operator_is_parenthesized() {
+        let options = Options::default();
         let mutants = mutate_source_str(
             indoc! {"
-                fn main() {
-                    let mut _v = std::vec::Vec::<String>::with_capacity(2 * 100);
+                fn f(v: &[u8], a: usize, m: usize) -> u8 {
+                    v[a & m] + v[a << 2]
                 }
             "},
             &options,
         )
-        .expect("walk_file_string");
-        dbg!(&mutants);
-        // The main fn plus two mutations of the `*` expression.
-        assert_eq!(mutants.len(), 3);
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::Index)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:2:7: replace a & m with (a & m) + 1 in f",
+                "src/main.rs:2:7: replace a & m with (a & m) - 1 in f",
+                "src/main.rs:2:18: replace a << 2 with (a << 2) + 1 in f",
+                "src/main.rs:2:18: replace a << 2 with (a << 2) - 1 in f",
+            ]
+        );
     }
 
     #[test]
-    fn skip_method_calls_by_name() {
-        let options = Options::from_arg_strs(["mutants", "--skip-calls", "dont_touch_this"]);
+    fn delete_early_returns_from_guard_clauses() {
+        let options = Options::default();
         let mutants = mutate_source_str(
             indoc! {"
-                fn main() {
-                    let mut v = v::new();
-                    v.dont_touch_this(2 + 3);
+                fn f(a: &[u8]) -> Result<u8, Error> {
+                    if a.is_empty() {
+                        log_empty();
+                        return Err(Error::Empty);
+                    }
+                    if a.len() > 10 { return Err(Error::TooLong) }
+                    let b = if a[0] == 0 { return Ok(0); } else { a[0] };
+                    Ok(b)
                 }
             "},
             &options,
         )
         .unwrap();
-        dbg!(&mutants);
         assert_eq!(
             mutants
                 .iter()
-                .filter(|mutant| mutant.genre != Genre::FnValue)
-                .count(),
-            0
+                .filter(|m| m.genre == Genre::EarlyReturn)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:4:9: delete return Err(Error::Empty); in f",
+                "src/main.rs:6:23: delete return Err(Error::TooLong) in f",
+            ]
         );
     }
 
     #[test]
-    fn mutant_name_includes_type_parameters() {
-        // From https://github.com/sourcefrog/cargo-mutants/issues/334
-        let options = Options::from_arg_strs(["mutants"]);
+    fn mutate_expressions_in_macro_arguments() {
+        let options = Options {
+            skip_calls: vec!["dont_touch".to_owned()],
+            ..Options::default()
+        };
         let mutants = mutate_source_str(
             indoc! {r#"
-            impl AsRef<str> for Apath {
-                fn as_ref(&self) -> &str {
-                    &self.0
-                }
-            }
+                static TABLE: [u8; 2] = my_table![1 + 1, 2];
 
-            impl From<Apath> for String {
-                fn from(a: Apath) -> String {
-                    a.0
+                fn f(a: u32, b: u32) -> Vec<u32> {
+                    println!("{} {}", a - b, "x");
+                    debug!("{}", a * b);
+                    dont_touch!(a / b);
+                    assert!(matches!(a, 1 | 2));
+                    let c = vec![0; (a + 1) as usize];
+                    vec![a + b, c.len() as u32]
                 }
-            }
+            "#},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::BinaryOperator)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:4:25: replace - with + in f",
+                "src/main.rs:4:25: replace - with / in f",
+                "src/main.rs:8:24: replace + with - in f",
+                "src/main.rs:8:24: replace + with * in f",
+                "src/main.rs:9:12: replace + with - in f",
+                "src/main.rs:9:12: replace + with * in f",
+            ]
+        );
+    }
 
-            impl<'a> From<&'a str> for Apath {
-                fn from(s: &'a str) -> Apath {
-                    assert!(Apath::is_valid(s), "invalid apath: {s:?}");
-                    Apath(s.to_string())
+    #[test]
+    fn macro_arguments_that_are_not_expressions_are_not_mutated() {
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn f(a: u32) -> u32 {
+                    my_dsl! { when a > 1 => a + 1 }
                 }
+            "},
+            &Options::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            mutants.iter().map(|m| m.name(true)).collect_vec(),
+            [
+                "src/main.rs:2:5: replace f -> u32 with 0",
+                "src/main.rs:2:5: replace f -> u32 with 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn mutate_impls_generated_by_derive() {
+        let code = indoc! {"
+            #[derive(Debug, PartialEq)]
+            struct Point {
+                x: i32,
             }
-            "#},
+        "};
+        let expanded = indoc! {"
+            struct Point {
+                x: i32,
+            }
+            #[automatically_derived]
+            impl ::core::cmp::PartialEq for Point {
+                fn eq(&self, other: &Point) -> bool { self.x == other.x }
+            }
+        "};
+        let source_file = SourceFile::for_tests(
+            Utf8Path::new("src/main.rs"),
+            code,
+            "cargo-mutants-testdata-internal",
+            true,
+        );
+        let derived_impls = crate::expand::derived_impls(expanded).unwrap();
+        let options = Options::default();
+        let (mutants, _, _) = walk_file(
+            &source_file,
+            false,
+            &ErrorValues::default(),
+            &derived_impls,
+            &CfgContext::default(),
             &options,
         )
         .unwrap();
-        dbg!(&mutants);
-        let mutant_names = mutants.iter().map(|m| m.name(false) + "\n").join("");
         assert_eq!(
-            mutant_names,
-            indoc! {r#"
-                src/main.rs: replace <impl AsRef<str> for Apath>::as_ref -> &str with ""
-                src/main.rs: replace <impl AsRef<str> for Apath>::as_ref -> &str with "xyzzy"
-                src/main.rs: replace <impl From<Apath> for String>::from -> String with String::new()
-                src/main.rs: replace <impl From<Apath> for String>::from -> String with "xyzzy".into()
-                src/main.rs: replace <impl From<&'a str> for Apath>::from -> Apath with Default::default()
-            "#}
+            mutants.iter().map(|m| m.name(true)).collect_vec(),
+            [
+                "src/main.rs:1:1: replace <impl ::core::cmp::PartialEq for Point>::eq -> bool with true expanded from #[derive(PartialEq)]",
+                "src/main.rs:1:1: replace <impl ::core::cmp::PartialEq for Point>::eq -> bool with false expanded from #[derive(PartialEq)]",
+                "src/main.rs:1:1: replace == with != in <impl ::core::cmp::PartialEq for Point>::eq expanded from #[derive(PartialEq)]",
+            ]
+        );
+        assert_eq!(
+            mutants[2].mutated_code(),
+            indoc! {"
+                #[derive(Debug)]
+                struct Point {
+                    x: i32,
+                }
+                #[automatically_derived]
+                impl ::core::cmp::PartialEq for Point {
+                    fn eq(&self, other: &Point) -> bool { self.x != /* ~ changed by cargo-mutants ~ */ other.x }
+                } /* ~ changed by cargo-mutants ~ */
+            "}
         );
     }
 
@@ -1161,6 +4556,37 @@ mod test {
         );
     }
 
+    #[test]
+    fn mutate_match_arms_with_binding_fallback() {
+        let options = Options::default();
+        let mutants = mutate_source_str(
+            indoc! {"
+                fn main() {
+                    match x {
+                        1 => {},
+                        None => {},
+                        n @ 2..=4 => {},
+                        other => {},
+                    }
+                }
+            "},
+            &options,
+        )
+        .unwrap();
+        assert_eq!(
+            mutants
+                .iter()
+                .filter(|m| m.genre == Genre::MatchArm)
+                .map(|m| m.name(true))
+                .collect_vec(),
+            [
+                "src/main.rs:3:9: delete match arm",
+                "src/main.rs:4:9: delete match arm",
+                "src/main.rs:5:9: delete match arm",
+            ]
+        );
+    }
+
     #[test]
     fn skip_match_arms_without_fallback() {
         let options = Options::default();
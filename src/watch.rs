@@ -0,0 +1,135 @@
+// Copyright 2025 Martin Pool
+
+//! Watch the source tree after a run, and test the mutants in files that change.
+//!
+//! Files are polled for changes in their modification time, so that this works the same
+//! way on every platform without a filesystem notification library. A change in the
+//! modification time only triggers a new discovery: files are considered changed only if
+//! their text is different from the previous run, so touching a file or saving it
+//! unchanged doesn't start a new run.
+
+#![warn(clippy::pedantic)]
+
+use std::collections::BTreeMap;
+use std::fs::metadata;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use camino::{Utf8Path, Utf8PathBuf};
+use itertools::Itertools;
+use tracing::{debug, warn};
+
+use crate::console::Console;
+use crate::interrupt::check_interrupted;
+use crate::lab::test_mutants;
+use crate::options::Options;
+use crate::output::OutputDir;
+use crate::source::SourceFile;
+use crate::workspace::{PackageFilter, Workspace};
+
+/// How often to look for changed files.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Wait for source files to change, and test the mutants in the changed files, until
+/// interrupted.
+///
+/// `files` are the source files from the previous run.
+pub fn watch(
+    workspace: &Workspace,
+    package_filter: &PackageFilter,
+    options: &Options,
+    console: &Console,
+    output_parent_dir: &Utf8Path,
+    mut files: Vec<SourceFile>,
+) -> Result<()> {
+    console.message("Watching for changes; press Ctrl-C to stop\n");
+    loop {
+        let mtimes = modification_times(workspace.root(), &files);
+        while modification_times(workspace.root(), &files) == mtimes {
+            check_interrupted()?;
+            sleep(POLL_INTERVAL);
+        }
+        let discovered = match workspace.discover(package_filter, options, console) {
+            Ok(discovered) => discovered,
+            Err(err) => {
+                // Probably the tree is half-edited: wait for it to change again.
+                warn!("Failed to find mutants in the changed tree: {err:#}");
+                continue;
+            }
+        };
+        let changed = changed_files(&files, &discovered.files);
+        files = discovered.files;
+        if changed.is_empty() {
+            debug!("Modification times changed but the text is the same");
+            continue;
+        }
+        console.message(&format!("Changed: {}\n", changed.iter().join(", ")));
+        let mutants = discovered
+            .mutants
+            .into_iter()
+            .filter(|mutant| changed.contains(&mutant.source_file.tree_relative_path))
+            .collect::<Vec<_>>();
+        if mutants.is_empty() {
+            console.message("No mutants in the changed files\n");
+        } else {
+            let output_dir = OutputDir::new(output_parent_dir)?;
+            console.set_debug_log(output_dir.open_debug_log()?);
+            test_mutants(mutants, workspace, output_dir, options, console)?;
+        }
+        console.message("Watching for changes; press Ctrl-C to stop\n");
+    }
+}
+
+/// The modification time of each source file, or None if it can't be read.
+fn modification_times(
+    root: &Utf8Path,
+    files: &[SourceFile],
+) -> BTreeMap<Utf8PathBuf, Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| {
+            let mtime = metadata(root.join(&file.tree_relative_path))
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            (file.tree_relative_path.clone(), mtime)
+        })
+        .collect()
+}
+
+/// Return the paths of files that are new, or whose text is different from the
+/// previous run.
+fn changed_files(previous: &[SourceFile], current: &[SourceFile]) -> Vec<Utf8PathBuf> {
+    let previous = previous
+        .iter()
+        .map(|file| (&file.tree_relative_path, file.code()))
+        .collect::<BTreeMap<_, _>>();
+    current
+        .iter()
+        .filter(|file| previous.get(&file.tree_relative_path) != Some(&file.code()))
+        .map(|file| file.tree_relative_path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn changed_files_are_new_or_edited() {
+        let previous = [
+            SourceFile::for_tests("src/lib.rs", "mod a;\n", "small", true),
+            SourceFile::for_tests("src/a.rs", "fn a() {}\n", "small", false),
+            SourceFile::for_tests("src/b.rs", "fn b() {}\n", "small", false),
+        ];
+        let current = [
+            SourceFile::for_tests("src/lib.rs", "mod a;\nmod c;\n", "small", true),
+            SourceFile::for_tests("src/a.rs", "fn a() {}\n", "small", false),
+            SourceFile::for_tests("src/c.rs", "fn c() {}\n", "small", false),
+        ];
+        assert_eq!(
+            changed_files(&previous, &current),
+            ["src/lib.rs", "src/c.rs"]
+        );
+    }
+}
@@ -23,6 +23,7 @@ use std::sync::Arc;
 
 use anyhow::{anyhow, bail, ensure, Context};
 use camino::{Utf8Path, Utf8PathBuf};
+use globset::{Glob, GlobSetBuilder};
 use itertools::Itertools;
 use serde_json::Value;
 use tracing::{debug, error, warn};
@@ -88,6 +89,16 @@ impl Workspace {
         &self.metadata.workspace_root
     }
 
+    /// The directory where cargo puts build output for the workspace, usually `target`.
+    pub fn target_dir(&self) -> &Utf8Path {
+        &self.metadata.target_directory
+    }
+
+    /// All the packages in the workspace.
+    pub fn packages(&self) -> &[Arc<Package>] {
+        &self.packages
+    }
+
     /// Open the workspace containing a given directory.
     pub fn open<P: AsRef<Path>>(start_dir: P) -> Result<Self> {
         let start_dir = start_dir.as_ref();
@@ -112,20 +123,36 @@ impl Workspace {
         Ok(Workspace { metadata, packages })
     }
 
-    pub fn packages_by_name<S: AsRef<str>>(&self, names: &[S]) -> Vec<Arc<Package>> {
-        names
+    /// Find the packages whose names match any of these patterns, which may be globs
+    /// like `svc-*`.
+    ///
+    /// Patterns that don't match any package are reported as warnings.
+    pub fn packages_by_name<S: AsRef<str>>(&self, names: &[S]) -> Result<Vec<Arc<Package>>> {
+        let mut builder = GlobSetBuilder::new();
+        for name in names {
+            let name = name.as_ref();
+            builder.add(Glob::new(name).with_context(|| format!("Invalid package glob {name:?}"))?);
+        }
+        let globs = builder.build().context("Failed to build package globs")?;
+        let mut matched_globs = vec![false; names.len()];
+        let packages = self
+            .packages
             .iter()
-            .map(AsRef::as_ref)
-            .sorted()
-            .filter_map(|name| {
-                if let Some(p) = self.packages.iter().find(|p| p.name == name) {
-                    Some(Arc::clone(p))
-                } else {
-                    warn!("Package {name:?} not found in source tree");
-                    None
+            .filter(|package| {
+                let matches = globs.matches(&package.name);
+                for &i in &matches {
+                    matched_globs[i] = true;
                 }
+                !matches.is_empty()
             })
-            .collect()
+            .cloned()
+            .collect();
+        for (name, matched) in names.iter().zip(matched_globs) {
+            if !matched {
+                warn!("Package {:?} not found in source tree", name.as_ref());
+            }
+        }
+        Ok(packages)
     }
 
     /// Match a `PackageFilter` to the actual packages in this workspace, returning a list of packages.
@@ -155,7 +182,7 @@ impl Workspace {
                     package_dir == root,
                     "package {package_dir:?} doesn't match any child and doesn't match the workspace root {root:?}?",
                 );
-                let default_packages = self.default_packages();
+                let default_packages = self.default_packages()?;
                 debug!(
                     ?default_packages,
                     "Resolved auto package filter to workspace default packages"
@@ -164,7 +191,7 @@ impl Workspace {
             }
             PackageFilter::All => Ok(PackageSelection::All),
             PackageFilter::Explicit(names) => {
-                Ok(PackageSelection::Explicit(self.packages_by_name(names)))
+                Ok(PackageSelection::Explicit(self.packages_by_name(names)?))
             }
         }
     }
@@ -183,19 +210,19 @@ impl Workspace {
         options: &Options,
         console: &Console,
     ) -> Result<Discovered> {
-        walk_tree(
-            self.root(),
-            &self.expand_selection(self.filter_packages(package_filter)?),
-            options,
-            console,
-        )
+        let mut packages = self.expand_selection(self.filter_packages(package_filter)?);
+        if !options.exclude_packages.is_empty() {
+            let excluded = self.packages_by_name(&options.exclude_packages)?;
+            packages.retain(|package| !excluded.contains(package));
+        }
+        walk_tree(self.root(), &packages, options, console)
     }
 
     /// Return the default workspace packages.
     ///
     /// Default packages can be specified in the workspace's `Cargo.toml` file;
     /// if not, all packages are included.
-    fn default_packages(&self) -> PackageSelection {
+    fn default_packages(&self) -> Result<PackageSelection> {
         let metadata = &self.metadata;
         // `cargo_metadata::workspace_default_packages` will panic when calling Cargo older than 1.71;
         // in that case we'll just fall back to everything, for lack of a better option.
@@ -211,14 +238,16 @@ impl Workspace {
                     ?default_package_names,
                     "Manifest defines explicit default packages"
                 );
-                PackageSelection::Explicit(self.packages_by_name(&default_package_names))
+                Ok(PackageSelection::Explicit(
+                    self.packages_by_name(&default_package_names)?,
+                ))
             }
             Err(err) => {
                 warn!(
                     cargo_metadata_error = err.downcast::<String>().unwrap_or_default(),
                     "workspace_default_packages is not supported; testing all packages",
                 );
-                PackageSelection::All
+                Ok(PackageSelection::All)
             }
         }
     }
@@ -413,4 +442,43 @@ mod test {
             ["main/src/main.rs", "main2/src/main.rs"]
         );
     }
+
+    #[test]
+    fn filter_by_package_glob() {
+        let tmp = copy_of_testdata("workspace");
+        let workspace = Workspace::open(tmp.path()).expect("Find workspace root");
+        let filter = PackageFilter::explicit(["main*"]);
+        let PackageSelection::Explicit(packages) = workspace.filter_packages(&filter).unwrap()
+        else {
+            panic!("Expected PackageSelection::Explicit");
+        };
+        assert_eq!(
+            packages.iter().map(|p| &p.name).collect_vec(),
+            ["main", "main2"]
+        );
+        assert!(workspace
+            .filter_packages(&PackageFilter::explicit(["main["]))
+            .is_err());
+    }
+
+    #[test]
+    fn exclude_packages_by_glob() {
+        let tmp = copy_of_testdata("workspace");
+        let workspace = Workspace::open(tmp.path()).expect("Find workspace root");
+        let options = Options {
+            exclude_packages: vec!["*utils".to_owned(), "main2".to_owned()],
+            ..Default::default()
+        };
+        let discovered = workspace
+            .discover(&PackageFilter::All, &options, &Console::new())
+            .unwrap();
+        assert_eq!(
+            discovered
+                .files
+                .iter()
+                .map(|sf| sf.tree_relative_path.clone())
+                .collect_vec(),
+            ["main/src/main.rs"]
+        );
+    }
 }
@@ -0,0 +1,17 @@
+pub fn scale(x: u32) -> u32 {
+    x * 2
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scale_by_factor_from_env() {
+        let factor: u32 = std::env::var("CARGO_MUTANTS_TESTDATA_FACTOR")
+            .expect("CARGO_MUTANTS_TESTDATA_FACTOR should be set")
+            .parse()
+            .unwrap();
+        assert_eq!(scale(3), 3 * factor);
+    }
+}
@@ -0,0 +1,57 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--blame-since` and `--blame-author`.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use indoc::indoc;
+
+mod util;
+use util::{copy_of_testdata, git_commit, git_init, run};
+
+#[test]
+fn blame_filters_select_new_code() {
+    let tmp = copy_of_testdata("small_well_tested");
+    git_init(tmp.path());
+    git_commit(tmp.path(), "Old", "2020-01-01T00:00:00");
+    OpenOptions::new()
+        .append(true)
+        .open(tmp.path().join("src/lib.rs"))
+        .unwrap()
+        .write_all(b"\npub fn double(n: u32) -> u32 {\n    n * 2\n}\n")
+        .unwrap();
+    git_commit(tmp.path(), "New", "2024-06-01T00:00:00");
+    let expected = indoc! { "
+        src/lib.rs: replace double -> u32 with 0
+        src/lib.rs: replace double -> u32 with 1
+        src/lib.rs: replace * with + in double
+        src/lib.rs: replace * with / in double
+    " };
+    for filter_args in [
+        &["--blame-since", "2022-01-01"][..],
+        &["--blame-author", "new@example.com"],
+        &["--blame-author", "New", "--blame-since", "2022-01-01"],
+    ] {
+        run()
+            .args(["mutants", "--list", "--line-col=false"])
+            .args(filter_args)
+            .current_dir(tmp.path())
+            .assert()
+            .success()
+            .stdout(expected);
+    }
+    run()
+        .args([
+            "mutants",
+            "--list",
+            "--blame-author",
+            "Old",
+            "--blame-since",
+            "2022-01-01",
+        ])
+        .current_dir(tmp.path())
+        .assert()
+        .success()
+        .stdout("");
+}
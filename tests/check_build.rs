@@ -18,25 +18,28 @@ fn small_well_tested_tree_check_only() {
         .assert()
         .success()
         .stdout(indoc! {r"
-            Found 4 mutants to test
+            Found 5 mutants to test
             ok       Unmutated baseline
             ok       src/lib.rs:5:5: replace factorial -> u32 with 0
             ok       src/lib.rs:5:5: replace factorial -> u32 with 1
+            ok       src/lib.rs:6:15: replace ..= with .. in factorial
             ok       src/lib.rs:7:11: replace *= with += in factorial
             ok       src/lib.rs:7:11: replace *= with /= in factorial
-            4 mutants tested: 4 succeeded
+            5 mutants tested: 5 succeeded
         "})
         .stderr("");
     let outcomes = outcome_json_counts(&tmp_src_dir);
     assert_eq!(
         outcomes,
         serde_json::json!({
-            "success": 4, // They did all build
+            "success": 5, // They did all build
             "caught": 0, // They weren't actually tested
             "unviable": 0,
             "missed": 0,
             "timeout": 0,
-            "total_mutants": 4,
+            "equivalent": 0,
+            "uncovered": 0,
+            "total_mutants": 5,
         })
     );
 }
@@ -49,16 +52,18 @@ fn small_well_tested_tree_check_only_shuffled() {
         .current_dir(tmp_src_dir.path())
         .assert()
         .success()
-        .stdout(predicate::str::contains("4 mutants tested: 4 succeeded"));
+        .stdout(predicate::str::contains("5 mutants tested: 5 succeeded"));
     assert_eq!(
         outcome_json_counts(&tmp_src_dir),
         serde_json::json!({
-            "success": 4, // They did all build
+            "success": 5, // They did all build
             "caught": 0, // They weren't actually tested
             "unviable": 0,
             "missed": 0,
             "timeout": 0,
-            "total_mutants": 4,
+            "equivalent": 0,
+            "uncovered": 0,
+            "total_mutants": 5,
         })
     );
 }
@@ -90,13 +95,14 @@ fn check_succeeds_in_tree_that_builds_but_fails_tests() {
         .success()
         .stdout(predicate::function(|stdout: &str| {
             insta::assert_snapshot!(stdout, @r###"
-            Found 4 mutants to test
+            Found 5 mutants to test
             ok       Unmutated baseline
             ok       src/lib.rs:2:5: replace factorial -> u32 with 0
             ok       src/lib.rs:2:5: replace factorial -> u32 with 1
+            ok       src/lib.rs:3:15: replace ..= with .. in factorial
             ok       src/lib.rs:4:11: replace *= with += in factorial
             ok       src/lib.rs:4:11: replace *= with /= in factorial
-            4 mutants tested: 4 succeeded
+            5 mutants tested: 5 succeeded
             "###);
             true
         }));
@@ -105,10 +111,12 @@ fn check_succeeds_in_tree_that_builds_but_fails_tests() {
         serde_json::json!({
             "caught": 0,
             "missed": 0,
-            "success": 4,
+            "success": 5,
             "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
             "unviable": 0,
-            "total_mutants": 4,
+            "total_mutants": 5,
         })
     );
 }
@@ -124,14 +132,18 @@ fn check_tree_with_mutants_skip() {
         .assert()
         .success()
         .stdout(indoc! { r"
-            Found 5 mutants to test
+            Found 9 mutants to test
             ok       Unmutated baseline
             ok       src/lib.rs:15:5: replace controlled_loop with ()
+            ok       src/lib.rs:18:12: replace should_stop() with !(should_stop()) in controlled_loop
+            ok       src/lib.rs:19:13: replace break with continue in controlled_loop
+            ok       src/lib.rs:21:12: replace start.elapsed() > Duration::from_secs(60 * 5) with !(start.elapsed() > Duration::from_secs(60 * 5)) in controlled_loop
             ok       src/lib.rs:21:28: replace > with == in controlled_loop
             ok       src/lib.rs:21:28: replace > with < in controlled_loop
+            ok       src/lib.rs:21:28: replace > with >= in controlled_loop
             ok       src/lib.rs:21:53: replace * with + in controlled_loop
             ok       src/lib.rs:21:53: replace * with / in controlled_loop
-            5 mutants tested: 5 succeeded
+            9 mutants tested: 9 succeeded
             "})
         .stderr("");
     assert_eq!(
@@ -139,10 +151,12 @@ fn check_tree_with_mutants_skip() {
         serde_json::json!({
             "caught": 0,
             "missed": 0,
-            "success": 5,
+            "success": 9,
             "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
             "unviable": 0,
-            "total_mutants": 5,
+            "total_mutants": 9,
         })
     );
 }
@@ -165,6 +179,8 @@ fn check_tree_where_build_fails() {
             "missed": 0,
             "success": 0,
             "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
             "unviable": 0,
             "total_mutants": 0,
         })
@@ -202,6 +218,88 @@ fn unviable_mutation_of_struct_with_no_default() {
             "unviable": 1,
             "missed": 0,
             "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
+            "total_mutants": 1,
+        })
+    );
+}
+
+#[test]
+fn check_first_then_test_viable_mutants() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args([
+            "mutants",
+            "--check-first",
+            "--no-shuffle",
+            "--no-times",
+            "--caught",
+            "-j2",
+        ])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    assert_eq!(
+        outcome_json_counts(&tmp_src_dir),
+        serde_json::json!({
+            "success": 0,
+            "caught": 5,
+            "unviable": 0,
+            "missed": 0,
+            "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
+            "total_mutants": 5,
+        })
+    );
+    let outcomes: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    let phases = outcomes["outcomes"][1]["phase_results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|phase_result| phase_result["phase"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(phases, ["Check", "Build", "Test"]);
+}
+
+#[test]
+fn check_first_finds_unviable_mutant() {
+    let tmp_src_dir = copy_of_testdata("struct_with_no_default");
+    run()
+        .args([
+            "mutants",
+            "--check-first",
+            "--line-col=false",
+            "--no-times",
+            "--no-shuffle",
+            "-v",
+            "-V",
+        ])
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"unviable *src/lib.rs:\d+:\d+: replace make_an_s -> S with Default::default\(\)",
+            )
+            .unwrap(),
+        );
+    assert_eq!(
+        outcome_json_counts(&tmp_src_dir),
+        serde_json::json!({
+            "success": 0,
+            "caught": 0,
+            "unviable": 1,
+            "missed": 0,
+            "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
             "total_mutants": 1,
         })
     );
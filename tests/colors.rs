@@ -8,7 +8,7 @@
 use predicates::prelude::*;
 
 mod util;
-use util::run;
+use util::{copy_of_testdata, run};
 
 fn has_color_listing() -> impl Predicate<str> {
     predicates::str::contains("with \x1b[33m0\x1b[0m")
@@ -106,3 +106,15 @@ fn clicolor_force_shows_in_stdout_and_trace() {
         .stdout(has_color_listing())
         .stderr(has_color_debug());
 }
+
+#[test]
+fn colors_always_highlights_listed_diffs() {
+    let tmp = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--list", "--diff", "--colors=always", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("\x1b[1m--- src/lib.rs\x1b[0m\n"))
+        .stdout(predicates::str::contains("\x1b[32m+    0 \x1b[0m"));
+}
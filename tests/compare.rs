@@ -0,0 +1,68 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `cargo mutants compare`.
+
+use std::fs::{read_to_string, write};
+
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn compare_finds_newly_missed_mutants() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let old = tmp_src_dir.path().join("old");
+    let new = tmp_src_dir.path().join("new");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("-o")
+        .arg(&old)
+        .assert()
+        .success();
+
+    // Weaken the test so that some mutants are missed.
+    let lib_rs = tmp_src_dir.path().join("src/lib.rs");
+    let code = read_to_string(&lib_rs).unwrap();
+    write(
+        &lib_rs,
+        code.replace(
+            "assert_eq!(factorial(6), 720);",
+            "assert!(factorial(6) > 0);",
+        ),
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("-o")
+        .arg(&new)
+        .assert()
+        .code(2);
+
+    run()
+        .args(["mutants", "compare"])
+        .arg(&old)
+        .arg(new.join("mutants.out/outcomes.json"))
+        .assert()
+        .code(2)
+        .stdout(
+            predicate::str::starts_with("Newly missed:\n")
+                .and(predicate::str::contains(
+                    "  src/lib.rs:5:5: replace factorial -> u32 with 1\n",
+                ))
+                .and(predicate::str::contains("Newly caught").not())
+                .and(predicate::str::ends_with(
+                    "newly missed, 0 newly caught, 0 new, 0 removed\n",
+                )),
+        );
+
+    run()
+        .args(["mutants", "compare"])
+        .arg(old.join("mutants.out"))
+        .arg(&old)
+        .assert()
+        .success()
+        .stdout("0 mutants newly missed, 0 newly caught, 0 new, 0 removed\n");
+}
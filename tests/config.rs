@@ -178,10 +178,37 @@ fn list_with_config_file_regexps() {
     src/simple_fns.rs: replace == with != in divisible_by_three
     src/simple_fns.rs: replace % with / in divisible_by_three
     src/simple_fns.rs: replace % with + in divisible_by_three
+    src/simple_fns.rs: replace a % 3 with 3 % a in divisible_by_three
     "###
     );
 }
 
+#[test]
+fn list_with_config_file_return_type() {
+    let testdata = copy_of_testdata("well_tested");
+    write_config_file(
+        &testdata,
+        r#"
+        return_type = ["^u32$"]
+        "#,
+    );
+    run()
+        .args([
+            "mutants",
+            "--list",
+            "--line-col=false",
+            "--file=simple_fns.rs",
+            "-d",
+        ])
+        .arg(testdata.path())
+        .assert()
+        .success()
+        .stdout(indoc! { "
+            src/simple_fns.rs: replace returns_42u32 -> u32 with 0
+            src/simple_fns.rs: replace returns_42u32 -> u32 with 1
+        " });
+}
+
 #[test]
 fn exclude_re_overrides_config() {
     let testdata = copy_of_testdata("well_tested");
@@ -214,16 +241,194 @@ fn exclude_re_overrides_config() {
     src/simple_fns.rs: replace == with != in divisible_by_three
     src/simple_fns.rs: replace % with / in divisible_by_three
     src/simple_fns.rs: replace % with + in divisible_by_three
+    src/simple_fns.rs: replace a % 3 with 3 % a in divisible_by_three
+    src/simple_fns.rs: delete r.push_str(s); in double_string
     "###);
 }
 
+#[test]
+fn config_check_reports_all_unknown_keys() {
+    let testdata = copy_of_testdata("well_tested");
+    write_config_file(
+        &testdata,
+        r#"
+        exclude_regex = ["a"]
+        timeout = 20
+
+        [profile.quick]
+        genre = ["FnValue"]
+        "#,
+    );
+    run()
+        .args(["mutants", "config", "check", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .failure()
+        .stdout("")
+        .stderr(
+            predicates::str::contains("unknown key \"exclude_regex\"")
+                .and(predicates::str::contains("unknown key \"timeout\""))
+                .and(predicates::str::contains(
+                    "unknown key \"profile.quick.genre\"",
+                ))
+                .and(predicates::str::contains(
+                    "Found 3 unknown keys in config files",
+                )),
+        );
+}
+
+#[test]
+fn config_check_prints_effective_config() {
+    let testdata = copy_of_testdata("well_tested");
+    write_config_file(
+        &testdata,
+        r#"
+        exclude_re = ["impl Debug"]
+
+        [profile.quick]
+        genres = ["FnValue"]
+        profile = "mutants"
+        "#,
+    );
+    run()
+        .args(["mutants", "config", "check", "--profile=quick", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .success()
+        .stdout(
+            predicates::str::contains("exclude_re = [\"impl Debug\"]\n")
+                .and(predicates::str::contains("genres = [\"FnValue\"]\n"))
+                .and(predicates::str::contains("cargo_profile = \"mutants\"\n")),
+        )
+        .stderr(predicates::str::contains(
+            "`profile.quick.profile` naming a cargo profile is deprecated",
+        ));
+}
+
+#[test]
+fn config_extends_shared_file() {
+    let testdata = copy_of_testdata("well_tested");
+    let shared = TempDir::new().unwrap();
+    let shared_path = shared.path().join("mutants-base.toml");
+    write(
+        &shared_path,
+        indoc! { r#"
+            examine_globs = ["src/simple_fns.rs"]
+            exclude_re = ["-> bool with true"]
+        "# },
+    )
+    .unwrap();
+    write_config_file(
+        &testdata,
+        &format!(
+            "extends = {:?}\nexamine_re = [\"divisible\"]\n",
+            shared_path.to_str().unwrap()
+        ),
+    );
+    run()
+        .args(["mutants", "--list", "--line-col=false", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .success()
+        .stdout(indoc! { "
+            src/simple_fns.rs: replace divisible_by_three -> bool with false
+            src/simple_fns.rs: replace == with != in divisible_by_three
+            src/simple_fns.rs: replace % with / in divisible_by_three
+            src/simple_fns.rs: replace % with + in divisible_by_three
+            src/simple_fns.rs: replace a % 3 with 3 % a in divisible_by_three
+        " });
+}
+
+#[test]
+fn named_profile_overrides_config() {
+    let testdata = copy_of_testdata("well_tested");
+    write_config_file(
+        &testdata,
+        r#"
+        examine_globs = ["src/simple_fns.rs"]
+        exclude_re = ["-> bool with true"]
+
+        [profile.quick]
+        examine_re = ["divisible"]
+        "#,
+    );
+    // Without a profile, the profile tables are ignored.
+    let cmd = run()
+        .args(["mutants", "--list", "--line-col=false", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .success();
+    assert!(String::from_utf8_lossy(&cmd.get_output().stdout).contains("in double_string"));
+    run()
+        .args([
+            "mutants",
+            "--list",
+            "--line-col=false",
+            "--profile=quick",
+            "-d",
+        ])
+        .arg(testdata.path())
+        .assert()
+        .success()
+        .stdout(indoc! { "
+            src/simple_fns.rs: replace divisible_by_three -> bool with false
+            src/simple_fns.rs: replace == with != in divisible_by_three
+            src/simple_fns.rs: replace % with / in divisible_by_three
+            src/simple_fns.rs: replace % with + in divisible_by_three
+            src/simple_fns.rs: replace a % 3 with 3 % a in divisible_by_three
+        " })
+        .stderr(predicates::str::contains(
+            "Using profile \"quick\" from the config file",
+        ));
+}
+
+#[test]
+fn env_from_config_and_args_is_passed_to_cargo() {
+    let testdata = copy_of_testdata("needs_env");
+    write_config_file(
+        &testdata,
+        r#"
+        [env]
+        CARGO_MUTANTS_TESTDATA_FACTOR = "2"
+        "#,
+    );
+    run()
+        .args(["mutants", "--no-shuffle", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("4 mutants tested"));
+    // The command line overrides the config, and this value makes the baseline fail.
+    run()
+        .args(["mutants", "--env", "CARGO_MUTANTS_TESTDATA_FACTOR=3", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .code(4)
+        .stdout(predicates::str::contains("right: 9"));
+}
+
+#[test]
+fn tree_without_needed_feature_has_no_mutants() {
+    // The only mutable function is behind `#[cfg(feature = "needed")]`, which isn't
+    // active unless the feature is turned on, so there's nothing to test.
+    let testdata = copy_of_testdata("fails_without_feature");
+    run()
+        .args(["mutants", "-d"])
+        .arg(testdata.path())
+        .assert()
+        .success()
+        .stdout(predicates::str::contains("Found 0 mutants to test"));
+}
+
 #[test]
 fn tree_fails_without_needed_feature() {
     // The point of this tree is to check that Cargo features can be turned on,
-    // but let's make sure it does fail as intended if they're not.
+    // but let's make sure it does fail as intended if they're not. Passing
+    // features through the cargo args means the feature cfgs are unknown, so
+    // the factorial function is still mutated.
     let testdata = copy_of_testdata("fails_without_feature");
     run()
-        .args(["mutants", "-d"])
+        .args(["mutants", "--cargo-arg=--features=", "-d"])
         .arg(testdata.path())
         .assert()
         .failure()
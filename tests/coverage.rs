@@ -0,0 +1,74 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--coverage`.
+
+use std::fs::{read_to_string, write};
+
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn mutants_in_unexecuted_lines_are_missed_without_testing() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let lcov_path = tmp_src_dir.path().join("lcov.info");
+    write(
+        &lcov_path,
+        "SF:src/lib.rs\nDA:4,0\nDA:5,0\nDA:6,0\nDA:7,0\nDA:9,0\nDA:10,0\nend_of_record\n",
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "--coverage"])
+        .arg(&lcov_path)
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "5 mutants aren't executed by any tests",
+        ))
+        .stdout(predicate::str::contains("Unmutated baseline").not())
+        .stdout(predicate::str::contains(
+            "5 mutants tested: 5 missed (5 uncovered)",
+        ));
+    let out_dir = tmp_src_dir.path().join("mutants.out");
+    assert_eq!(
+        read_to_string(out_dir.join("uncovered.txt"))
+            .unwrap()
+            .lines()
+            .count(),
+        5
+    );
+    assert_eq!(
+        read_to_string(out_dir.join("missed.txt"))
+            .unwrap()
+            .lines()
+            .count(),
+        5
+    );
+    let log = read_to_string(out_dir.join("log/src__lib.rs_line_7_col_11.log")).unwrap();
+    assert!(log.contains("no tests execute this code"));
+}
+
+#[test]
+fn mutants_in_executed_lines_are_tested() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let lcov_path = tmp_src_dir.path().join("lcov.info");
+    // Only the body of the loop is unexecuted.
+    write(
+        &lcov_path,
+        "SF:src/lib.rs\nDA:4,1\nDA:5,1\nDA:6,1\nDA:7,0\nDA:9,1\nDA:10,1\nend_of_record\n",
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "--coverage"])
+        .arg(&lcov_path)
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "5 mutants tested: 2 missed (2 uncovered), 3 caught",
+        ));
+}
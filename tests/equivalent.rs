@@ -0,0 +1,70 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--detect-equivalent`
+
+use std::fs::{read_to_string, OpenOptions};
+use std::io::Write;
+
+use indoc::indoc;
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+
+mod util;
+use util::{copy_of_testdata, outcome_json_counts, run};
+
+#[test]
+fn mutants_of_unused_function_are_equivalent() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    // This function is never called from the tests, so isn't included in the test binary.
+    OpenOptions::new()
+        .append(true)
+        .open(tmp_src_dir.path().join("src/lib.rs"))
+        .unwrap()
+        .write_all(
+            indoc! { "
+
+                #[allow(dead_code)]
+                fn unused() -> u32 {
+                    2
+                }
+            "}
+            .as_bytes(),
+        )
+        .unwrap();
+    run()
+        .args([
+            "mutants",
+            "--detect-equivalent",
+            "--no-shuffle",
+            "--no-times",
+        ])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "equivalent src/lib.rs:25:5: replace unused -> u32 with 0",
+        ))
+        .stdout(predicate::str::contains(
+            "7 mutants tested: 5 caught, 2 equivalent",
+        ));
+    assert_eq!(
+        outcome_json_counts(&tmp_src_dir),
+        serde_json::json!({
+            "success": 0,
+            "caught": 5,
+            "unviable": 0,
+            "missed": 0,
+            "timeout": 0,
+            "equivalent": 2,
+            "uncovered": 0,
+            "total_mutants": 7,
+        })
+    );
+    assert_eq!(
+        read_to_string(tmp_src_dir.path().join("mutants.out/equivalent.txt")).unwrap(),
+        indoc! { "
+            src/lib.rs:25:5: replace unused -> u32 with 0
+            src/lib.rs:25:5: replace unused -> u32 with 1
+        "}
+    );
+}
@@ -0,0 +1,107 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--events-file`.
+
+use std::fs::read_to_string;
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn events_file_has_an_event_as_each_scenario_starts_and_finishes() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let events_dir = TempDir::new().unwrap();
+    let events_path = events_dir.path().join("events.ndjson");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("--events-file")
+        .arg(&events_path)
+        .assert()
+        .success();
+    let events: Vec<Value> = read_to_string(&events_path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("parse event"))
+        .collect();
+    let names: Vec<&str> = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.first(), Some(&"testing_started"));
+    assert_eq!(names.last(), Some(&"testing_finished"));
+    assert_eq!(events[0]["total_mutants"], 5);
+    assert_eq!(names[1..3], ["scenario_started", "scenario_finished"]);
+    assert_eq!(events[1]["name"], "baseline");
+    assert_eq!(events[2]["outcome"]["summary"], "Success");
+    assert_eq!(
+        names
+            .iter()
+            .filter(|name| **name == "scenario_finished")
+            .count(),
+        6
+    );
+    let caught = events
+        .iter()
+        .filter(|event| event["outcome"]["summary"] == "CaughtMutant")
+        .count();
+    assert_eq!(caught, 5);
+    assert_eq!(events.last().unwrap()["caught"], 5);
+    assert!(events.iter().all(|event| event["time"]
+        .as_str()
+        .is_some_and(|time| time.ends_with('Z'))));
+}
+
+#[test]
+fn json_progress_style_writes_events_and_progress_to_stderr() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let output = run()
+        .args(["mutants", "--no-times", "--progress-style=json", "-d"])
+        .arg(tmp_src_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let events: Vec<Value> = stderr
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).expect("parse event"))
+        .collect();
+    let names: Vec<&str> = events
+        .iter()
+        .map(|event| event["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.first(), Some(&"testing_started"));
+    assert_eq!(names.last(), Some(&"testing_finished"));
+    let progress = &events[names.iter().position(|name| *name == "progress").unwrap()];
+    assert_eq!(progress["total_mutants"], 5);
+    assert!(progress["mutants_done"].is_u64());
+    assert!(progress["elapsed_secs"].is_f64());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains('{'));
+    assert!(stdout.contains("5 mutants tested: 5 caught"));
+}
+
+#[test]
+fn explicit_json_progress_style_wins_over_quiet_progress() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let output = run()
+        .args([
+            "mutants",
+            "--no-times",
+            "--quiet-progress",
+            "--progress-style=json",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr
+        .lines()
+        .any(|line| line.starts_with('{') && line.contains("\"testing_finished\"")));
+}
@@ -0,0 +1,48 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--output-format=github`.
+
+use std::fs::read_to_string;
+
+use predicates::prelude::*;
+use tempfile::NamedTempFile;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn github_annotations_and_job_summary_for_missed_mutants() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    let summary_file = NamedTempFile::new().unwrap();
+    run()
+        .args(["mutants", "--no-times", "--output-format=github", "-d"])
+        .arg(tmp_src_dir.path())
+        .env("GITHUB_STEP_SUMMARY", summary_file.path())
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "::warning file=src/lib.rs,line=2,col=5,endLine=2,endColumn=37,title=Missed mutant::mutant survived: replace is_symlink -> bool with true\n",
+        ))
+        .stdout(predicate::str::contains(
+            "::warning file=src/lib.rs,line=2,col=22,endLine=2,endColumn=23,title=Missed mutant::mutant survived: replace & with | in is_symlink\n",
+        ))
+        .stdout(predicate::str::contains("## cargo-mutants").not());
+    let summary = read_to_string(summary_file.path()).unwrap();
+    assert!(summary.starts_with(
+        "## cargo-mutants\n\n5 mutants tested: 3 missed, 2 caught\n\n| Outcome | Mutants |\n| --- | ---: |\n| Missed | 3 |\n| Caught | 2 |\n\n### Missed mutants\n\n- `src/lib.rs:2:5`: `replace is_symlink -> bool with true`\n"
+    ));
+    assert!(summary.contains("- `src/lib.rs:2:22`: `replace & with | in is_symlink`\n"));
+}
+
+#[test]
+fn no_annotations_by_default() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .env_remove("GITHUB_STEP_SUMMARY")
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("::warning").not())
+        .stdout(predicate::str::contains("## cargo-mutants").not());
+}
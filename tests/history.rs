@@ -0,0 +1,60 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--history-file` and `cargo mutants history`.
+
+use std::fs::read_to_string;
+
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn history_records_each_run_and_shows_trend() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let history_file = tmp_src_dir.path().join("history.jsonl");
+    for _ in 0..2 {
+        run()
+            .args(["mutants", "--no-shuffle", "-d"])
+            .arg(tmp_src_dir.path())
+            .arg("--history-file")
+            .arg(&history_file)
+            .assert()
+            .success();
+    }
+    let history = read_to_string(&history_file).unwrap();
+    let records: Vec<serde_json::Value> = history
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0]["missed"], 0);
+    assert_eq!(records[0]["score"], 100.0);
+    assert_eq!(records[0]["missed_mutants"], serde_json::json!([]));
+
+    run()
+        .args(["mutants", "history", "--runs", "1", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("--history-file")
+        .arg(&history_file)
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::starts_with("Time ")
+                .and(predicate::str::contains("100.0%    +0.0\n"))
+                .and(predicate::function(|stdout: &str| {
+                    stdout.lines().count() == 2
+                })),
+        );
+}
+
+#[test]
+fn history_without_file_is_an_error() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "history", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No history file"));
+}
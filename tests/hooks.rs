@@ -0,0 +1,65 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for shell hooks run before and after scenarios and the whole run.
+
+#![cfg(unix)]
+
+use std::fs::{create_dir, read_to_string, write};
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn hooks_run_around_each_scenario_and_the_run() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let record_dir = TempDir::new().unwrap();
+    let record = record_dir.path().join("hooks.txt");
+    create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        format!(
+            r#"[hooks]
+before_scenario = "echo $MUTANTS_HOOK $MUTANTS_SCENARIO $MUTANTS_LINE >> {record}"
+after_scenario = "echo $MUTANTS_HOOK $MUTANTS_SCENARIO $MUTANTS_OUTCOME >> {record}"
+"#,
+            record = record.display()
+        ),
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-shuffle", "-F", "with 0", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("--before-run")
+        .arg(format!("echo $MUTANTS_HOOK >> {}", record.display()))
+        .arg("--after-run")
+        .arg(format!("echo $MUTANTS_HOOK >> {}", record.display()))
+        .assert()
+        .success();
+    assert_eq!(
+        read_to_string(&record).unwrap(),
+        "before_run\n\
+        before_scenario baseline\n\
+        after_scenario baseline Success\n\
+        before_scenario mutant 5\n\
+        after_scenario mutant CaughtMutant\n\
+        after_run\n"
+    );
+    let log = read_to_string(tmp_src_dir.path().join("mutants.out/log/before_run.log")).unwrap();
+    assert!(log.contains("*** before_run hook\n"), "{log}");
+}
+
+#[test]
+fn failing_hook_stops_the_run() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--before-scenario", "exit 3", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "before_scenario hook \"exit 3\" failed with Failure(3)",
+        ));
+}
@@ -1,6 +1,6 @@
 // Copyright 2023 Martin Pool
 
-use std::fs::read_to_string;
+use std::fs::{copy, read_to_string, rename, write};
 use std::io::Write;
 
 use indoc::indoc;
@@ -9,7 +9,7 @@ use similar::TextDiff;
 use tempfile::NamedTempFile;
 
 mod util;
-use util::{copy_of_testdata, run};
+use util::{copy_of_testdata, git_commit, git_init, run};
 
 #[test]
 fn diff_trees_well_tested() {
@@ -171,3 +171,73 @@ fn diff_with_multiple_deletions_is_ok() {
         ))
         .success();
 }
+
+#[test]
+fn in_diff_from_git_revision_range() {
+    let tmp = copy_of_testdata("diff0");
+    git_init(tmp.path());
+    git_commit(tmp.path(), "Test", "2025-01-01T00:00:00");
+    copy("testdata/diff1/src/lib.rs", tmp.path().join("src/lib.rs")).unwrap();
+    git_commit(tmp.path(), "Test", "2025-01-02T00:00:00");
+    run()
+        .args([
+            "mutants",
+            "--no-shuffle",
+            "--list",
+            "--in-diff",
+            "HEAD~1..HEAD",
+        ])
+        .current_dir(tmp.path())
+        .assert()
+        .success()
+        .stdout(indoc! {r#"
+            src/lib.rs:6:5: replace two -> String with String::new()
+            src/lib.rs:6:5: replace two -> String with "xyzzy".into()
+        "#});
+}
+
+#[test]
+fn in_diff_from_git_detects_renames() {
+    let tmp = copy_of_testdata("well_tested");
+    git_init(tmp.path());
+    git_commit(tmp.path(), "Test", "2025-01-01T00:00:00");
+    rename(tmp.path().join("src/arc.rs"), tmp.path().join("src/rc.rs")).unwrap();
+    let lib_rs = read_to_string(tmp.path().join("src/lib.rs")).unwrap();
+    write(
+        tmp.path().join("src/lib.rs"),
+        lib_rs.replace("mod arc;", "#[path = \"rc.rs\"]\nmod arc;"),
+    )
+    .unwrap();
+    let arc_rs = read_to_string(tmp.path().join("src/rc.rs")).unwrap();
+    write(
+        tmp.path().join("src/rc.rs"),
+        arc_rs + "\nfn is_empty(s: &str) -> bool {\n    s.is_empty()\n}\n",
+    )
+    .unwrap();
+    git_commit(tmp.path(), "Test", "2025-01-02T00:00:00");
+    run()
+        .args(["mutants", "--no-shuffle", "--list", "--line-col=false"])
+        .args(["--in-diff", "HEAD~1..HEAD"])
+        .current_dir(tmp.path())
+        .assert()
+        .success()
+        .stdout(indoc! {"
+            src/rc.rs: replace is_empty -> bool with true
+            src/rc.rs: replace is_empty -> bool with false
+        "});
+}
+
+#[test]
+fn in_diff_neither_file_nor_revision_is_an_error() {
+    let tmp = copy_of_testdata("diff0");
+    git_init(tmp.path());
+    git_commit(tmp.path(), "Test", "2025-01-01T00:00:00");
+    run()
+        .args(["mutants", "--list", "--in-diff", "no-such-file.diff"])
+        .current_dir(tmp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "\"no-such-file.diff\" is not a diff file, and git diff failed",
+        ));
+}
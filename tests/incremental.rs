@@ -0,0 +1,110 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--incremental`.
+
+use std::fs::{create_dir, read_to_string, write};
+
+use indoc::indoc;
+
+use predicates::prelude::*;
+use serde_json::Value;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn incremental_run_reuses_outcomes_of_unchanged_mutants() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "--incremental", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 5 mutants to test"));
+    assert!(tmp_src_dir.path().join("mutants.out/cache.json").is_file());
+
+    run()
+        .args(["mutants", "--no-times", "--incremental", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Reused outcomes of 5 unchanged mutants from earlier runs",
+        ))
+        .stdout(predicate::str::contains("Unmutated baseline").not())
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    let outcomes: Value = serde_json::from_str(
+        &read_to_string(tmp_src_dir.path().join("mutants.out/outcomes.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(outcomes["total_mutants"], 5);
+    assert_eq!(outcomes["caught"], 5);
+    let caught = read_to_string(tmp_src_dir.path().join("mutants.out/caught.txt")).unwrap();
+    assert_eq!(caught.lines().count(), 5);
+
+    // Changing the file means its mutants are tested again.
+    let lib_path = tmp_src_dir.path().join("src/lib.rs");
+    let lib = read_to_string(&lib_path).unwrap();
+    write(&lib_path, format!("{lib}\n// changed\n")).unwrap();
+    run()
+        .args(["mutants", "--no-times", "--incremental", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Reused outcomes").not())
+        .stdout(predicate::str::contains("Found 5 mutants to test"));
+}
+
+#[test]
+fn changed_options_invalidate_cached_outcomes() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "--incremental", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    run()
+        .args(["mutants", "--no-times", "--incremental", "--check", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Reused outcomes").not());
+}
+
+#[test]
+fn new_test_in_another_file_catches_previously_missed_mutant() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "--incremental", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "5 mutants tested: 3 missed, 2 caught",
+        ));
+
+    // A test in another file catches the mutants that were missed, and the caught ones
+    // are reused because src/lib.rs is unchanged.
+    create_dir(tmp_src_dir.path().join("tests")).unwrap();
+    write(
+        tmp_src_dir.path().join("tests/not_symlink.rs"),
+        indoc! {"
+            use cargo_mutants_testdata_missing_test::is_symlink;
+
+            #[test]
+            fn no_permissions_is_not_symlink() {
+                assert!(!is_symlink(0));
+            }
+        "},
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "--incremental", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Reused outcomes of 2 unchanged mutants from earlier runs",
+        ))
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+}
@@ -24,7 +24,7 @@ fn list_diff_json_contains_diffs() {
     println!("{}", String::from_utf8_lossy(&out.stdout));
     let out_json = serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap();
     let mutants_json = out_json.as_array().expect("json output is array");
-    assert_eq!(mutants_json.len(), 5);
+    assert_eq!(mutants_json.len(), 7);
     assert!(mutants_json.iter().all(|e| e.as_object().unwrap()["diff"]
         .as_str()
         .unwrap()
@@ -159,6 +159,23 @@ fn list_mutants_with_diffs_in_factorial() {
         .assert_insta("list_mutants_with_diffs_in_factorial");
 }
 
+#[test]
+fn list_diffs_without_context() {
+    let tmp = copy_of_testdata("factorial");
+    let output = run()
+        .args(["mutants", "--list", "--diff", "--diff-context=0"])
+        .current_dir(&tmp)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("\n@@ -"));
+    assert!(
+        stdout.lines().all(|line| !line.starts_with(' ')),
+        "unexpected context lines in {stdout}"
+    );
+}
+
 #[test]
 fn list_mutants_well_tested() {
     let tmp = copy_of_testdata("well_tested");
@@ -243,6 +260,110 @@ fn list_mutants_regex_anchored_matches_full_line() {
         .stdout("src/simple_fns.rs:8:5: replace returns_unit with ()\n");
 }
 
+#[test]
+fn list_mutants_return_type_filter() {
+    let tmp = copy_of_testdata("well_tested");
+    run()
+        .arg("mutants")
+        .args([
+            "--list",
+            "--line-col=false",
+            "--file",
+            "simple_fns.rs",
+            "--return-type",
+            "^bool$",
+            "--return-type",
+            r"^\(\)$",
+        ])
+        .arg("-d")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(indoc::indoc! {"
+            src/simple_fns.rs: replace returns_unit with ()
+            src/simple_fns.rs: replace += with -= in returns_unit
+            src/simple_fns.rs: replace += with *= in returns_unit
+            src/simple_fns.rs: replace divisible_by_three -> bool with true
+            src/simple_fns.rs: replace divisible_by_three -> bool with false
+            src/simple_fns.rs: replace == with != in divisible_by_three
+            src/simple_fns.rs: replace % with / in divisible_by_three
+            src/simple_fns.rs: replace % with + in divisible_by_three
+            src/simple_fns.rs: replace a % 3 with 3 % a in divisible_by_three
+        "});
+}
+
+/// `pub` items in modules that aren't `pub`, including modules in other files, aren't
+/// public, but trait impls are.
+#[test]
+fn list_only_pub_skips_items_in_private_modules() {
+    let tmp = copy_of_testdata("well_tested");
+    run()
+        .args(["mutants", "--list", "--line-col=false", "--only-pub", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(indoc::indoc! {r#"
+            src/methods.rs: replace <impl fmt::Display for Foo>::fmt -> fmt::Result with Ok(Default::default())
+            src/methods.rs: replace <impl fmt::Debug for &Foo>::fmt -> fmt::Result with Ok(Default::default())
+            src/simple_fns.rs: replace double_string -> String with String::new()
+            src/simple_fns.rs: replace double_string -> String with "xyzzy".into()
+            src/simple_fns.rs: delete r.push_str(s); in double_string
+        "#});
+}
+
+#[test]
+fn list_mutants_in_line_ranges() {
+    let tmp = copy_of_testdata("well_tested");
+    run()
+        .arg("mutants")
+        .args(["--list", "--file", "simple_fns.rs", "--lines", "8,12-16"])
+        .arg("-d")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(indoc::indoc! {"
+            src/simple_fns.rs:8:5: replace returns_unit with ()
+            src/simple_fns.rs:8:8: replace += with -= in returns_unit
+            src/simple_fns.rs:8:8: replace += with *= in returns_unit
+            src/simple_fns.rs:13:5: replace returns_42u32 -> u32 with 0
+            src/simple_fns.rs:13:5: replace returns_42u32 -> u32 with 1
+        "});
+}
+
+#[test]
+fn list_skips_code_behind_inactive_features() {
+    let tmp = copy_of_testdata("fails_without_feature");
+    run()
+        .args(["mutants", "--list", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout("");
+    run()
+        .args(["mutants", "--list", "--features", "needed", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "src/bin/factorial.rs:10:5: replace factorial -> u32 with 0",
+        ));
+}
+
+#[test]
+fn invalid_return_type_regex_is_an_error() {
+    let tmp = copy_of_testdata("well_tested");
+    run()
+        .arg("mutants")
+        .args(["--list", "--return-type", "Result<("])
+        .arg("-d")
+        .arg(tmp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Failed to compile return_type regex",
+        ));
+}
+
 #[test]
 fn list_mutants_regex_filters_json() {
     let tmp = copy_of_testdata("well_tested");
@@ -57,6 +57,39 @@ fn show_help() {
         ));
 }
 
+#[test]
+fn schema_describes_genres_in_list_json() {
+    let output = run()
+        .args(["mutants", "schema", "mutants"])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let schema: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    assert_eq!(
+        schema["$schema"],
+        "https://json-schema.org/draft/2020-12/schema"
+    );
+    let genres = schema["$defs"]["Mutant"]["properties"]["genre"]["enum"]
+        .as_array()
+        .unwrap();
+
+    let tmp_src_dir = copy_of_testdata("well_tested");
+    let output = run()
+        .args(["mutants", "--list", "--json", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let mutants: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    for mutant in mutants.as_array().unwrap() {
+        assert!(genres.contains(&mutant["genre"]), "{mutant}");
+    }
+}
+
 #[test]
 fn uses_cargo_env_var_to_run_cargo_so_invalid_value_fails() {
     let tmp_src_dir = copy_of_testdata("well_tested");
@@ -136,6 +169,36 @@ fn copy_testdata_doesnt_include_build_artifacts() {
     assert!(tmp_src_dir.path().join("Cargo.toml").exists());
 }
 
+#[test]
+fn timings_are_written_and_updated_by_later_runs() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let timings_path = tmp_src_dir.path().join("mutants.out/timings.json");
+    let file_mutants = |timings_path: &Path| {
+        let timings: serde_json::Value =
+            serde_json::from_str(&read_to_string(timings_path).unwrap()).unwrap();
+        assert!(
+            timings["files"]["src/lib.rs"]["mean_secs"]
+                .as_f64()
+                .unwrap()
+                > 0.0
+        );
+        timings["files"]["src/lib.rs"]["mutants"].as_u64().unwrap()
+    };
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    assert_eq!(file_mutants(&timings_path), 5);
+    // Testing one mutant in the file replaces the timing from the previous run.
+    run()
+        .args(["mutants", "--no-times", "--re", "with 0", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    assert_eq!(file_mutants(&timings_path), 1);
+}
+
 #[test]
 fn small_well_tested_tree_is_clean() {
     let tmp_src_dir = copy_of_testdata("small_well_tested");
@@ -233,7 +296,7 @@ fn proc_macro_tree_is_well_tested() {
         .assert()
         .success()
         .stdout(predicate::str::contains(
-            "2 mutants tested: 1 caught, 1 unviable",
+            "4 mutants tested: 3 caught, 1 unviable",
         ));
 }
 
@@ -424,7 +487,7 @@ fn factorial_mutants_no_copy_target() {
     let tmp_src_dir = copy_of_testdata("factorial");
     run()
         .arg("mutants")
-        .args(["--no-times"])
+        .args(["--no-times", "--no-shuffle"])
         .arg("-d")
         .arg(tmp_src_dir.path())
         .assert()
@@ -714,7 +777,7 @@ fn timeout_when_unmutated_tree_test_hangs() {
 /// This test is a bit at risk of being flaky, because it depends on the progress
 /// of real time and tests can be unexpectedly slow on CI.
 ///
-/// The `hang_when_mutated` tree generates three mutants:
+/// The `hang_when_mutated` tree generates mutants including:
 ///
 /// * `controlled_loop` could be replaced to return 0 and this will be
 ///   detected, because it should normally return at least one.
@@ -727,9 +790,18 @@ fn timeout_when_unmutated_tree_test_hangs() {
 ///   the loop will never stop, but the test should eventually be killed
 ///   by a timeout.
 ///
+/// * The statement setting `TRIGGER` in `should_stop` could be deleted,
+///   which also makes the loop never stop.
+///
+/// * The `true` stored into `TRIGGER` could be flipped to `false`, with
+///   the same effect.
+///
 /// * `should_stop_const` could change to always return `false`, in which
 ///   case the loop in the block for the const `VAL` will never stop, but
 ///   the build should eventually be killed by a timeout.
+///
+/// Early returns are also deleted by default, which causes more hangs, but they're
+/// tested separately to keep this test fast.
 #[test]
 fn mutants_causing_tests_to_hang_are_stopped_by_manual_timeout() {
     let tmp_src_dir = copy_of_testdata("hang_when_mutated");
@@ -737,14 +809,17 @@ fn mutants_causing_tests_to_hang_are_stopped_by_manual_timeout() {
     let out = run()
         .arg("mutants")
         .args(["-t", "8.1", "--build-timeout=15.5"])
+        .args(["--exclude-genre", "EarlyReturn"])
         .current_dir(tmp_src_dir.path())
         .env_remove("RUST_BACKTRACE")
         .timeout(OUTER_TIMEOUT)
         .assert()
         .code(3); // exit_code::TIMEOUT
-    println!(
-        "output:\n{}",
-        String::from_utf8_lossy(&out.get_output().stdout)
+    let stdout = String::from_utf8_lossy(&out.get_output().stdout);
+    println!("output:\n{stdout}");
+    assert!(
+        stdout.contains("3 timeouts (3 test)"),
+        "timeouts by phase not found in:\n{stdout}"
     );
     let unviable_txt = read_to_string(tmp_src_dir.path().join("mutants.out/unviable.txt"))
         .expect("read timeout.txt");
@@ -756,6 +831,14 @@ fn mutants_causing_tests_to_hang_are_stopped_by_manual_timeout() {
         timeout_txt.contains("replace should_stop -> bool with false"),
         "expected text not found in:\n{timeout_txt}"
     );
+    assert!(
+        timeout_txt.contains("delete TRIGGER.store(true, Ordering::Relaxed); in should_stop"),
+        "expected text not found in:\n{timeout_txt}"
+    );
+    assert!(
+        timeout_txt.contains("replace true with false in should_stop"),
+        "expected text not found in:\n{timeout_txt}"
+    );
     assert!(
         unviable_txt.contains("replace should_stop_const -> bool with false"),
         "expected text not found in:\n{unviable_txt}"
@@ -773,7 +856,7 @@ fn mutants_causing_tests_to_hang_are_stopped_by_manual_timeout() {
             .expect("read outcomes.json")
             .parse()
             .expect("parse outcomes.json");
-    assert_eq!(outcomes_json["timeout"], 1);
+    assert_eq!(outcomes_json["timeout"], 3);
 
     let phases_for_const_fn = outcomes_json["outcomes"]
         .as_array()
@@ -790,6 +873,35 @@ fn mutants_causing_tests_to_hang_are_stopped_by_manual_timeout() {
     assert_eq!(phases_for_const_fn[0]["phase"], "Build");
 }
 
+/// Deleting the early `return true` in `should_stop`, or the `return i` that stops the
+/// loop in `controlled_loop`, makes the loop never stop, so the test times out.
+#[test]
+fn deleted_early_returns_causing_hangs_are_stopped_by_timeout() {
+    let tmp_src_dir = copy_of_testdata("hang_when_mutated");
+    run()
+        .arg("mutants")
+        .args([
+            "-t",
+            "8.1",
+            "--build-timeout=15.5",
+            "--genre",
+            "EarlyReturn",
+        ])
+        .current_dir(tmp_src_dir.path())
+        .env_remove("RUST_BACKTRACE")
+        .timeout(OUTER_TIMEOUT)
+        .assert()
+        .code(3) // exit_code::TIMEOUT
+        .stdout(contains("2 timeouts (2 test)"));
+    let timeout_txt = read_to_string(tmp_src_dir.path().join("mutants.out/timeout.txt"))
+        .expect("read timeout.txt");
+    assert_eq!(
+        timeout_txt,
+        "src/lib.rs:26:9: delete return true; in should_stop\n\
+         src/lib.rs:42:13: delete return i; in controlled_loop\n"
+    );
+}
+
 #[test]
 fn hang_avoided_by_build_timeout_with_cap_lints() {
     let tmp_src_dir = copy_of_testdata("hang_when_mutated");
@@ -1021,3 +1133,61 @@ fn completions_option_generates_something() {
             .stdout(predicate::str::is_empty().not());
     }
 }
+
+/// With `--minimum-score`, missed mutants fail the run only if the score is too low.
+#[test]
+fn minimum_score_decides_exit_code() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    // Weaken the test so that only two of the five mutants are caught.
+    let lib_rs = tmp_src_dir.path().join("src/lib.rs");
+    let code = read_to_string(&lib_rs).unwrap();
+    fs::write(
+        &lib_rs,
+        code.replace(
+            "assert_eq!(factorial(6), 720);",
+            "assert!(factorial(6) > 0);",
+        ),
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "--minimum-score=40", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stderr(contains("Mutation score 40.0% meets the minimum of 40%"));
+    run()
+        .args(["mutants", "--no-times", "--minimum-score=50", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(5)
+        .stderr(contains("Mutation score 40.0% is below the minimum of 50%"));
+}
+
+#[test]
+fn replay_tests_one_mutant_in_a_copy_and_prints_its_log() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let lib_rs = tmp_src_dir.path().join("src/lib.rs");
+    let code = read_to_string(&lib_rs).unwrap();
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .args(["replay", "src/lib.rs: replace factorial -> u32 with 1"])
+        .assert()
+        .success()
+        .stdout(contains(
+            "caught   src/lib.rs:5:5: replace factorial -> u32 with 1",
+        ))
+        .stdout(contains("test test::test_factorial ... FAILED"));
+    assert_eq!(read_to_string(&lib_rs).unwrap(), code);
+    assert!(!tmp_src_dir.path().join("mutants.out").exists());
+    // Cargo's intermediate files are kept in the source tree's target directory, so that
+    // they're reused by the next replay.
+    assert!(tmp_src_dir.path().join("target/debug").is_dir());
+    run()
+        .args(["mutants", "-d"])
+        .arg(tmp_src_dir.path())
+        .args(["replay", "src/lib.rs: replace nothing"])
+        .assert()
+        .code(1)
+        .stderr(contains("No mutant named"));
+}
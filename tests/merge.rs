@@ -0,0 +1,104 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `cargo mutants merge`.
+
+use std::fs::{read_to_string, write};
+
+use predicates::prelude::*;
+use serde_json::Value;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn merge_shards() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let tmp_path = tmp_src_dir.path();
+    for k in 0..2 {
+        run()
+            .args(["mutants", "--no-times", "-d"])
+            .arg(tmp_path)
+            .args(["--shard", &format!("{k}/2"), "-o"])
+            .arg(tmp_path.join(format!("shard{k}")))
+            .assert()
+            .success();
+    }
+
+    let merged = tmp_path.join("merged");
+    run()
+        .args(["mutants", "merge"])
+        .arg(tmp_path.join("shard0"))
+        .arg(tmp_path.join("shard1/mutants.out"))
+        .arg("-o")
+        .arg(&merged)
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("5 mutants tested: 5 caught\n"));
+    let merged_out = merged.join("mutants.out");
+    let outcomes: Value =
+        serde_json::from_str(&read_to_string(merged_out.join("outcomes.json")).unwrap()).unwrap();
+    assert_eq!(outcomes["total_mutants"], 5);
+    assert_eq!(outcomes["caught"], 5);
+    // One baseline and all the mutants, each with its own log and diff.
+    let records = outcomes["outcomes"].as_array().unwrap();
+    assert_eq!(records.len(), 6);
+    for record in records {
+        let log_path = record["log_path"].as_str().unwrap();
+        assert!(merged_out.join(log_path).is_file(), "{log_path} is missing");
+        if let Some(diff_path) = record["diff_path"].as_str() {
+            assert!(
+                merged_out.join(diff_path).is_file(),
+                "{diff_path} is missing"
+            );
+        }
+    }
+    assert_eq!(
+        read_to_string(merged_out.join("caught.txt"))
+            .unwrap()
+            .lines()
+            .count(),
+        5
+    );
+    assert_eq!(read_to_string(merged_out.join("missed.txt")).unwrap(), "");
+
+    // The merged directory can't be overwritten.
+    run()
+        .args(["mutants", "merge"])
+        .arg(tmp_path.join("shard0"))
+        .arg("-o")
+        .arg(&merged)
+        .assert()
+        .code(1)
+        .stderr(predicate::str::contains("already exists"));
+
+    // Mark a mutant as missed in a copy of the first shard, and merge it first.
+    let edited = tmp_path.join("edited");
+    cp_r::CopyOptions::new()
+        .copy_tree(tmp_path.join("shard0/mutants.out"), &edited)
+        .unwrap();
+    let json = read_to_string(edited.join("outcomes.json")).unwrap();
+    write(
+        edited.join("outcomes.json"),
+        json.replacen("\"CaughtMutant\"", "\"MissedMutant\"", 1),
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "merge"])
+        .arg(edited.join("outcomes.json"))
+        .arg(tmp_path.join("shard0"))
+        .arg(tmp_path.join("shard1"))
+        .arg("-o")
+        .arg(tmp_path.join("conflicted"))
+        .assert()
+        .code(2)
+        .stdout(
+            predicate::str::contains("3 mutants were tested in more than one run\n")
+                .and(predicate::str::contains(
+                    "Conflicting outcomes, keeping the first:\n",
+                ))
+                .and(predicate::str::contains(": missed in "))
+                .and(predicate::str::ends_with(
+                    "5 mutants tested: 1 missed, 4 caught\n",
+                )),
+        );
+}
@@ -0,0 +1,83 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for the missed-mutant baseline file, `mutants-baseline.toml`.
+
+mod util;
+
+use std::fs::{read_to_string, write};
+
+use indoc::indoc;
+use predicates::prelude::*;
+
+use self::util::{copy_of_testdata, run};
+
+#[test]
+fn update_and_apply_missed_baseline() {
+    let tmp = copy_of_testdata("factorial");
+    let baseline_path = tmp.path().join("mutants-baseline.toml");
+
+    // Without a baseline, the missed mutant fails the run.
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .code(2);
+
+    // Writing the baseline accepts the missed mutant.
+    run()
+        .args(["mutants", "--no-times", "--update-missed-baseline", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .code(0)
+        .stderr(predicate::str::contains("Wrote 2 accepted missed mutants"));
+    let baseline = read_to_string(&baseline_path).unwrap();
+    assert!(baseline.contains("\n    \"src/bin/factorial.rs: replace main with ()\",\n"));
+
+    // Later runs read the baseline by default.
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .code(0)
+        .stderr(predicate::str::contains(
+            "2 missed mutants are accepted by the baseline",
+        ));
+
+    // Missed mutants that aren't in the baseline still fail the run.
+    write(
+        &baseline_path,
+        "missed = [\"src/bin/factorial.rs: replace main with ()\"]\n",
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp.path())
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "Missed mutant is not in the baseline: src/bin/factorial.rs: replace ..= with .. in main",
+        ));
+}
+
+#[test]
+fn missed_baseline_path_option() {
+    let tmp = copy_of_testdata("factorial");
+    let baseline_path = tmp.path().join("accepted.toml");
+    write(
+        &baseline_path,
+        indoc! { r#"
+            missed = [
+                "src/bin/factorial.rs: replace ..= with .. in main",
+                "src/bin/factorial.rs: replace main with ()",
+            ]
+        "# },
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "--missed-baseline"])
+        .arg(&baseline_path)
+        .arg("-d")
+        .arg(tmp.path())
+        .assert()
+        .code(0);
+}
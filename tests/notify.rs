@@ -0,0 +1,62 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for notifications when a run finishes.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use serde_json::Value;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+/// Accept one HTTP request on the listener, reply OK, and return the request body.
+fn receive_one_post(listener: &TcpListener) -> String {
+    let (stream, _) = listener.accept().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+    reader
+        .get_mut()
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+        .unwrap();
+    String::from_utf8(body).unwrap()
+}
+
+#[test]
+fn webhook_receives_summary_when_run_finishes() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let server = thread::spawn(move || receive_one_post(&listener));
+    run()
+        .args(["mutants", "--no-times", "--notify-webhook", &url, "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    let payload: Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+    let tree_name = tmp_src_dir.path().file_name().unwrap().to_str().unwrap();
+    assert_eq!(
+        payload["text"],
+        format!("{tree_name}: 5 mutants tested, score 100.0%, 0 missed")
+    );
+    assert_eq!(payload["total_mutants"], 5);
+    assert_eq!(payload["caught"], 5);
+    assert_eq!(payload["missed"], 0);
+    assert_eq!(payload["score"], 100.0);
+}
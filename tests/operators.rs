@@ -0,0 +1,32 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `cargo mutants operators`.
+
+use std::fs::{create_dir, write};
+
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn operators_show_which_genres_the_config_enables() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    create_dir(tmp_src_dir.path().join(".cargo")).unwrap();
+    write(
+        tmp_src_dir.path().join(".cargo/mutants.toml"),
+        "enable_genres = [\"StringLiteral\"]\nexclude_genres = [\"SwapOperands\"]\n",
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "operators", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "FnValue: enabled\n    Replace the body of a function with a fixed value of its return type.\n",
+        ))
+        .stdout(predicate::str::contains("\nStringLiteral: enabled\n"))
+        .stdout(predicate::str::contains("\nNumericLiteral: off by default\n"))
+        .stdout(predicate::str::contains("\nSwapOperands: disabled\n    Swap the operands of a non-commutative binary operator.\n    - a - b\n    + b - a\n"));
+}
@@ -0,0 +1,68 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--prescreen`
+
+use std::fs::read_to_string;
+
+use predicates::prelude::*;
+use pretty_assertions::assert_eq;
+
+mod util;
+use util::{copy_of_testdata, outcome_json_counts, run};
+
+#[test]
+fn prescreen_finds_unviable_mutant() {
+    let tmp_src_dir = copy_of_testdata("struct_with_no_default");
+    run()
+        .args([
+            "mutants",
+            "--prescreen",
+            "--line-col=false",
+            "--no-times",
+            "--no-shuffle",
+            "-v",
+            "-V",
+        ])
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(
+                r"unviable *src/lib.rs:\d+:\d+: replace make_an_s -> S with Default::default\(\)",
+            )
+            .unwrap(),
+        );
+    assert_eq!(
+        outcome_json_counts(&tmp_src_dir),
+        serde_json::json!({
+            "success": 0,
+            "caught": 0,
+            "unviable": 1,
+            "missed": 0,
+            "timeout": 0,
+            "equivalent": 0,
+            "uncovered": 0,
+            "total_mutants": 1,
+        })
+    );
+    let log = read_to_string(
+        tmp_src_dir
+            .path()
+            .join("mutants.out/log/src__lib.rs_line_12_col_5.log"),
+    )
+    .unwrap();
+    assert!(log.contains("unviable in prescreening batch"), "{log}");
+    assert!(log.contains("E0277"), "{log}");
+}
+
+#[test]
+fn prescreen_passes_viable_mutants_to_tests() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--prescreen", "--no-shuffle", "--no-times"])
+        .current_dir(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+}
@@ -0,0 +1,373 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `cargo mutants report`.
+
+use std::fs::{self, read_to_string};
+
+use indoc::indoc;
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn html_report_from_previous_run() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    run()
+        .args(["mutants", "report", "--html", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("mutants.out/report.html\n"));
+    let html = read_to_string(tmp_src_dir.path().join("mutants.out/report.html")).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<p class=\"totals\">5 mutants tested: 5 caught</p>"));
+    assert!(html.contains("<summary><code>src/lib.rs</code> 5 caught</summary>"));
+    assert_eq!(html.matches("<tr class=\"mutant caught\">").count(), 5);
+    assert!(html.contains("<details><summary>diff</summary><pre>--- src/lib.rs"));
+}
+
+#[test]
+#[cfg(unix)]
+fn open_html_report_in_browser() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    // `echo` stands in for the browser, so that we can see what it was asked to open.
+    run()
+        .args(["mutants", "--no-times", "--open", "-d"])
+        .arg(tmp_src_dir.path())
+        .env("BROWSER", "echo")
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("mutants.out/report.html\n"));
+    let report_path = tmp_src_dir.path().join("mutants.out/report.html");
+    std::fs::remove_file(&report_path).unwrap();
+    run()
+        .args(["mutants", "report", "--open", "-d"])
+        .arg(tmp_src_dir.path())
+        .env("BROWSER", "echo")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mutants.out/report.html\n").count(2));
+    assert!(read_to_string(report_path)
+        .unwrap()
+        .starts_with("<!DOCTYPE html>"));
+}
+
+#[test]
+fn html_report_without_outcomes_fails() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "report", "--html", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("run cargo mutants first"));
+}
+
+#[test]
+fn sarif_report_lists_missed_mutants() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2);
+    run()
+        .args(["mutants", "report", "--sarif", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("mutants.out/mutants.sarif\n"));
+    let sarif: serde_json::Value = serde_json::from_str(
+        &read_to_string(tmp_src_dir.path().join("mutants.out/mutants.sarif")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(sarif["version"], "2.1.0");
+    let mut messages: Vec<&str> = sarif["runs"][0]["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| result["message"]["text"].as_str().unwrap())
+        .collect();
+    // Mutants at the same position are in the order they finished.
+    messages.sort_unstable();
+    assert_eq!(
+        messages,
+        [
+            "Missed mutant: replace & with ^ in is_symlink",
+            "Missed mutant: replace & with | in is_symlink",
+            "Missed mutant: replace is_symlink -> bool with true",
+        ]
+    );
+    let region = &sarif["runs"][0]["results"][2]["locations"][0]["physicalLocation"]["region"];
+    assert_eq!(region["startLine"], 2);
+    assert_eq!(region["startColumn"], 22);
+}
+
+#[test]
+fn junit_report_has_missed_mutants_as_failures() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2);
+    run()
+        .args(["mutants", "report", "--junit", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("mutants.out/junit.xml\n"));
+    let xml = read_to_string(tmp_src_dir.path().join("mutants.out/junit.xml")).unwrap();
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"cargo-mutants\" tests=\"5\" failures=\"3\" errors=\"0\" skipped=\"0\" "));
+    assert!(xml.contains("<testsuite name=\"src/lib.rs\" tests=\"5\" failures=\"3\" "));
+    assert_eq!(xml.matches("<testcase ").count(), 5);
+    assert!(xml.contains(
+        "<failure type=\"missed\" message=\"missed: replace &amp; with | in is_symlink\">--- src/lib.rs"
+    ));
+}
+
+#[test]
+fn gitlab_code_quality_report_lists_missed_mutants() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2);
+    run()
+        .args(["mutants", "report", "--gitlab-code-quality", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with(
+            "mutants.out/gl-code-quality-report.json\n",
+        ));
+    let findings: serde_json::Value = serde_json::from_str(
+        &read_to_string(
+            tmp_src_dir
+                .path()
+                .join("mutants.out/gl-code-quality-report.json"),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let findings = findings.as_array().unwrap();
+    assert_eq!(findings.len(), 3);
+    let finding = &findings[0];
+    assert_eq!(
+        finding["description"],
+        "Missed mutant: replace is_symlink -> bool with true"
+    );
+    assert_eq!(finding["severity"], "minor");
+    assert_eq!(finding["location"]["path"], "src/lib.rs");
+    assert_eq!(finding["location"]["positions"]["begin"]["line"], 2);
+    let mut fingerprints: Vec<&str> = findings
+        .iter()
+        .map(|finding| finding["fingerprint"].as_str().unwrap())
+        .collect();
+    fingerprints.sort_unstable();
+    fingerprints.dedup();
+    assert_eq!(fingerprints.len(), 3);
+}
+
+#[test]
+fn markdown_summary_with_score_and_missed_diffs() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2);
+    run()
+        .args([
+            "mutants",
+            "report",
+            "--markdown",
+            "--markdown-missed=1",
+            "-d",
+        ])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::ends_with("mutants.out/summary.md\n"));
+    let md = read_to_string(tmp_src_dir.path().join("mutants.out/summary.md")).unwrap();
+    assert!(md.starts_with(indoc! { "
+        ## cargo-mutants
+
+        **Mutation score: 40.0%** (2 of 5 viable mutants caught)
+
+        | Package | Mutants | Caught | Missed | Timeouts | Unviable | Score |
+        | --- | ---: | ---: | ---: | ---: | ---: | ---: |
+        | cargo-mutants-testdata-missing-test | 5 | 2 | 3 | 0 | 0 | 40.0% |
+
+        ### Missed mutants
+
+        <details>
+        <summary><code>src/lib.rs:2:5</code>: replace is_symlink -&gt; bool with true</summary>
+    " }));
+    assert_eq!(md.matches("<details>").count(), 1);
+    assert!(md.ends_with("See `mutants.out/missed.txt` for 2 more missed mutants.\n"));
+}
+
+#[test]
+fn badge_with_mutation_score() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    run()
+        .args(["mutants", "report", "--badge", "--badge-svg", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("mutants.out/badge.json\n"))
+        .stdout(predicate::str::ends_with("mutants.out/badge.svg\n"));
+    let badge: serde_json::Value = serde_json::from_str(
+        &read_to_string(tmp_src_dir.path().join("mutants.out/badge.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        badge,
+        serde_json::json!({
+            "schemaVersion": 1,
+            "label": "mutants",
+            "message": "100.0%",
+            "color": "brightgreen"
+        })
+    );
+    let svg = read_to_string(tmp_src_dir.path().join("mutants.out/badge.svg")).unwrap();
+    assert!(svg.contains("<title>mutants: 100.0%</title>"));
+}
+
+#[test]
+fn patches_for_missed_mutants_apply_to_the_tree() {
+    let tmp_src_dir = copy_of_testdata("missing_test");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2);
+    run()
+        .args(["mutants", "report", "--patches", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("mutants.out/patches\n")
+                .and(predicate::str::ends_with("mutants.out/missed.patch\n")),
+        );
+    let patches_dir = tmp_src_dir.path().join("mutants.out/patches");
+    let mut patches: Vec<_> = std::fs::read_dir(&patches_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    patches.sort();
+    assert_eq!(patches.len(), 3);
+    for patch in &patches {
+        assert_eq!(patch.extension().unwrap(), "patch");
+        let content = read_to_string(patch).unwrap();
+        assert!(content.starts_with("Missed mutant: src/lib.rs:"));
+        assert!(content.contains("\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ "));
+        let status = std::process::Command::new("git")
+            .args(["apply", "--check"])
+            .arg(patch)
+            .current_dir(tmp_src_dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success(), "{patch:?} doesn't apply");
+    }
+    let missed = read_to_string(tmp_src_dir.path().join("mutants.out/missed.patch")).unwrap();
+    assert_eq!(missed.matches("Missed mutant: ").count(), 3);
+}
+
+#[test]
+fn show_mutant_by_name_with_or_without_line_and_column() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    for name in [
+        "src/lib.rs:5:5: replace factorial -> u32 with 0",
+        "src/lib.rs: replace factorial -> u32 with 0",
+    ] {
+        run()
+            .args(["mutants", "show", name, "-d"])
+            .arg(tmp_src_dir.path())
+            .assert()
+            .success()
+            .stdout(predicate::str::starts_with(
+                "src/lib.rs:5:5: replace factorial -> u32 with 0\nOutcome:  caught in ",
+            ))
+            .stdout(predicate::str::contains("Genre:    FnValue\n"))
+            // Two mutants are at this position, so the log name depends on the order they ran.
+            .stdout(
+                predicate::str::is_match(
+                    r"\nLog: +.*mutants.out[/\\]log[/\\]src__lib.rs_line_5_col_5(_001)?.log\n",
+                )
+                .unwrap(),
+            )
+            .stdout(predicate::str::contains(
+                "\n--- src/lib.rs\n+++ replace factorial -> u32 with 0\n",
+            ));
+    }
+    run()
+        .args(["mutants", "show", "src/lib.rs: replace nothing", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "No mutant named \"src/lib.rs: replace nothing\"",
+        ));
+}
+
+#[test]
+fn triage_records_decisions_about_missed_mutants() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    // Weaken the test so that three of the five mutants are missed.
+    let lib_rs = tmp_src_dir.path().join("src/lib.rs");
+    let code = read_to_string(&lib_rs).unwrap();
+    fs::write(
+        &lib_rs,
+        code.replace(
+            "assert_eq!(factorial(6), 720);",
+            "assert!(factorial(6) > 0);",
+        ),
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2);
+    run()
+        .args(["mutants", "triage", "-d"])
+        .arg(tmp_src_dir.path())
+        .write_stdin("a\nt\ne\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\n[1/3]\nsrc/lib.rs:"))
+        .stdout(predicate::str::contains(
+            "Triaged 3 mutants: 1 accepted, 1 todo, 1 equivalent\n",
+        ));
+    let baseline = read_to_string(tmp_src_dir.path().join("mutants-baseline.toml")).unwrap();
+    assert_eq!(baseline.matches("\n    \"src/lib.rs: ").count(), 1);
+    let triage = read_to_string(tmp_src_dir.path().join("mutants-triage.toml")).unwrap();
+    assert!(triage.contains("todo = [\n    \"src/lib.rs: "));
+    assert!(triage.contains("equivalent = [\n    \"src/lib.rs: "));
+    run()
+        .args(["mutants", "triage", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout("All 3 missed mutants have already been triaged\n");
+}
@@ -0,0 +1,59 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--resume`.
+
+use std::fs::{read_to_string, write};
+
+use predicates::prelude::*;
+use serde_json::Value;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn resume_tests_only_the_mutants_that_did_not_finish() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success();
+    // Make it look like the run was interrupted after the baseline and two mutants.
+    let outcomes_path = tmp_src_dir.path().join("mutants.out/outcomes.json");
+    let mut outcomes: Value =
+        serde_json::from_str(&read_to_string(&outcomes_path).unwrap()).unwrap();
+    let total = outcomes["outcomes"].as_array().unwrap().len();
+    assert_eq!(total, 6);
+    outcomes["outcomes"].as_array_mut().unwrap().truncate(3);
+    write(&outcomes_path, serde_json::to_string(&outcomes).unwrap()).unwrap();
+    let mutants_json = read_to_string(tmp_src_dir.path().join("mutants.out/mutants.json")).unwrap();
+
+    run()
+        .args(["mutants", "--no-times", "--resume", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 3 mutants to test"))
+        .stdout(predicate::str::contains("Unmutated baseline").not())
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    let outcomes: Value = serde_json::from_str(&read_to_string(&outcomes_path).unwrap()).unwrap();
+    assert_eq!(outcomes["outcomes"].as_array().unwrap().len(), total);
+    assert_eq!(outcomes["total_mutants"], 5);
+    assert_eq!(outcomes["caught"], 5);
+    assert_eq!(
+        read_to_string(tmp_src_dir.path().join("mutants.out/mutants.json")).unwrap(),
+        mutants_json,
+        "the list of mutants is kept so the run can be resumed again"
+    );
+}
+
+#[test]
+fn resume_without_an_earlier_run_fails() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--resume", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No interrupted run to resume in"));
+}
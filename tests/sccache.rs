@@ -0,0 +1,83 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--sccache`, using a fake sccache that just runs the compiler.
+
+#![cfg(unix)]
+
+use std::env;
+use std::fs::{set_permissions, write, Permissions};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+/// Write a fake `sccache` into `dir`, and return a `PATH` that finds it first.
+fn fake_sccache(dir: &Path, script: &str) -> String {
+    let path = dir.join("sccache");
+    write(&path, script).unwrap();
+    set_permissions(&path, Permissions::from_mode(0o755)).unwrap();
+    format!("{}:{}", dir.display(), env::var("PATH").unwrap())
+}
+
+#[test]
+fn sccache_stats_are_reported() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let bin_dir = TempDir::new().unwrap();
+    let calls = bin_dir.path().join("calls");
+    // Every compilation counts as a cache hit.
+    let path = fake_sccache(
+        bin_dir.path(),
+        &format!(
+            r#"#!/bin/sh
+if [ "$1" = "--show-stats" ]; then
+    hits=$(cat {calls} 2>/dev/null | wc -l)
+    echo "{{\"stats\": {{\"cache_hits\": {{\"counts\": {{\"Rust\": $hits}}}}, \"cache_misses\": {{\"counts\": {{}}}}}}}}"
+    exit 0
+fi
+echo >> {calls}
+exec "$@"
+"#,
+            calls = calls.display()
+        ),
+    );
+    run()
+        .args(["mutants", "--no-times", "--sccache", "-d"])
+        .arg(tmp_src_dir.path())
+        .env("PATH", path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"))
+        .stderr(
+            predicate::str::is_match(r"sccache had \d+ cache hits and 0 misses \(100% hit rate\)")
+                .unwrap(),
+        );
+    assert!(
+        calls.is_file(),
+        "cargo should have run rustc through sccache"
+    );
+}
+
+#[test]
+fn broken_sccache_stops_the_run_before_building() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let bin_dir = TempDir::new().unwrap();
+    let path = fake_sccache(
+        bin_dir.path(),
+        "#!/bin/sh\necho 'server failed to start' >&2\nexit 2\n",
+    );
+    run()
+        .args(["mutants", "--sccache", "-d"])
+        .arg(tmp_src_dir.path())
+        .env("PATH", path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "sccache (sccache) isn't working: check that it's installed and on the PATH",
+        ))
+        .stderr(predicate::str::contains("server failed to start"))
+        .stdout(predicate::str::contains("baseline").not());
+}
@@ -0,0 +1,84 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--schemata`.
+
+use std::fs::{read_to_string, OpenOptions};
+use std::io::Write;
+
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn schemata_build_mutants_of_a_file_together() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    run()
+        .args(["mutants", "--no-times", "--no-shuffle", "--schemata", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    let schema_log = read_to_string(
+        tmp_src_dir
+            .path()
+            .join("mutants.out/log/schema_src__lib.rs.log"),
+    )
+    .unwrap();
+    assert!(
+        schema_log.contains("*** build 2 mutants in src/lib.rs together\n"),
+        "{schema_log}"
+    );
+    let mutant_log = read_to_string(
+        tmp_src_dir
+            .path()
+            .join("mutants.out/log/src__lib.rs_line_5_col_5_001.log"),
+    )
+    .unwrap();
+    assert!(
+        mutant_log.contains(
+            "built together with the other mutants in log/schema_src__lib.rs.log, \
+            and tested with CARGO_MUTANTS_ACTIVE_MUTANT=2"
+        ),
+        "{mutant_log}"
+    );
+}
+
+#[test]
+fn schema_that_does_not_build_is_split() {
+    let tmp_src_dir = copy_of_testdata("struct_with_no_default");
+    let mut lib = OpenOptions::new()
+        .append(true)
+        .open(tmp_src_dir.path().join("src/lib.rs"))
+        .unwrap();
+    write!(
+        lib,
+        "
+pub fn double(x: u32) -> u32 {{
+    x * 2
+}}
+
+#[test]
+fn test_double() {{
+    assert_eq!(double(3), 6);
+}}
+"
+    )
+    .unwrap();
+    drop(lib);
+    run()
+        .args(["mutants", "--no-times", "--no-shuffle", "--schemata", "-d"])
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "5 mutants tested: 4 caught, 1 unviable",
+        ));
+    let log_dir = tmp_src_dir.path().join("mutants.out/log");
+    assert!(read_to_string(log_dir.join("schema_src__lib.rs.log"))
+        .unwrap()
+        .contains("*** build 3 mutants in src/lib.rs together\n"));
+    assert!(read_to_string(log_dir.join("schema_src__lib.rs_001.log"))
+        .unwrap()
+        .contains("*** build 2 mutants in src/lib.rs together\n"));
+}
@@ -0,0 +1,46 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--scratch-dir`.
+
+use std::fs::read_dir;
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn build_dirs_are_made_in_scratch_dir() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let scratch = TempDir::new().unwrap();
+    run()
+        .args(["mutants", "--no-times", "--leak-dirs", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("--scratch-dir")
+        .arg(scratch.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    let names = read_dir(scratch.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names.len(), 1, "{names:?}");
+    assert!(names[0].starts_with("cargo-mutants-"), "{names:?}");
+}
+
+#[test]
+fn missing_scratch_dir_falls_back_to_temp_dir() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let scratch = TempDir::new().unwrap();
+    run()
+        .args(["mutants", "--no-times", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("--scratch-dir")
+        .arg(scratch.path().join("missing"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"))
+        .stderr(predicate::str::contains("Can't use scratch directory"));
+}
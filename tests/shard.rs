@@ -1,4 +1,4 @@
-// Copyright 2023-2024 Martin Pool
+// Copyright 2023-2025 Martin Pool
 
 //! Test `--shard`
 
@@ -57,9 +57,8 @@ fn shard_divides_all_mutants() {
         full_list.iter().sorted().collect_vec()
     );
 
-    // The shards should also be approximately the same size.
-    let shard_lens = shard_lists.iter().map(|l| l.len()).collect_vec();
-    assert!(shard_lens.iter().max().unwrap() - shard_lens.iter().min().unwrap() <= 1);
+    // Mutants are assigned by hash so the sizes vary, but every shard has some.
+    assert!(shard_lists.iter().all(|l| !l.is_empty()));
 
     // And the shards are disjoint
     for i in 0..n_shards {
@@ -78,3 +77,73 @@ fn shard_divides_all_mutants() {
         }
     }
 }
+
+#[test]
+fn list_shard_prints_shard_sizes() {
+    let tmp = copy_of_testdata("well_tested");
+    let tmp_path = tmp.path().to_str().unwrap();
+    let n_shards = 3;
+    let shard_lens = (0..n_shards)
+        .map(|k| {
+            let output = run()
+                .args(["mutants", "--list", "-d", tmp_path])
+                .args(["--shard", &format!("{k}/{n_shards}")])
+                .assert()
+                .success()
+                .get_output()
+                .clone();
+            let stderr = String::from_utf8(output.stderr).unwrap();
+            let n_listed = String::from_utf8(output.stdout).unwrap().lines().count();
+            assert!(
+                stderr.contains(&format!("Shard {k}/{n_shards} has {n_listed} of ")),
+                "{stderr}"
+            );
+            n_listed
+        })
+        .collect_vec();
+    let output = run()
+        .args(["mutants", "--list", "-d", tmp_path, "--shard", "0/3"])
+        .assert()
+        .success()
+        .get_output()
+        .clone();
+    assert!(String::from_utf8(output.stderr).unwrap().contains(&format!(
+        "the shards have {} mutants",
+        shard_lens.iter().join(", ")
+    )));
+}
+
+#[test]
+fn adding_a_file_does_not_move_other_mutants_between_shards() {
+    let tmp = copy_of_testdata("well_tested");
+    let list_shard = || {
+        String::from_utf8(
+            run()
+                .args(["mutants", "--list", "-d", tmp.path().to_str().unwrap()])
+                .args(["--shard", "1/4"])
+                .assert()
+                .success()
+                .get_output()
+                .stdout
+                .clone(),
+        )
+        .unwrap()
+        .lines()
+        .map(ToOwned::to_owned)
+        .collect_vec()
+    };
+    let before = list_shard();
+    let lib_rs = tmp.path().join("src/lib.rs");
+    let mut lib_source = std::fs::read_to_string(&lib_rs).unwrap();
+    lib_source.push_str("\nmod added;\n");
+    std::fs::write(&lib_rs, lib_source).unwrap();
+    std::fs::write(
+        tmp.path().join("src/added.rs"),
+        "pub fn triple(x: u32) -> u32 {\n    x * 3\n}\n\npub fn is_empty(s: &str) -> bool {\n    s.is_empty()\n}\n",
+    )
+    .unwrap();
+    let after = list_shard();
+    for name in &before {
+        assert!(after.contains(name), "{name} moved out of the shard");
+    }
+}
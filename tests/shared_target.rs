@@ -0,0 +1,24 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--shared-target-dir`.
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn build_dirs_share_intermediate_files() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let shared = TempDir::new().unwrap();
+    run()
+        .args(["mutants", "--no-times", "-j2", "-d"])
+        .arg(tmp_src_dir.path())
+        .arg("--shared-target-dir")
+        .arg(shared.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    assert!(shared.path().join("debug/deps").is_dir());
+}
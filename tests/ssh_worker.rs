@@ -0,0 +1,121 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--ssh-worker`, using fake `ssh` and `rsync` that run and copy locally.
+
+#![cfg(unix)]
+
+use std::env;
+use std::fs::{read_dir, read_to_string, set_permissions, write, Permissions};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use predicates::prelude::*;
+use tempfile::TempDir;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+/// Write fake `ssh` and `rsync` into `dir`, and return a `PATH` that finds them first.
+///
+/// The fake ssh records the host and runs the command in a local shell; the fake rsync
+/// copies the source directory to the path after the host name.
+fn fake_ssh_and_rsync(dir: &Path) -> String {
+    let hosts = dir.join("hosts");
+    for (name, script) in [
+        (
+            "ssh",
+            format!(
+                "#!/bin/sh\necho \"$1\" >> {hosts}\nshift\nexec sh -c \"$*\"\n",
+                hosts = hosts.display()
+            ),
+        ),
+        (
+            "rsync",
+            "#!/bin/sh\nfor arg; do src=$dest; dest=$arg; done\nmkdir -p \"${dest#*:}\"\ncp -R \"$src\". \"${dest#*:}\"\n"
+                .to_owned(),
+        ),
+    ] {
+        let path = dir.join(name);
+        write(&path, script).unwrap();
+        set_permissions(&path, Permissions::from_mode(0o755)).unwrap();
+    }
+    format!("{}:{}", dir.display(), env::var("PATH").unwrap())
+}
+
+#[test]
+fn mutants_are_tested_on_ssh_workers() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let bin_dir = TempDir::new().unwrap();
+    let remote_dir = TempDir::new().unwrap();
+    let path = fake_ssh_and_rsync(bin_dir.path());
+    run()
+        .args(["mutants", "--no-times", "--ssh-worker", "worker1.example"])
+        .arg("--remote-dir")
+        .arg(remote_dir.path().join("tree"))
+        .arg("--remote-command")
+        .arg(format!("{} mutants", env!("CARGO_BIN_EXE_cargo-mutants")))
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .env("PATH", path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    assert!(remote_dir.path().join("tree/src/lib.rs").is_file());
+    let hosts = read_to_string(bin_dir.path().join("hosts")).unwrap();
+    assert!(hosts.lines().all(|host| host == "worker1.example"));
+    let log_dir = tmp_src_dir.path().join("mutants.out/log");
+    assert!(log_dir.join("ssh_1_worker1.example.log").is_file());
+    // Mutants are shared between the local build dir and the worker, and the worker's
+    // logs are copied back.
+    let remote_logs = read_dir(&log_dir)
+        .unwrap()
+        .map(|entry| read_to_string(entry.unwrap().path()).unwrap())
+        .filter(|log| log.contains("tested on worker1.example"))
+        .collect::<Vec<_>>();
+    assert!(!remote_logs.is_empty());
+    assert!(remote_logs.iter().all(|log| log.contains("test result")));
+}
+
+#[test]
+fn worker_refuses_coordinator_with_another_version() {
+    run()
+        .args(["mutants", "worker"])
+        .write_stdin("{\"version\": \"0.0.1\", \"args\": [\"mutants\"]}\n")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "The coordinator runs cargo-mutants 0.0.1",
+        ));
+}
+
+#[test]
+fn mutant_from_failed_worker_is_tested_locally() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let bin_dir = TempDir::new().unwrap();
+    let remote_dir = TempDir::new().unwrap();
+    let path = fake_ssh_and_rsync(bin_dir.path());
+    // A worker that says it's ready, and then exits when it's asked to test a mutant,
+    // like one whose connection was lost.
+    let dying_worker = bin_dir.path().join("dying_worker");
+    write(
+        &dying_worker,
+        "#!/bin/sh\nread hello\necho '{\"Ready\": {\"mutants\": 5}}'\nread request\nexit 1\n",
+    )
+    .unwrap();
+    set_permissions(&dying_worker, Permissions::from_mode(0o755)).unwrap();
+    run()
+        .args(["mutants", "--no-times", "--ssh-worker", "worker1.example"])
+        .arg("--remote-dir")
+        .arg(remote_dir.path().join("tree"))
+        .arg("--remote-command")
+        .arg(&dying_worker)
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .env("PATH", path)
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Worker on worker1.example failed: Worker on worker1.example stopped with exit status: 1",
+        ))
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+}
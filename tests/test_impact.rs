@@ -0,0 +1,64 @@
+// Copyright 2025 Martin Pool
+
+//! Tests for `--test-map`.
+//!
+//! `--test-impact` needs cargo-llvm-cov, so only its map format is tested here, with a
+//! map written by hand.
+
+use std::fs::{read_to_string, write};
+
+use predicates::prelude::*;
+
+mod util;
+use util::{copy_of_testdata, run};
+
+#[test]
+fn test_map_selects_tests_covering_each_mutant() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let map_path = tmp_src_dir.path().join("test_map.json");
+    write(
+        &map_path,
+        r#"{
+            "tests": {
+                "test::test_factorial": [
+                    {"file": "src/lib.rs", "start_line": 4, "end_line": 10}
+                ]
+            }
+        }"#,
+    )
+    .unwrap();
+    run()
+        .args(["mutants", "--no-times", "--no-shuffle", "--test-map"])
+        .arg(&map_path)
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "Running only the tests that cover each mutant, from a map of 1 tests",
+        ))
+        .stdout(predicate::str::contains("5 mutants tested: 5 caught"));
+    let log = read_to_string(
+        tmp_src_dir
+            .path()
+            .join("mutants.out/log/src__lib.rs_line_5_col_5.log"),
+    )
+    .unwrap();
+    assert!(log.contains("run 1 tests that cover this mutant"));
+    assert!(log.contains("--exact test::test_factorial"));
+}
+
+#[test]
+fn mutants_not_covered_by_the_test_map_are_missed() {
+    let tmp_src_dir = copy_of_testdata("small_well_tested");
+    let map_path = tmp_src_dir.path().join("test_map.json");
+    write(&map_path, r#"{"tests": {}}"#).unwrap();
+    run()
+        .args(["mutants", "--no-times", "--test-map"])
+        .arg(&map_path)
+        .arg("-d")
+        .arg(tmp_src_dir.path())
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains("5 mutants tested: 5 missed"));
+}
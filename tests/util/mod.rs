@@ -11,6 +11,7 @@ use std::borrow::Borrow;
 use std::env;
 use std::fs::{read_dir, read_to_string, rename};
 use std::path::{Path, PathBuf};
+use std::process::Command;
 use std::time::Duration;
 
 use itertools::Itertools;
@@ -150,7 +151,37 @@ pub fn outcome_json(tmp_src_dir: &TempDir) -> serde_json::Value {
 
 pub fn outcome_json_counts(tmp_src_dir: &TempDir) -> serde_json::Value {
     let mut outcomes = outcome_json(tmp_src_dir);
-    // We don't want to compare the detailed outcomes
-    outcomes.as_object_mut().unwrap().remove("outcomes");
+    let object = outcomes.as_object_mut().unwrap();
+    assert_eq!(object.remove("schema_version"), Some(1.into()));
+    // We don't want to compare the detailed outcomes or the breakdown of scores
+    object.remove("outcomes");
+    object.remove("scores");
     outcomes
 }
+
+/// Make a directory into a git repo.
+pub fn git_init(dir: &Path) {
+    git(dir, &["init", "-q"], "Test", "2020-01-01T00:00:00");
+}
+
+/// Commit everything in the tree as a given author, at a given date.
+pub fn git_commit(dir: &Path, author: &str, date: &str) {
+    git(dir, &["add", "-A"], author, date);
+    git(dir, &["commit", "-q", "-m", "commit"], author, date);
+}
+
+fn git(dir: &Path, args: &[&str], author: &str, date: &str) {
+    let status = Command::new("git")
+        .args(["-c", &format!("user.name={author}")])
+        .args([
+            "-c",
+            &format!("user.email={}@example.com", author.to_lowercase()),
+        ])
+        .args(args)
+        .env("GIT_AUTHOR_DATE", date)
+        .env("GIT_COMMITTER_DATE", date)
+        .current_dir(dir)
+        .status()
+        .expect("run git");
+    assert!(status.success(), "git {args:?} failed");
+}
@@ -4,6 +4,7 @@
 
 use std::fs::{self, create_dir, read_to_string, write};
 
+use indoc::indoc;
 use insta::assert_snapshot;
 use itertools::Itertools;
 use predicates::{prelude::predicate, str::PredicateStrExt};
@@ -121,6 +122,16 @@ fn workspace_tree_is_well_tested() {
     assert_eq!(json["caught"].as_u64().unwrap(), total);
     assert_eq!(json["missed"].as_u64().unwrap(), 0);
     assert_eq!(json["timeout"].as_u64().unwrap(), 0);
+    let scores = &json["scores"];
+    assert_eq!(
+        scores["packages"].as_object().unwrap().keys().collect_vec(),
+        ["cargo_mutants_testdata_workspace_utils", "main", "main2"]
+    );
+    assert_eq!(
+        scores["modules"]["cargo_mutants_testdata_workspace_utils"]["score"],
+        100.0
+    );
+    assert_eq!(scores["files"]["main2/src/main.rs"]["caught"], 3);
     let outcomes = json["outcomes"].as_array().unwrap();
 
     {
@@ -328,7 +339,7 @@ fn cross_package_tests() {
 
     // Testing only this one package will find gaps.
     run()
-        .args(["mutants", "-v", "--shard=0/4"])
+        .args(["mutants", "-v", "--shard=3/4"])
         .arg("--no-times")
         .arg("-d")
         .arg(path.join("lib"))
@@ -339,7 +350,7 @@ fn cross_package_tests() {
     // Just asking to *mutate* the whole workspace will not cause us
     // to run the tests in "tests" against mutants in "lib".
     run()
-        .args(["mutants", "--workspace", "--shard=0/4"])
+        .args(["mutants", "--workspace", "--shard=3/4"])
         .arg("-d")
         .arg(path.join("lib"))
         .assert()
@@ -348,7 +359,7 @@ fn cross_package_tests() {
 
     // Similarly, starting in the workspace dir is not enough.
     run()
-        .args(["mutants", "-v", "--shard=0/4"])
+        .args(["mutants", "-v", "--shard=3/4"])
         .arg("-d")
         .arg(path)
         .assert()
@@ -357,7 +368,7 @@ fn cross_package_tests() {
 
     // Testing the whole workspace does catch everything.
     run()
-        .args(["mutants", "--test-workspace=true", "--shard=0/4"])
+        .args(["mutants", "--test-workspace=true", "--shard=3/4"])
         .arg("-d")
         .arg(path.join("lib"))
         .assert()
@@ -369,7 +380,7 @@ fn cross_package_tests() {
         .args([
             "mutants",
             "--test-package=cargo-mutants-testdata-cross-package-tests-tests",
-            "--shard=0/4",
+            "--shard=3/4",
         ])
         .arg("-d")
         .arg(path.join("lib"))
@@ -397,7 +408,7 @@ fn cross_package_tests() {
     // Now the mutants are caught
     run()
         .args(["mutants"])
-        .arg("--shard=0/4")
+        .arg("--shard=3/4")
         .arg("-d")
         .arg(path.join("lib"))
         .assert()
@@ -412,10 +423,83 @@ fn cross_package_tests() {
     .unwrap();
     run()
         .args(["mutants"])
-        .arg("--shard=0/4")
+        .arg("--shard=3/4")
         .arg("-d")
         .arg(path.join("lib"))
         .assert()
         .stdout(predicate::str::contains("1 caught"))
         .code(0);
 }
+
+#[test]
+fn package_config_is_merged_with_workspace_config() {
+    let tmp = copy_of_testdata("workspace");
+    create_dir(tmp.path().join(".cargo")).unwrap();
+    write(
+        tmp.path().join(".cargo/mutants.toml"),
+        "exclude_re = [\"with 1$\"]\n",
+    )
+    .unwrap();
+    create_dir(tmp.path().join("utils/.cargo")).unwrap();
+    write(
+        tmp.path().join("utils/.cargo/mutants.toml"),
+        "exclude_re = [\"with -1$\"]\n",
+    )
+    .unwrap();
+    let cmd = run()
+        .args(["mutants", "--list", "--workspace", "--line-col=false"])
+        .current_dir(tmp.path())
+        .assert()
+        .success();
+    let stdout = String::from_utf8_lossy(&cmd.get_output().stdout);
+    // Only the utils package excludes `with -1`, and all packages exclude `with 1`.
+    assert_eq!(
+        stdout.lines().collect_vec(),
+        [
+            "utils/src/lib.rs: replace triple -> i32 with 0",
+            "utils/src/lib.rs: replace * with + in triple",
+            "utils/src/lib.rs: replace * with / in triple",
+            "main/src/main.rs: replace factorial -> u32 with 0",
+            "main/src/main.rs: replace ..= with .. in factorial",
+            "main/src/main.rs: replace *= with += in factorial",
+            "main/src/main.rs: replace *= with /= in factorial",
+            "main2/src/main.rs: replace triple_3 -> i32 with 0",
+            "main2/src/main.rs: replace triple_3 -> i32 with -1",
+        ]
+    );
+}
+
+/// Each package's mutants get a test timeout based on how long that package's own tests
+/// took in the baseline, not the slowest package in the workspace.
+#[test]
+fn test_timeouts_are_set_for_each_package() {
+    let tmp = copy_of_testdata("workspace");
+    let utils_lib = tmp.path().join("utils/src/lib.rs");
+    let mut source = read_to_string(&utils_lib).unwrap();
+    source.push_str(indoc! {"
+
+        #[test]
+        fn slow_test() {
+            std::thread::sleep(std::time::Duration::from_secs(2));
+        }
+    "});
+    write(&utils_lib, source).unwrap();
+    let cmd = run()
+        .args([
+            "mutants",
+            "--workspace",
+            "--minimum-test-timeout=0",
+            "-F",
+            "replace triple -> i32 with 0",
+            "-F",
+            "replace factorial -> u32 with 0",
+        ])
+        .current_dir(tmp.path())
+        .assert()
+        .success();
+    let stderr = String::from_utf8_lossy(&cmd.get_output().stderr);
+    assert!(
+        stderr.contains("Auto-set test timeouts for each package from "),
+        "{stderr}"
+    );
+}